@@ -0,0 +1,408 @@
+use std::collections::HashMap;
+use crate::logic::variable_bit_cpu::{ALUInstruction, Instructions, Register};
+
+//Converts assembly into the binary strings expected by run_instructions() inside run_circuit.rs.
+// Each line holds an optional label followed by an optional instruction. Everything after a ';' is
+// a comment.
+//
+//  loop:   ADD R1 R2       ;ALU instructions take reg_a then reg_b.
+//          DATA R0 5       ;Values can be decimal, 0b or 0x.
+//          JMP loop        ;Addresses can be a label or a value.
+//          JCZ loop        ;JMPIF is J followed by any of the flags C, A, E and Z.
+//          OUT ADDR R0     ;IN and OUT take DATA or ADDR followed by the register.
+//          MOV R1 R2       ;Copies reg_a into reg_b.
+//          SWAP R1 R2      ;Exchanges reg_a and reg_b.
+//          LDI R1 5        ;Loads the value into the register.
+//          CLF
+//          END
+//
+//Labels are resolved in two passes so a jump can refer to a label before it is defined.
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum AssemblerError {
+    /// The mnemonic on the line is not a known instruction. Holds the line number and mnemonic.
+    UnknownInstruction(usize, String),
+    /// An operand is missing, extra or malformed. Holds the line number and the line.
+    InvalidOperands(usize, String),
+    /// A value does not fit inside a single RAM cell. Holds the line number and the value.
+    ValueTooLarge(usize, u64),
+    /// A jump refers to a label that is never defined. Holds the line number and the label.
+    UndefinedLabel(usize, String),
+    /// The same label is defined twice. Holds the line number of the second definition and the
+    /// label.
+    DuplicateLabel(usize, String),
+}
+
+enum Operand {
+    Value(u64),
+    Label(String),
+}
+
+//A single instruction after the first pass. Line numbers start at 1.
+struct ParsedInstruction {
+    line_number: usize,
+    line: String,
+    mnemonic: String,
+    operands: Vec<String>,
+}
+
+#[allow(dead_code)]
+//Returns one binary string per RAM cell with the most significant bit first. Every cell is
+// number_bits wide, instructions are padded with leading zeros.
+pub fn assemble(source: &str, number_bits: usize) -> Result<Vec<String>, AssemblerError> {
+    assert!(number_bits >= 8);
+
+    //First pass, find the address of every label.
+    let mut labels: HashMap<String, u64> = HashMap::new();
+    let mut parsed_instructions = Vec::new();
+    let mut address: u64 = 0;
+    for (i, line) in source.lines().enumerate() {
+        let line_number = i + 1;
+        let mut code = line.split(';').next().unwrap().trim();
+
+        if let Some((label, rest)) = code.split_once(':') {
+            let label = label.trim();
+            if !is_valid_label(label) {
+                return Err(AssemblerError::InvalidOperands(line_number, line.to_string()));
+            }
+
+            if labels.insert(label.to_string(), address).is_some() {
+                return Err(AssemblerError::DuplicateLabel(line_number, label.to_string()));
+            }
+
+            code = rest.trim();
+        }
+
+        let mut tokens = code.split_whitespace();
+        let mnemonic = match tokens.next() {
+            Some(mnemonic) => mnemonic.to_uppercase(),
+            None => continue,
+        };
+
+        let parsed_instruction = ParsedInstruction {
+            line_number,
+            line: line.to_string(),
+            mnemonic,
+            operands: tokens.map(|token| token.to_string()).collect(),
+        };
+
+        address += instruction_size(&parsed_instruction) as u64;
+        parsed_instructions.push(parsed_instruction);
+    }
+
+    //Second pass, encode every instruction now that all labels are known.
+    let mut binary_strings = Vec::new();
+    for parsed_instruction in parsed_instructions.iter() {
+        for binary in encode_instruction(parsed_instruction, &labels, number_bits)? {
+            binary_strings.push(format!("{:0>width$}", binary, width = number_bits));
+        }
+    }
+
+    Ok(binary_strings)
+}
+
+fn is_valid_label(label: &str) -> bool {
+    let mut chars = label.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+//DATA and the jumps to an address use the next RAM cell as well. MOV, SWAP, LDI and ADC are
+// extended instructions so they are preceded by Instructions::EXTENDED_PREFIX.
+fn instruction_size(parsed_instruction: &ParsedInstruction) -> usize {
+    if parsed_instruction.mnemonic == "LDI" {
+        3
+    } else if parsed_instruction.mnemonic == "DATA"
+        || parsed_instruction.mnemonic == "JMP"
+        || parsed_instruction.mnemonic == "MOV"
+        || parsed_instruction.mnemonic == "SWAP"
+        || parsed_instruction.mnemonic == "ADC"
+        || parse_jump_if_flags(&parsed_instruction.mnemonic).is_some() {
+        2
+    } else {
+        1
+    }
+}
+
+//Returns carry, a_larger, equal and zero for mnemonics such as JC or JAEZ.
+fn parse_jump_if_flags(mnemonic: &str) -> Option<(bool, bool, bool, bool)> {
+    let flags = mnemonic.strip_prefix('J')?;
+    if flags.is_empty() || !flags.chars().all(|c| "CAEZ".contains(c)) {
+        return None;
+    }
+
+    Some((flags.contains('C'), flags.contains('A'), flags.contains('E'), flags.contains('Z')))
+}
+
+fn encode_instruction(
+    parsed_instruction: &ParsedInstruction,
+    labels: &HashMap<String, u64>,
+    number_bits: usize,
+) -> Result<Vec<String>, AssemblerError> {
+    let line_number = parsed_instruction.line_number;
+    let operands = &parsed_instruction.operands;
+    let invalid_operands = || AssemblerError::InvalidOperands(line_number, parsed_instruction.line.clone());
+
+    let expect_operands = |num_operands: usize| {
+        if operands.len() == num_operands {
+            Ok(())
+        } else {
+            Err(invalid_operands())
+        }
+    };
+
+    let register = |index: usize| parse_register(&operands[index]).ok_or_else(invalid_operands);
+
+    let value = |index: usize| {
+        let value = match parse_operand(&operands[index]).ok_or_else(invalid_operands)? {
+            Operand::Value(value) => value,
+            Operand::Label(label) => match labels.get(&label) {
+                Some(address) => *address,
+                None => return Err(AssemblerError::UndefinedLabel(line_number, label)),
+            }
+        };
+
+        if number_bits < u64::BITS as usize && value >> number_bits != 0 {
+            return Err(AssemblerError::ValueTooLarge(line_number, value));
+        }
+
+        Ok(format!("{:0width$b}", value, width = number_bits))
+    };
+
+    let alu_instruction = |opt: ALUInstruction| -> Result<Vec<String>, AssemblerError> {
+        expect_operands(2)?;
+        Ok(vec![Instructions::binary(Instructions::ALU { opt, reg_a: register(0)?, reg_b: register(1)? })])
+    };
+
+    let binary_strings = match parsed_instruction.mnemonic.as_str() {
+        "ADD" => alu_instruction(ALUInstruction::ADD)?,
+        "SHR" => alu_instruction(ALUInstruction::SHR)?,
+        "SHL" => alu_instruction(ALUInstruction::SHL)?,
+        "NOT" => alu_instruction(ALUInstruction::NOT)?,
+        "AND" => alu_instruction(ALUInstruction::AND)?,
+        "OR" => alu_instruction(ALUInstruction::OR)?,
+        "XOR" => alu_instruction(ALUInstruction::XOR)?,
+        "CMP" => alu_instruction(ALUInstruction::CMP)?,
+        "LD" => {
+            expect_operands(2)?;
+            vec![Instructions::binary(Instructions::Load { reg_a: register(0)?, reg_b: register(1)? })]
+        }
+        "ST" => {
+            expect_operands(2)?;
+            vec![Instructions::binary(Instructions::Store { reg_a: register(0)?, reg_b: register(1)? })]
+        }
+        "DATA" => {
+            expect_operands(2)?;
+            vec![Instructions::binary(Instructions::Data { reg: register(0)? }), value(1)?]
+        }
+        "JMPR" => {
+            expect_operands(1)?;
+            vec![Instructions::binary(Instructions::JumpRegister { reg: register(0)? })]
+        }
+        "JMP" => {
+            expect_operands(1)?;
+            vec![Instructions::binary(Instructions::JumpAddress), value(0)?]
+        }
+        "CLF" => {
+            expect_operands(0)?;
+            vec![Instructions::binary(Instructions::ClearFlags)]
+        }
+        "END" => {
+            expect_operands(0)?;
+            vec![Instructions::binary(Instructions::End)]
+        }
+        "MOV" => {
+            expect_operands(2)?;
+            vec![
+                Instructions::EXTENDED_PREFIX.to_string(),
+                format!("0000{}{}", Register::binary(register(0)?), Register::binary(register(1)?)),
+            ]
+        }
+        "SWAP" => {
+            expect_operands(2)?;
+            vec![
+                Instructions::EXTENDED_PREFIX.to_string(),
+                format!("0001{}{}", Register::binary(register(0)?), Register::binary(register(1)?)),
+            ]
+        }
+        "ADC" => {
+            let mut binary_strings = vec![Instructions::EXTENDED_PREFIX.to_string()];
+            binary_strings.append(&mut alu_instruction(ALUInstruction::ADD)?);
+            binary_strings
+        }
+        "LDI" => {
+            expect_operands(2)?;
+            vec![
+                Instructions::EXTENDED_PREFIX.to_string(),
+                format!("001000{}", Register::binary(register(0)?)),
+                value(1)?,
+            ]
+        }
+        "IN" | "OUT" => {
+            expect_operands(2)?;
+            let address = match operands[0].to_uppercase().as_str() {
+                "DATA" => '0',
+                "ADDR" => '1',
+                _ => return Err(invalid_operands()),
+            };
+            let output = if parsed_instruction.mnemonic == "OUT" { '1' } else { '0' };
+            vec![format!("0111{}{}{}", output, address, Register::binary(register(1)?))]
+        }
+        mnemonic => {
+            match parse_jump_if_flags(mnemonic) {
+                Some((carry, a_larger, equal, zero)) => {
+                    expect_operands(1)?;
+                    vec![Instructions::binary(Instructions::JumpIf { carry, a_larger, equal, zero }), value(0)?]
+                }
+                None => {
+                    return Err(AssemblerError::UnknownInstruction(line_number, mnemonic.to_string()));
+                }
+            }
+        }
+    };
+
+    Ok(binary_strings)
+}
+
+fn parse_register(operand: &str) -> Option<Register> {
+    match operand.to_uppercase().as_str() {
+        "R0" => Some(Register::R0),
+        "R1" => Some(Register::R1),
+        "R2" => Some(Register::R2),
+        "R3" => Some(Register::R3),
+        _ => None,
+    }
+}
+
+fn parse_operand(operand: &str) -> Option<Operand> {
+    let lowercase = operand.to_lowercase();
+    let value =
+        if let Some(binary) = lowercase.strip_prefix("0b") {
+            u64::from_str_radix(binary, 2).ok()
+        } else if let Some(hex) = lowercase.strip_prefix("0x") {
+            u64::from_str_radix(hex, 16).ok()
+        } else if operand.starts_with(|c: char| c.is_ascii_digit()) {
+            operand.parse::<u64>().ok()
+        } else if is_valid_label(operand) {
+            return Some(Operand::Label(operand.to_string()));
+        } else {
+            None
+        };
+
+    value.map(Operand::Value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn assemble_backward_label() {
+        let source = "
+                    DATA R0 0
+                    DATA R1 1
+            loop:   ADD R1 R0   ;Address 4
+                    CLF
+                    JMP loop
+                    END
+        ";
+
+        let binary_strings = assemble(source, 8).unwrap();
+
+        assert_eq!(binary_strings.len(), 9);
+        assert_eq!(binary_strings[4], "10000100");
+        assert_eq!(binary_strings[6], "01000000");
+        assert_eq!(binary_strings[7], format!("{:08b}", 4));
+    }
+
+    #[test]
+    fn assemble_multiplication_program() {
+        let source = "
+                    DATA R0 5
+                    DATA R1 5
+                    DATA R3 1
+                    XOR R2 R2
+            loop:   CLF
+                    SHR R0 R0
+                    JC add
+                    JMP shift
+            add:    CLF
+                    ADD R1 R2
+            shift:  CLF
+                    SHL R1 R1
+                    SHL R3 R3
+                    JC done
+                    JMP loop
+            done:   END
+        ";
+
+        let expected: Vec<String> = include_str!("../programs/multiplication.ms")
+            .lines()
+            .map(|line| line.to_string())
+            .collect();
+
+        assert_eq!(assemble(source, 8).unwrap(), expected);
+    }
+
+    #[test]
+    fn assemble_pads_to_number_bits() {
+        let binary_strings = assemble("DATA R2 0x1FF\nOUT ADDR R2", 12).unwrap();
+
+        assert_eq!(binary_strings, vec!["000000100010", "000111111111", "000001111110"]);
+    }
+
+    #[test]
+    fn assemble_extended_move() {
+        let binary_strings = assemble("MOV R1 R2\nend: END\nJMP end", 8).unwrap();
+
+        assert_eq!(binary_strings, vec![Instructions::EXTENDED_PREFIX, "00000110", "11001111", "01000000", "00000010"]);
+    }
+
+    #[test]
+    fn assemble_extended_swap() {
+        let binary_strings = assemble("SWAP R3 R0", 8).unwrap();
+
+        assert_eq!(binary_strings, vec![Instructions::EXTENDED_PREFIX, "00011100"]);
+    }
+
+    #[test]
+    fn assemble_extended_load_immediate() {
+        let binary_strings = assemble("LDI R2 0x2A\nend: END\nJMP end", 8).unwrap();
+
+        assert_eq!(binary_strings, vec![Instructions::EXTENDED_PREFIX, "00100010", "00101010", "11001111", "01000000", "00000011"]);
+    }
+
+    #[test]
+    fn assemble_extended_add_with_carry() {
+        let binary_strings = assemble("ADC R1 R2\nend: END\nJMP end", 8).unwrap();
+
+        assert_eq!(binary_strings, vec![Instructions::EXTENDED_PREFIX, "10000110", "11001111", "01000000", "00000010"]);
+    }
+
+    #[test]
+    fn assemble_undefined_label() {
+        assert_eq!(
+            assemble("JMP nowhere", 8),
+            Err(AssemblerError::UndefinedLabel(1, "nowhere".to_string()))
+        );
+    }
+
+    #[test]
+    fn assemble_duplicate_label() {
+        assert_eq!(
+            assemble("start: CLF\nstart: END", 8),
+            Err(AssemblerError::DuplicateLabel(2, "start".to_string()))
+        );
+    }
+
+    #[test]
+    fn assemble_value_too_large() {
+        assert_eq!(
+            assemble("DATA R0 256", 8),
+            Err(AssemblerError::ValueTooLarge(1, 256))
+        );
+    }
+}
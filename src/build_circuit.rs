@@ -1,6 +1,15 @@
 use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
 use std::rc::Rc;
-use crate::logic::foundations::LogicGate;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use crate::isa::{InstructionDescriptor, InstructionSet};
+use crate::logic::foundations::{extract_signals_from_gate_output_states, signals_to_u64, Endianness, GateInput, GateTagInfo, LogicGate, Signal, UniqueID};
+use crate::logic::io::IoDevice;
+use crate::logic::arithmetic_gates::VariableBitDivider;
+use crate::logic::variable_bit_cpu::{DivByZeroTrap, Instructions, VariableBitCPU};
+use crate::run_circuit::{reset_cpu_to_initial_state, run_instructions_on_cpu_with_observer};
+use crate::shared_mutex::SharedMutex;
 
 #[allow(unused_imports)]
 use crate::logic::foundations::Signal::{HIGH, LOW_};
@@ -16,7 +25,8 @@ use crate::logic::basic_gates::{
 #[allow(unused_imports)]
 use crate::logic::input_gates::{
     AutomaticInput,
-    Clock
+    Clock,
+    SimpleInput,
 };
 #[allow(unused_imports)]
 use crate::logic::memory_gates::{ActiveLowSRLatch, SRLatch, OneBitMemoryCell};
@@ -24,6 +34,7 @@ use crate::logic::memory_gates::{ActiveLowSRLatch, SRLatch, OneBitMemoryCell};
 use crate::logic::output_gates::LogicGateAndOutputGate;
 #[allow(unused_imports)]
 use crate::logic::output_gates::SimpleOutput;
+use crate::logic::processor_components::RAMUnit;
 
 #[allow(dead_code)]
 pub struct InputAndOutputGates {
@@ -40,4 +51,1116 @@ pub fn build_simple_circuit() -> InputAndOutputGates {
         input_gates,
         output_gates,
     }
-}
\ No newline at end of file
+}
+
+//Builds a VariableBitCPU without running anything on it. The program can then be run with
+// run_instructions_on_cpu() inside run_circuit.rs.
+#[allow(dead_code)]
+#[derive(Clone)]
+pub struct CpuConfig {
+    bus_width: usize,
+    address_bits: usize,
+    initial_state_seed: Option<u64>,
+    zero_fill_ram: bool,
+    reset_vector: u64,
+    io_devices: Vec<SharedMutex<dyn IoDevice>>,
+    div_by_zero_trap: Option<(SharedMutex<VariableBitDivider>, DivByZeroTrap)>,
+}
+
+#[allow(dead_code)]
+impl CpuConfig {
+    pub fn new() -> Self {
+        CpuConfig {
+            bus_width: 8,
+            address_bits: 4,
+            initial_state_seed: None,
+            zero_fill_ram: false,
+            reset_vector: 0,
+            io_devices: Vec::new(),
+            div_by_zero_trap: None,
+        }
+    }
+
+    pub fn bus_width(mut self, bus_width: usize) -> Self {
+        self.bus_width = bus_width;
+        self
+    }
+
+    //The RAM address is split evenly between the row and column decoders so this must be even.
+    pub fn address_bits(mut self, address_bits: usize) -> Self {
+        self.address_bits = address_bits;
+        self
+    }
+
+    //Fills the unused RAM cells and R0-R3 with pseudo-random bits generated from seed before a
+    // program runs instead of leaving them LOW. Programs that read a register or address before
+    // writing it will behave differently, see VariableBitCPU::randomize_initial_state().
+    pub fn randomize_initial_state(mut self, seed: u64) -> Self {
+        self.initial_state_seed = Some(seed);
+        self
+    }
+
+    //Sets every RAM cell to LOW when the cpu is built, see VariableBitCPU::zero_fill_ram(). The
+    // cells the program is loaded into are overwritten when it runs.
+    pub fn zero_fill_ram(mut self) -> Self {
+        self.zero_fill_ram = true;
+        self
+    }
+
+    //The address the program starts from once it has been loaded into RAM, see
+    // VariableBitCPU::set_reset_vector().
+    pub fn reset_vector(mut self, address: u64) -> Self {
+        self.reset_vector = address;
+        self
+    }
+
+    //Attaches io_device to the cpu IO bus of every cpu built from this config. The device is shared
+    // between those cpus, see VariableBitCPU::attach_io_device().
+    pub fn io_device(mut self, io_device: SharedMutex<dyn IoDevice>) -> Self {
+        self.io_devices.push(io_device);
+        self
+    }
+
+    //Traps when DIV_BY_ZERO of divider goes HIGH while a program runs. The divider is shared
+    // between every cpu built from this config, see VariableBitCPU::set_div_by_zero_trap().
+    pub fn div_by_zero_trap(mut self, divider: SharedMutex<VariableBitDivider>, trap: DivByZeroTrap) -> Self {
+        self.div_by_zero_trap = Some((divider, trap));
+        self
+    }
+
+    pub fn get_bus_width(&self) -> usize {
+        self.bus_width
+    }
+
+    pub fn decoder_input_size(&self) -> usize {
+        self.address_bits / 2
+    }
+
+    //Builds a cpu from this config and runs run_builtin_selftest() on it. The program needs 16 RAM
+    // cells, so address_bits must be at least 4.
+    pub fn run_builtin_selftest(&self) -> SelfTestReport {
+        assert!(self.address_bits >= 4);
+
+        run_builtin_selftest(&self.build())
+    }
+
+    pub fn build(&self) -> SharedMutex<VariableBitCPU> {
+        assert_ne!(self.address_bits, 0);
+        assert_eq!(self.address_bits % 2, 0, "address_bits must be even");
+        //Instructions are 8 bits wide.
+        assert!(self.bus_width >= 8);
+        //The memory address register is loaded from the bus.
+        assert!(self.address_bits <= self.bus_width);
+        assert!(self.reset_vector < 1 << self.address_bits);
+        if let Some((divider, trap)) = self.div_by_zero_trap.as_ref() {
+            //The divider is driven from R0 and R1.
+            assert_eq!(divider.lock().unwrap().num_inputs(), 2 * self.bus_width);
+            if let DivByZeroTrap::Jump(address) = trap {
+                assert!(*address < 1 << self.address_bits);
+            }
+        }
+
+        let cpu = VariableBitCPU::new(self.bus_width, self.decoder_input_size());
+
+        if self.zero_fill_ram {
+            cpu.lock().unwrap().zero_fill_ram();
+        }
+
+        if let Some(seed) = self.initial_state_seed {
+            cpu.lock().unwrap().randomize_initial_state(seed);
+        }
+
+        cpu.lock().unwrap().set_reset_vector(self.reset_vector);
+
+        for io_device in self.io_devices.iter() {
+            cpu.lock().unwrap().attach_io_device(io_device.clone());
+        }
+
+        if let Some((divider, trap)) = self.div_by_zero_trap.as_ref() {
+            cpu.lock().unwrap().set_div_by_zero_trap(divider.clone(), *trap);
+        }
+
+        cpu
+    }
+}
+
+//Power on self test. Each register and each ALU operation is exercised with known values and every
+// register is read back after each instruction completes. The test is a program, so cpu is returned
+// to its initial state before and after it runs and anything cpu held is lost. The run settings of
+// cpu (reset vector, IO devices, traps and initial state seed) are set aside while the program runs
+// because it starts at address 0 and uses none of them. cpu is passed shared instead of as
+// &mut VariableBitCPU because running the program connects a clock to it.
+#[allow(dead_code)]
+pub fn run_builtin_selftest(cpu: &SharedMutex<VariableBitCPU>) -> SelfTestReport {
+    reset_cpu_to_initial_state(cpu);
+    let run_settings = cpu.lock().unwrap().take_run_settings();
+
+    let report = run_selftest_program(cpu);
+
+    cpu.lock().unwrap().restore_run_settings(run_settings);
+    reset_cpu_to_initial_state(cpu);
+
+    report
+}
+
+//Asserts or releases the HALT input of the cpu. While HALT is HIGH run_instructions_on_cpu() inside
+// run_circuit.rs holds the clock in its current state so no further instructions execute. HALT
+// starts LOW.
+#[allow(dead_code)]
+pub fn set_halt(cpu: &SharedMutex<VariableBitCPU>, signal: Signal) {
+    let mut cpu = cpu.lock().unwrap();
+    let halt_index = cpu.get_index_from_tag(VariableBitCPU::HALT);
+    cpu.update_input_signal(
+        GateInput::new(
+            halt_index,
+            signal,
+            UniqueID::zero_id(),
+        )
+    );
+}
+
+pub fn halt_asserted(cpu: &SharedMutex<VariableBitCPU>) -> bool {
+    let halt_input = {
+        let cpu = cpu.lock().unwrap();
+        let halt_index = cpu.get_index_from_tag(VariableBitCPU::HALT);
+        cpu.get_input_gates()[halt_index].clone()
+    };
+
+    let halt_output = halt_input.lock().unwrap().fetch_output_signals_calculate().unwrap();
+    extract_signals_from_gate_output_states(&halt_output)[0] == HIGH
+}
+
+const REGISTER_TAGS: [&str; 4] = [
+    VariableBitCPU::R0,
+    VariableBitCPU::R1,
+    VariableBitCPU::R2,
+    VariableBitCPU::R3,
+];
+
+//Returns None if any bit of the register is NONE.
+fn read_register(cpu: &SharedMutex<VariableBitCPU>, register_tag: &str) -> Option<u64> {
+    let mut cpu = cpu.lock().unwrap();
+    let cpu_output = cpu.fetch_output_signals_no_calculate().unwrap();
+    let cpu_signals = extract_signals_from_gate_output_states(&cpu_output);
+
+    read_output_bits(
+        &cpu.get_complex_gate().gate_tags_to_index,
+        &cpu_signals,
+        |bit| format!("{}_{}", register_tag, bit),
+    )
+}
+
+//Reads bits starting at bit 0 until bit_tag() returns a tag the cpu does not have. Returns None if
+// any bit is NONE.
+fn read_output_bits<F>(
+    gate_tags_to_index: &HashMap<String, GateTagInfo>,
+    cpu_signals: &[Signal],
+    bit_tag: F,
+) -> Option<u64>
+    where F: Fn(usize) -> String
+{
+    let mut signals = Vec::new();
+    while let Some(gate_tag_info) = gate_tags_to_index.get(&bit_tag(signals.len())) {
+        signals.push(cpu_signals[gate_tag_info.index].clone());
+    }
+
+    signals_to_u64(&signals, signals.len(), Endianness::Little)
+}
+
+const SNAPSHOT_REGISTER_TAGS: [&str; 5] = [
+    VariableBitCPU::R0,
+    VariableBitCPU::R1,
+    VariableBitCPU::R2,
+    VariableBitCPU::R3,
+    VariableBitCPU::IAR,
+];
+
+const FLAG_NAMES: [&str; 4] = ["C", "A", "E", "Z"];
+
+//The registers, flags and RAM of a cpu at a single point in time. ACC, TMP and IR are left out
+// because every instruction overwrites them. A value is None if any of its bits are NONE.
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CpuSnapshot {
+    pub registers: Vec<(String, Option<u64>)>,
+    pub flags: Vec<(String, Signal)>,
+    pub ram: Vec<Option<u64>>,
+}
+
+#[allow(dead_code)]
+impl CpuSnapshot {
+    pub fn capture(cpu: &SharedMutex<VariableBitCPU>) -> Self {
+        let mut cpu = cpu.lock().unwrap();
+        let cpu_output = cpu.fetch_output_signals_no_calculate().unwrap();
+        let cpu_signals = extract_signals_from_gate_output_states(&cpu_output);
+        let gate_tags_to_index = &cpu.get_complex_gate().gate_tags_to_index;
+
+        let registers = SNAPSHOT_REGISTER_TAGS
+            .iter()
+            .map(|register_tag| {
+                let value = read_output_bits(
+                    gate_tags_to_index,
+                    &cpu_signals,
+                    |bit| format!("{}_{}", register_tag, bit),
+                );
+                (register_tag.to_string(), value)
+            })
+            .collect();
+
+        let mut ram = Vec::new();
+        while gate_tags_to_index.contains_key(&RAMUnit::get_ram_output_string(ram.len(), 0)) {
+            let cell_index = ram.len();
+            ram.push(
+                read_output_bits(
+                    gate_tags_to_index,
+                    &cpu_signals,
+                    |bit| RAMUnit::get_ram_output_string(cell_index, bit),
+                )
+            );
+        }
+
+        let flags = FLAG_NAMES
+            .iter()
+            .map(|flag| flag.to_string())
+            .zip(cpu.get_flags())
+            .collect();
+
+        CpuSnapshot {
+            registers,
+            flags,
+            ram,
+        }
+    }
+}
+
+//A single difference found by diff_cpu_state(). The first value is from snapshot a and the second
+// is from snapshot b.
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum StateDiff {
+    Register(String, Option<u64>, Option<u64>),
+    Flag(String, Signal, Signal),
+    Ram(usize, Option<u64>, Option<u64>),
+}
+
+#[allow(dead_code)]
+//Lists every register, flag and RAM address that differs between the snapshots. RAM addresses that
+// only exist in one of the snapshots are reported with None for the other.
+pub fn diff_cpu_state(a: &CpuSnapshot, b: &CpuSnapshot) -> Vec<StateDiff> {
+    let mut differences = Vec::new();
+
+    for ((register_tag, a_value), (_, b_value)) in a.registers.iter().zip(b.registers.iter()) {
+        if a_value != b_value {
+            differences.push(StateDiff::Register(register_tag.clone(), *a_value, *b_value));
+        }
+    }
+
+    for ((flag, a_signal), (_, b_signal)) in a.flags.iter().zip(b.flags.iter()) {
+        if a_signal != b_signal {
+            differences.push(StateDiff::Flag(flag.clone(), a_signal.clone(), b_signal.clone()));
+        }
+    }
+
+    for address in 0..a.ram.len().max(b.ram.len()) {
+        let a_value = a.ram.get(address).copied().flatten();
+        let b_value = b.ram.get(address).copied().flatten();
+        if a_value != b_value {
+            differences.push(StateDiff::Ram(address, a_value, b_value));
+        }
+    }
+
+    differences
+}
+
+//The pass/fail result of each component checked by run_builtin_selftest().
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct SelfTestReport {
+    pub results: Vec<(String, bool)>,
+}
+
+#[allow(dead_code)]
+impl SelfTestReport {
+    fn new() -> Self {
+        SelfTestReport {
+            results: Vec::new(),
+        }
+    }
+
+    fn record(&mut self, component: &str, passed: bool) {
+        self.results.push((component.to_string(), passed));
+    }
+
+    pub fn all_passed(&self) -> bool {
+        self.results.iter().all(|(_, passed)| *passed)
+    }
+
+    pub fn failed_components(&self) -> Vec<&str> {
+        self.results
+            .iter()
+            .filter(|(_, passed)| !*passed)
+            .map(|(component, _)| component.as_str())
+            .collect()
+    }
+}
+
+//An instruction of the self test program along with what it should leave behind.
+struct SelfTestInstruction {
+    words: Vec<String>,
+    //The ALU operation if there is one, the register the result is written to and the result.
+    result: Option<(Option<&'static str>, &'static str, u64)>,
+    //The value of reg_b that an ALU instruction latches into TMP.
+    tmp: Option<u64>,
+}
+
+//The registers of the cpu once an instruction has completed.
+struct SelfTestSample {
+    snapshot: CpuSnapshot,
+    ir: Option<u64>,
+    acc: Option<u64>,
+    tmp: Option<u64>,
+    mar: Option<u64>,
+}
+
+//Runs the self test program on cpu, see run_builtin_selftest(). The program is loaded into RAM
+// starting at address 0, so cpu must be in its initial state.
+fn run_selftest_program(cpu: &SharedMutex<VariableBitCPU>) -> SelfTestReport {
+    let bus_width = cpu.lock().unwrap().read_ir().len();
+    let num_ram_cells = CpuSnapshot::capture(cpu).ram.len();
+    let decoder_input_size = num_ram_cells.trailing_zeros() as usize / 2;
+
+    let mask = if bus_width >= u64::BITS as usize {
+        u64::MAX
+    } else {
+        (1 << bus_width) - 1
+    };
+    let a_num: u64 = 0b0101_1010;
+    let b_num: u64 = 0b0011_0110;
+    let not_a = !a_num & mask;
+    let not_b = !b_num & mask;
+    let sum = (a_num + b_num) & mask;
+
+    let instruction = |binary: &str, result, tmp| SelfTestInstruction {
+        words: vec![binary.to_string()],
+        result,
+        tmp,
+    };
+    let data = |binary: &str, register_tag, value: u64| SelfTestInstruction {
+        words: vec![binary.to_string(), format!("{:b}", value)],
+        result: Some((None, register_tag, value)),
+        tmp: None,
+    };
+
+    //Every register is loaded with a different pattern so a register wired to the wrong output is
+    // caught.
+    let instructions = vec![
+        data("00100000", VariableBitCPU::R0, a_num), //Data R0
+        data("00100001", VariableBitCPU::R1, b_num), //Data R1
+        instruction("10110010", Some((Some("NOT"), VariableBitCPU::R2, not_a)), None), //Not R0 R2
+        instruction("10110111", Some((None, VariableBitCPU::R3, not_b)), None), //Not R1 R3
+        instruction("10000001", Some((Some("ADD"), VariableBitCPU::R1, sum)), Some(b_num)), //Add R0 R1
+        instruction("11001110", Some((Some("AND"), VariableBitCPU::R2, not_b & not_a)), Some(not_a)), //And R3 R2
+        instruction("11010011", Some((Some("OR"), VariableBitCPU::R3, a_num | not_b)), Some(not_b)), //Or R0 R3
+        instruction("11100001", Some((Some("XOR"), VariableBitCPU::R1, a_num ^ sum)), Some(sum)), //Xor R0 R1
+        instruction("10100010", Some((Some("SHL"), VariableBitCPU::R2, (a_num << 1) & mask)), None), //Shl R0 R2
+        instruction("10010011", Some((Some("SHR"), VariableBitCPU::R3, a_num >> 1)), None), //Shr R0 R3
+        instruction("11110000", None, None), //Cmp R0 R0
+    ];
+
+    let mut binary_strings: Vec<String> = instructions
+        .iter()
+        .flat_map(|instruction| instruction.words.iter())
+        .map(|binary| format!("{:0>width$}", binary, width = bus_width))
+        .collect();
+    binary_strings.push(format!("{:0>width$}", "11001111", width = bus_width)); //End
+    assert!(binary_strings.len() <= num_ram_cells);
+    let binary_strings: Vec<&str> = binary_strings.iter().map(|binary| binary.as_str()).collect();
+
+    //The stepper moving back to step 1 means an instruction has completed.
+    let mut samples = Vec::new();
+    let mut previous_step = cpu.lock().unwrap().get_current_step();
+    run_instructions_on_cpu_with_observer(
+        cpu,
+        decoder_input_size,
+        &binary_strings,
+        |cpu| {
+            let current_step = cpu.lock().unwrap().get_current_step();
+            if current_step == Some(0) && previous_step != Some(0) {
+                let ir = cpu.lock().unwrap().read_ir();
+                let mar = cpu.lock().unwrap().read_memory_address();
+                samples.push(
+                    SelfTestSample {
+                        snapshot: CpuSnapshot::capture(cpu),
+                        ir: signals_to_u64(&ir, ir.len(), Endianness::Little),
+                        acc: read_register(cpu, VariableBitCPU::ACC),
+                        tmp: read_register(cpu, VariableBitCPU::TMP),
+                        mar: signals_to_u64(&mar, mar.len(), Endianness::Little),
+                    }
+                );
+            }
+            previous_step = current_step;
+        },
+    );
+
+    let sample_register = |sample: &SelfTestSample, register_tag: &str| {
+        sample.snapshot.registers.iter().find(|(tag, _)| tag == register_tag).unwrap().1
+    };
+
+    //Each instruction is paired with its sample and the address of the next instruction. A missing
+    // sample fails every check made against it.
+    let mut next_address = 0;
+    let checks: Vec<(&SelfTestInstruction, Option<&SelfTestSample>, u64)> = instructions
+        .iter()
+        .enumerate()
+        .map(|(i, instruction)| {
+            next_address += instruction.words.len() as u64;
+            (instruction, samples.get(i), next_address)
+        })
+        .collect();
+
+    let mut report = SelfTestReport::new();
+
+    for register_tag in REGISTER_TAGS {
+        report.record(
+            register_tag,
+            checks.iter().all(|(instruction, sample, _)| match instruction.result {
+                Some((_, result_tag, value)) if result_tag == register_tag => {
+                    sample.is_some_and(|sample| sample_register(sample, register_tag) == Some(value))
+                }
+                _ => true,
+            }),
+        );
+    }
+
+    report.record(
+        VariableBitCPU::IAR,
+        checks.iter().all(|(_, sample, next_address)| {
+            sample.is_some_and(|sample| sample_register(sample, VariableBitCPU::IAR) == Some(*next_address))
+        }),
+    );
+
+    report.record(
+        VariableBitCPU::IR,
+        checks.iter().all(|(instruction, sample, _)| {
+            let opcode = u64::from_str_radix(&instruction.words[0], 2).ok();
+            sample.is_some_and(|sample| sample.ir == opcode)
+        }),
+    );
+
+    //The ALU result passes through ACC on its way to reg_b.
+    report.record(
+        VariableBitCPU::ACC,
+        checks.iter().all(|(instruction, sample, _)| match instruction.result {
+            Some((Some(_), _, value)) => sample.is_some_and(|sample| sample.acc == Some(value)),
+            _ => true,
+        }),
+    );
+
+    report.record(
+        VariableBitCPU::TMP,
+        checks.iter().all(|(instruction, sample, _)| match instruction.tmp {
+            Some(value) => sample.is_some_and(|sample| sample.tmp == Some(value)),
+            None => true,
+        }),
+    );
+
+    //MAR is left holding the address of the last word read, DATA reads the word after itself.
+    report.record(
+        "MAR",
+        checks.iter().all(|(_, sample, next_address)| {
+            sample.is_some_and(|sample| sample.mar == Some(next_address - 1))
+        }),
+    );
+
+    for (instruction, sample, _) in checks.iter() {
+        if let Some((Some(operation), register_tag, value)) = instruction.result {
+            report.record(
+                operation,
+                sample.is_some_and(|sample| sample_register(sample, register_tag) == Some(value)),
+            );
+        }
+    }
+
+    //Flags are ordered C, A_L, EQ, Z.
+    let cmp_sample = checks.last().and_then(|(_, sample, _)| *sample);
+    report.record("CMP", cmp_sample.is_some_and(|sample| sample.snapshot.flags[2].1 == HIGH));
+
+    report
+}
+
+//Walks every path through the program starting at address 0 and returns the addresses that are
+// never reached. The byte after DATA, JMP and JMPIF is data so it is neither reachable nor reported
+// when the instruction consuming it is reachable. The same goes for the extended instruction after
+// the prefix and the immediate after an extended LDI. JMPR jumps to an address held in a register,
+// so if a JMPR is reachable any address could be next and nothing is reported.
+#[allow(dead_code)]
+pub fn find_unreachable_instructions(machine_code: &[&str]) -> Vec<usize> {
+    let mut reachable = vec![false; machine_code.len()];
+    let mut consumed_as_data = vec![false; machine_code.len()];
+    let mut next_addresses = VecDeque::from([0]);
+
+    while let Some(address) = next_addresses.pop_front() {
+        if address >= machine_code.len() || reachable[address] {
+            continue;
+        }
+        reachable[address] = true;
+
+        //Returns the address stored in the byte after the instruction.
+        let jump_target = || {
+            machine_code
+                .get(address + 1)
+                .and_then(|target| usize::from_str_radix(target, 2).ok())
+        };
+
+        match Instructions::opcode_name(machine_code[address]) {
+            "END" => {}
+            "JMPR" => return Vec::new(),
+            "DATA" => {
+                if address + 1 < machine_code.len() {
+                    consumed_as_data[address + 1] = true;
+                }
+                next_addresses.push_back(address + 2);
+            }
+            "JMP" => {
+                if address + 1 < machine_code.len() {
+                    consumed_as_data[address + 1] = true;
+                }
+                next_addresses.extend(jump_target());
+            }
+            "JMPIF" => {
+                if address + 1 < machine_code.len() {
+                    consumed_as_data[address + 1] = true;
+                }
+                next_addresses.extend(jump_target());
+                next_addresses.push_back(address + 2);
+            }
+            "EXT" => {
+                let size = extended_instruction_size(machine_code, address);
+                for consumed_address in address + 1..(address + size).min(machine_code.len()) {
+                    consumed_as_data[consumed_address] = true;
+                }
+                next_addresses.push_back(address + size);
+            }
+            _ => next_addresses.push_back(address + 1),
+        }
+    }
+
+    (0..machine_code.len())
+        .filter(|&address| !reachable[address] && !consumed_as_data[address])
+        .collect()
+}
+
+//Returns the number of RAM address bits needed to hold the program and every address it refers
+// to. The targets of JMP and JMPIF and the values loaded by DATA are all treated as addresses
+// because DATA is often used to load the address for LD and ST, the immediate of an extended LDI
+// is treated the same way. The result is rounded up to an even number so it can be passed directly
+// to CpuConfig::address_bits().
+#[allow(dead_code)]
+pub fn required_address_bits(machine_code: &[&str]) -> usize {
+    let mut highest_address = machine_code.len().saturating_sub(1);
+
+    let mut address = 0;
+    while address < machine_code.len() {
+        match Instructions::opcode_name(machine_code[address]) {
+            "DATA" | "JMP" | "JMPIF" => {
+                if let Some(referenced_address) = machine_code
+                    .get(address + 1)
+                    .and_then(|value| usize::from_str_radix(value, 2).ok()) {
+                    highest_address = highest_address.max(referenced_address);
+                }
+                address += 2;
+            }
+            "EXT" => {
+                let size = extended_instruction_size(machine_code, address);
+                if size == 3 {
+                    if let Some(referenced_address) = machine_code
+                        .get(address + 2)
+                        .and_then(|value| usize::from_str_radix(value, 2).ok()) {
+                        highest_address = highest_address.max(referenced_address);
+                    }
+                }
+                address += size;
+            }
+            _ => address += 1,
+        }
+    }
+
+    let bits = (usize::BITS - highest_address.leading_zeros()) as usize;
+    (bits + bits % 2).max(2)
+}
+
+//Returns the number of cells taken up by the extended instruction whose prefix is at address. This
+// is the prefix and the extended instruction, LDI also has its immediate after them.
+fn extended_instruction_size(machine_code: &[&str], address: usize) -> usize {
+    match machine_code.get(address + 1).map(|binary| Instructions::extended_opcode_name(binary)) {
+        Some("LDI") => 3,
+        _ => 2,
+    }
+}
+
+//JMPR jumps to whatever address is inside of a register and IN and OUT need an IO device, so none
+// of them are generated. END is only placed at the end of the program.
+const RANDOM_PROGRAM_EXCLUDED_MNEMONICS: [&str; 4] = ["JMPR", "IN", "OUT", "END"];
+
+//The number of cells after END that LD and ST inside of a random program use.
+const RANDOM_PROGRAM_DATA_CELLS: usize = 2;
+
+//Generates num_instructions random 8 bit instructions from isa followed by END. The same seed
+// always generates the same program. Every jump goes forward to the start of a later instruction
+// so the program always reaches END. Each LD and ST is preceded by a DATA that loads reg_a with
+// the address of one of the cells after END so the program never overwrites itself. Values are
+// kept below the length of the program so that required_address_bits() stays small.
+#[allow(dead_code)]
+pub fn generate_random_program(num_instructions: usize, isa: &InstructionSet, seed: u64) -> Vec<String> {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let find_instruction = |mnemonic: &str| {
+        isa.instructions.iter().find(|instruction| instruction.mnemonic == mnemonic)
+    };
+    let data_instruction = find_instruction("DATA");
+    let end_instruction = find_instruction("END").expect("The isa must contain END.");
+
+    let candidates: Vec<&InstructionDescriptor> = isa.instructions
+        .iter()
+        .filter(|instruction| !RANDOM_PROGRAM_EXCLUDED_MNEMONICS.contains(&instruction.mnemonic))
+        .filter(|instruction| {
+            data_instruction.is_some() || !["LD", "ST"].contains(&instruction.mnemonic)
+        })
+        .collect();
+    assert!(!candidates.is_empty());
+
+    let instructions: Vec<&InstructionDescriptor> = (0..num_instructions)
+        .map(|_| candidates[rng.gen_range(0..candidates.len())])
+        .collect();
+
+    //The jump targets are only known once the address of every instruction is known.
+    let mut start_addresses = Vec::with_capacity(instructions.len());
+    let mut end_address = 0;
+    for instruction in instructions.iter() {
+        start_addresses.push(end_address);
+        end_address += instruction.encoding.len();
+        if ["LD", "ST"].contains(&instruction.mnemonic) {
+            end_address += data_instruction.unwrap().encoding.len();
+        }
+    }
+
+    let program_length = end_address + end_instruction.encoding.len() + RANDOM_PROGRAM_DATA_CELLS;
+    assert!(program_length <= 256, "The program must fit inside of 8 bit addresses.");
+
+    //Fills in every lower case letter of the encoding, see isa.rs. The a bits are set to reg_a
+    // when it is passed in.
+    let encode = |rng: &mut StdRng, cell: &str, reg_a: Option<usize>, jump_targets: &[usize]| {
+        match cell {
+            "value" => format!("{:08b}", rng.gen_range(0..program_length)),
+            "address" => format!("{:08b}", jump_targets[rng.gen_range(0..jump_targets.len())]),
+            _ => {
+                let mut a_bits = reg_a.map(|reg_a| format!("{:02b}", reg_a)).unwrap_or_default().into_bytes().into_iter();
+                cell.chars()
+                    .map(|c| match c {
+                        '0' | '1' => c,
+                        'a' if reg_a.is_some() => a_bits.next().unwrap() as char,
+                        'r' if reg_a.is_some() => a_bits.next().unwrap() as char,
+                        _ => if rng.gen() { '1' } else { '0' },
+                    })
+                    .collect()
+            }
+        }
+    };
+
+    let mut program = Vec::with_capacity(program_length);
+    for (i, instruction) in instructions.iter().enumerate() {
+        let mut jump_targets = start_addresses[i + 1..].to_vec();
+        jump_targets.push(end_address);
+
+        let mut reg_a = None;
+        if ["LD", "ST"].contains(&instruction.mnemonic) {
+            let address_reg = rng.gen_range(0..4);
+            let data_address = end_address + end_instruction.encoding.len() + rng.gen_range(0..RANDOM_PROGRAM_DATA_CELLS);
+
+            let data_instruction = data_instruction.unwrap();
+            program.push(encode(&mut rng, data_instruction.encoding[0], Some(address_reg), &jump_targets));
+            program.push(format!("{:08b}", data_address));
+
+            reg_a = Some(address_reg);
+        }
+
+        for cell in instruction.encoding.iter() {
+            program.push(encode(&mut rng, cell, reg_a, &jump_targets));
+        }
+    }
+
+    for cell in end_instruction.encoding.iter() {
+        program.push(encode(&mut rng, cell, None, &[]));
+    }
+    program.extend((0..RANDOM_PROGRAM_DATA_CELLS).map(|_| format!("{:08b}", 0)));
+
+    program
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::logic::io::TimerDevice;
+    use crate::run_circuit::{collect_signals_from_logic_gate, disable_snapshot_trace, enable_snapshot_trace, run_instructions, run_instructions_on_cpu, run_instructions_with_limit};
+    use crate::shared_mutex::new_shared_mutex;
+    use super::*;
+
+    #[test]
+    fn cpu_config_matches_run_instructions() {
+        let binary_strings = vec![
+            "00100001", //Data R1
+            "00000101", //5
+            "00100010", //Data R2
+            "00000011", //3
+            "10000110", //Add R1 R2
+            "11001111", //End
+        ];
+
+        let config = CpuConfig::new()
+            .bus_width(8)
+            .address_bits(4);
+
+        let cpu = config.build();
+
+        run_instructions_on_cpu(
+            &cpu,
+            config.decoder_input_size(),
+            &binary_strings,
+        );
+
+        let expected_cpu = run_instructions(
+            config.get_bus_width(),
+            config.decoder_input_size(),
+            &binary_strings,
+        );
+
+        let collected_signals = collect_signals_from_logic_gate(cpu.clone());
+        let expected_signals = collect_signals_from_logic_gate(expected_cpu.clone());
+
+        assert_eq!(collected_signals, expected_signals);
+    }
+
+    #[test]
+    fn randomized_initial_state() {
+        //Adds R0 into R1 without ever setting R0, so it only produces 5 when R0 starts out zeroed.
+        let assumes_zeroed_registers = vec![
+            "00100001", //Data R1
+            "00000101", //5
+            "10000001", //Add R0 R1
+            "11001111", //End
+        ];
+
+        let zeroed_cpu = CpuConfig::new().build();
+        run_instructions_on_cpu(&zeroed_cpu, 2, &assumes_zeroed_registers);
+        assert_eq!(read_register(&zeroed_cpu, VariableBitCPU::R1), Some(5));
+
+        //With this seed R0 starts out as 0b11111110 so the addition overflows and R1 ends up as 3.
+        let randomized_cpu = CpuConfig::new().randomize_initial_state(42).build();
+        run_instructions_on_cpu(&randomized_cpu, 2, &assumes_zeroed_registers);
+        assert_eq!(read_register(&randomized_cpu, VariableBitCPU::R0), Some(0b11111110));
+        assert_eq!(read_register(&randomized_cpu, VariableBitCPU::R1), Some(3));
+
+        //A program that sets every register it reads is unaffected.
+        let sets_its_registers = vec![
+            "00100000", //Data R0
+            "00000011", //3
+            "00100001", //Data R1
+            "00000101", //5
+            "10000001", //Add R0 R1
+            "11001111", //End
+        ];
+
+        let randomized_cpu = CpuConfig::new().randomize_initial_state(42).build();
+        run_instructions_on_cpu(&randomized_cpu, 2, &sets_its_registers);
+        assert_eq!(read_register(&randomized_cpu, VariableBitCPU::R0), Some(3));
+        assert_eq!(read_register(&randomized_cpu, VariableBitCPU::R1), Some(8));
+    }
+
+    #[test]
+    fn cpu_config_zero_fill_ram() {
+        let cpu = CpuConfig::new().zero_fill_ram().build();
+        let ram = CpuSnapshot::capture(&cpu).ram;
+        assert_eq!(ram, vec![Some(0); 16]);
+
+        let binary_strings = vec![
+            "00100000", //Data R0
+            "00000101", //5
+            "11001111", //End
+        ];
+
+        run_instructions_on_cpu(&cpu, 2, &binary_strings);
+        assert_eq!(read_register(&cpu, VariableBitCPU::R0), Some(5));
+
+        let ram = CpuSnapshot::capture(&cpu).ram;
+        assert_eq!(ram[0], Some(0b00100000));
+        assert_eq!(ram[3..], vec![Some(0); 13]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn cpu_config_odd_address_bits() {
+        CpuConfig::new().address_bits(3).build();
+    }
+
+    #[test]
+    fn cpu_config_reset_vector() {
+        let binary_strings = vec![
+            "00100001", //Data R1
+            "00000111", //7
+            "00100000", //Data R0
+            "00000101", //5
+            "11001111", //End
+        ];
+
+        //Starting at address 2 skips loading R1.
+        let cpu = CpuConfig::new().reset_vector(2).build();
+        run_instructions_on_cpu(&cpu, 2, &binary_strings);
+
+        assert_eq!(read_register(&cpu, VariableBitCPU::R0), Some(5));
+        assert_eq!(read_register(&cpu, VariableBitCPU::R1), Some(0));
+        assert!(cpu.lock().unwrap().is_halted());
+    }
+
+    #[test]
+    fn cpu_config_io_device() {
+        let binary_strings = vec![
+            "00100000", //Data R0
+            "00000011", //3
+            "01111100", //Out Addr R0
+            "00100001", //Data R1
+            "00000100", //4
+            "01111001", //Out Data R1
+            "01100000", //Clf
+            "01100000", //Clf
+            "11001111", //End
+        ];
+
+        let timer = new_shared_mutex(0, TimerDevice::new(3));
+
+        let cpu = CpuConfig::new().io_device(timer.clone()).build();
+        run_instructions_on_cpu(&cpu, 2, &binary_strings);
+
+        let timer = timer.lock().unwrap();
+        assert_eq!(timer.irq(), HIGH);
+        assert_eq!(timer.irq_cycle(), Some(timer.programmed_cycle().unwrap() + 4));
+    }
+
+    #[test]
+    fn diff_cpu_state_reports_changed_register() {
+        let binary_strings = vec![
+            "00100001", //Data R1
+            "00000101", //5
+            "00100010", //Data R2
+            "00000011", //3
+            "11001111", //End
+        ];
+
+        enable_snapshot_trace();
+        let cpu = run_instructions(8, 2, &binary_strings);
+        let snapshots = disable_snapshot_trace();
+
+        let before = &snapshots[0];
+        let after = &snapshots[1];
+
+        assert_eq!(
+            diff_cpu_state(before, after),
+            vec![
+                StateDiff::Register(VariableBitCPU::R2.to_string(), Some(0), Some(3)),
+                StateDiff::Register(VariableBitCPU::IAR.to_string(), Some(2), Some(4)),
+            ]
+        );
+
+        assert!(diff_cpu_state(after, after).is_empty());
+        assert_eq!(after.ram, CpuSnapshot::capture(&cpu).ram);
+    }
+
+    #[test]
+    fn find_unreachable_instructions_after_jump() {
+        let machine_code = vec![
+            "00100001", //0 Data R1
+            "00000101", //1 5
+            "01000000", //2 Jmp
+            "00000111", //3 7
+            "10000101", //4 Add R1 R1 (dead)
+            "00100010", //5 Data R2 (dead)
+            "00000011", //6 3 (dead)
+            "01010010", //7 JmpIf E
+            "00001010", //8 10
+            "01100000", //9 Clf
+            "11001111", //10 End
+            "10000101", //11 Add R1 R1 (dead)
+        ];
+
+        assert_eq!(find_unreachable_instructions(&machine_code), vec![4, 5, 6, 11]);
+
+        //A data byte that happens to look like a jump is not followed.
+        let machine_code = vec![
+            "00100001", //0 Data R1
+            "01000000", //1 64
+            "11001111", //2 End
+        ];
+
+        assert!(find_unreachable_instructions(&machine_code).is_empty());
+    }
+
+    #[test]
+    fn find_unreachable_instructions_with_extended_instructions() {
+        let machine_code = vec![
+            Instructions::EXTENDED_PREFIX, //0 Ext
+            "00100001", //1 Ldi R1
+            "01000000", //2 64
+            Instructions::EXTENDED_PREFIX, //3 Ext
+            "00000110", //4 Mov R1 R2
+            "11001111", //5 End
+            "10000101", //6 Add R1 R1 (dead)
+        ];
+
+        assert_eq!(find_unreachable_instructions(&machine_code), vec![6]);
+    }
+
+    #[test]
+    fn required_address_bits_for_referenced_address() {
+        let machine_code = vec![
+            "00100001", //Data R1
+            "11001000", //200
+            "00010110", //St R1 R2
+            "11001111", //End
+        ];
+
+        assert_eq!(required_address_bits(&machine_code), 8);
+
+        //Only the program itself, addresses 0-5 fit inside 4 bits.
+        let machine_code = vec![
+            "00100001", //Data R1
+            "00000101", //5
+            "01000000", //Jmp
+            "00000101", //5
+            "10000101", //Add R1 R1
+            "11001111", //End
+        ];
+
+        assert_eq!(required_address_bits(&machine_code), 4);
+    }
+
+    #[test]
+    fn required_address_bits_for_extended_load_immediate() {
+        let machine_code = vec![
+            Instructions::EXTENDED_PREFIX, //Ext
+            "00100001", //Ldi R1
+            "11001000", //200
+            "00010110", //St R1 R2
+            "11001111", //End
+        ];
+
+        assert_eq!(required_address_bits(&machine_code), 8);
+
+        //The extended instruction after the prefix is not decoded as a JMP from the base table.
+        let machine_code = vec![
+            Instructions::EXTENDED_PREFIX, //Ext
+            "01000000", //Nop
+            "11001111", //End
+        ];
+
+        assert_eq!(required_address_bits(&machine_code), 2);
+    }
+
+    #[test]
+    fn random_program_reaches_end() {
+        let isa = InstructionSet::default();
+        let program = generate_random_program(8, &isa, 7);
+
+        assert_eq!(program, generate_random_program(8, &isa, 7));
+
+        let machine_code: Vec<&str> = program.iter().map(|cell| cell.as_str()).collect();
+        let address_bits = required_address_bits(&machine_code);
+
+        let (_cpu, reached_end) = run_instructions_with_limit(
+            8,
+            address_bits / 2,
+            &machine_code,
+            10000,
+        );
+
+        assert!(reached_end, "{:#?}", machine_code);
+        //The program cannot finish inside of a single clock tick.
+        let (_cpu, reached_end) = run_instructions_with_limit(
+            8,
+            address_bits / 2,
+            &machine_code,
+            1,
+        );
+
+        assert!(!reached_end);
+    }
+
+    #[test]
+    fn halt_input_holds_the_clock() {
+        let decoder_input_size = 2;
+        let binary_strings = vec![
+            "00100001", //Data R1
+            "00000101", //5
+            "10000101", //Add R1 R1
+            "10000101", //Add R1 R1
+            "10000101", //Add R1 R1
+            "11001111", //End
+        ];
+
+        let ticks_before_halt = 30;
+        let ticks_held = 20;
+
+        let mut running_ticks = 0;
+        let mut halted_snapshot = None;
+        let mut held_states = Vec::new();
+
+        let cpu = CpuConfig::new().build();
+        run_instructions_on_cpu_with_observer(
+            &cpu,
+            decoder_input_size,
+            &binary_strings,
+            |cpu| {
+                if halt_asserted(cpu) {
+                    held_states.push((CpuSnapshot::capture(cpu), cpu.lock().unwrap().cycles_since_reset()));
+                    if held_states.len() == ticks_held {
+                        set_halt(cpu, LOW_);
+                    }
+                } else {
+                    running_ticks += 1;
+                    if running_ticks == ticks_before_halt {
+                        halted_snapshot = Some((CpuSnapshot::capture(cpu), cpu.lock().unwrap().cycles_since_reset()));
+                        set_halt(cpu, HIGH);
+                    }
+                }
+            },
+        );
+
+        //Nothing changed while HALT was held.
+        let halted_snapshot = halted_snapshot.unwrap();
+        assert_eq!(held_states.len(), ticks_held);
+        assert!(held_states.iter().all(|held_state| *held_state == halted_snapshot));
+
+        //The program was still in progress when HALT was asserted and finished once it was released.
+        assert!(running_ticks > ticks_before_halt);
+        let (halted_cpu_state, _) = &halted_snapshot;
+        let halted_r1 = halted_cpu_state.registers.iter().find(|(tag, _)| tag == VariableBitCPU::R1).unwrap();
+        assert_ne!(halted_r1.1, Some(40));
+        assert_eq!(read_register(&cpu, VariableBitCPU::R1), Some(40));
+    }
+
+    #[test]
+    fn builtin_selftest_passes() {
+        let config = CpuConfig::new().reset_vector(2);
+        let cpu = config.build();
+        let initial_signals = collect_signals_from_logic_gate(cpu.clone());
+
+        let report = run_builtin_selftest(&cpu);
+
+        assert_eq!(report.results.len(), 17);
+        assert!(report.all_passed(), "failed components {:?}", report.failed_components());
+
+        //The cpu is returned to its initial state with its run settings put back.
+        assert_eq!(collect_signals_from_logic_gate(cpu.clone()), initial_signals);
+        assert_eq!(cpu.lock().unwrap().reset_vector(), 2);
+        let binary_strings = vec!["00100000", "00000101", "00100000", "00000111", "11001111"]; //Data R0 5, Data R0 7, End
+        run_instructions_on_cpu(&cpu, config.decoder_input_size(), &binary_strings);
+        assert_eq!(read_register(&cpu, VariableBitCPU::R0), Some(7));
+    }
+}
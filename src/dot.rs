@@ -0,0 +1,142 @@
+use std::collections::{HashSet, VecDeque};
+use crate::logic::foundations::{GateOutputState, GateType, LogicGate};
+
+//The fill color of a node, grouped by what the gate does.
+pub fn color_for(gate_type: GateType) -> &'static str {
+    match gate_type {
+        GateType::SimpleInputType
+        | GateType::AutomaticInputType
+        | GateType::DynamicInputType
+        | GateType::ClockType => "green",
+        GateType::SimpleOutputType
+        | GateType::LatchedOutputType => "red",
+        GateType::NotType
+        | GateType::OrType
+        | GateType::AndType
+        | GateType::NorType
+        | GateType::NandType
+        | GateType::XOrType
+        | GateType::XnorType
+        | GateType::HalfAdderType
+        | GateType::FullAdderType
+        | GateType::VariableBitAdderType
+        | GateType::VariableBitShiftLeftType
+        | GateType::VariableBitNotType
+        | GateType::VariableBitAndType
+        | GateType::VariableBitOrType
+        | GateType::XOrLEType
+        | GateType::VariableBitXOrLEType
+        | GateType::VariableBitZType
+        | GateType::ArithmeticLogicUnitType
+        | GateType::BinaryToGrayType
+        | GateType::GrayToBinaryType
+        | GateType::SignMagnitudeToTwosComplementType
+        | GateType::MaskType
+        | GateType::PatternMatchType
+        | GateType::VariableBitSaturatingSubtractorType
+        | GateType::CountLeadingZerosType
+        | GateType::CascadableComparatorType
+        | GateType::VariableBitDividerType
+        | GateType::VariableBitMultiplierType => "blue",
+        GateType::ControlledBufferType
+        | GateType::SignalGatekeeperType
+        | GateType::BusKeeperType
+        | GateType::VariableCPUEnableType
+        | GateType::FourCycleClockHookupType
+        | GateType::VariableBitCounterType
+        | GateType::VariableBitMultiplexerType
+        | GateType::MultiplexerType
+        | GateType::DemultiplexerType
+        | GateType::LutType
+        | GateType::VariableDecoderType
+        | GateType::VariableBitEnableType
+        | GateType::VariableBitBusOneType
+        | GateType::VariableOutputStepperType
+        | GateType::ControlSectionType
+        | GateType::ClockDividerType
+        | GateType::RingCounterType
+        | GateType::ShiftRegisterType => "orange",
+        _ => "black",
+    }
+}
+
+//Returns the gates directly inside a complex gate as a Graphviz digraph. Each node is labeled with
+// its GateType and tag and each connection between two gates is a single edge.
+#[allow(dead_code)]
+pub fn export_dot(gate: &dyn LogicGate) -> String {
+    let mut nodes = Vec::new();
+    let mut edges = Vec::new();
+    let mut visited = HashSet::new();
+    let mut next_gates = VecDeque::from(gate.get_input_gates());
+
+    while let Some(gate_cell) = next_gates.pop_front() {
+        let mut child_gate = gate_cell.lock().unwrap();
+        let gate_id = child_gate.get_unique_id().id();
+        if !visited.insert(gate_id) {
+            continue;
+        }
+
+        let gate_type = child_gate.get_gate_type();
+        nodes.push(
+            format!(
+                "    g{} [label=\"{}\\n{}\", color={}];",
+                gate_id,
+                gate_type,
+                child_gate.get_tag(),
+                color_for(gate_type),
+            )
+        );
+
+        let gate_output = child_gate.fetch_output_signals_no_calculate().unwrap();
+        drop(child_gate);
+
+        for output in gate_output.into_iter() {
+            if let GateOutputState::Connected(next_gate_info) = output {
+                let next_gate_id = next_gate_info.gate.lock().unwrap().get_unique_id().id();
+                let edge = format!("    g{} -> g{};", gate_id, next_gate_id);
+                if !edges.contains(&edge) {
+                    edges.push(edge);
+                }
+                next_gates.push_back(next_gate_info.gate);
+            }
+        }
+    }
+
+    let mut dot = String::from("digraph circuit {\n");
+    for line in nodes.iter().chain(edges.iter()) {
+        dot.push_str(line);
+        dot.push('\n');
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::logic::complex_logic::PatternMatch;
+    use crate::logic::foundations::Signal::HIGH;
+    use super::*;
+
+    #[test]
+    fn export_dot_colors_nodes_by_type() {
+        let pattern_match = PatternMatch::new(&[HIGH, HIGH]);
+        let pattern_match = pattern_match.lock().unwrap();
+        let dot = export_dot(&*pattern_match);
+
+        assert!(dot.starts_with("digraph circuit {\n"), "{}", dot);
+
+        let line_for = |gate_type: GateType| {
+            dot.lines()
+                .find(|line| line.contains(&format!("label=\"{}\\n", gate_type)))
+                .unwrap_or_else(|| panic!("no {} node in {}", gate_type, dot))
+                .to_string()
+        };
+
+        assert!(line_for(GateType::AndType).contains("color=blue"));
+        assert!(line_for(GateType::SimpleInputType).contains("color=green"));
+        assert!(line_for(GateType::SimpleOutputType).contains("color=red"));
+
+        //Two inputs drive the And gate which drives the output.
+        assert_eq!(dot.lines().filter(|line| line.contains(" -> ")).count(), 3);
+    }
+}
@@ -1,4 +1,6 @@
+use std::cell::{Cell, RefCell};
 use std::sync::atomic::{AtomicBool, AtomicUsize};
+use crate::logic::foundations::GateLogicError;
 
 /// This will represent the current clock tick number. It should only ever by incremented directly
 /// after a clock tick occurs with only a single thread running.
@@ -23,4 +25,66 @@ pub(crate) static NEXT_UNIQUE_ID: AtomicUsize = AtomicUsize::new(1);
 pub(crate) static RUN_CIRCUIT_IS_HIGH_LEVEL: AtomicBool = AtomicBool::new(false);
 
 /// This is set to the output gate that ends the program.
-pub static END_OUTPUT_GATE_TAG: &'static str = "_END_OUTPUT_";
\ No newline at end of file
+pub static END_OUTPUT_GATE_TAG: &'static str = "_END_OUTPUT_";
+
+thread_local! {
+    /// When this is set, logic errors that would normally panic (oscillation and unresolvable
+    /// contention) are stored in PENDING_LOGIC_ERROR instead and run_circuit() stops early. It is
+    /// thread local so that it only affects the circuit running on the current thread.
+    static RETURN_LOGIC_ERRORS: Cell<bool> = const { Cell::new(false) };
+
+    /// The first logic error that occurred while RETURN_LOGIC_ERRORS was set.
+    static PENDING_LOGIC_ERROR: RefCell<Option<GateLogicError>> = const { RefCell::new(None) };
+
+    /// Every warning for a SimpleOutput that was calculated as NONE since
+    /// enable_none_output_warnings() was called. None means the warnings are disabled.
+    static NONE_OUTPUT_WARNINGS: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+}
+
+pub fn set_return_logic_errors(return_logic_errors: bool) {
+    RETURN_LOGIC_ERRORS.with(|flag| flag.set(return_logic_errors));
+}
+
+pub fn return_logic_errors() -> bool {
+    RETURN_LOGIC_ERRORS.with(|flag| flag.get())
+}
+
+/// Only the first error is kept, later errors are usually a side effect of the first one.
+pub fn store_logic_error(error: GateLogicError) {
+    PENDING_LOGIC_ERROR.with(|pending_error| {
+        let mut pending_error = pending_error.borrow_mut();
+        if pending_error.is_none() {
+            *pending_error = Some(error);
+        }
+    });
+}
+
+pub fn get_pending_logic_error() -> Option<GateLogicError> {
+    PENDING_LOGIC_ERROR.with(|pending_error| pending_error.borrow().clone())
+}
+
+pub fn take_pending_logic_error() -> Option<GateLogicError> {
+    PENDING_LOGIC_ERROR.with(|pending_error| pending_error.borrow_mut().take())
+}
+
+/// An output that is NONE is usually a gate that was never connected. While enabled, every
+/// SimpleOutput that calculates NONE prints a warning with its tag.
+#[allow(dead_code)]
+pub fn enable_none_output_warnings() {
+    NONE_OUTPUT_WARNINGS.with(|warnings| *warnings.borrow_mut() = Some(Vec::new()));
+}
+
+/// Returns every warning that was printed while the warnings were enabled.
+#[allow(dead_code)]
+pub fn disable_none_output_warnings() -> Vec<String> {
+    NONE_OUTPUT_WARNINGS.with(|warnings| warnings.borrow_mut().take().unwrap_or_default())
+}
+
+pub fn record_none_output_warning(warning: String) {
+    NONE_OUTPUT_WARNINGS.with(|warnings| {
+        if let Some(warnings) = warnings.borrow_mut().as_mut() {
+            println!("WARNING: {}", warning);
+            warnings.push(warning);
+        }
+    });
+}
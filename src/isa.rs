@@ -0,0 +1,133 @@
+use crate::logic::variable_bit_cpu::Instructions;
+
+//Describes the instructions understood by the assembler. Encodings are written with the most
+// significant bit first and lower case letters mark fields that are filled in by the operands.
+//  aa    reg_a
+//  bb    reg_b
+//  rr    reg
+//  caez  The carry, a_larger, equal and zero flags checked by JMPIF.
+//  t     The target of IN and OUT, 0 for DATA and 1 for ADDR.
+//Instructions that take more than one RAM cell have a cell per space separated entry. A cell named
+// value or address holds the full width operand.
+
+pub struct InstructionDescriptor {
+    pub mnemonic: &'static str,
+    pub encoding: Vec<&'static str>,
+    pub operands: Vec<&'static str>,
+    pub description: &'static str,
+}
+
+impl InstructionDescriptor {
+    fn new(
+        mnemonic: &'static str,
+        encoding: Vec<&'static str>,
+        operands: Vec<&'static str>,
+        description: &'static str,
+    ) -> Self {
+        InstructionDescriptor {
+            mnemonic,
+            encoding,
+            operands,
+            description,
+        }
+    }
+}
+
+pub struct InstructionSet {
+    pub instructions: Vec<InstructionDescriptor>,
+}
+
+impl Default for InstructionSet {
+    fn default() -> Self {
+        let ext = Instructions::EXTENDED_PREFIX;
+        let alu_operands = || vec!["reg_a", "reg_b"];
+
+        InstructionSet {
+            instructions: vec![
+                InstructionDescriptor::new("ADD", vec!["1000aabb"], alu_operands(), "Adds reg_a to reg_b and stores the result in reg_b."),
+                InstructionDescriptor::new("SHR", vec!["1001aabb"], alu_operands(), "Shifts reg_a right and stores the result in reg_b."),
+                InstructionDescriptor::new("SHL", vec!["1010aabb"], alu_operands(), "Shifts reg_a left and stores the result in reg_b."),
+                InstructionDescriptor::new("NOT", vec!["1011aabb"], alu_operands(), "Inverts reg_a and stores the result in reg_b."),
+                InstructionDescriptor::new("AND", vec!["1100aabb"], alu_operands(), "Ands reg_a with reg_b and stores the result in reg_b."),
+                InstructionDescriptor::new("OR", vec!["1101aabb"], alu_operands(), "Ors reg_a with reg_b and stores the result in reg_b."),
+                InstructionDescriptor::new("XOR", vec!["1110aabb"], alu_operands(), "XOrs reg_a with reg_b and stores the result in reg_b."),
+                InstructionDescriptor::new("CMP", vec!["1111aabb"], alu_operands(), "Compares reg_a with reg_b, only the flags are set."),
+                InstructionDescriptor::new("LD", vec!["0000aabb"], vec!["reg_a", "reg_b"], "Loads the RAM cell at the address inside reg_a into reg_b."),
+                InstructionDescriptor::new("ST", vec!["0001aabb"], vec!["reg_a", "reg_b"], "Stores reg_b into the RAM cell at the address inside reg_a."),
+                InstructionDescriptor::new("DATA", vec!["001000rr", "value"], vec!["reg", "value"], "Loads the next RAM cell into reg."),
+                InstructionDescriptor::new("JMPR", vec!["001100rr"], vec!["reg"], "Jumps to the address inside reg."),
+                InstructionDescriptor::new("JMP", vec!["01000000", "address"], vec!["address"], "Jumps to the address inside the next RAM cell."),
+                InstructionDescriptor::new("JMPIF", vec!["0101caez", "address"], vec!["address"], "Written as J followed by the flags, jumps to the address inside the next RAM cell if any of the flags are set."),
+                InstructionDescriptor::new("CLF", vec!["01100000"], vec![], "Clears the flags."),
+                InstructionDescriptor::new("IN", vec!["01110trr"], vec!["DATA|ADDR", "reg"], "Reads from the IO bus into reg."),
+                InstructionDescriptor::new("OUT", vec!["01111trr"], vec!["DATA|ADDR", "reg"], "Writes reg to the IO bus."),
+                InstructionDescriptor::new("END", vec!["11001111"], vec![], "Stops the clock."),
+                InstructionDescriptor::new("MOV", vec![ext, "0000aabb"], vec!["reg_a", "reg_b"], "Copies reg_a into reg_b."),
+                InstructionDescriptor::new("SWAP", vec![ext, "0001aabb"], vec!["reg_a", "reg_b"], "Exchanges reg_a and reg_b."),
+                InstructionDescriptor::new("LDI", vec![ext, "001000rr", "value"], vec!["reg", "value"], "Loads the value into reg."),
+                InstructionDescriptor::new("ADC", vec![ext, "1000aabb"], alu_operands(), "Adds reg_a and the carry flag to reg_b and stores the result in reg_b."),
+            ],
+        }
+    }
+}
+
+#[allow(dead_code)]
+//Returns a markdown table with one row per instruction.
+pub fn export_isa(isa: &InstructionSet) -> String {
+    let mut table = String::from("| Mnemonic | Encoding | Operands | Description |\n");
+    table.push_str("|---|---|---|---|\n");
+
+    for instruction in isa.instructions.iter() {
+        //The | inside of the operands would otherwise split the column.
+        let operands = instruction.operands.join(" ").replace('|', "\\|");
+
+        table.push_str(
+            format!(
+                "| {} | `{}` | {} | {} |\n",
+                instruction.mnemonic,
+                instruction.encoding.join(" "),
+                operands,
+                instruction.description,
+            ).as_str()
+        );
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export_default_isa() {
+        let table = export_isa(&InstructionSet::default());
+
+        assert!(table.starts_with("| Mnemonic | Encoding | Operands | Description |\n"));
+        assert!(table.contains("| ADD | `1000aabb` | reg_a reg_b |"));
+        assert!(table.contains("| JMP | `01000000 address` | address |"));
+        assert_eq!(table.lines().count(), InstructionSet::default().instructions.len() + 2);
+    }
+
+    #[test]
+    fn default_isa_matches_opcode_names() {
+        for instruction in InstructionSet::default().instructions {
+            //Extended instructions are decoded from the cell after the prefix.
+            if instruction.encoding[0] == Instructions::EXTENDED_PREFIX {
+                continue;
+            }
+
+            let opcode: String = instruction.encoding[0]
+                .chars()
+                .map(|c| if c == '1' { '1' } else { '0' })
+                .collect();
+
+            let expected_name = match instruction.mnemonic {
+                "IN" | "OUT" => "IO",
+                mnemonic => mnemonic,
+            };
+
+            assert_eq!(Instructions::opcode_name(&opcode), expected_name);
+        }
+    }
+}
@@ -7,4 +7,6 @@ pub mod complex_logic;
 pub mod processor_components;
 pub mod arithmetic_gates;
 pub mod control_section;
-pub mod variable_bit_cpu;
\ No newline at end of file
+pub mod variable_bit_cpu;
+pub mod io;
+pub mod netlist;
\ No newline at end of file
@@ -1,14 +1,18 @@
 use std::sync::MutexGuard;
 use std::time::Instant;
 use crate::ALU_TIME;
-use crate::logic::basic_gates::{And, ControlledBuffer, Not, Or, Splitter, XOr};
-use crate::logic::complex_logic::SignalGatekeeper;
+use crate::globals::get_pending_logic_error;
+use crate::logic::basic_gates::{And, ControlledBuffer, Nor, Not, Or, Splitter, XOr};
+use crate::logic::complex_logic::{SignalGatekeeper, VariableBitMultiplexer};
 
-use crate::logic::foundations::{GateInput, GateOutputState, LogicGate, UniqueID, GateLogicError, GateType, InputSignalReturn, Signal, ComplexGateMembers, build_simple_inputs_and_outputs, connect_gates};
+use crate::logic::foundations::{GateBuildError, GateInput, GateOutputState, LogicGate, UniqueID, GateLogicError, GateCounts, GateType, InputSignalReturn, Signal, ComplexGateMembers, build_simple_inputs_and_outputs, build_simple_inputs_and_outputs_with_and, connect_gates, GateTagType, impl_complex_logic_gate};
 use crate::logic::foundations::Signal::{HIGH, LOW_};
 use crate::logic::input_gates::SimpleInput;
+use crate::logic::memory_gates::VariableBitMemoryCell;
 use crate::logic::output_gates::{LogicGateAndOutputGate, SimpleOutput};
 use crate::logic::processor_components::{VariableBitRegister, VariableDecoder};
+use crate::logic::netlist::Netlist;
+use crate::run_circuit::run_circuit_with_pass_budget;
 use crate::shared_mutex::{LoggingMutexGuard, new_shared_mutex, SharedMutex, UsedMutex};
 
 pub struct HalfAdder {
@@ -109,76 +113,7 @@ impl HalfAdder {
     }
 }
 
-impl LogicGate for HalfAdder {
-    fn internal_connect_output(&mut self, current_gate_output_key: usize, next_gate_input_key: usize, next_gate: SharedMutex<dyn LogicGate>) -> Signal {
-        self.complex_gate.connect_output(
-            self.get_unique_id(),
-            current_gate_output_key,
-            next_gate_input_key,
-            next_gate,
-        )
-    }
-
-    fn internal_update_index_to_id(&mut self, sending_id: UniqueID, gate_input_index: usize, signal: Signal) {
-        self.complex_gate.internal_update_index_to_id(sending_id, gate_input_index, signal);
-    }
-
-    fn update_input_signal(&mut self, input: GateInput) -> InputSignalReturn {
-        self.complex_gate.update_input_signal(input)
-    }
-
-    fn fetch_output_signals_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
-        self.complex_gate.fetch_output_signals_calculate(
-            &self.get_tag(),
-        )
-    }
-
-    fn fetch_output_signals_no_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
-        self.complex_gate.fetch_output_signals_no_calculate(
-            &self.get_tag(),
-        )
-    }
-
-    fn get_gate_type(&self) -> GateType {
-        self.complex_gate.simple_gate.gate_type
-    }
-
-    fn get_unique_id(&self) -> UniqueID {
-        self.complex_gate.simple_gate.unique_id
-    }
-
-    fn toggle_output_printing(&mut self, print_output: bool) {
-        self.complex_gate.simple_gate.should_print_output = print_output;
-    }
-
-    fn get_tag(&self) -> String {
-        self.complex_gate.simple_gate.tag.clone()
-    }
-
-    fn set_tag(&mut self, tag: &str) {
-        self.complex_gate.simple_gate.tag = tag.to_string();
-    }
-
-    fn get_index_from_tag(&self, tag: &str) -> usize {
-        self.complex_gate.get_index_from_tag(tag)
-    }
-
-    fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
-        self.complex_gate.remove_connected_input(input_index, connected_id);
-    }
-
-    fn toggle_print_each_input_output_gate(&mut self, print_each_input_output_gate: bool) {
-        self.complex_gate.toggle_print_each_input_output_gate(print_each_input_output_gate);
-    }
-
-    fn num_children_gates(&self) -> usize {
-        self.complex_gate.simple_gate.number_child_gates
-    }
-
-    fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
-        self.complex_gate.input_gates.clone()
-    }
-}
+impl_complex_logic_gate!(HalfAdder);
 
 pub struct FullAdder {
     complex_gate: ComplexGateMembers,
@@ -358,6 +293,14 @@ impl LogicGate for FullAdder {
         self.complex_gate.get_index_from_tag(tag)
     }
 
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         self.complex_gate.remove_connected_input(input_index, connected_id);
     }
@@ -370,9 +313,29 @@ impl LogicGate for FullAdder {
         self.complex_gate.simple_gate.number_child_gates
     }
 
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         self.complex_gate.input_gates.clone()
     }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
 }
 
 pub struct VariableBitAdder {
@@ -382,8 +345,10 @@ pub struct VariableBitAdder {
 
 #[allow(dead_code)]
 impl VariableBitAdder {
-    pub fn new(num_bits: usize) -> SharedMutex<Self> {
-        assert_ne!(num_bits, 0);
+    pub fn new(num_bits: usize) -> Result<SharedMutex<Self>, GateBuildError> {
+        if num_bits == 0 {
+            return Err(GateBuildError::ZeroWidth);
+        }
 
 
         let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
@@ -438,7 +403,7 @@ impl VariableBitAdder {
         );
 
 
-        new_shared_mutex(variable_bit_adder.get_unique_id().id(), variable_bit_adder)
+        Ok(new_shared_mutex(variable_bit_adder.get_unique_id().id(), variable_bit_adder))
     }
 
     fn build_and_prime_circuit(
@@ -580,6 +545,14 @@ impl LogicGate for VariableBitAdder {
         self.complex_gate.get_index_from_tag(tag)
     }
 
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         self.complex_gate.remove_connected_input(input_index, connected_id);
     }
@@ -592,57 +565,97 @@ impl LogicGate for VariableBitAdder {
         self.complex_gate.simple_gate.number_child_gates
     }
 
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         self.complex_gate.input_gates.clone()
     }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
 }
 
-pub struct VariableBitShiftLeft<const LEFT_SHIFT: bool> {
+//Unsigned subtraction that clamps at zero, the output is max(0, a - b). The subtraction is done as
+// NOT(NOT(a) + b). The carry out of NOT(a) + b is HIGH exactly when b > a, so it is the borrow and
+// is used to force the result LOW. The borrow is also output on B_OUT.
+pub struct VariableBitSaturatingSubtractor {
     complex_gate: ComplexGateMembers,
-    first_register: SharedMutex<VariableBitRegister>,
-    second_register: SharedMutex<VariableBitRegister>,
+    a_not: SharedMutex<VariableBitNot>,
+    adder: SharedMutex<VariableBitAdder>,
+    sum_not: SharedMutex<VariableBitNot>,
+    borrow_splitter: SharedMutex<Splitter>,
+    borrow_not: SharedMutex<Not>,
+    clamp_enable: SharedMutex<VariableBitEnable>,
 }
 
 #[allow(dead_code)]
-impl<const LEFT_SHIFT: bool> VariableBitShiftLeft<LEFT_SHIFT> {
-    pub fn new(num_bits: usize) -> SharedMutex<Self> {
-        assert_ne!(num_bits, 0);
+impl VariableBitSaturatingSubtractor {
+    pub fn new(num_bits: usize) -> Result<SharedMutex<Self>, GateBuildError> {
+        if num_bits == 0 {
+            return Err(GateBuildError::ZeroWidth);
+        }
 
         let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
         let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
         let mut output_gates_logic: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
 
-        build_simple_inputs_and_outputs(
-            num_bits,
-            &mut input_gates,
-            &mut output_gates,
-            &mut output_gates_logic,
-        );
+        for i in 0..num_bits {
+            let a_input_tag = format!("a_{}", i);
+            input_gates.push(SimpleInput::new(1, a_input_tag.as_str()));
 
-        input_gates.push(SimpleInput::new(1, "S_IN"));
+            let output_tag = format!("o_{}", i);
+            let output_gate = SimpleOutput::new(output_tag.as_str());
+            output_gates.push(output_gate.clone());
+            output_gates_logic.push(output_gate);
+        }
 
-        let shift_output_gate = SimpleOutput::new("S_OUT");
-        output_gates.push(shift_output_gate.clone());
-        output_gates_logic.push(shift_output_gate);
+        //All a gates should go in before all b gates for consistency.
+        for i in 0..num_bits {
+            let b_input_tag = format!("b_{}", i);
+            input_gates.push(SimpleInput::new(1, b_input_tag.as_str()));
+        }
 
-        let mut variable_bit_shift_left = VariableBitShiftLeft {
+        let borrow_output_gate = SimpleOutput::new("B_OUT");
+        output_gates.push(borrow_output_gate.clone());
+        output_gates_logic.push(borrow_output_gate);
+
+        let mut saturating_subtractor = VariableBitSaturatingSubtractor {
             complex_gate: ComplexGateMembers::new(
+                num_bits * 2,
                 num_bits + 1,
-                num_bits + 1,
-                GateType::VariableBitShiftLeftType,
+                GateType::VariableBitSaturatingSubtractorType,
                 input_gates,
                 output_gates,
             ),
-            first_register: VariableBitRegister::new(num_bits),
-            second_register: VariableBitRegister::new(num_bits),
+            a_not: VariableBitNot::new(num_bits)?,
+            adder: VariableBitAdder::new(num_bits)?,
+            sum_not: VariableBitNot::new(num_bits)?,
+            borrow_splitter: Splitter::new(1, 2),
+            borrow_not: Not::new(1),
+            clamp_enable: VariableBitEnable::new_with_disabled_low(num_bits)?,
         };
 
-        variable_bit_shift_left.build_and_prime_circuit(
+        saturating_subtractor.build_and_prime_circuit(
             num_bits,
             output_gates_logic,
         );
 
-        new_shared_mutex(variable_bit_shift_left.get_unique_id().id(), variable_bit_shift_left)
+        Ok(new_shared_mutex(saturating_subtractor.get_unique_id().id(), saturating_subtractor))
     }
 
     fn build_and_prime_circuit(
@@ -650,86 +663,91 @@ impl<const LEFT_SHIFT: bool> VariableBitShiftLeft<LEFT_SHIFT> {
         num_bits: usize,
         output_gates: Vec<SharedMutex<dyn LogicGate>>,
     ) {
-        fn tie_register_bits_high(
-            mut register: MutexGuard<VariableBitRegister>
-        ) {
-            let set_index = register.get_index_from_tag("S");
-            let enable_index = register.get_index_from_tag("E");
+        for i in 0..num_bits {
+            let a_input_index = self.get_index_from_tag(format!("a_{}", i).as_str());
+            let b_input_index = self.get_index_from_tag(format!("b_{}", i).as_str());
+            let output_index = self.get_index_from_tag(format!("o_{}", i).as_str());
 
-            register.update_input_signal(
-                GateInput::new(
-                    set_index,
-                    HIGH,
-                    UniqueID::zero_id(),
-                )
-            );
+            let adder_a_index = self.adder.lock().unwrap().get_index_from_tag(format!("a_{}", i).as_str());
+            let adder_b_index = self.adder.lock().unwrap().get_index_from_tag(format!("b_{}", i).as_str());
+            let adder_output_index = self.adder.lock().unwrap().get_index_from_tag(format!("o_{}", i).as_str());
+            let enable_input_index = self.clamp_enable.lock().unwrap().get_index_from_tag(format!("i_{}", i).as_str());
 
-            register.update_input_signal(
-                GateInput::new(
-                    enable_index,
-                    HIGH,
-                    UniqueID::zero_id(),
-                )
+            connect_gates(
+                self.complex_gate.input_gates[a_input_index].clone(),
+                0,
+                self.a_not.clone(),
+                i,
             );
-        }
 
-        tie_register_bits_high(self.first_register.lock().unwrap());
-        tie_register_bits_high(self.second_register.lock().unwrap());
+            connect_gates(
+                self.a_not.clone(),
+                i,
+                self.adder.clone(),
+                adder_a_index,
+            );
 
-        for i in 0..num_bits {
             connect_gates(
-                self.complex_gate.input_gates[i].clone(),
+                self.complex_gate.input_gates[b_input_index].clone(),
                 0,
-                self.first_register.clone(),
-                i,
+                self.adder.clone(),
+                adder_b_index,
             );
 
-            if (LEFT_SHIFT && i == (num_bits - 1))
-                || (!LEFT_SHIFT && i == 0) {
-                let shift_out_index = self.get_index_from_tag("S_OUT");
-                connect_gates(
-                    self.first_register.clone(),
-                    i,
-                    output_gates[shift_out_index].clone(),
-                    0,
-                );
-            } else {
-                let second_index =
-                    if LEFT_SHIFT {
-                        i + 1
-                    } else {
-                        i - 1
-                    };
+            connect_gates(
+                self.adder.clone(),
+                adder_output_index,
+                self.sum_not.clone(),
+                i,
+            );
 
-                connect_gates(
-                    self.first_register.clone(),
-                    i,
-                    self.second_register.clone(),
-                    second_index,
-                );
-            }
+            connect_gates(
+                self.sum_not.clone(),
+                i,
+                self.clamp_enable.clone(),
+                enable_input_index,
+            );
 
             connect_gates(
-                self.second_register.clone(),
+                self.clamp_enable.clone(),
                 i,
-                output_gates[i].clone(),
+                output_gates[output_index].clone(),
                 0,
             );
         }
 
-        let shift_in_index = self.get_index_from_tag("S_IN");
-        let second_index =
-            if LEFT_SHIFT {
-                0
-            } else {
-                num_bits - 1
-            };
+        //C_IN of the adder is left unconnected so it stays LOW.
+        let adder_carry_out_index = self.adder.lock().unwrap().get_index_from_tag("C_OUT");
+        connect_gates(
+            self.adder.clone(),
+            adder_carry_out_index,
+            self.borrow_splitter.clone(),
+            0,
+        );
 
+        let borrow_output_index = self.get_index_from_tag("B_OUT");
+        let splitter_output_index = self.borrow_splitter.lock().unwrap().get_index_for_output(0, 0);
         connect_gates(
-            self.complex_gate.input_gates[shift_in_index].clone(),
+            self.borrow_splitter.clone(),
+            splitter_output_index,
+            output_gates[borrow_output_index].clone(),
             0,
-            self.second_register.clone(),
-            second_index,
+        );
+
+        let splitter_output_index = self.borrow_splitter.lock().unwrap().get_index_for_output(0, 1);
+        connect_gates(
+            self.borrow_splitter.clone(),
+            splitter_output_index,
+            self.borrow_not.clone(),
+            0,
+        );
+
+        let enable_index = self.clamp_enable.lock().unwrap().get_index_from_tag("E");
+        connect_gates(
+            self.borrow_not.clone(),
+            0,
+            self.clamp_enable.clone(),
+            enable_index,
         );
 
         //Prime gates
@@ -739,7 +757,7 @@ impl<const LEFT_SHIFT: bool> VariableBitShiftLeft<LEFT_SHIFT> {
     }
 }
 
-impl<const LEFT_SHIFT: bool> LogicGate for VariableBitShiftLeft<LEFT_SHIFT> {
+impl LogicGate for VariableBitSaturatingSubtractor {
     fn internal_connect_output(&mut self, current_gate_output_key: usize, next_gate_input_key: usize, next_gate: SharedMutex<dyn LogicGate>) -> Signal {
         self.complex_gate.connect_output(
             self.get_unique_id(),
@@ -793,6 +811,14 @@ impl<const LEFT_SHIFT: bool> LogicGate for VariableBitShiftLeft<LEFT_SHIFT> {
         self.complex_gate.get_index_from_tag(tag)
     }
 
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         self.complex_gate.remove_connected_input(input_index, connected_id);
     }
@@ -805,58 +831,608 @@ impl<const LEFT_SHIFT: bool> LogicGate for VariableBitShiftLeft<LEFT_SHIFT> {
         self.complex_gate.simple_gate.number_child_gates
     }
 
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         self.complex_gate.input_gates.clone()
     }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
 }
 
-pub struct VariableBitNot {
+//Unsigned restoring division where a is the dividend and b is the divisor. There is one stage for
+// each dividend bit starting with the most significant. A stage shifts its dividend bit into the
+// bottom of the partial remainder and subtracts b from it. If the subtraction does not borrow, the
+// quotient bit is HIGH and the difference becomes the new partial remainder. Otherwise the shifted
+// partial remainder is kept (restored). The quotient is output on q_i and the remainder on r_i.
+// DIV_BY_ZERO is HIGH when every bit of b is LOW. Nothing is ever subtracted in that case so the
+// quotient is all HIGH and the remainder is a.
+pub struct VariableBitDivider {
     complex_gate: ComplexGateMembers,
-    not_gates: Vec<SharedMutex<Not>>,
+    subtractors: Vec<SharedMutex<VariableBitSaturatingSubtractor>>,
+    quotient_not_gates: Vec<SharedMutex<Not>>,
+    remainder_multiplexers: Vec<SharedMutex<VariableBitMultiplexer>>,
+    remainder_splitters: Vec<SharedMutex<Splitter>>,
+    zero_divisor_nor: SharedMutex<Nor>,
 }
 
 #[allow(dead_code)]
-impl VariableBitNot {
-    pub fn new(num_bits: usize) -> SharedMutex<Self> {
-        assert_ne!(num_bits, 0);
+impl VariableBitDivider {
+    pub fn new(num_bits: usize) -> Result<SharedMutex<Self>, GateBuildError> {
+        if num_bits == 0 {
+            return Err(GateBuildError::ZeroWidth);
+        }
 
         let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
         let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
         let mut output_gates_logic: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
 
-        let mut not_gates = Vec::new();
+        let mut subtractors = Vec::new();
+        let mut quotient_not_gates = Vec::new();
+        let mut remainder_multiplexers = Vec::new();
+        let mut remainder_splitters = Vec::new();
 
+        //Each dividend bit goes to the subtractor and the multiplexer of its stage.
         for i in 0..num_bits {
-            let input_tag = format!("i_{}", i);
-            input_gates.push(SimpleInput::new(1, input_tag.as_str()));
+            let a_input_tag = format!("a_{}", i);
+            input_gates.push(SimpleInput::new(2, a_input_tag.as_str()));
+        }
 
+        //Each divisor bit goes to every stage and to the zero check.
+        for i in 0..num_bits {
+            let b_input_tag = format!("b_{}", i);
+            input_gates.push(SimpleInput::new(num_bits + 1, b_input_tag.as_str()));
+        }
+
+        let mut store_output = |gate: SharedMutex<SimpleOutput>| {
+            output_gates.push(gate.clone());
+            output_gates_logic.push(gate);
+        };
+
+        for i in 0..num_bits {
+            store_output(SimpleOutput::new(format!("q_{}", i).as_str()));
+        }
+
+        for i in 0..num_bits {
+            store_output(SimpleOutput::new(format!("r_{}", i).as_str()));
+        }
+
+        store_output(SimpleOutput::new("DIV_BY_ZERO"));
+
+        //The shifted partial remainder can be one bit wider than b, so the subtractors are too.
+        for i in 0..num_bits {
+            subtractors.push(VariableBitSaturatingSubtractor::new(num_bits + 1)?);
+            quotient_not_gates.push(Not::new(2));
+            remainder_multiplexers.push(VariableBitMultiplexer::new(num_bits, 2));
+
+            //The final stage drives the remainder outputs directly.
+            if i != num_bits - 1 {
+                remainder_splitters.push(Splitter::new(num_bits, 2));
+            }
+        }
+
+        let mut divider = VariableBitDivider {
+            complex_gate: ComplexGateMembers::new(
+                num_bits * 2,
+                num_bits * 2 + 1,
+                GateType::VariableBitDividerType,
+                input_gates,
+                output_gates,
+            ),
+            subtractors,
+            quotient_not_gates,
+            remainder_multiplexers,
+            remainder_splitters,
+            zero_divisor_nor: Nor::new(num_bits, 1),
+        };
+
+        divider.build_and_prime_circuit(
+            num_bits,
+            output_gates_logic,
+        );
+
+        Ok(new_shared_mutex(divider.get_unique_id().id(), divider))
+    }
+
+    fn build_and_prime_circuit(
+        &mut self,
+        num_bits: usize,
+        output_gates: Vec<SharedMutex<dyn LogicGate>>,
+    ) {
+        for stage in 0..num_bits {
+            let dividend_bit = num_bits - 1 - stage;
+            let subtractor = self.subtractors[stage].clone();
+            let quotient_not = self.quotient_not_gates[stage].clone();
+            let multiplexer = self.remainder_multiplexers[stage].clone();
+
+            //The dividend bit is the least significant bit of the shifted partial remainder.
+            let a_input_index = self.get_index_from_tag(format!("a_{}", dividend_bit).as_str());
+            let subtractor_a_index = subtractor.lock().unwrap().get_index_from_tag("a_0");
+            let multiplexer_input_index = multiplexer.lock().unwrap().get_index_from_tag("I_0_bit_0");
+            connect_gates(
+                self.complex_gate.input_gates[a_input_index].clone(),
+                0,
+                subtractor.clone(),
+                subtractor_a_index,
+            );
+
+            connect_gates(
+                self.complex_gate.input_gates[a_input_index].clone(),
+                1,
+                multiplexer.clone(),
+                multiplexer_input_index,
+            );
+
+            //The partial remainder of the previous stage is shifted up by one. The first stage has
+            // no previous remainder so these inputs are left unconnected and stay LOW.
+            if stage > 0 {
+                let splitter = self.remainder_splitters[stage - 1].clone();
+                for i in 0..num_bits {
+                    let subtractor_a_index = subtractor.lock().unwrap().get_index_from_tag(format!("a_{}", i + 1).as_str());
+                    let splitter_output_index = splitter.lock().unwrap().get_index_for_output(i, 0);
+                    connect_gates(
+                        splitter.clone(),
+                        splitter_output_index,
+                        subtractor.clone(),
+                        subtractor_a_index,
+                    );
+
+                    //When the subtraction borrows, the shifted remainder is less than b so its
+                    // top bit is always LOW and does not need to be kept.
+                    if i + 1 < num_bits {
+                        let multiplexer_input_index = multiplexer.lock().unwrap().get_index_from_tag(format!("I_0_bit_{}", i + 1).as_str());
+                        let splitter_output_index = splitter.lock().unwrap().get_index_for_output(i, 1);
+                        connect_gates(
+                            splitter.clone(),
+                            splitter_output_index,
+                            multiplexer.clone(),
+                            multiplexer_input_index,
+                        );
+                    }
+                }
+            }
+
+            //The most significant bit of b on the subtractor is left unconnected so it stays LOW.
+            for i in 0..num_bits {
+                let b_input_index = self.get_index_from_tag(format!("b_{}", i).as_str());
+                let subtractor_b_index = subtractor.lock().unwrap().get_index_from_tag(format!("b_{}", i).as_str());
+                connect_gates(
+                    self.complex_gate.input_gates[b_input_index].clone(),
+                    stage,
+                    subtractor.clone(),
+                    subtractor_b_index,
+                );
+            }
+
+            let borrow_output_index = subtractor.lock().unwrap().get_index_from_tag("B_OUT");
+            connect_gates(
+                subtractor.clone(),
+                borrow_output_index,
+                quotient_not.clone(),
+                0,
+            );
+
+            let quotient_output_index = self.get_index_from_tag(format!("q_{}", dividend_bit).as_str());
+            connect_gates(
+                quotient_not.clone(),
+                0,
+                output_gates[quotient_output_index].clone(),
+                0,
+            );
+
+            //A HIGH quotient bit selects the difference, otherwise the shifted remainder is kept.
+            let control_index = multiplexer.lock().unwrap().get_index_from_tag("C_0");
+            connect_gates(
+                quotient_not.clone(),
+                1,
+                multiplexer.clone(),
+                control_index,
+            );
+
+            for i in 0..num_bits {
+                let subtractor_output_index = subtractor.lock().unwrap().get_index_from_tag(format!("o_{}", i).as_str());
+                let multiplexer_input_index = multiplexer.lock().unwrap().get_index_from_tag(format!("I_1_bit_{}", i).as_str());
+                connect_gates(
+                    subtractor.clone(),
+                    subtractor_output_index,
+                    multiplexer.clone(),
+                    multiplexer_input_index,
+                );
+
+                let multiplexer_output_index = multiplexer.lock().unwrap().get_index_from_tag(format!("o_{}", i).as_str());
+                if stage == num_bits - 1 {
+                    let remainder_output_index = self.get_index_from_tag(format!("r_{}", i).as_str());
+                    connect_gates(
+                        multiplexer.clone(),
+                        multiplexer_output_index,
+                        output_gates[remainder_output_index].clone(),
+                        0,
+                    );
+                } else {
+                    connect_gates(
+                        multiplexer.clone(),
+                        multiplexer_output_index,
+                        self.remainder_splitters[stage].clone(),
+                        i,
+                    );
+                }
+            }
+        }
+
+        for i in 0..num_bits {
+            let b_input_index = self.get_index_from_tag(format!("b_{}", i).as_str());
+            connect_gates(
+                self.complex_gate.input_gates[b_input_index].clone(),
+                num_bits,
+                self.zero_divisor_nor.clone(),
+                i,
+            );
+        }
+
+        let div_by_zero_index = self.get_index_from_tag("DIV_BY_ZERO");
+        connect_gates(
+            self.zero_divisor_nor.clone(),
+            0,
+            output_gates[div_by_zero_index].clone(),
+            0,
+        );
+
+        //Prime gates
+        self.complex_gate.calculate_output_from_inputs_and_set_child_count(
+            true,
+        );
+    }
+}
+
+impl_complex_logic_gate!(VariableBitDivider);
+
+//Unsigned shift-add multiplication, the full 2 * num_bits wide product of a and b is output on
+// o_0 (least significant) upwards. There is one stage for each bit of b. Stage j shifts a left j
+// times, lets it through when b_j is HIGH and adds it to the sum of the previous stages. Every bus
+// inside is 2 * num_bits wide so the sum can never carry out.
+pub struct VariableBitMultiplier {
+    complex_gate: ComplexGateMembers,
+    shifters: Vec<SharedMutex<VariableBitShiftLeft<true>>>,
+    shift_splitters: Vec<SharedMutex<Splitter>>,
+    partial_product_enables: Vec<SharedMutex<VariableBitEnable>>,
+    adders: Vec<SharedMutex<VariableBitAdder>>,
+}
+
+#[allow(dead_code)]
+impl VariableBitMultiplier {
+    pub fn new(num_bits: usize) -> Result<SharedMutex<Self>, GateBuildError> {
+        if num_bits == 0 {
+            return Err(GateBuildError::ZeroWidth);
+        }
+
+        let product_bits = num_bits * 2;
+
+        let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+        let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
+        let mut output_gates_logic: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+
+        //Each bit of a goes to the first partial product and the first shifter.
+        for i in 0..num_bits {
+            let a_input_tag = format!("a_{}", i);
+            input_gates.push(SimpleInput::new(2, a_input_tag.as_str()));
+        }
+
+        for i in 0..num_bits {
+            let b_input_tag = format!("b_{}", i);
+            input_gates.push(SimpleInput::new(1, b_input_tag.as_str()));
+        }
+
+        for i in 0..product_bits {
             let output_tag = format!("o_{}", i);
             let output_gate = SimpleOutput::new(output_tag.as_str());
             output_gates.push(output_gate.clone());
             output_gates_logic.push(output_gate);
+        }
 
-            not_gates.push(
-                Not::new(1)
+        let mut shifters = Vec::new();
+        let mut shift_splitters = Vec::new();
+        let mut partial_product_enables = Vec::new();
+        let mut adders = Vec::new();
+
+        for stage in 0..num_bits {
+            //A disabled partial product must be LOW to be added.
+            partial_product_enables.push(VariableBitEnable::new_with_disabled_low(product_bits)?);
+
+            //The first stage uses a unshifted and the first partial product is the starting sum.
+            if stage > 0 {
+                shifters.push(VariableBitShiftLeft::<true>::new(product_bits)?);
+                adders.push(VariableBitAdder::new(product_bits)?);
+            }
+
+            //The final shifter only drives the final partial product.
+            if stage > 0 && stage < num_bits - 1 {
+                shift_splitters.push(Splitter::new(product_bits, 2));
+            }
+        }
+
+        let mut multiplier = VariableBitMultiplier {
+            complex_gate: ComplexGateMembers::new(
+                num_bits * 2,
+                product_bits,
+                GateType::VariableBitMultiplierType,
+                input_gates,
+                output_gates,
+            ),
+            shifters,
+            shift_splitters,
+            partial_product_enables,
+            adders,
+        };
+
+        multiplier.build_and_prime_circuit(
+            num_bits,
+            output_gates_logic,
+        );
+
+        Ok(new_shared_mutex(multiplier.get_unique_id().id(), multiplier))
+    }
+
+    fn build_and_prime_circuit(
+        &mut self,
+        num_bits: usize,
+        output_gates: Vec<SharedMutex<dyn LogicGate>>,
+    ) {
+        let product_bits = num_bits * 2;
+
+        //a -> First partial product & First shifter
+        // The upper half of both is left unconnected so it stays LOW.
+        for i in 0..num_bits {
+            let a_input_index = self.get_index_from_tag(format!("a_{}", i).as_str());
+            let input_tag = format!("i_{}", i);
+
+            let enable_input_index = self.partial_product_enables[0].lock().unwrap().get_index_from_tag(input_tag.as_str());
+            connect_gates(
+                self.complex_gate.input_gates[a_input_index].clone(),
+                0,
+                self.partial_product_enables[0].clone(),
+                enable_input_index,
             );
+
+            if let Some(shifter) = self.shifters.first() {
+                let shifter_input_index = shifter.lock().unwrap().get_index_from_tag(input_tag.as_str());
+                connect_gates(
+                    self.complex_gate.input_gates[a_input_index].clone(),
+                    1,
+                    shifter.clone(),
+                    shifter_input_index,
+                );
+            }
         }
 
-        let mut variable_bit_not = VariableBitNot {
+        for stage in 0..num_bits {
+            let enable = self.partial_product_enables[stage].clone();
+
+            //b_j -> Partial product enable
+            let b_input_index = self.get_index_from_tag(format!("b_{}", stage).as_str());
+            let enable_index = enable.lock().unwrap().get_index_from_tag("E");
+            connect_gates(
+                self.complex_gate.input_gates[b_input_index].clone(),
+                0,
+                enable.clone(),
+                enable_index,
+            );
+
+            if stage == 0 {
+                continue;
+            }
+
+            //The shift in bit is left unconnected so it stays LOW. Nothing is ever shifted out
+            // because a only fills the lower half of the bus.
+            let shifter = self.shifters[stage - 1].clone();
+            let adder = self.adders[stage - 1].clone();
+
+            for i in 0..product_bits {
+                let input_tag = format!("i_{}", i);
+                let output_tag = format!("o_{}", i);
+
+                //Shifter -> Splitter -> Partial product enable & Next shifter
+                let shifter_output_index = shifter.lock().unwrap().get_index_from_tag(output_tag.as_str());
+                let enable_input_index = enable.lock().unwrap().get_index_from_tag(input_tag.as_str());
+                if stage < num_bits - 1 {
+                    let splitter = self.shift_splitters[stage - 1].clone();
+                    connect_gates(
+                        shifter.clone(),
+                        shifter_output_index,
+                        splitter.clone(),
+                        i,
+                    );
+
+                    let splitter_output_index = splitter.lock().unwrap().get_index_for_output(i, 0);
+                    connect_gates(
+                        splitter.clone(),
+                        splitter_output_index,
+                        enable.clone(),
+                        enable_input_index,
+                    );
+
+                    let next_shifter = self.shifters[stage].clone();
+                    let next_shifter_input_index = next_shifter.lock().unwrap().get_index_from_tag(input_tag.as_str());
+                    let splitter_output_index = splitter.lock().unwrap().get_index_for_output(i, 1);
+                    connect_gates(
+                        splitter.clone(),
+                        splitter_output_index,
+                        next_shifter.clone(),
+                        next_shifter_input_index,
+                    );
+                } else {
+                    connect_gates(
+                        shifter.clone(),
+                        shifter_output_index,
+                        enable.clone(),
+                        enable_input_index,
+                    );
+                }
+
+                //Previous sum -> Adder a & Partial product enable -> Adder b
+                let adder_a_input_index = adder.lock().unwrap().get_index_from_tag(format!("a_{}", i).as_str());
+                if stage == 1 {
+                    let first_enable_output_index = self.partial_product_enables[0].lock().unwrap().get_index_from_tag(output_tag.as_str());
+                    connect_gates(
+                        self.partial_product_enables[0].clone(),
+                        first_enable_output_index,
+                        adder.clone(),
+                        adder_a_input_index,
+                    );
+                } else {
+                    let previous_adder = self.adders[stage - 2].clone();
+                    let previous_adder_output_index = previous_adder.lock().unwrap().get_index_from_tag(output_tag.as_str());
+                    connect_gates(
+                        previous_adder.clone(),
+                        previous_adder_output_index,
+                        adder.clone(),
+                        adder_a_input_index,
+                    );
+                }
+
+                let enable_output_index = enable.lock().unwrap().get_index_from_tag(output_tag.as_str());
+                let adder_b_input_index = adder.lock().unwrap().get_index_from_tag(format!("b_{}", i).as_str());
+                connect_gates(
+                    enable.clone(),
+                    enable_output_index,
+                    adder.clone(),
+                    adder_b_input_index,
+                );
+            }
+        }
+
+        //Final sum -> Output
+        // With a single bit there are no adders and the only partial product is the product.
+        for i in 0..product_bits {
+            let output_tag = format!("o_{}", i);
+            let product_output_index = self.get_index_from_tag(output_tag.as_str());
+
+            match self.adders.last() {
+                Some(adder) => {
+                    let adder_output_index = adder.lock().unwrap().get_index_from_tag(output_tag.as_str());
+                    connect_gates(
+                        adder.clone(),
+                        adder_output_index,
+                        output_gates[product_output_index].clone(),
+                        0,
+                    );
+                }
+                None => {
+                    let enable_output_index = self.partial_product_enables[0].lock().unwrap().get_index_from_tag(output_tag.as_str());
+                    connect_gates(
+                        self.partial_product_enables[0].clone(),
+                        enable_output_index,
+                        output_gates[product_output_index].clone(),
+                        0,
+                    );
+                }
+            }
+        }
+
+        //Prime gates
+        self.complex_gate.calculate_output_from_inputs_and_set_child_count(
+            true,
+        );
+    }
+}
+
+impl_complex_logic_gate!(VariableBitMultiplier);
+
+//Counts the number of zero bits above the most significant HIGH bit. The count is output as a binary
+// number on o_0 (least significant) upwards and ALL_ZERO is HIGH when no input bit is set, in which
+// case the count equals num_bits.
+pub struct CountLeadingZeros {
+    complex_gate: ComplexGateMembers,
+    input_not_gates: Vec<SharedMutex<Not>>,
+    //first_one_and_gates[k] is HIGH when bit k is the most significant HIGH bit.
+    first_one_and_gates: Vec<SharedMutex<And>>,
+    all_zero_and_gate: SharedMutex<And>,
+    count_or_gates: Vec<SharedMutex<Or>>,
+}
+
+#[allow(dead_code)]
+impl CountLeadingZeros {
+    pub const ALL_ZERO: &'static str = "ALL_ZERO";
+
+    pub fn new(num_bits: usize) -> Result<SharedMutex<Self>, GateBuildError> {
+        if num_bits == 0 {
+            return Err(GateBuildError::ZeroWidth);
+        }
+
+        let count_bits = CountLeadingZeros::count_bits(num_bits);
+
+        let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+        let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
+        let mut output_gates_logic: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+
+        let mut input_not_gates = Vec::new();
+        let mut first_one_and_gates = Vec::new();
+        for i in 0..num_bits {
+            let input_tag = format!("i_{}", i);
+            input_gates.push(SimpleInput::new(2, input_tag.as_str()));
+
+            //The inverted bit blocks every lower bit from being the first one as well as going to
+            // the all zero gate.
+            input_not_gates.push(Not::new(i + 1));
+            first_one_and_gates.push(And::new(num_bits - i, count_bits));
+        }
+
+        let mut count_or_gates = Vec::new();
+        for i in 0..count_bits {
+            let output_tag = format!("o_{}", i);
+            let output_gate = SimpleOutput::new(output_tag.as_str());
+            output_gates.push(output_gate.clone());
+            output_gates_logic.push(output_gate);
+
+            //Every possible count (0 to num_bits) with this bit set drives the or gate.
+            let num_inputs = (0..=num_bits).filter(|count| (count >> i) & 1 == 1).count();
+            count_or_gates.push(Or::new(num_inputs, 1));
+        }
+
+        let all_zero_output_gate = SimpleOutput::new(CountLeadingZeros::ALL_ZERO);
+        output_gates.push(all_zero_output_gate.clone());
+        output_gates_logic.push(all_zero_output_gate);
+
+        let mut count_leading_zeros = CountLeadingZeros {
             complex_gate: ComplexGateMembers::new(
                 num_bits,
-                num_bits,
-                GateType::VariableBitNotType,
+                count_bits + 1,
+                GateType::CountLeadingZerosType,
                 input_gates,
                 output_gates,
             ),
-            not_gates,
+            input_not_gates,
+            first_one_and_gates,
+            all_zero_and_gate: And::new(num_bits, count_bits + 1),
+            count_or_gates,
         };
 
-        variable_bit_not.build_and_prime_circuit(
+        count_leading_zeros.build_and_prime_circuit(
             num_bits,
             output_gates_logic,
         );
 
-        new_shared_mutex(variable_bit_not.get_unique_id().id(), variable_bit_not)
+        Ok(new_shared_mutex(count_leading_zeros.get_unique_id().id(), count_leading_zeros))
+    }
+
+    //The number of output bits needed to hold a count from 0 to num_bits.
+    pub fn count_bits(num_bits: usize) -> usize {
+        (usize::BITS - num_bits.leading_zeros()) as usize
     }
 
     fn build_and_prime_circuit(
@@ -864,22 +1440,81 @@ impl VariableBitNot {
         num_bits: usize,
         output_gates: Vec<SharedMutex<dyn LogicGate>>,
     ) {
-        for i in 0..num_bits {
+        let count_bits = self.count_or_gates.len();
+        let mut next_or_input = vec![0; count_bits];
+
+        for k in 0..num_bits {
             connect_gates(
-                self.complex_gate.input_gates[i].clone(),
+                self.complex_gate.input_gates[k].clone(),
                 0,
-                self.not_gates[i].clone(),
+                self.first_one_and_gates[k].clone(),
                 0,
             );
 
             connect_gates(
-                self.not_gates[i].clone(),
+                self.complex_gate.input_gates[k].clone(),
+                1,
+                self.input_not_gates[k].clone(),
                 0,
-                output_gates[i].clone(),
+            );
+
+            //Each more significant bit must be LOW for bit k to be the first one.
+            for m in 0..k {
+                connect_gates(
+                    self.input_not_gates[k].clone(),
+                    m,
+                    self.first_one_and_gates[m].clone(),
+                    k - m,
+                );
+            }
+
+            connect_gates(
+                self.input_not_gates[k].clone(),
+                k,
+                self.all_zero_and_gate.clone(),
+                k,
+            );
+
+            let leading_zeros = num_bits - 1 - k;
+            for (j, next_input) in next_or_input.iter_mut().enumerate() {
+                if (leading_zeros >> j) & 1 == 1 {
+                    connect_gates(
+                        self.first_one_and_gates[k].clone(),
+                        j,
+                        self.count_or_gates[j].clone(),
+                        *next_input,
+                    );
+                    *next_input += 1;
+                }
+            }
+        }
+
+        for (j, next_input) in next_or_input.iter_mut().enumerate() {
+            if (num_bits >> j) & 1 == 1 {
+                connect_gates(
+                    self.all_zero_and_gate.clone(),
+                    j,
+                    self.count_or_gates[j].clone(),
+                    *next_input,
+                );
+            }
+
+            connect_gates(
+                self.count_or_gates[j].clone(),
+                0,
+                output_gates[j].clone(),
                 0,
             );
         }
 
+        let all_zero_index = self.get_index_from_tag(CountLeadingZeros::ALL_ZERO);
+        connect_gates(
+            self.all_zero_and_gate.clone(),
+            count_bits,
+            output_gates[all_zero_index].clone(),
+            0,
+        );
+
         //Prime gates
         self.complex_gate.calculate_output_from_inputs_and_set_child_count(
             true,
@@ -887,7 +1522,7 @@ impl VariableBitNot {
     }
 }
 
-impl LogicGate for VariableBitNot {
+impl LogicGate for CountLeadingZeros {
     fn internal_connect_output(&mut self, current_gate_output_key: usize, next_gate_input_key: usize, next_gate: SharedMutex<dyn LogicGate>) -> Signal {
         self.complex_gate.connect_output(
             self.get_unique_id(),
@@ -941,6 +1576,14 @@ impl LogicGate for VariableBitNot {
         self.complex_gate.get_index_from_tag(tag)
     }
 
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         self.complex_gate.remove_connected_input(input_index, connected_id);
     }
@@ -953,63 +1596,79 @@ impl LogicGate for VariableBitNot {
         self.complex_gate.simple_gate.number_child_gates
     }
 
-    fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
+    fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         self.complex_gate.input_gates.clone()
     }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
 }
 
-pub struct VariableBitAnd {
+pub struct VariableBitShiftLeft<const LEFT_SHIFT: bool> {
     complex_gate: ComplexGateMembers,
-    and_gates: Vec<SharedMutex<And>>,
+    first_register: SharedMutex<VariableBitRegister>,
+    second_register: SharedMutex<VariableBitRegister>,
 }
 
 #[allow(dead_code)]
-impl VariableBitAnd {
-    pub fn new(num_bits: usize) -> SharedMutex<Self> {
-        assert_ne!(num_bits, 0);
+impl<const LEFT_SHIFT: bool> VariableBitShiftLeft<LEFT_SHIFT> {
+    pub fn new(num_bits: usize) -> Result<SharedMutex<Self>, GateBuildError> {
+        if num_bits == 0 {
+            return Err(GateBuildError::ZeroWidth);
+        }
 
         let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
         let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
         let mut output_gates_logic: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
 
-        let mut and_gates = Vec::new();
-
-        for i in 0..num_bits {
-            let a_input_tag = format!("a_{}", i);
-            input_gates.push(SimpleInput::new(1, a_input_tag.as_str()));
-
-            let output_tag = format!("o_{}", i);
-            let output_gate = SimpleOutput::new(output_tag.as_str());
-            output_gates.push(output_gate.clone());
-            output_gates_logic.push(output_gate);
+        build_simple_inputs_and_outputs(
+            num_bits,
+            &mut input_gates,
+            &mut output_gates,
+            &mut output_gates_logic,
+        );
 
-            and_gates.push(
-                And::new(2, 1)
-            );
-        }
+        input_gates.push(SimpleInput::new(1, "S_IN"));
 
-        for i in 0..num_bits {
-            let b_input_tag = format!("b_{}", i);
-            input_gates.push(SimpleInput::new(1, b_input_tag.as_str()));
-        }
+        let shift_output_gate = SimpleOutput::new("S_OUT");
+        output_gates.push(shift_output_gate.clone());
+        output_gates_logic.push(shift_output_gate);
 
-        let mut variable_bit_and = VariableBitAnd {
+        let mut variable_bit_shift_left = VariableBitShiftLeft {
             complex_gate: ComplexGateMembers::new(
-                2 * num_bits,
-                num_bits,
-                GateType::VariableBitAndType,
+                num_bits + 1,
+                num_bits + 1,
+                GateType::VariableBitShiftLeftType,
                 input_gates,
                 output_gates,
             ),
-            and_gates,
+            first_register: VariableBitRegister::new(num_bits),
+            second_register: VariableBitRegister::new(num_bits),
         };
 
-        variable_bit_and.build_and_prime_circuit(
+        variable_bit_shift_left.build_and_prime_circuit(
             num_bits,
             output_gates_logic,
         );
 
-        new_shared_mutex(variable_bit_and.get_unique_id().id(), variable_bit_and)
+        Ok(new_shared_mutex(variable_bit_shift_left.get_unique_id().id(), variable_bit_shift_left))
     }
 
     fn build_and_prime_circuit(
@@ -1017,35 +1676,88 @@ impl VariableBitAnd {
         num_bits: usize,
         output_gates: Vec<SharedMutex<dyn LogicGate>>,
     ) {
-        for i in 0..num_bits {
-            let a_input_tag = format!("a_{}", i);
-            let b_input_tag = format!("b_{}", i);
+        fn tie_register_bits_high(
+            mut register: MutexGuard<VariableBitRegister>
+        ) {
+            let set_index = register.get_index_from_tag("S");
+            let enable_index = register.get_index_from_tag("E");
 
-            let a_input_index = self.get_index_from_tag(a_input_tag.as_str());
-            let b_input_index = self.get_index_from_tag(b_input_tag.as_str());
+            register.update_input_signal(
+                GateInput::new(
+                    set_index,
+                    HIGH,
+                    UniqueID::zero_id(),
+                )
+            );
 
-            connect_gates(
-                self.complex_gate.input_gates[a_input_index].clone(),
-                0,
-                self.and_gates[i].clone(),
-                0,
+            register.update_input_signal(
+                GateInput::new(
+                    enable_index,
+                    HIGH,
+                    UniqueID::zero_id(),
+                )
             );
+        }
+
+        tie_register_bits_high(self.first_register.lock().unwrap());
+        tie_register_bits_high(self.second_register.lock().unwrap());
 
+        for i in 0..num_bits {
             connect_gates(
-                self.complex_gate.input_gates[b_input_index].clone(),
+                self.complex_gate.input_gates[i].clone(),
                 0,
-                self.and_gates[i].clone(),
-                1,
+                self.first_register.clone(),
+                i,
             );
 
+            if (LEFT_SHIFT && i == (num_bits - 1))
+                || (!LEFT_SHIFT && i == 0) {
+                let shift_out_index = self.get_index_from_tag("S_OUT");
+                connect_gates(
+                    self.first_register.clone(),
+                    i,
+                    output_gates[shift_out_index].clone(),
+                    0,
+                );
+            } else {
+                let second_index =
+                    if LEFT_SHIFT {
+                        i + 1
+                    } else {
+                        i - 1
+                    };
+
+                connect_gates(
+                    self.first_register.clone(),
+                    i,
+                    self.second_register.clone(),
+                    second_index,
+                );
+            }
+
             connect_gates(
-                self.and_gates[i].clone(),
-                0,
+                self.second_register.clone(),
+                i,
                 output_gates[i].clone(),
                 0,
             );
         }
 
+        let shift_in_index = self.get_index_from_tag("S_IN");
+        let second_index =
+            if LEFT_SHIFT {
+                0
+            } else {
+                num_bits - 1
+            };
+
+        connect_gates(
+            self.complex_gate.input_gates[shift_in_index].clone(),
+            0,
+            self.second_register.clone(),
+            second_index,
+        );
+
         //Prime gates
         self.complex_gate.calculate_output_from_inputs_and_set_child_count(
             true,
@@ -1053,7 +1765,7 @@ impl VariableBitAnd {
     }
 }
 
-impl LogicGate for VariableBitAnd {
+impl<const LEFT_SHIFT: bool> LogicGate for VariableBitShiftLeft<LEFT_SHIFT> {
     fn internal_connect_output(&mut self, current_gate_output_key: usize, next_gate_input_key: usize, next_gate: SharedMutex<dyn LogicGate>) -> Signal {
         self.complex_gate.connect_output(
             self.get_unique_id(),
@@ -1107,6 +1819,14 @@ impl LogicGate for VariableBitAnd {
         self.complex_gate.get_index_from_tag(tag)
     }
 
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         self.complex_gate.remove_connected_input(input_index, connected_id);
     }
@@ -1119,63 +1839,80 @@ impl LogicGate for VariableBitAnd {
         self.complex_gate.simple_gate.number_child_gates
     }
 
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         self.complex_gate.input_gates.clone()
     }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
 }
 
-pub struct VariableBitOr {
+pub struct VariableBitNot {
     complex_gate: ComplexGateMembers,
-    or_gates: Vec<SharedMutex<Or>>,
+    not_gates: Vec<SharedMutex<Not>>,
 }
 
 #[allow(dead_code)]
-impl VariableBitOr {
-    pub fn new(num_bits: usize) -> SharedMutex<Self> {
-        assert_ne!(num_bits, 0);
+impl VariableBitNot {
+    pub fn new(num_bits: usize) -> Result<SharedMutex<Self>, GateBuildError> {
+        if num_bits == 0 {
+            return Err(GateBuildError::ZeroWidth);
+        }
 
         let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
         let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
         let mut output_gates_logic: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
 
-        let mut or_gates = Vec::new();
+        let mut not_gates = Vec::new();
 
         for i in 0..num_bits {
-            let a_input_tag = format!("a_{}", i);
-            input_gates.push(SimpleInput::new(1, a_input_tag.as_str()));
+            let input_tag = format!("i_{}", i);
+            input_gates.push(SimpleInput::new(1, input_tag.as_str()));
 
             let output_tag = format!("o_{}", i);
             let output_gate = SimpleOutput::new(output_tag.as_str());
             output_gates.push(output_gate.clone());
             output_gates_logic.push(output_gate);
 
-            or_gates.push(
-                Or::new(2, 1)
+            not_gates.push(
+                Not::new(1)
             );
         }
 
-        for i in 0..num_bits {
-            let b_input_tag = format!("b_{}", i);
-            input_gates.push(SimpleInput::new(1, b_input_tag.as_str()));
-        }
-
-        let mut variable_bit_and = VariableBitOr {
+        let mut variable_bit_not = VariableBitNot {
             complex_gate: ComplexGateMembers::new(
-                2 * num_bits,
                 num_bits,
-                GateType::VariableBitOrType,
+                num_bits,
+                GateType::VariableBitNotType,
                 input_gates,
                 output_gates,
             ),
-            or_gates,
+            not_gates,
         };
 
-        variable_bit_and.build_and_prime_circuit(
+        variable_bit_not.build_and_prime_circuit(
             num_bits,
             output_gates_logic,
         );
 
-        new_shared_mutex(variable_bit_and.get_unique_id().id(), variable_bit_and)
+        Ok(new_shared_mutex(variable_bit_not.get_unique_id().id(), variable_bit_not))
     }
 
     fn build_and_prime_circuit(
@@ -1184,28 +1921,15 @@ impl VariableBitOr {
         output_gates: Vec<SharedMutex<dyn LogicGate>>,
     ) {
         for i in 0..num_bits {
-            let a_input_tag = format!("a_{}", i);
-            let b_input_tag = format!("b_{}", i);
-
-            let a_input_index = self.get_index_from_tag(a_input_tag.as_str());
-            let b_input_index = self.get_index_from_tag(b_input_tag.as_str());
-
             connect_gates(
-                self.complex_gate.input_gates[a_input_index].clone(),
-                0,
-                self.or_gates[i].clone(),
+                self.complex_gate.input_gates[i].clone(),
                 0,
-            );
-
-            connect_gates(
-                self.complex_gate.input_gates[b_input_index].clone(),
+                self.not_gates[i].clone(),
                 0,
-                self.or_gates[i].clone(),
-                1,
             );
 
             connect_gates(
-                self.or_gates[i].clone(),
+                self.not_gates[i].clone(),
                 0,
                 output_gates[i].clone(),
                 0,
@@ -1219,7 +1943,7 @@ impl VariableBitOr {
     }
 }
 
-impl LogicGate for VariableBitOr {
+impl LogicGate for VariableBitNot {
     fn internal_connect_output(&mut self, current_gate_output_key: usize, next_gate_input_key: usize, next_gate: SharedMutex<dyn LogicGate>) -> Signal {
         self.complex_gate.connect_output(
             self.get_unique_id(),
@@ -1273,6 +1997,14 @@ impl LogicGate for VariableBitOr {
         self.complex_gate.get_index_from_tag(tag)
     }
 
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         self.complex_gate.remove_connected_input(input_index, connected_id);
     }
@@ -1285,168 +2017,120 @@ impl LogicGate for VariableBitOr {
         self.complex_gate.simple_gate.number_child_gates
     }
 
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         self.complex_gate.input_gates.clone()
     }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
 }
 
-pub struct XOrLE {
+pub struct VariableBitAnd {
     complex_gate: ComplexGateMembers,
-    xor_gate: SharedMutex<XOr>,
-    not_gate: SharedMutex<Not>,
-    equal_and_gate: SharedMutex<And>,
-    middle_and_gate: SharedMutex<And>,
-    or_gate: SharedMutex<Or>,
+    and_gates: Vec<SharedMutex<And>>,
 }
 
 #[allow(dead_code)]
-impl XOrLE {
-    pub fn new() -> SharedMutex<Self> {
+impl VariableBitAnd {
+    pub fn new(num_bits: usize) -> Result<SharedMutex<Self>, GateBuildError> {
+        if num_bits == 0 {
+            return Err(GateBuildError::ZeroWidth);
+        }
+
         let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
         let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
         let mut output_gates_logic: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
 
-        input_gates.push(SimpleInput::new(2, "A"));
-        input_gates.push(SimpleInput::new(1, "B"));
-        input_gates.push(SimpleInput::new(2, "ABOVE_E"));
-        input_gates.push(SimpleInput::new(1, "ABOVE_L"));
+        let mut and_gates = Vec::new();
 
-        let c_output_gate = SimpleOutput::new("C");
-        let equal_output_gate = SimpleOutput::new("E");
-        let a_larger_output_gate = SimpleOutput::new("A_L");
+        for i in 0..num_bits {
+            let a_input_tag = format!("a_{}", i);
+            input_gates.push(SimpleInput::new(1, a_input_tag.as_str()));
 
-        output_gates.push(c_output_gate.clone());
-        output_gates.push(equal_output_gate.clone());
-        output_gates.push(a_larger_output_gate.clone());
-        output_gates_logic.push(c_output_gate);
-        output_gates_logic.push(equal_output_gate);
-        output_gates_logic.push(a_larger_output_gate);
+            let output_tag = format!("o_{}", i);
+            let output_gate = SimpleOutput::new(output_tag.as_str());
+            output_gates.push(output_gate.clone());
+            output_gates_logic.push(output_gate);
 
-        let mut variable_bit_and = XOrLE {
+            and_gates.push(
+                And::new(2, 1)
+            );
+        }
+
+        for i in 0..num_bits {
+            let b_input_tag = format!("b_{}", i);
+            input_gates.push(SimpleInput::new(1, b_input_tag.as_str()));
+        }
+
+        let mut variable_bit_and = VariableBitAnd {
             complex_gate: ComplexGateMembers::new(
-                4,
-                3,
-                GateType::XOrLEType,
+                2 * num_bits,
+                num_bits,
+                GateType::VariableBitAndType,
                 input_gates,
                 output_gates,
             ),
-            xor_gate: XOr::new(2, 3),
-            not_gate: Not::new(1),
-            equal_and_gate: And::new(2, 1),
-            middle_and_gate: And::new(3, 1),
-            or_gate: Or::new(2, 1),
+            and_gates,
         };
 
         variable_bit_and.build_and_prime_circuit(
+            num_bits,
             output_gates_logic,
         );
 
-        new_shared_mutex(variable_bit_and.get_unique_id().id(), variable_bit_and)
+        Ok(new_shared_mutex(variable_bit_and.get_unique_id().id(), variable_bit_and))
     }
 
     fn build_and_prime_circuit(
         &mut self,
+        num_bits: usize,
         output_gates: Vec<SharedMutex<dyn LogicGate>>,
     ) {
-        let a_input_gate = self.complex_gate.input_gates[self.get_index_from_tag("A")].clone();
-        let b_input_gate = self.complex_gate.input_gates[self.get_index_from_tag("B")].clone();
-        let equal_input_gate = self.complex_gate.input_gates[self.get_index_from_tag("ABOVE_E")].clone();
-        let larger_input_gate = self.complex_gate.input_gates[self.get_index_from_tag("ABOVE_L")].clone();
-
-        let c_output_gate = output_gates[self.get_index_from_tag("C")].clone();
-        let equal_output_gate = output_gates[self.get_index_from_tag("E")].clone();
-        let a_larger_output_gate = output_gates[self.get_index_from_tag("A_L")].clone();
-
-        connect_gates(
-            a_input_gate.clone(),
-            0,
-            self.xor_gate.clone(),
-            0,
-        );
-
-        connect_gates(
-            a_input_gate.clone(),
-            1,
-            self.middle_and_gate.clone(),
-            1,
-        );
-
-        connect_gates(
-            b_input_gate.clone(),
-            0,
-            self.xor_gate.clone(),
-            1,
-        );
-
-        connect_gates(
-            self.xor_gate.clone(),
-            0,
-            self.not_gate.clone(),
-            0,
-        );
-
-        connect_gates(
-            self.xor_gate.clone(),
-            1,
-            self.middle_and_gate.clone(),
-            2,
-        );
-
-        connect_gates(
-            self.xor_gate.clone(),
-            2,
-            c_output_gate.clone(),
-            2,
-        );
-
-        connect_gates(
-            self.not_gate.clone(),
-            0,
-            self.equal_and_gate.clone(),
-            0,
-        );
-
-        connect_gates(
-            self.equal_and_gate.clone(),
-            0,
-            equal_output_gate.clone(),
-            0,
-        );
-
-        connect_gates(
-            equal_input_gate.clone(),
-            0,
-            self.middle_and_gate.clone(),
-            0,
-        );
+        for i in 0..num_bits {
+            let a_input_tag = format!("a_{}", i);
+            let b_input_tag = format!("b_{}", i);
 
-        connect_gates(
-            equal_input_gate.clone(),
-            1,
-            self.equal_and_gate.clone(),
-            1,
-        );
+            let a_input_index = self.get_index_from_tag(a_input_tag.as_str());
+            let b_input_index = self.get_index_from_tag(b_input_tag.as_str());
 
-        connect_gates(
-            self.middle_and_gate.clone(),
-            0,
-            self.or_gate.clone(),
-            0,
-        );
+            connect_gates(
+                self.complex_gate.input_gates[a_input_index].clone(),
+                0,
+                self.and_gates[i].clone(),
+                0,
+            );
 
-        connect_gates(
-            larger_input_gate.clone(),
-            0,
-            self.or_gate.clone(),
-            1,
-        );
+            connect_gates(
+                self.complex_gate.input_gates[b_input_index].clone(),
+                0,
+                self.and_gates[i].clone(),
+                1,
+            );
 
-        connect_gates(
-            self.or_gate.clone(),
-            0,
-            a_larger_output_gate.clone(),
-            0,
-        );
+            connect_gates(
+                self.and_gates[i].clone(),
+                0,
+                output_gates[i].clone(),
+                0,
+            );
+        }
 
         //Prime gates
         self.complex_gate.calculate_output_from_inputs_and_set_child_count(
@@ -1455,7 +2139,7 @@ impl XOrLE {
     }
 }
 
-impl LogicGate for XOrLE {
+impl LogicGate for VariableBitAnd {
     fn internal_connect_output(&mut self, current_gate_output_key: usize, next_gate_input_key: usize, next_gate: SharedMutex<dyn LogicGate>) -> Signal {
         self.complex_gate.connect_output(
             self.get_unique_id(),
@@ -1509,6 +2193,14 @@ impl LogicGate for XOrLE {
         self.complex_gate.get_index_from_tag(tag)
     }
 
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         self.complex_gate.remove_connected_input(input_index, connected_id);
     }
@@ -1521,180 +2213,148 @@ impl LogicGate for XOrLE {
         self.complex_gate.simple_gate.number_child_gates
     }
 
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         self.complex_gate.input_gates.clone()
     }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
 }
 
-pub struct VariableBitXOrLE {
+//ANDs the data on i_n with a mask that is latched inside a register. The mask is loaded from the
+// mask_n inputs while S is HIGH and is then reused for every value passed in.
+pub struct MaskGate {
     complex_gate: ComplexGateMembers,
-    xor_le_gates: Vec<SharedMutex<XOrLE>>,
+    mask_register: SharedMutex<VariableBitMemoryCell>,
+    and: SharedMutex<VariableBitAnd>,
 }
 
 #[allow(dead_code)]
-impl VariableBitXOrLE {
-    pub fn new(num_bits: usize) -> SharedMutex<Self> {
-        assert_ne!(num_bits, 0);
+impl MaskGate {
+    pub const MASK: &'static str = "mask";
+
+    pub fn new(num_bits: usize) -> Result<SharedMutex<Self>, GateBuildError> {
+        if num_bits == 0 {
+            return Err(GateBuildError::ZeroWidth);
+        }
 
         let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
         let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
         let mut output_gates_logic: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
 
-        let mut xor_le_gates = Vec::new();
-
         for i in 0..num_bits {
-            let a_input_tag = format!("a_{}", i);
-            input_gates.push(SimpleInput::new(1, a_input_tag.as_str()));
+            let input_tag = format!("i_{}", i);
+            input_gates.push(SimpleInput::new(1, input_tag.as_str()));
 
             let output_tag = format!("o_{}", i);
             let output_gate = SimpleOutput::new(output_tag.as_str());
             output_gates.push(output_gate.clone());
             output_gates_logic.push(output_gate);
-
-            xor_le_gates.push(
-                XOrLE::new()
-            );
         }
 
         for i in 0..num_bits {
-            let b_input_tag = format!("b_{}", i);
-            input_gates.push(SimpleInput::new(1, b_input_tag.as_str()));
+            let mask_input_tag = format!("{}_{}", MaskGate::MASK, i);
+            input_gates.push(SimpleInput::new(1, mask_input_tag.as_str()));
         }
 
-        let larger_output_gate = SimpleOutput::new("L");
-        let equal_output_gate = SimpleOutput::new("E");
-        // larger_output_gate.lock().unwrap().toggle_output_printing(true);
-        // equal_output_gate.lock().unwrap().toggle_output_printing(true);
-        output_gates.push(larger_output_gate.clone());
-        output_gates.push(equal_output_gate.clone());
-        output_gates_logic.push(larger_output_gate);
-        output_gates_logic.push(equal_output_gate);
+        input_gates.push(SimpleInput::new(1, "S"));
 
-        let mut variable_bit_and = VariableBitXOrLE {
+        let mut mask_gate = MaskGate {
             complex_gate: ComplexGateMembers::new(
-                2 * num_bits,
-                num_bits + 2,
-                GateType::VariableBitXOrLEType,
+                2 * num_bits + 1,
+                num_bits,
+                GateType::MaskType,
                 input_gates,
                 output_gates,
             ),
-            xor_le_gates,
+            mask_register: VariableBitMemoryCell::new(num_bits),
+            and: VariableBitAnd::new(num_bits)?,
         };
 
-        variable_bit_and.build_and_prime_circuit(
-            num_bits,
-            output_gates_logic,
-        );
+        mask_gate.mask_register.lock().unwrap().set_tag("mask_register");
+        mask_gate.and.lock().unwrap().set_tag("and");
 
-        new_shared_mutex(variable_bit_and.get_unique_id().id(), variable_bit_and)
+        mask_gate.build_and_prime_circuit(output_gates_logic);
+
+        Ok(new_shared_mutex(mask_gate.get_unique_id().id(), mask_gate))
     }
 
     fn build_and_prime_circuit(
         &mut self,
-        num_bits: usize,
         output_gates: Vec<SharedMutex<dyn LogicGate>>,
     ) {
-        for i in 0..num_bits {
-            let xor_a_input_index = self.xor_le_gates[i].lock().unwrap().get_index_from_tag("A");
-            let xor_b_input_index = self.xor_le_gates[i].lock().unwrap().get_index_from_tag("B");
-
-            let c_output_index = self.xor_le_gates[i].lock().unwrap().get_index_from_tag("C");
+        let set_input_index = self.get_index_from_tag("S");
+        let mask_register_set_index = self.mask_register.lock().unwrap().get_index_from_tag("S");
+        connect_gates(
+            self.complex_gate.input_gates[set_input_index].clone(),
+            0,
+            self.mask_register.clone(),
+            mask_register_set_index,
+        );
 
-            let a_input_tag = format!("a_{}", i);
-            let b_input_tag = format!("b_{}", i);
+        for (i, output_gate) in output_gates.iter().enumerate() {
+            let input_index = self.get_index_from_tag(format!("i_{}", i).as_str());
+            let mask_input_index = self.get_index_from_tag(format!("{}_{}", MaskGate::MASK, i).as_str());
 
-            let a_input_index = self.get_index_from_tag(a_input_tag.as_str());
-            let b_input_index = self.get_index_from_tag(b_input_tag.as_str());
+            let and_a_index = self.and.lock().unwrap().get_index_from_tag(format!("a_{}", i).as_str());
+            let and_b_index = self.and.lock().unwrap().get_index_from_tag(format!("b_{}", i).as_str());
+            let mask_register_input_index = self.mask_register.lock().unwrap().get_index_from_tag(format!("i_{}", i).as_str());
+            let mask_register_output_index = self.mask_register.lock().unwrap().get_index_from_tag(format!("o_{}", i).as_str());
 
             connect_gates(
-                self.complex_gate.input_gates[a_input_index].clone(),
+                self.complex_gate.input_gates[input_index].clone(),
                 0,
-                self.xor_le_gates[i].clone(),
-                xor_a_input_index,
+                self.and.clone(),
+                and_a_index,
             );
 
             connect_gates(
-                self.complex_gate.input_gates[b_input_index].clone(),
+                self.complex_gate.input_gates[mask_input_index].clone(),
                 0,
-                self.xor_le_gates[i].clone(),
-                xor_b_input_index,
+                self.mask_register.clone(),
+                mask_register_input_index,
             );
 
             connect_gates(
-                self.xor_le_gates[i].clone(),
-                c_output_index,
-                output_gates[i].clone(),
-                0,
+                self.mask_register.clone(),
+                mask_register_output_index,
+                self.and.clone(),
+                and_b_index,
             );
 
-            if i == num_bits - 1 {
-                let xor_equal_input_index = self.xor_le_gates[i].lock().unwrap().get_index_from_tag("ABOVE_E");
-                let xor_larger_input_index = self.xor_le_gates[i].lock().unwrap().get_index_from_tag("ABOVE_L");
+            connect_gates(
+                self.and.clone(),
+                i,
+                output_gate.clone(),
+                0,
+            );
+        }
 
-                self.xor_le_gates[i].lock().unwrap().update_input_signal(
-                    GateInput::new(
-                        xor_equal_input_index,
-                        HIGH,
-                        UniqueID::zero_id(),
-                    )
-                );
+        //Prime gates
+        self.complex_gate.calculate_output_from_inputs_and_set_child_count(
+            true,
+        );
+    }
+}
 
-                self.xor_le_gates[i].lock().unwrap().update_input_signal(
-                    GateInput::new(
-                        xor_larger_input_index,
-                        LOW_,
-                        UniqueID::zero_id(),
-                    )
-                );
-            } else {
-                let equal_output_index = self.xor_le_gates[i + 1].lock().unwrap().get_index_from_tag("E");
-                let a_larger_output_index = self.xor_le_gates[i + 1].lock().unwrap().get_index_from_tag("A_L");
-                let xor_equal_input_index = self.xor_le_gates[i].lock().unwrap().get_index_from_tag("ABOVE_E");
-                let xor_larger_input_index = self.xor_le_gates[i].lock().unwrap().get_index_from_tag("ABOVE_L");
-
-                connect_gates(
-                    self.xor_le_gates[i + 1].clone(),
-                    equal_output_index,
-                    self.xor_le_gates[i].clone(),
-                    xor_equal_input_index,
-                );
-
-                connect_gates(
-                    self.xor_le_gates[i + 1].clone(),
-                    a_larger_output_index,
-                    self.xor_le_gates[i].clone(),
-                    xor_larger_input_index,
-                );
-            }
-        }
-
-        let xor_equal_output_index = self.xor_le_gates[0].lock().unwrap().get_index_from_tag("E");
-        let xor_a_larger_output_index = self.xor_le_gates[0].lock().unwrap().get_index_from_tag("A_L");
-        let equal_output_index = self.get_index_from_tag("E");
-        let larger_output_index = self.get_index_from_tag("L");
-
-        connect_gates(
-            self.xor_le_gates[0].clone(),
-            xor_equal_output_index,
-            output_gates[equal_output_index].clone(),
-            0,
-        );
-
-        connect_gates(
-            self.xor_le_gates[0].clone(),
-            xor_a_larger_output_index,
-            output_gates[larger_output_index].clone(),
-            0,
-        );
-
-        //Prime gates
-        self.complex_gate.calculate_output_from_inputs_and_set_child_count(
-            true,
-        );
-    }
-}
-
-impl LogicGate for VariableBitXOrLE {
+impl LogicGate for MaskGate {
     fn internal_connect_output(&mut self, current_gate_output_key: usize, next_gate_input_key: usize, next_gate: SharedMutex<dyn LogicGate>) -> Signal {
         self.complex_gate.connect_output(
             self.get_unique_id(),
@@ -1748,6 +2408,14 @@ impl LogicGate for VariableBitXOrLE {
         self.complex_gate.get_index_from_tag(tag)
     }
 
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         self.complex_gate.remove_connected_input(input_index, connected_id);
     }
@@ -1760,53 +2428,85 @@ impl LogicGate for VariableBitXOrLE {
         self.complex_gate.simple_gate.number_child_gates
     }
 
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         self.complex_gate.input_gates.clone()
     }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
 }
 
-pub struct VariableBitZ {
+pub struct VariableBitOr {
     complex_gate: ComplexGateMembers,
-    or_gate: SharedMutex<Or>,
-    not_gate: SharedMutex<Not>,
+    or_gates: Vec<SharedMutex<Or>>,
 }
 
 #[allow(dead_code)]
-impl VariableBitZ {
-    pub fn new(num_bits: usize) -> SharedMutex<Self> {
-        assert_ne!(num_bits, 0);
+impl VariableBitOr {
+    pub fn new(num_bits: usize) -> Result<SharedMutex<Self>, GateBuildError> {
+        if num_bits == 0 {
+            return Err(GateBuildError::ZeroWidth);
+        }
 
         let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
         let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
         let mut output_gates_logic: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
 
+        let mut or_gates = Vec::new();
+
         for i in 0..num_bits {
-            let input_tag = format!("i_{}", i);
-            input_gates.push(SimpleInput::new(1, input_tag.as_str()));
+            let a_input_tag = format!("a_{}", i);
+            input_gates.push(SimpleInput::new(1, a_input_tag.as_str()));
+
+            let output_tag = format!("o_{}", i);
+            let output_gate = SimpleOutput::new(output_tag.as_str());
+            output_gates.push(output_gate.clone());
+            output_gates_logic.push(output_gate);
+
+            or_gates.push(
+                Or::new(2, 1)
+            );
         }
 
-        let output_gate = SimpleOutput::new("O");
-        output_gates.push(output_gate.clone());
-        output_gates_logic.push(output_gate);
+        for i in 0..num_bits {
+            let b_input_tag = format!("b_{}", i);
+            input_gates.push(SimpleInput::new(1, b_input_tag.as_str()));
+        }
 
-        let mut variable_bit_z = VariableBitZ {
+        let mut variable_bit_and = VariableBitOr {
             complex_gate: ComplexGateMembers::new(
+                2 * num_bits,
                 num_bits,
-                1,
-                GateType::VariableBitZType,
+                GateType::VariableBitOrType,
                 input_gates,
                 output_gates,
             ),
-            or_gate: Or::new(num_bits, 1),
-            not_gate: Not::new(1),
+            or_gates,
         };
 
-        variable_bit_z.build_and_prime_circuit(
+        variable_bit_and.build_and_prime_circuit(
             num_bits,
             output_gates_logic,
         );
 
-        new_shared_mutex(variable_bit_z.get_unique_id().id(), variable_bit_z)
+        Ok(new_shared_mutex(variable_bit_and.get_unique_id().id(), variable_bit_and))
     }
 
     fn build_and_prime_circuit(
@@ -1815,27 +2515,33 @@ impl VariableBitZ {
         output_gates: Vec<SharedMutex<dyn LogicGate>>,
     ) {
         for i in 0..num_bits {
+            let a_input_tag = format!("a_{}", i);
+            let b_input_tag = format!("b_{}", i);
+
+            let a_input_index = self.get_index_from_tag(a_input_tag.as_str());
+            let b_input_index = self.get_index_from_tag(b_input_tag.as_str());
+
             connect_gates(
-                self.complex_gate.input_gates[i].clone(),
+                self.complex_gate.input_gates[a_input_index].clone(),
+                0,
+                self.or_gates[i].clone(),
                 0,
-                self.or_gate.clone(),
-                i,
             );
-        }
 
-        connect_gates(
-            self.or_gate.clone(),
-            0,
-            self.not_gate.clone(),
-            0,
-        );
+            connect_gates(
+                self.complex_gate.input_gates[b_input_index].clone(),
+                0,
+                self.or_gates[i].clone(),
+                1,
+            );
 
-        connect_gates(
-            self.not_gate.clone(),
-            0,
-            output_gates[0].clone(),
-            0,
-        );
+            connect_gates(
+                self.or_gates[i].clone(),
+                0,
+                output_gates[i].clone(),
+                0,
+            );
+        }
 
         //Prime gates
         self.complex_gate.calculate_output_from_inputs_and_set_child_count(
@@ -1844,7 +2550,7 @@ impl VariableBitZ {
     }
 }
 
-impl LogicGate for VariableBitZ {
+impl LogicGate for VariableBitOr {
     fn internal_connect_output(&mut self, current_gate_output_key: usize, next_gate_input_key: usize, next_gate: SharedMutex<dyn LogicGate>) -> Signal {
         self.complex_gate.connect_output(
             self.get_unique_id(),
@@ -1898,6 +2604,14 @@ impl LogicGate for VariableBitZ {
         self.complex_gate.get_index_from_tag(tag)
     }
 
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         self.complex_gate.remove_connected_input(input_index, connected_id);
     }
@@ -1910,92 +2624,197 @@ impl LogicGate for VariableBitZ {
         self.complex_gate.simple_gate.number_child_gates
     }
 
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         self.complex_gate.input_gates.clone()
     }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
 }
 
-pub struct VariableBitEnable {
+pub struct XOrLE {
     complex_gate: ComplexGateMembers,
-    control_buffer: SharedMutex<ControlledBuffer>,
+    xor_gate: SharedMutex<XOr>,
+    not_gate: SharedMutex<Not>,
+    equal_and_gate: SharedMutex<And>,
+    middle_and_gate: SharedMutex<And>,
+    or_gate: SharedMutex<Or>,
 }
 
 #[allow(dead_code)]
-impl VariableBitEnable {
-    pub fn new(num_bits: usize) -> SharedMutex<Self> {
-        assert_ne!(num_bits, 0);
-
+impl XOrLE {
+    pub fn new() -> SharedMutex<Self> {
         let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
         let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
         let mut output_gates_logic: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
 
-        build_simple_inputs_and_outputs(
-            num_bits,
-            &mut input_gates,
-            &mut output_gates,
-            &mut output_gates_logic,
-        );
+        input_gates.push(SimpleInput::new(2, "A"));
+        input_gates.push(SimpleInput::new(1, "B"));
+        input_gates.push(SimpleInput::new(2, "ABOVE_E"));
+        input_gates.push(SimpleInput::new(1, "ABOVE_L"));
+
+        let c_output_gate = SimpleOutput::new("C");
+        let equal_output_gate = SimpleOutput::new("E");
+        let a_larger_output_gate = SimpleOutput::new("A_L");
 
-        input_gates.push(SimpleInput::new(1, "E"));
+        output_gates.push(c_output_gate.clone());
+        output_gates.push(equal_output_gate.clone());
+        output_gates.push(a_larger_output_gate.clone());
+        output_gates_logic.push(c_output_gate);
+        output_gates_logic.push(equal_output_gate);
+        output_gates_logic.push(a_larger_output_gate);
 
-        let mut variable_bit_enable = VariableBitEnable {
+        let mut variable_bit_and = XOrLE {
             complex_gate: ComplexGateMembers::new(
-                num_bits + 1,
-                num_bits,
-                GateType::VariableBitEnableType,
+                4,
+                3,
+                GateType::XOrLEType,
                 input_gates,
                 output_gates,
             ),
-            control_buffer: ControlledBuffer::new(num_bits),
+            xor_gate: XOr::new(2, 3),
+            not_gate: Not::new(1),
+            equal_and_gate: And::new(2, 1),
+            middle_and_gate: And::new(3, 1),
+            or_gate: Or::new(2, 1),
         };
 
-        variable_bit_enable.build_and_prime_circuit(
-            num_bits,
+        variable_bit_and.build_and_prime_circuit(
             output_gates_logic,
         );
 
-        new_shared_mutex(variable_bit_enable. get_unique_id().id(), variable_bit_enable)
+        new_shared_mutex(variable_bit_and.get_unique_id().id(), variable_bit_and)
     }
 
     fn build_and_prime_circuit(
         &mut self,
-        num_bits: usize,
         output_gates: Vec<SharedMutex<dyn LogicGate>>,
     ) {
-        let e_input_gate = self.complex_gate.input_gates[self.get_index_from_tag("E")].clone();
-        let controlled_buffer_enable_index = self.control_buffer.lock().unwrap().get_index_from_tag("E");
+        let a_input_gate = self.complex_gate.input_gates[self.get_index_from_tag("A")].clone();
+        let b_input_gate = self.complex_gate.input_gates[self.get_index_from_tag("B")].clone();
+        let equal_input_gate = self.complex_gate.input_gates[self.get_index_from_tag("ABOVE_E")].clone();
+        let larger_input_gate = self.complex_gate.input_gates[self.get_index_from_tag("ABOVE_L")].clone();
+
+        let c_output_gate = output_gates[self.get_index_from_tag("C")].clone();
+        let equal_output_gate = output_gates[self.get_index_from_tag("E")].clone();
+        let a_larger_output_gate = output_gates[self.get_index_from_tag("A_L")].clone();
 
         connect_gates(
-            e_input_gate.clone(),
+            a_input_gate.clone(),
+            0,
+            self.xor_gate.clone(),
             0,
-            self.control_buffer.clone(),
-            controlled_buffer_enable_index,
         );
 
-        for i in 0..num_bits {
-            connect_gates(
-                self.complex_gate.input_gates[i].clone(),
-                0,
-                self.control_buffer.clone(),
-                i,
-            );
-
-            connect_gates(
-                self.control_buffer.clone(),
-                i,
-                output_gates[i].clone(),
-                0,
-            );
-        }
-
-        //Prime gates
-        self.complex_gate.calculate_output_from_inputs_and_set_child_count(
-            true,
+        connect_gates(
+            a_input_gate.clone(),
+            1,
+            self.middle_and_gate.clone(),
+            1,
         );
-    }
+
+        connect_gates(
+            b_input_gate.clone(),
+            0,
+            self.xor_gate.clone(),
+            1,
+        );
+
+        connect_gates(
+            self.xor_gate.clone(),
+            0,
+            self.not_gate.clone(),
+            0,
+        );
+
+        connect_gates(
+            self.xor_gate.clone(),
+            1,
+            self.middle_and_gate.clone(),
+            2,
+        );
+
+        connect_gates(
+            self.xor_gate.clone(),
+            2,
+            c_output_gate.clone(),
+            2,
+        );
+
+        connect_gates(
+            self.not_gate.clone(),
+            0,
+            self.equal_and_gate.clone(),
+            0,
+        );
+
+        connect_gates(
+            self.equal_and_gate.clone(),
+            0,
+            equal_output_gate.clone(),
+            0,
+        );
+
+        connect_gates(
+            equal_input_gate.clone(),
+            0,
+            self.middle_and_gate.clone(),
+            0,
+        );
+
+        connect_gates(
+            equal_input_gate.clone(),
+            1,
+            self.equal_and_gate.clone(),
+            1,
+        );
+
+        connect_gates(
+            self.middle_and_gate.clone(),
+            0,
+            self.or_gate.clone(),
+            0,
+        );
+
+        connect_gates(
+            larger_input_gate.clone(),
+            0,
+            self.or_gate.clone(),
+            1,
+        );
+
+        connect_gates(
+            self.or_gate.clone(),
+            0,
+            a_larger_output_gate.clone(),
+            0,
+        );
+
+        //Prime gates
+        self.complex_gate.calculate_output_from_inputs_and_set_child_count(
+            true,
+        );
+    }
 }
 
-impl LogicGate for VariableBitEnable {
+impl LogicGate for XOrLE {
     fn internal_connect_output(&mut self, current_gate_output_key: usize, next_gate_input_key: usize, next_gate: SharedMutex<dyn LogicGate>) -> Signal {
         self.complex_gate.connect_output(
             self.get_unique_id(),
@@ -2049,6 +2868,14 @@ impl LogicGate for VariableBitEnable {
         self.complex_gate.get_index_from_tag(tag)
     }
 
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         self.complex_gate.remove_connected_input(input_index, connected_id);
     }
@@ -2061,169 +2888,1318 @@ impl LogicGate for VariableBitEnable {
         self.complex_gate.simple_gate.number_child_gates
     }
 
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         self.complex_gate.input_gates.clone()
     }
-}
 
-#[allow(dead_code)]
-enum AluOperations {
-    None,
-    XOrLe,
-    Or,
-    And,
-    Not,
-    Shl,
-    Shr,
-    Adder,
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
 }
 
-#[allow(dead_code)]
-struct AluReturns {
-    a: Vec<Signal>,
-    b: Vec<Signal>,
-    c: Vec<Signal>,
+//A one bit magnitude comparator that can be chained to compare numbers of any width. The chain
+// runs from the most significant bit to the least significant bit with the cascade outputs of
+// each comparator connected to the cascade inputs of the next, less significant, comparator.
+// Inputs
+//  A, B         The bits being compared.
+//  EQUAL_IN     HIGH when every more significant bit of a and b was equal. The most significant
+//               comparator must have this set HIGH.
+//  A_LARGER_IN  HIGH when a more significant bit already decided that a is larger. The most
+//               significant comparator must have this set LOW.
+// Outputs
+//  EQUAL_OUT    HIGH when this bit and every more significant bit are equal.
+//  A_LARGER_OUT HIGH when a is larger considering this bit and every more significant bit.
+//The more significant bits always win. A lower bit is only compared when everything above it was
+// equal. When the outputs of the least significant comparator are both LOW, b is larger.
+pub struct CascadableComparator {
+    complex_gate: ComplexGateMembers,
+    xor_le: SharedMutex<XOrLE>,
 }
 
 #[allow(dead_code)]
-impl AluReturns {
-    fn new(a: Signal, b: Signal, c: Signal) -> Self {
-        AluReturns { a: vec![a], b: vec![b], c: vec![c] }
+impl CascadableComparator {
+    pub fn new() -> SharedMutex<Self> {
+        let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+        let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
+        let mut output_gates_logic: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+
+        input_gates.push(SimpleInput::new(1, "A"));
+        input_gates.push(SimpleInput::new(1, "B"));
+        input_gates.push(SimpleInput::new(1, "EQUAL_IN"));
+        input_gates.push(SimpleInput::new(1, "A_LARGER_IN"));
+
+        let equal_output_gate = SimpleOutput::new("EQUAL_OUT");
+        let a_larger_output_gate = SimpleOutput::new("A_LARGER_OUT");
+        output_gates.push(equal_output_gate.clone());
+        output_gates.push(a_larger_output_gate.clone());
+        output_gates_logic.push(equal_output_gate);
+        output_gates_logic.push(a_larger_output_gate);
+
+        let mut cascadable_comparator = CascadableComparator {
+            complex_gate: ComplexGateMembers::new(
+                4,
+                2,
+                GateType::CascadableComparatorType,
+                input_gates,
+                output_gates,
+            ),
+            xor_le: XOrLE::new(),
+        };
+
+        cascadable_comparator.build_and_prime_circuit(output_gates_logic);
+
+        new_shared_mutex(cascadable_comparator.get_unique_id().id(), cascadable_comparator)
     }
-}
 
-//Rules for the ALU input/output.
-// idx 0 is the least significant bit
-// idx 7 is the most significant bit
-#[allow(dead_code)]
-impl AluOperations {
-    fn get_vectors(alu_operation: AluOperations) -> AluReturns {
-        match alu_operation {
-            AluOperations::None => AluReturns::new(HIGH, HIGH, HIGH),
-            AluOperations::XOrLe => AluReturns::new(HIGH, HIGH, LOW_),
-            AluOperations::Or => AluReturns::new(HIGH, LOW_, HIGH),
-            AluOperations::And => AluReturns::new(HIGH, LOW_, LOW_),
-            AluOperations::Not => AluReturns::new(LOW_, HIGH, HIGH),
-            AluOperations::Shl => AluReturns::new(LOW_, HIGH, LOW_),
-            AluOperations::Shr => AluReturns::new(LOW_, LOW_, HIGH),
-            AluOperations::Adder => AluReturns::new(LOW_, LOW_, LOW_),
+    fn build_and_prime_circuit(
+        &mut self,
+        output_gates: Vec<SharedMutex<dyn LogicGate>>,
+    ) {
+        for (input_tag, xor_le_input_tag) in [
+            ("A", "A"),
+            ("B", "B"),
+            ("EQUAL_IN", "ABOVE_E"),
+            ("A_LARGER_IN", "ABOVE_L"),
+        ] {
+            let input_index = self.get_index_from_tag(input_tag);
+            let xor_le_input_index = self.xor_le.lock().unwrap().get_index_from_tag(xor_le_input_tag);
+            connect_gates(
+                self.complex_gate.input_gates[input_index].clone(),
+                0,
+                self.xor_le.clone(),
+                xor_le_input_index,
+            );
         }
+
+        //The C output of the XOrLE is the XOr of the bits, it is not part of the comparison.
+        for (xor_le_output_tag, output_tag) in [
+            ("E", "EQUAL_OUT"),
+            ("A_L", "A_LARGER_OUT"),
+        ] {
+            let xor_le_output_index = self.xor_le.lock().unwrap().get_index_from_tag(xor_le_output_tag);
+            let output_index = self.get_index_from_tag(output_tag);
+            connect_gates(
+                self.xor_le.clone(),
+                xor_le_output_index,
+                output_gates[output_index].clone(),
+                0,
+            );
+        }
+
+        //Prime gates
+        self.complex_gate.calculate_output_from_inputs_and_set_child_count(
+            true,
+        );
     }
 }
 
-pub struct ArithmeticLogicUnit {
+impl_complex_logic_gate!(CascadableComparator);
+
+pub struct VariableBitXOrLE {
     complex_gate: ComplexGateMembers,
-    xor_le: SharedMutex<VariableBitXOrLE>,
-    or: SharedMutex<VariableBitOr>,
-    and: SharedMutex<VariableBitAnd>,
-    not: SharedMutex<VariableBitNot>,
-    shift_left: SharedMutex<VariableBitShiftLeft<true>>,
-    shift_right: SharedMutex<VariableBitShiftLeft<false>>,
-    adder: SharedMutex<VariableBitAdder>,
-    decoder: SharedMutex<VariableDecoder>,
-    decoder_splitters: Vec<SharedMutex<Splitter>>,
-    enable_gates: Vec<SharedMutex<VariableBitEnable>>,
-    enable_splitters: Vec<SharedMutex<Splitter>>,
-    shl_controlled_buffer: SharedMutex<ControlledBuffer>,
-    shr_controlled_buffer: SharedMutex<ControlledBuffer>,
-    adder_controlled_buffer: SharedMutex<ControlledBuffer>,
-    z: SharedMutex<VariableBitZ>,
-    input_signal_gatekeepers: Vec<SharedMutex<SignalGatekeeper>>,
-    carry_in_signal_gatekeepers: Vec<SharedMutex<SignalGatekeeper>>,
+    xor_le_gates: Vec<SharedMutex<XOrLE>>,
 }
 
 #[allow(dead_code)]
-impl ArithmeticLogicUnit {
-    pub fn new(num_bits: usize) -> SharedMutex<Self> {
-        assert_ne!(num_bits, 0);
-
+impl VariableBitXOrLE {
+    pub fn new(num_bits: usize) -> Result<SharedMutex<Self>, GateBuildError> {
+        if num_bits == 0 {
+            return Err(GateBuildError::ZeroWidth);
+        }
 
         let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
         let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
         let mut output_gates_logic: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
 
+        let mut xor_le_gates = Vec::new();
+
         for i in 0..num_bits {
             let a_input_tag = format!("a_{}", i);
-            input_gates.push(SimpleInput::new(7, a_input_tag.as_str()));
+            input_gates.push(SimpleInput::new(1, a_input_tag.as_str()));
 
             let output_tag = format!("o_{}", i);
             let output_gate = SimpleOutput::new(output_tag.as_str());
             output_gates.push(output_gate.clone());
             output_gates_logic.push(output_gate);
+
+            xor_le_gates.push(
+                XOrLE::new()
+            );
         }
 
         for i in 0..num_bits {
             let b_input_tag = format!("b_{}", i);
-            input_gates.push(SimpleInput::new(4, b_input_tag.as_str()));
+            input_gates.push(SimpleInput::new(1, b_input_tag.as_str()));
         }
 
-        input_gates.push(SimpleInput::new(1, "A"));
-        input_gates.push(SimpleInput::new(1, "B"));
-        input_gates.push(SimpleInput::new(1, "C"));
-        input_gates.push(SimpleInput::new(3, "C_IN"));
-
-        let a_larger_output_gate = SimpleOutput::new("A_L");
-        let equal_output_gate = SimpleOutput::new("EQ");
-        let zero_output_gate = SimpleOutput::new("Z");
-        let carry_out_output_gate = SimpleOutput::new("C_OUT");
-        output_gates.push(a_larger_output_gate.clone());
+        let larger_output_gate = SimpleOutput::new("L");
+        let equal_output_gate = SimpleOutput::new("E");
+        // larger_output_gate.lock().unwrap().toggle_output_printing(true);
+        // equal_output_gate.lock().unwrap().toggle_output_printing(true);
+        output_gates.push(larger_output_gate.clone());
         output_gates.push(equal_output_gate.clone());
-        output_gates.push(zero_output_gate.clone());
-        output_gates.push(carry_out_output_gate.clone());
-        output_gates_logic.push(a_larger_output_gate);
+        output_gates_logic.push(larger_output_gate);
         output_gates_logic.push(equal_output_gate);
-        output_gates_logic.push(zero_output_gate);
-        output_gates_logic.push(carry_out_output_gate);
 
-        let mut enable_gates = Vec::new();
-        let mut enable_splitters = Vec::new();
-        for _ in 0..7 {
-            enable_gates.push(VariableBitEnable::new(num_bits));
-            enable_splitters.push(Splitter::new(num_bits, 2));
-        }
-
-        let mut input_signal_gatekeepers = Vec::new();
-
-        for _ in 0..9 {
-            input_signal_gatekeepers.push(SignalGatekeeper::new(num_bits));
-        }
+        let mut variable_bit_and = VariableBitXOrLE {
+            complex_gate: ComplexGateMembers::new(
+                2 * num_bits,
+                num_bits + 2,
+                GateType::VariableBitXOrLEType,
+                input_gates,
+                output_gates,
+            ),
+            xor_le_gates,
+        };
 
-        let mut carry_in_signal_gatekeepers = Vec::new();
+        variable_bit_and.build_and_prime_circuit(
+            num_bits,
+            output_gates_logic,
+        );
 
-        for _ in 0..3 {
-            carry_in_signal_gatekeepers.push(SignalGatekeeper::new(1));
-        }
+        Ok(new_shared_mutex(variable_bit_and.get_unique_id().id(), variable_bit_and))
+    }
 
-        let mut decoder_splitters = Vec::new();
-        decoder_splitters.push(Splitter::new(1, 3)); // 0; Or
+    fn build_and_prime_circuit(
+        &mut self,
+        num_bits: usize,
+        output_gates: Vec<SharedMutex<dyn LogicGate>>,
+    ) {
+        for i in 0..num_bits {
+            let xor_a_input_index = self.xor_le_gates[i].lock().unwrap().get_index_from_tag("A");
+            let xor_b_input_index = self.xor_le_gates[i].lock().unwrap().get_index_from_tag("B");
+
+            let c_output_index = self.xor_le_gates[i].lock().unwrap().get_index_from_tag("C");
+
+            let a_input_tag = format!("a_{}", i);
+            let b_input_tag = format!("b_{}", i);
+
+            let a_input_index = self.get_index_from_tag(a_input_tag.as_str());
+            let b_input_index = self.get_index_from_tag(b_input_tag.as_str());
+
+            connect_gates(
+                self.complex_gate.input_gates[a_input_index].clone(),
+                0,
+                self.xor_le_gates[i].clone(),
+                xor_a_input_index,
+            );
+
+            connect_gates(
+                self.complex_gate.input_gates[b_input_index].clone(),
+                0,
+                self.xor_le_gates[i].clone(),
+                xor_b_input_index,
+            );
+
+            connect_gates(
+                self.xor_le_gates[i].clone(),
+                c_output_index,
+                output_gates[i].clone(),
+                0,
+            );
+
+            if i == num_bits - 1 {
+                let xor_equal_input_index = self.xor_le_gates[i].lock().unwrap().get_index_from_tag("ABOVE_E");
+                let xor_larger_input_index = self.xor_le_gates[i].lock().unwrap().get_index_from_tag("ABOVE_L");
+
+                self.xor_le_gates[i].lock().unwrap().update_input_signal(
+                    GateInput::new(
+                        xor_equal_input_index,
+                        HIGH,
+                        UniqueID::zero_id(),
+                    )
+                );
+
+                self.xor_le_gates[i].lock().unwrap().update_input_signal(
+                    GateInput::new(
+                        xor_larger_input_index,
+                        LOW_,
+                        UniqueID::zero_id(),
+                    )
+                );
+            } else {
+                let equal_output_index = self.xor_le_gates[i + 1].lock().unwrap().get_index_from_tag("E");
+                let a_larger_output_index = self.xor_le_gates[i + 1].lock().unwrap().get_index_from_tag("A_L");
+                let xor_equal_input_index = self.xor_le_gates[i].lock().unwrap().get_index_from_tag("ABOVE_E");
+                let xor_larger_input_index = self.xor_le_gates[i].lock().unwrap().get_index_from_tag("ABOVE_L");
+
+                connect_gates(
+                    self.xor_le_gates[i + 1].clone(),
+                    equal_output_index,
+                    self.xor_le_gates[i].clone(),
+                    xor_equal_input_index,
+                );
+
+                connect_gates(
+                    self.xor_le_gates[i + 1].clone(),
+                    a_larger_output_index,
+                    self.xor_le_gates[i].clone(),
+                    xor_larger_input_index,
+                );
+            }
+        }
+
+        let xor_equal_output_index = self.xor_le_gates[0].lock().unwrap().get_index_from_tag("E");
+        let xor_a_larger_output_index = self.xor_le_gates[0].lock().unwrap().get_index_from_tag("A_L");
+        let equal_output_index = self.get_index_from_tag("E");
+        let larger_output_index = self.get_index_from_tag("L");
+
+        connect_gates(
+            self.xor_le_gates[0].clone(),
+            xor_equal_output_index,
+            output_gates[equal_output_index].clone(),
+            0,
+        );
+
+        connect_gates(
+            self.xor_le_gates[0].clone(),
+            xor_a_larger_output_index,
+            output_gates[larger_output_index].clone(),
+            0,
+        );
+
+        //Prime gates
+        self.complex_gate.calculate_output_from_inputs_and_set_child_count(
+            true,
+        );
+    }
+}
+
+impl LogicGate for VariableBitXOrLE {
+    fn internal_connect_output(&mut self, current_gate_output_key: usize, next_gate_input_key: usize, next_gate: SharedMutex<dyn LogicGate>) -> Signal {
+        self.complex_gate.connect_output(
+            self.get_unique_id(),
+            current_gate_output_key,
+            next_gate_input_key,
+            next_gate,
+        )
+    }
+
+    fn internal_update_index_to_id(&mut self, sending_id: UniqueID, gate_input_index: usize, signal: Signal) {
+        self.complex_gate.internal_update_index_to_id(sending_id, gate_input_index, signal);
+    }
+
+    fn update_input_signal(&mut self, input: GateInput) -> InputSignalReturn {
+        self.complex_gate.update_input_signal(input)
+    }
+
+    fn fetch_output_signals_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
+        self.complex_gate.fetch_output_signals_calculate(
+            &self.get_tag(),
+        )
+    }
+
+    fn fetch_output_signals_no_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
+        self.complex_gate.fetch_output_signals_no_calculate(
+            &self.get_tag(),
+        )
+    }
+
+    fn get_gate_type(&self) -> GateType {
+        self.complex_gate.simple_gate.gate_type
+    }
+
+    fn get_unique_id(&self) -> UniqueID {
+        self.complex_gate.simple_gate.unique_id
+    }
+
+    fn toggle_output_printing(&mut self, print_output: bool) {
+        self.complex_gate.simple_gate.should_print_output = print_output;
+    }
+
+    fn get_tag(&self) -> String {
+        self.complex_gate.simple_gate.tag.clone()
+    }
+
+    fn set_tag(&mut self, tag: &str) {
+        self.complex_gate.simple_gate.tag = tag.to_string();
+    }
+
+    fn get_index_from_tag(&self, tag: &str) -> usize {
+        self.complex_gate.get_index_from_tag(tag)
+    }
+
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
+    fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
+        self.complex_gate.remove_connected_input(input_index, connected_id);
+    }
+
+    fn toggle_print_each_input_output_gate(&mut self, print_each_input_output_gate: bool) {
+        self.complex_gate.toggle_print_each_input_output_gate(print_each_input_output_gate);
+    }
+
+    fn num_children_gates(&self) -> usize {
+        self.complex_gate.simple_gate.number_child_gates
+    }
+
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
+    fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
+        self.complex_gate.input_gates.clone()
+    }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
+}
+
+pub struct VariableBitZ {
+    complex_gate: ComplexGateMembers,
+    or_gate: SharedMutex<Or>,
+    not_gate: SharedMutex<Not>,
+}
+
+#[allow(dead_code)]
+impl VariableBitZ {
+    pub fn new(num_bits: usize) -> Result<SharedMutex<Self>, GateBuildError> {
+        if num_bits == 0 {
+            return Err(GateBuildError::ZeroWidth);
+        }
+
+        let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+        let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
+        let mut output_gates_logic: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+
+        for i in 0..num_bits {
+            let input_tag = format!("i_{}", i);
+            input_gates.push(SimpleInput::new(1, input_tag.as_str()));
+        }
+
+        let output_gate = SimpleOutput::new("O");
+        output_gates.push(output_gate.clone());
+        output_gates_logic.push(output_gate);
+
+        let mut variable_bit_z = VariableBitZ {
+            complex_gate: ComplexGateMembers::new(
+                num_bits,
+                1,
+                GateType::VariableBitZType,
+                input_gates,
+                output_gates,
+            ),
+            or_gate: Or::new(num_bits, 1),
+            not_gate: Not::new(1),
+        };
+
+        variable_bit_z.build_and_prime_circuit(
+            num_bits,
+            output_gates_logic,
+        );
+
+        Ok(new_shared_mutex(variable_bit_z.get_unique_id().id(), variable_bit_z))
+    }
+
+    fn build_and_prime_circuit(
+        &mut self,
+        num_bits: usize,
+        output_gates: Vec<SharedMutex<dyn LogicGate>>,
+    ) {
+        for i in 0..num_bits {
+            connect_gates(
+                self.complex_gate.input_gates[i].clone(),
+                0,
+                self.or_gate.clone(),
+                i,
+            );
+        }
+
+        connect_gates(
+            self.or_gate.clone(),
+            0,
+            self.not_gate.clone(),
+            0,
+        );
+
+        connect_gates(
+            self.not_gate.clone(),
+            0,
+            output_gates[0].clone(),
+            0,
+        );
+
+        //Prime gates
+        self.complex_gate.calculate_output_from_inputs_and_set_child_count(
+            true,
+        );
+    }
+}
+
+impl LogicGate for VariableBitZ {
+    fn internal_connect_output(&mut self, current_gate_output_key: usize, next_gate_input_key: usize, next_gate: SharedMutex<dyn LogicGate>) -> Signal {
+        self.complex_gate.connect_output(
+            self.get_unique_id(),
+            current_gate_output_key,
+            next_gate_input_key,
+            next_gate,
+        )
+    }
+
+    fn internal_update_index_to_id(&mut self, sending_id: UniqueID, gate_input_index: usize, signal: Signal) {
+        self.complex_gate.internal_update_index_to_id(sending_id, gate_input_index, signal);
+    }
+
+    fn update_input_signal(&mut self, input: GateInput) -> InputSignalReturn {
+        self.complex_gate.update_input_signal(input)
+    }
+
+    fn fetch_output_signals_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
+        self.complex_gate.fetch_output_signals_calculate(
+            &self.get_tag(),
+        )
+    }
+
+    fn fetch_output_signals_no_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
+        self.complex_gate.fetch_output_signals_no_calculate(
+            &self.get_tag(),
+        )
+    }
+
+    fn get_gate_type(&self) -> GateType {
+        self.complex_gate.simple_gate.gate_type
+    }
+
+    fn get_unique_id(&self) -> UniqueID {
+        self.complex_gate.simple_gate.unique_id
+    }
+
+    fn toggle_output_printing(&mut self, print_output: bool) {
+        self.complex_gate.simple_gate.should_print_output = print_output;
+    }
+
+    fn get_tag(&self) -> String {
+        self.complex_gate.simple_gate.tag.clone()
+    }
+
+    fn set_tag(&mut self, tag: &str) {
+        self.complex_gate.simple_gate.tag = tag.to_string();
+    }
+
+    fn get_index_from_tag(&self, tag: &str) -> usize {
+        self.complex_gate.get_index_from_tag(tag)
+    }
+
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
+    fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
+        self.complex_gate.remove_connected_input(input_index, connected_id);
+    }
+
+    fn toggle_print_each_input_output_gate(&mut self, print_each_input_output_gate: bool) {
+        self.complex_gate.toggle_print_each_input_output_gate(print_each_input_output_gate);
+    }
+
+    fn num_children_gates(&self) -> usize {
+        self.complex_gate.simple_gate.number_child_gates
+    }
+
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
+    fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
+        self.complex_gate.input_gates.clone()
+    }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
+}
+
+pub struct VariableBitEnable {
+    complex_gate: ComplexGateMembers,
+    control_buffer: SharedMutex<ControlledBuffer>,
+    //Only used when the outputs are LOW instead of NONE while disabled.
+    disabled_low_and_gates: Vec<SharedMutex<And>>,
+}
+
+#[allow(dead_code)]
+impl VariableBitEnable {
+    pub fn new(num_bits: usize) -> Result<SharedMutex<Self>, GateBuildError> {
+        VariableBitEnable::new_with_mode(num_bits, false)
+    }
+
+    //The outputs are LOW instead of NONE when E is LOW. This prevents NONE from being passed into
+    // gates such as the VariableBitZ that need a valid signal on every input.
+    pub fn new_with_disabled_low(num_bits: usize) -> Result<SharedMutex<Self>, GateBuildError> {
+        VariableBitEnable::new_with_mode(num_bits, true)
+    }
+
+    fn new_with_mode(num_bits: usize, disabled_low: bool) -> Result<SharedMutex<Self>, GateBuildError> {
+        if num_bits == 0 {
+            return Err(GateBuildError::ZeroWidth);
+        }
+
+        let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+        let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
+        let mut output_gates_logic: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+        let mut disabled_low_and_gates = Vec::new();
+
+        if disabled_low {
+            build_simple_inputs_and_outputs_with_and(
+                num_bits,
+                &mut input_gates,
+                &mut output_gates,
+                &mut output_gates_logic,
+                &mut disabled_low_and_gates,
+            );
+
+            input_gates.push(SimpleInput::new(num_bits, "E"));
+        } else {
+            build_simple_inputs_and_outputs(
+                num_bits,
+                &mut input_gates,
+                &mut output_gates,
+                &mut output_gates_logic,
+            );
+
+            input_gates.push(SimpleInput::new(1, "E"));
+        }
+
+        let mut variable_bit_enable = VariableBitEnable {
+            complex_gate: ComplexGateMembers::new(
+                num_bits + 1,
+                num_bits,
+                GateType::VariableBitEnableType,
+                input_gates,
+                output_gates,
+            ),
+            control_buffer: ControlledBuffer::new(num_bits),
+            disabled_low_and_gates,
+        };
+
+        variable_bit_enable.build_and_prime_circuit(
+            num_bits,
+            output_gates_logic,
+        );
+
+        Ok(new_shared_mutex(variable_bit_enable. get_unique_id().id(), variable_bit_enable))
+    }
+
+    fn build_and_prime_circuit(
+        &mut self,
+        num_bits: usize,
+        output_gates: Vec<SharedMutex<dyn LogicGate>>,
+    ) {
+        let e_input_gate = self.complex_gate.input_gates[self.get_index_from_tag("E")].clone();
+
+        if self.disabled_low_and_gates.is_empty() {
+            let controlled_buffer_enable_index = self.control_buffer.lock().unwrap().get_index_from_tag("E");
+
+            connect_gates(
+                e_input_gate.clone(),
+                0,
+                self.control_buffer.clone(),
+                controlled_buffer_enable_index,
+            );
+
+            for i in 0..num_bits {
+                connect_gates(
+                    self.complex_gate.input_gates[i].clone(),
+                    0,
+                    self.control_buffer.clone(),
+                    i,
+                );
+
+                connect_gates(
+                    self.control_buffer.clone(),
+                    i,
+                    output_gates[i].clone(),
+                    0,
+                );
+            }
+        } else {
+            for (i, (and_gate, output_gate)) in self.disabled_low_and_gates.iter().zip(output_gates.iter()).enumerate() {
+                connect_gates(
+                    self.complex_gate.input_gates[i].clone(),
+                    0,
+                    and_gate.clone(),
+                    0,
+                );
+
+                connect_gates(
+                    e_input_gate.clone(),
+                    i,
+                    and_gate.clone(),
+                    1,
+                );
+
+                connect_gates(
+                    and_gate.clone(),
+                    0,
+                    output_gate.clone(),
+                    0,
+                );
+            }
+        }
+
+        //Prime gates
+        self.complex_gate.calculate_output_from_inputs_and_set_child_count(
+            true,
+        );
+    }
+}
+
+impl LogicGate for VariableBitEnable {
+    fn internal_connect_output(&mut self, current_gate_output_key: usize, next_gate_input_key: usize, next_gate: SharedMutex<dyn LogicGate>) -> Signal {
+        self.complex_gate.connect_output(
+            self.get_unique_id(),
+            current_gate_output_key,
+            next_gate_input_key,
+            next_gate,
+        )
+    }
+
+    fn internal_update_index_to_id(&mut self, sending_id: UniqueID, gate_input_index: usize, signal: Signal) {
+        self.complex_gate.internal_update_index_to_id(sending_id, gate_input_index, signal);
+    }
+
+    fn update_input_signal(&mut self, input: GateInput) -> InputSignalReturn {
+        self.complex_gate.update_input_signal(input)
+    }
+
+    fn fetch_output_signals_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
+        self.complex_gate.fetch_output_signals_calculate(
+            &self.get_tag(),
+        )
+    }
+
+    fn fetch_output_signals_no_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
+        self.complex_gate.fetch_output_signals_no_calculate(
+            &self.get_tag(),
+        )
+    }
+
+    fn get_gate_type(&self) -> GateType {
+        self.complex_gate.simple_gate.gate_type
+    }
+
+    fn get_unique_id(&self) -> UniqueID {
+        self.complex_gate.simple_gate.unique_id
+    }
+
+    fn toggle_output_printing(&mut self, print_output: bool) {
+        self.complex_gate.simple_gate.should_print_output = print_output;
+    }
+
+    fn get_tag(&self) -> String {
+        self.complex_gate.simple_gate.tag.clone()
+    }
+
+    fn set_tag(&mut self, tag: &str) {
+        self.complex_gate.simple_gate.tag = tag.to_string();
+    }
+
+    fn get_index_from_tag(&self, tag: &str) -> usize {
+        self.complex_gate.get_index_from_tag(tag)
+    }
+
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
+    fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
+        self.complex_gate.remove_connected_input(input_index, connected_id);
+    }
+
+    fn toggle_print_each_input_output_gate(&mut self, print_each_input_output_gate: bool) {
+        self.complex_gate.toggle_print_each_input_output_gate(print_each_input_output_gate);
+    }
+
+    fn num_children_gates(&self) -> usize {
+        self.complex_gate.simple_gate.number_child_gates
+    }
+
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
+    fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
+        self.complex_gate.input_gates.clone()
+    }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
+}
+
+//Input i_{num_bits - 1} is the sign bit and the remaining inputs are the magnitude. When the sign
+// bit is set the magnitude is negated by inverting it and adding one, so negative zero becomes
+// zero.
+pub struct SignMagnitudeToTwosComplement {
+    complex_gate: ComplexGateMembers,
+    xor_gates: Vec<SharedMutex<XOr>>,
+    half_adders: Vec<SharedMutex<HalfAdder>>,
+}
+
+#[allow(dead_code)]
+impl SignMagnitudeToTwosComplement {
+    pub fn new(num_bits: usize) -> SharedMutex<Self> {
+        //At least one bit is required for the magnitude.
+        assert!(num_bits > 1);
+
+        let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+        let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
+        let mut output_gates_logic: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+
+        let mut xor_gates = Vec::new();
+        let mut half_adders = Vec::new();
+
+        for i in 0..num_bits {
+            let input_tag = format!("i_{}", i);
+
+            //The sign bit goes to every xor gate, the most significant half adder and the carry in
+            // of the least significant half adder.
+            let input_gate =
+                if i == num_bits - 1 {
+                    SimpleInput::new(num_bits + 1, input_tag.as_str())
+                } else {
+                    xor_gates.push(XOr::new(2, 1));
+                    SimpleInput::new(1, input_tag.as_str())
+                };
+            input_gates.push(input_gate);
+
+            let output_tag = format!("o_{}", i);
+            let output_gate = SimpleOutput::new(output_tag.as_str());
+            output_gates.push(output_gate.clone());
+            output_gates_logic.push(output_gate);
+
+            half_adders.push(HalfAdder::new());
+        }
+
+        let mut sign_magnitude_to_twos_complement = SignMagnitudeToTwosComplement {
+            complex_gate: ComplexGateMembers::new(
+                num_bits,
+                num_bits,
+                GateType::SignMagnitudeToTwosComplementType,
+                input_gates,
+                output_gates,
+            ),
+            xor_gates,
+            half_adders,
+        };
+
+        sign_magnitude_to_twos_complement.build_and_prime_circuit(
+            num_bits,
+            output_gates_logic,
+        );
+
+        new_shared_mutex(sign_magnitude_to_twos_complement.get_unique_id().id(), sign_magnitude_to_twos_complement)
+    }
+
+    fn build_and_prime_circuit(
+        &mut self,
+        num_bits: usize,
+        output_gates: Vec<SharedMutex<dyn LogicGate>>,
+    ) {
+        let sign_input_gate = self.complex_gate.input_gates[num_bits - 1].clone();
+
+        let half_adder = self.half_adders[0].lock().unwrap();
+        let a_index = half_adder.get_index_from_tag("A");
+        let b_index = half_adder.get_index_from_tag("B");
+        let s_index = half_adder.get_index_from_tag("S");
+        let c_index = half_adder.get_index_from_tag("C");
+        drop(half_adder);
+
+        for (i, output_gate) in output_gates.iter().enumerate() {
+            //The most significant bit of the magnitude is always zero so inverting it gives the
+            // sign bit.
+            if i == num_bits - 1 {
+                connect_gates(
+                    sign_input_gate.clone(),
+                    i,
+                    self.half_adders[i].clone(),
+                    a_index,
+                );
+            } else {
+                connect_gates(
+                    self.complex_gate.input_gates[i].clone(),
+                    0,
+                    self.xor_gates[i].clone(),
+                    0,
+                );
+
+                connect_gates(
+                    sign_input_gate.clone(),
+                    i,
+                    self.xor_gates[i].clone(),
+                    1,
+                );
+
+                connect_gates(
+                    self.xor_gates[i].clone(),
+                    0,
+                    self.half_adders[i].clone(),
+                    a_index,
+                );
+            }
+
+            if i == 0 {
+                connect_gates(
+                    sign_input_gate.clone(),
+                    num_bits,
+                    self.half_adders[i].clone(),
+                    b_index,
+                );
+            } else {
+                connect_gates(
+                    self.half_adders[i - 1].clone(),
+                    c_index,
+                    self.half_adders[i].clone(),
+                    b_index,
+                );
+            }
+
+            connect_gates(
+                self.half_adders[i].clone(),
+                s_index,
+                output_gate.clone(),
+                0,
+            );
+        }
+
+        //Prime gates
+        self.complex_gate.calculate_output_from_inputs_and_set_child_count(
+            true,
+        );
+    }
+}
+
+impl LogicGate for SignMagnitudeToTwosComplement {
+    fn internal_connect_output(&mut self, current_gate_output_key: usize, next_gate_input_key: usize, next_gate: SharedMutex<dyn LogicGate>) -> Signal {
+        self.complex_gate.connect_output(
+            self.get_unique_id(),
+            current_gate_output_key,
+            next_gate_input_key,
+            next_gate,
+        )
+    }
+
+    fn internal_update_index_to_id(&mut self, sending_id: UniqueID, gate_input_index: usize, signal: Signal) {
+        self.complex_gate.internal_update_index_to_id(sending_id, gate_input_index, signal);
+    }
+
+    fn update_input_signal(&mut self, input: GateInput) -> InputSignalReturn {
+        self.complex_gate.update_input_signal(input)
+    }
+
+    fn fetch_output_signals_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
+        self.complex_gate.fetch_output_signals_calculate(
+            &self.get_tag(),
+        )
+    }
+
+    fn fetch_output_signals_no_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
+        self.complex_gate.fetch_output_signals_no_calculate(
+            &self.get_tag(),
+        )
+    }
+
+    fn get_gate_type(&self) -> GateType {
+        self.complex_gate.simple_gate.gate_type
+    }
+
+    fn get_unique_id(&self) -> UniqueID {
+        self.complex_gate.simple_gate.unique_id
+    }
+
+    fn toggle_output_printing(&mut self, print_output: bool) {
+        self.complex_gate.simple_gate.should_print_output = print_output;
+    }
+
+    fn get_tag(&self) -> String {
+        self.complex_gate.simple_gate.tag.clone()
+    }
+
+    fn set_tag(&mut self, tag: &str) {
+        self.complex_gate.simple_gate.tag = tag.to_string();
+    }
+
+    fn get_index_from_tag(&self, tag: &str) -> usize {
+        self.complex_gate.get_index_from_tag(tag)
+    }
+
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
+    fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
+        self.complex_gate.remove_connected_input(input_index, connected_id);
+    }
+
+    fn toggle_print_each_input_output_gate(&mut self, print_each_input_output_gate: bool) {
+        self.complex_gate.toggle_print_each_input_output_gate(print_each_input_output_gate);
+    }
+
+    fn num_children_gates(&self) -> usize {
+        self.complex_gate.simple_gate.number_child_gates
+    }
+
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
+    fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
+        self.complex_gate.input_gates.clone()
+    }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Copy, PartialEq)]
+pub enum AluOperations {
+    None,
+    XOrLe,
+    Or,
+    And,
+    Not,
+    Shl,
+    Shr,
+    Adder,
+    Rcl,
+    Rcr,
+    Subtract,
+}
+
+#[allow(dead_code)]
+struct AluReturns {
+    a: Vec<Signal>,
+    b: Vec<Signal>,
+    c: Vec<Signal>,
+    sub: Vec<Signal>,
+}
+
+#[allow(dead_code)]
+impl AluReturns {
+    fn new(a: Signal, b: Signal, c: Signal) -> Self {
+        AluReturns { a: vec![a], b: vec![b], c: vec![c], sub: vec![LOW_] }
+    }
+}
+
+//Rules for the ALU input/output.
+// idx 0 is the least significant bit
+// idx 7 is the most significant bit
+#[allow(dead_code)]
+impl AluOperations {
+    fn get_vectors(alu_operation: AluOperations) -> AluReturns {
+        match alu_operation {
+            AluOperations::None => AluReturns::new(HIGH, HIGH, HIGH),
+            AluOperations::XOrLe => AluReturns::new(HIGH, HIGH, LOW_),
+            AluOperations::Or => AluReturns::new(HIGH, LOW_, HIGH),
+            AluOperations::And => AluReturns::new(HIGH, LOW_, LOW_),
+            AluOperations::Not => AluReturns::new(LOW_, HIGH, HIGH),
+            //Shl and Shr are the shifters with SUB set, which shifts in LOW instead of C_IN.
+            AluOperations::Shl => AluReturns {
+                sub: vec![HIGH],
+                ..AluReturns::new(LOW_, HIGH, LOW_)
+            },
+            AluOperations::Shr => AluReturns {
+                sub: vec![HIGH],
+                ..AluReturns::new(LOW_, LOW_, HIGH)
+            },
+            AluOperations::Adder => AluReturns::new(LOW_, LOW_, LOW_),
+            //Rcl and Rcr are the shifters with SUB clear, the shift in bit comes from C_IN.
+            AluOperations::Rcl => AluReturns::new(LOW_, HIGH, LOW_),
+            AluOperations::Rcr => AluReturns::new(LOW_, LOW_, HIGH),
+            //Subtract is the adder with SUB set, see ArithmeticLogicUnit::new_with_ops().
+            AluOperations::Subtract => AluReturns {
+                sub: vec![HIGH],
+                ..AluOperations::get_vectors(AluOperations::Adder)
+            },
+        }
+    }
+}
+
+pub struct ArithmeticLogicUnit {
+    complex_gate: ComplexGateMembers,
+    xor_le: Option<SharedMutex<VariableBitXOrLE>>,
+    or: Option<SharedMutex<VariableBitOr>>,
+    and: Option<SharedMutex<VariableBitAnd>>,
+    not: Option<SharedMutex<VariableBitNot>>,
+    shift_left: Option<SharedMutex<VariableBitShiftLeft<true>>>,
+    shift_right: Option<SharedMutex<VariableBitShiftLeft<false>>>,
+    adder: Option<SharedMutex<VariableBitAdder>>,
+    subtract_b_splitter: Option<SharedMutex<Splitter>>,
+    subtract_b_not: Option<SharedMutex<VariableBitNot>>,
+    subtract_b_multiplexer: Option<SharedMutex<VariableBitMultiplexer>>,
+    subtract_carry_in_or: Option<SharedMutex<Or>>,
+    subtract_borrow_xor: Option<SharedMutex<XOr>>,
+    decoder: SharedMutex<VariableDecoder>,
+    decoder_splitters: Vec<SharedMutex<Splitter>>,
+    enable_gates: Vec<SharedMutex<VariableBitEnable>>,
+    enable_splitters: Vec<SharedMutex<Splitter>>,
+    shl_controlled_buffer: SharedMutex<ControlledBuffer>,
+    shr_controlled_buffer: SharedMutex<ControlledBuffer>,
+    adder_controlled_buffer: SharedMutex<ControlledBuffer>,
+    z: SharedMutex<VariableBitZ>,
+    input_signal_gatekeepers: Vec<SharedMutex<SignalGatekeeper>>,
+    carry_in_signal_gatekeepers: Vec<SharedMutex<SignalGatekeeper>>,
+    op_invalid_or: Option<SharedMutex<Or>>,
+    op_valid_not: SharedMutex<Not>,
+    shl_shift_out_splitter: SharedMutex<Splitter>,
+    shr_shift_out_splitter: SharedMutex<Splitter>,
+    shl_shift_lost_and: SharedMutex<And>,
+    shr_shift_lost_and: SharedMutex<And>,
+    shift_lost_or: SharedMutex<Or>,
+    shift_carry_in_not: SharedMutex<Not>,
+    shl_carry_in_and: Option<SharedMutex<And>>,
+    shr_carry_in_and: Option<SharedMutex<And>>,
+    settle_budget: Option<usize>,
+    unsettled_gates: Vec<SharedMutex<dyn LogicGate>>,
+}
+
+#[allow(dead_code)]
+impl ArithmeticLogicUnit {
+    pub fn new(num_bits: usize) -> Result<SharedMutex<Self>, GateBuildError> {
+        ArithmeticLogicUnit::new_with_ops(
+            num_bits,
+            &[
+                AluOperations::XOrLe,
+                AluOperations::Or,
+                AluOperations::And,
+                AluOperations::Not,
+                AluOperations::Shl,
+                AluOperations::Shr,
+                AluOperations::Adder,
+                AluOperations::Rcl,
+                AluOperations::Rcr,
+                AluOperations::Subtract,
+            ],
+        )
+    }
+
+    //Only the sub-units for the operations inside ops are built. Selecting an operation that was
+    // left out drives every output bit LOW and OP_VALID LOW. When XOrLe is left out, A_L and EQ are
+    // always LOW.
+    //Rcl and Rcr rotate through the carry. They are selected with the Shl and Shr opcodes and SUB
+    // set LOW, the shifters then take their shift in bit from C_IN and send their shift out bit to
+    // C_OUT. Setting SUB HIGH selects Shl and Shr which shift in LOW and ignore C_IN. Rcl and Rcr
+    // use the shifters so they include Shl and Shr. The cpu never sets SUB, so its SHL and SHR
+    // instructions rotate through the carry flag.
+    //Subtract includes the adder. It is selected with the adder opcode and SUB set HIGH, which
+    // inverts b and forces the carry in HIGH so the adder calculates a + NOT(b) + 1 = a - b. C_IN
+    // is ignored while subtracting. The carry out is inverted so that C_OUT is HIGH when the
+    // subtraction borrowed (b > a). When Subtract is left out, SUB only selects between the shifts
+    // and the rotates.
+    pub fn new_with_ops(num_bits: usize, ops: &[AluOperations]) -> Result<SharedMutex<Self>, GateBuildError> {
+        if num_bits == 0 {
+            return Err(GateBuildError::ZeroWidth);
+        }
+
+
+        let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+        let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
+        let mut output_gates_logic: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+
+        for i in 0..num_bits {
+            let a_input_tag = format!("a_{}", i);
+            input_gates.push(SimpleInput::new(7, a_input_tag.as_str()));
+
+            let output_tag = format!("o_{}", i);
+            let output_gate = SimpleOutput::new(output_tag.as_str());
+            output_gates.push(output_gate.clone());
+            output_gates_logic.push(output_gate);
+        }
+
+        for i in 0..num_bits {
+            let b_input_tag = format!("b_{}", i);
+            input_gates.push(SimpleInput::new(4, b_input_tag.as_str()));
+        }
+
+        input_gates.push(SimpleInput::new(1, "A"));
+        input_gates.push(SimpleInput::new(1, "B"));
+        input_gates.push(SimpleInput::new(1, "C"));
+        input_gates.push(SimpleInput::new(3, "C_IN"));
+        input_gates.push(SimpleInput::new(4, "SUB"));
+
+        let a_larger_output_gate = SimpleOutput::new("A_L");
+        let equal_output_gate = SimpleOutput::new("EQ");
+        let zero_output_gate = SimpleOutput::new("Z");
+        let carry_out_output_gate = SimpleOutput::new("C_OUT");
+        let op_valid_output_gate = SimpleOutput::new("OP_VALID");
+        let shift_lost_output_gate = SimpleOutput::new("SHIFT_LOST");
+        output_gates.push(a_larger_output_gate.clone());
+        output_gates.push(equal_output_gate.clone());
+        output_gates.push(zero_output_gate.clone());
+        output_gates.push(carry_out_output_gate.clone());
+        output_gates.push(op_valid_output_gate.clone());
+        output_gates.push(shift_lost_output_gate.clone());
+        output_gates_logic.push(a_larger_output_gate);
+        output_gates_logic.push(equal_output_gate);
+        output_gates_logic.push(zero_output_gate);
+        output_gates_logic.push(carry_out_output_gate);
+        output_gates_logic.push(op_valid_output_gate);
+        output_gates_logic.push(shift_lost_output_gate);
+
+        let mut enable_gates = Vec::new();
+        let mut enable_splitters = Vec::new();
+        for _ in 0..7 {
+            enable_gates.push(VariableBitEnable::new(num_bits)?);
+            enable_splitters.push(Splitter::new(num_bits, 2));
+        }
+
+        let mut input_signal_gatekeepers = Vec::new();
+
+        for _ in 0..9 {
+            input_signal_gatekeepers.push(SignalGatekeeper::new(num_bits));
+        }
+
+        let mut carry_in_signal_gatekeepers = Vec::new();
+
+        for _ in 0..3 {
+            carry_in_signal_gatekeepers.push(SignalGatekeeper::new(1));
+        }
+
+        let mut decoder_splitters = Vec::new();
+        decoder_splitters.push(Splitter::new(1, 3)); // 0; Or
         decoder_splitters.push(Splitter::new(1, 3)); // 1; And
         decoder_splitters.push(Splitter::new(1, 2)); // 2; Not
-        decoder_splitters.push(Splitter::new(1, 4)); // 3; SHL
-        decoder_splitters.push(Splitter::new(1, 4)); // 4; SHR
+        decoder_splitters.push(Splitter::new(1, 5)); // 3; SHL
+        decoder_splitters.push(Splitter::new(1, 5)); // 4; SHR
         decoder_splitters.push(Splitter::new(1, 5)); // 5: Adder
 
 
-        let xor_le= VariableBitXOrLE::new(num_bits);
+        let includes_op = |operation: AluOperations| {
+            ops.iter().any(|&op| {
+                let op = match op {
+                    AluOperations::Rcl => AluOperations::Shl,
+                    AluOperations::Rcr => AluOperations::Shr,
+                    AluOperations::Subtract => AluOperations::Adder,
+                    op => op,
+                };
+                op == operation
+            })
+        };
+
+        let xor_le= includes_op(AluOperations::XOrLe).then(|| VariableBitXOrLE::new(num_bits)).transpose()?;
+
+        let or= includes_op(AluOperations::Or).then(|| VariableBitOr::new(num_bits)).transpose()?;
+
+        let and= includes_op(AluOperations::And).then(|| VariableBitAnd::new(num_bits)).transpose()?;
 
-        let or= VariableBitOr::new(num_bits);
+        let not= includes_op(AluOperations::Not).then(|| VariableBitNot::new(num_bits)).transpose()?;
 
-        let and= VariableBitAnd::new(num_bits);
+        let shift_left= includes_op(AluOperations::Shl).then(|| VariableBitShiftLeft::<true>::new(num_bits)).transpose()?;
 
-        let not= VariableBitNot::new(num_bits);
+        let shift_right= includes_op(AluOperations::Shr).then(|| VariableBitShiftLeft::<false>::new(num_bits)).transpose()?;
 
-        let shift_left= VariableBitShiftLeft::<true>::new(num_bits);
+        let shl_carry_in_and = shift_left.is_some().then(|| And::new(2, 1));
 
-        let shift_right= VariableBitShiftLeft::<false>::new(num_bits);
+        let shr_carry_in_and = shift_right.is_some().then(|| And::new(2, 1));
 
-        let adder= VariableBitAdder::new(num_bits);
+        let adder= includes_op(AluOperations::Adder).then(|| VariableBitAdder::new(num_bits)).transpose()?;
+
+        let subtract = ops.contains(&AluOperations::Subtract);
+
+        let subtract_b_not= subtract.then(|| VariableBitNot::new(num_bits)).transpose()?;
 
         let decoder= VariableDecoder::new(3);
 
+        //Every operation that was left out drives OP_VALID LOW, see build_and_prime_circuit().
+        let num_omitted_ops = [
+            AluOperations::XOrLe,
+            AluOperations::Or,
+            AluOperations::And,
+            AluOperations::Not,
+            AluOperations::Shl,
+            AluOperations::Shr,
+            AluOperations::Adder,
+        ].into_iter().filter(|&op| !includes_op(op)).count();
+
 
         let mut arithmetic_logic_unit = ArithmeticLogicUnit {
             complex_gate: ComplexGateMembers::new(
-                2 * num_bits + 4,
-                num_bits + 4,
+                2 * num_bits + 5,
+                num_bits + 6,
                 GateType::ArithmeticLogicUnitType,
                 input_gates,
                 output_gates,
@@ -2235,6 +4211,11 @@ impl ArithmeticLogicUnit {
             shift_left,
             shift_right,
             adder,
+            subtract_b_splitter: subtract.then(|| Splitter::new(num_bits, 2)),
+            subtract_b_not,
+            subtract_b_multiplexer: subtract.then(|| VariableBitMultiplexer::new(num_bits, 2)),
+            subtract_carry_in_or: subtract.then(|| Or::new(2, 1)),
+            subtract_borrow_xor: subtract.then(|| XOr::new(2, 1)),
             decoder,
             decoder_splitters,
             enable_gates,
@@ -2242,9 +4223,21 @@ impl ArithmeticLogicUnit {
             shl_controlled_buffer: ControlledBuffer::new(num_bits),
             shr_controlled_buffer: ControlledBuffer::new(num_bits),
             adder_controlled_buffer: ControlledBuffer::new(num_bits),
-            z: VariableBitZ::new(num_bits),
+            z: VariableBitZ::new(num_bits)?,
             input_signal_gatekeepers,
             carry_in_signal_gatekeepers,
+            op_invalid_or: (num_omitted_ops > 0).then(|| Or::new(num_omitted_ops + 1, 1)),
+            op_valid_not: Not::new(1),
+            shl_shift_out_splitter: Splitter::new(1, 2),
+            shr_shift_out_splitter: Splitter::new(1, 2),
+            shl_shift_lost_and: And::new(2, 1),
+            shr_shift_lost_and: And::new(2, 1),
+            shift_lost_or: Or::new(2, 1),
+            shift_carry_in_not: Not::new(2),
+            shl_carry_in_and,
+            shr_carry_in_and,
+            settle_budget: None,
+            unsettled_gates: Vec::new(),
         };
 
 
@@ -2253,7 +4246,7 @@ impl ArithmeticLogicUnit {
             output_gates_logic,
         );
 
-        new_shared_mutex(arithmetic_logic_unit.get_unique_id().id(), arithmetic_logic_unit)
+        Ok(new_shared_mutex(arithmetic_logic_unit.get_unique_id().id(), arithmetic_logic_unit))
     }
 
     fn build_and_prime_circuit(
@@ -2265,6 +4258,7 @@ impl ArithmeticLogicUnit {
         let b_input_gate = self.complex_gate.input_gates[self.get_index_from_tag("B")].clone();
         let c_input_gate = self.complex_gate.input_gates[self.get_index_from_tag("C")].clone();
         let c_in_input_gate = self.complex_gate.input_gates[self.get_index_from_tag("C_IN")].clone();
+        let sub_input_gate = self.complex_gate.input_gates[self.get_index_from_tag("SUB")].clone();
 
 
         for i in 0..num_bits {
@@ -2277,227 +4271,312 @@ impl ArithmeticLogicUnit {
 
             //A Input -> Signal Gatekeepers & Signal Gatekeepers -> Arithmetic gates
 
-            //Xor_le doesn't get a signal gatekeeper so that the larger and equal outputs will always
-            // be correct.
-            let xor_a_input_index = self.xor_le.lock().unwrap().get_index_from_tag(a_input_tag.as_str());
-            connect_gates(
-                self.complex_gate.input_gates[a_input_index].clone(),
-                0,
-                self.xor_le.clone(),
-                xor_a_input_index,
-            );
-
-            connect_gates(
-                self.complex_gate.input_gates[a_input_index].clone(),
-                1,
-                self.input_signal_gatekeepers[0].clone(),
-                i,
-            );
+            //Xor_le doesn't get a signal gatekeeper so that the larger and equal outputs will always
+            // be correct.
+            if let Some(xor_le) = &self.xor_le {
+                let xor_a_input_index = xor_le.lock().unwrap().get_index_from_tag(a_input_tag.as_str());
+                connect_gates(
+                    self.complex_gate.input_gates[a_input_index].clone(),
+                    0,
+                    xor_le.clone(),
+                    xor_a_input_index,
+                );
+            }
 
-            let or_a_input_index = self.or.lock().unwrap().get_index_from_tag(a_input_tag.as_str());
-            connect_gates(
-                self.input_signal_gatekeepers[0].clone(),
-                i,
-                self.or.clone(),
-                or_a_input_index,
-            );
+            if let Some(or) = &self.or {
+                connect_gates(
+                    self.complex_gate.input_gates[a_input_index].clone(),
+                    1,
+                    self.input_signal_gatekeepers[0].clone(),
+                    i,
+                );
 
-            connect_gates(
-                self.complex_gate.input_gates[a_input_index].clone(),
-                2,
-                self.input_signal_gatekeepers[1].clone(),
-                i,
-            );
+                let or_a_input_index = or.lock().unwrap().get_index_from_tag(a_input_tag.as_str());
+                connect_gates(
+                    self.input_signal_gatekeepers[0].clone(),
+                    i,
+                    or.clone(),
+                    or_a_input_index,
+                );
+            }
 
-            let and_a_input_index = self.and.lock().unwrap().get_index_from_tag(a_input_tag.as_str());
-            connect_gates(
-                self.input_signal_gatekeepers[1].clone(),
-                i,
-                self.and.clone(),
-                and_a_input_index,
-            );
+            if let Some(and) = &self.and {
+                connect_gates(
+                    self.complex_gate.input_gates[a_input_index].clone(),
+                    2,
+                    self.input_signal_gatekeepers[1].clone(),
+                    i,
+                );
 
-            connect_gates(
-                self.complex_gate.input_gates[a_input_index].clone(),
-                3,
-                self.input_signal_gatekeepers[2].clone(),
-                i,
-            );
+                let and_a_input_index = and.lock().unwrap().get_index_from_tag(a_input_tag.as_str());
+                connect_gates(
+                    self.input_signal_gatekeepers[1].clone(),
+                    i,
+                    and.clone(),
+                    and_a_input_index,
+                );
+            }
 
-            let not_a_input_index = self.not.lock().unwrap().get_index_from_tag(input_tag.as_str());
-            connect_gates(
-                self.input_signal_gatekeepers[2].clone(),
-                i,
-                self.not.clone(),
-                not_a_input_index,
-            );
+            if let Some(not) = &self.not {
+                connect_gates(
+                    self.complex_gate.input_gates[a_input_index].clone(),
+                    3,
+                    self.input_signal_gatekeepers[2].clone(),
+                    i,
+                );
 
-            connect_gates(
-                self.complex_gate.input_gates[a_input_index].clone(),
-                4,
-                self.input_signal_gatekeepers[3].clone(),
-                i,
-            );
+                let not_a_input_index = not.lock().unwrap().get_index_from_tag(input_tag.as_str());
+                connect_gates(
+                    self.input_signal_gatekeepers[2].clone(),
+                    i,
+                    not.clone(),
+                    not_a_input_index,
+                );
+            }
 
-            let shl_a_input_index = self.shift_left.lock().unwrap().get_index_from_tag(input_tag.as_str());
-            connect_gates(
-                self.input_signal_gatekeepers[3].clone(),
-                i,
-                self.shift_left.clone(),
-                shl_a_input_index,
-            );
+            if let Some(shift_left) = &self.shift_left {
+                connect_gates(
+                    self.complex_gate.input_gates[a_input_index].clone(),
+                    4,
+                    self.input_signal_gatekeepers[3].clone(),
+                    i,
+                );
 
-            connect_gates(
-                self.complex_gate.input_gates[a_input_index].clone(),
-                5,
-                self.input_signal_gatekeepers[4].clone(),
-                i,
-            );
+                let shl_a_input_index = shift_left.lock().unwrap().get_index_from_tag(input_tag.as_str());
+                connect_gates(
+                    self.input_signal_gatekeepers[3].clone(),
+                    i,
+                    shift_left.clone(),
+                    shl_a_input_index,
+                );
+            }
 
-            let shr_a_input_index = self.shift_right.lock().unwrap().get_index_from_tag(input_tag.as_str());
-            connect_gates(
-                self.input_signal_gatekeepers[4].clone(),
-                i,
-                self.shift_right.clone(),
-                shr_a_input_index,
-            );
+            if let Some(shift_right) = &self.shift_right {
+                connect_gates(
+                    self.complex_gate.input_gates[a_input_index].clone(),
+                    5,
+                    self.input_signal_gatekeepers[4].clone(),
+                    i,
+                );
 
-            connect_gates(
-                self.complex_gate.input_gates[a_input_index].clone(),
-                6,
-                self.input_signal_gatekeepers[5].clone(),
-                i,
-            );
+                let shr_a_input_index = shift_right.lock().unwrap().get_index_from_tag(input_tag.as_str());
+                connect_gates(
+                    self.input_signal_gatekeepers[4].clone(),
+                    i,
+                    shift_right.clone(),
+                    shr_a_input_index,
+                );
+            }
 
-            let adder_a_input_index = self.adder.lock().unwrap().get_index_from_tag(a_input_tag.as_str());
-            connect_gates(
-                self.input_signal_gatekeepers[5].clone(),
-                i,
-                self.adder.clone(),
-                adder_a_input_index,
-            );
+            if let Some(adder) = &self.adder {
+                connect_gates(
+                    self.complex_gate.input_gates[a_input_index].clone(),
+                    6,
+                    self.input_signal_gatekeepers[5].clone(),
+                    i,
+                );
+
+                let adder_a_input_index = adder.lock().unwrap().get_index_from_tag(a_input_tag.as_str());
+                connect_gates(
+                    self.input_signal_gatekeepers[5].clone(),
+                    i,
+                    adder.clone(),
+                    adder_a_input_index,
+                );
+            }
 
             //B Input -> Signal Gatekeepers & Signal Gatekeepers -> Arithmetic gates
 
             // Xor does not get a signal gatekeeper so that the larger and equal bits will be true.
-            let xor_b_input_index = self.xor_le.lock().unwrap().get_index_from_tag(b_input_tag.as_str());
-            connect_gates(
-                self.complex_gate.input_gates[b_input_index].clone(),
-                0,
-                self.xor_le.clone(),
-                xor_b_input_index,
-            );
+            if let Some(xor_le) = &self.xor_le {
+                let xor_b_input_index = xor_le.lock().unwrap().get_index_from_tag(b_input_tag.as_str());
+                connect_gates(
+                    self.complex_gate.input_gates[b_input_index].clone(),
+                    0,
+                    xor_le.clone(),
+                    xor_b_input_index,
+                );
+            }
 
-            connect_gates(
-                self.complex_gate.input_gates[b_input_index].clone(),
-                1,
-                self.input_signal_gatekeepers[6].clone(),
-                i,
-            );
+            if let Some(or) = &self.or {
+                connect_gates(
+                    self.complex_gate.input_gates[b_input_index].clone(),
+                    1,
+                    self.input_signal_gatekeepers[6].clone(),
+                    i,
+                );
 
-            let or_b_input_index = self.or.lock().unwrap().get_index_from_tag(b_input_tag.as_str());
-            connect_gates(
-                self.input_signal_gatekeepers[6].clone(),
-                i,
-                self.or.clone(),
-                or_b_input_index,
-            );
+                let or_b_input_index = or.lock().unwrap().get_index_from_tag(b_input_tag.as_str());
+                connect_gates(
+                    self.input_signal_gatekeepers[6].clone(),
+                    i,
+                    or.clone(),
+                    or_b_input_index,
+                );
+            }
 
-            connect_gates(
-                self.complex_gate.input_gates[b_input_index].clone(),
-                2,
-                self.input_signal_gatekeepers[7].clone(),
-                i,
-            );
+            if let Some(and) = &self.and {
+                connect_gates(
+                    self.complex_gate.input_gates[b_input_index].clone(),
+                    2,
+                    self.input_signal_gatekeepers[7].clone(),
+                    i,
+                );
 
-            let and_b_input_index = self.and.lock().unwrap().get_index_from_tag(b_input_tag.as_str());
-            connect_gates(
-                self.input_signal_gatekeepers[7].clone(),
-                i,
-                self.and.clone(),
-                and_b_input_index,
-            );
+                let and_b_input_index = and.lock().unwrap().get_index_from_tag(b_input_tag.as_str());
+                connect_gates(
+                    self.input_signal_gatekeepers[7].clone(),
+                    i,
+                    and.clone(),
+                    and_b_input_index,
+                );
+            }
 
-            connect_gates(
-                self.complex_gate.input_gates[b_input_index].clone(),
-                3,
-                self.input_signal_gatekeepers[8].clone(),
-                i,
-            );
+            if let Some(adder) = &self.adder {
+                connect_gates(
+                    self.complex_gate.input_gates[b_input_index].clone(),
+                    3,
+                    self.input_signal_gatekeepers[8].clone(),
+                    i,
+                );
 
-            let adder_b_input_index = self.adder.lock().unwrap().get_index_from_tag(b_input_tag.as_str());
-            connect_gates(
-                self.input_signal_gatekeepers[8].clone(),
-                i,
-                self.adder.clone(),
-                adder_b_input_index,
-            );
+                let adder_b_input_index = adder.lock().unwrap().get_index_from_tag(b_input_tag.as_str());
+                match (&self.subtract_b_splitter, &self.subtract_b_not, &self.subtract_b_multiplexer) {
+                    (Some(b_splitter), Some(b_not), Some(b_multiplexer)) => {
+                        //Signal Gatekeeper -> Splitter -> Multiplexer input 0 & Not -> Multiplexer
+                        // input 1 -> Adder. SUB selects the inverted b.
+                        connect_gates(
+                            self.input_signal_gatekeepers[8].clone(),
+                            i,
+                            b_splitter.clone(),
+                            i,
+                        );
+
+                        let splitter_output_index = b_splitter.lock().unwrap().get_index_for_output(i, 0);
+                        let multiplexer_input_index = b_multiplexer.lock().unwrap().get_index_from_tag(format!("I_0_bit_{}", i).as_str());
+                        connect_gates(
+                            b_splitter.clone(),
+                            splitter_output_index,
+                            b_multiplexer.clone(),
+                            multiplexer_input_index,
+                        );
+
+                        let splitter_output_index = b_splitter.lock().unwrap().get_index_for_output(i, 1);
+                        let not_input_index = b_not.lock().unwrap().get_index_from_tag(input_tag.as_str());
+                        connect_gates(
+                            b_splitter.clone(),
+                            splitter_output_index,
+                            b_not.clone(),
+                            not_input_index,
+                        );
+
+                        let not_output_index = b_not.lock().unwrap().get_index_from_tag(format!("o_{}", i).as_str());
+                        let multiplexer_input_index = b_multiplexer.lock().unwrap().get_index_from_tag(format!("I_1_bit_{}", i).as_str());
+                        connect_gates(
+                            b_not.clone(),
+                            not_output_index,
+                            b_multiplexer.clone(),
+                            multiplexer_input_index,
+                        );
+
+                        let multiplexer_output_index = b_multiplexer.lock().unwrap().get_index_from_tag(format!("o_{}", i).as_str());
+                        connect_gates(
+                            b_multiplexer.clone(),
+                            multiplexer_output_index,
+                            adder.clone(),
+                            adder_b_input_index,
+                        );
+                    }
+                    _ => {
+                        connect_gates(
+                            self.input_signal_gatekeepers[8].clone(),
+                            i,
+                            adder.clone(),
+                            adder_b_input_index,
+                        );
+                    }
+                }
+            }
 
             let output_tag = format!("o_{}", i);
 
             //Arithmetic gates -> Enable gates
-            let xor_output_index = self.xor_le.lock().unwrap().get_index_from_tag(output_tag.as_str());
-            let enable_input_index = self.enable_gates[0].lock().unwrap().get_index_from_tag(input_tag.as_str());
-            connect_gates(
-                self.xor_le.clone(),
-                xor_output_index,
-                self.enable_gates[0].clone(),
-                enable_input_index,
-            );
+            if let Some(xor_le) = &self.xor_le {
+                let xor_output_index = xor_le.lock().unwrap().get_index_from_tag(output_tag.as_str());
+                let enable_input_index = self.enable_gates[0].lock().unwrap().get_index_from_tag(input_tag.as_str());
+                connect_gates(
+                    xor_le.clone(),
+                    xor_output_index,
+                    self.enable_gates[0].clone(),
+                    enable_input_index,
+                );
+            }
 
-            let or_output_index = self.or.lock().unwrap().get_index_from_tag(output_tag.as_str());
-            let enable_input_index = self.enable_gates[1].lock().unwrap().get_index_from_tag(input_tag.as_str());
-            connect_gates(
-                self.or.clone(),
-                or_output_index,
-                self.enable_gates[1].clone(),
-                enable_input_index,
-            );
+            if let Some(or) = &self.or {
+                let or_output_index = or.lock().unwrap().get_index_from_tag(output_tag.as_str());
+                let enable_input_index = self.enable_gates[1].lock().unwrap().get_index_from_tag(input_tag.as_str());
+                connect_gates(
+                    or.clone(),
+                    or_output_index,
+                    self.enable_gates[1].clone(),
+                    enable_input_index,
+                );
+            }
 
-            let and_output_index = self.and.lock().unwrap().get_index_from_tag(output_tag.as_str());
-            let enable_input_index = self.enable_gates[2].lock().unwrap().get_index_from_tag(input_tag.as_str());
-            connect_gates(
-                self.and.clone(),
-                and_output_index,
-                self.enable_gates[2].clone(),
-                enable_input_index,
-            );
+            if let Some(and) = &self.and {
+                let and_output_index = and.lock().unwrap().get_index_from_tag(output_tag.as_str());
+                let enable_input_index = self.enable_gates[2].lock().unwrap().get_index_from_tag(input_tag.as_str());
+                connect_gates(
+                    and.clone(),
+                    and_output_index,
+                    self.enable_gates[2].clone(),
+                    enable_input_index,
+                );
+            }
 
-            let not_output_index = self.not.lock().unwrap().get_index_from_tag(output_tag.as_str());
-            let enable_input_index = self.enable_gates[3].lock().unwrap().get_index_from_tag(input_tag.as_str());
-            connect_gates(
-                self.not.clone(),
-                not_output_index,
-                self.enable_gates[3].clone(),
-                enable_input_index,
-            );
+            if let Some(not) = &self.not {
+                let not_output_index = not.lock().unwrap().get_index_from_tag(output_tag.as_str());
+                let enable_input_index = self.enable_gates[3].lock().unwrap().get_index_from_tag(input_tag.as_str());
+                connect_gates(
+                    not.clone(),
+                    not_output_index,
+                    self.enable_gates[3].clone(),
+                    enable_input_index,
+                );
+            }
 
-            let shl_output_index = self.shift_left.lock().unwrap().get_index_from_tag(output_tag.as_str());
-            let enable_input_index = self.enable_gates[4].lock().unwrap().get_index_from_tag(input_tag.as_str());
-            connect_gates(
-                self.shift_left.clone(),
-                shl_output_index,
-                self.enable_gates[4].clone(),
-                enable_input_index,
-            );
+            if let Some(shift_left) = &self.shift_left {
+                let shl_output_index = shift_left.lock().unwrap().get_index_from_tag(output_tag.as_str());
+                let enable_input_index = self.enable_gates[4].lock().unwrap().get_index_from_tag(input_tag.as_str());
+                connect_gates(
+                    shift_left.clone(),
+                    shl_output_index,
+                    self.enable_gates[4].clone(),
+                    enable_input_index,
+                );
+            }
 
-            let shr_output_index = self.shift_right.lock().unwrap().get_index_from_tag(output_tag.as_str());
-            let enable_input_index = self.enable_gates[5].lock().unwrap().get_index_from_tag(input_tag.as_str());
-            connect_gates(
-                self.shift_right.clone(),
-                shr_output_index,
-                self.enable_gates[5].clone(),
-                enable_input_index,
-            );
+            if let Some(shift_right) = &self.shift_right {
+                let shr_output_index = shift_right.lock().unwrap().get_index_from_tag(output_tag.as_str());
+                let enable_input_index = self.enable_gates[5].lock().unwrap().get_index_from_tag(input_tag.as_str());
+                connect_gates(
+                    shift_right.clone(),
+                    shr_output_index,
+                    self.enable_gates[5].clone(),
+                    enable_input_index,
+                );
+            }
 
-            let adder_output_index = self.adder.lock().unwrap().get_index_from_tag(output_tag.as_str());
-            let enable_input_index = self.enable_gates[6].lock().unwrap().get_index_from_tag(input_tag.as_str());
-            connect_gates(
-                self.adder.clone(),
-                adder_output_index,
-                self.enable_gates[6].clone(),
-                enable_input_index,
-            );
+            if let Some(adder) = &self.adder {
+                let adder_output_index = adder.lock().unwrap().get_index_from_tag(output_tag.as_str());
+                let enable_input_index = self.enable_gates[6].lock().unwrap().get_index_from_tag(input_tag.as_str());
+                connect_gates(
+                    adder.clone(),
+                    adder_output_index,
+                    self.enable_gates[6].clone(),
+                    enable_input_index,
+                );
+            }
 
             let z_input_index = self.z.lock().unwrap().get_index_from_tag(input_tag.as_str());
 
@@ -2537,77 +4616,160 @@ impl ArithmeticLogicUnit {
         }
 
 
-        //Carry In -> Signal gatekeepers & Signal gatekeepers -> Arithmetic gates
+        //SUB -> Not, the shifters only shift in C_IN (rotate through the carry) while SUB is LOW.
         connect_gates(
-            c_in_input_gate.clone(),
-            0,
-            self.carry_in_signal_gatekeepers[0].clone(),
+            sub_input_gate.clone(),
+            3,
+            self.shift_carry_in_not.clone(),
             0,
         );
 
 
-        let shift_left_shift_in_index = self.shift_left.lock().unwrap().get_index_from_tag("S_IN");
-        connect_gates(
-            self.carry_in_signal_gatekeepers[0].clone(),
-            0,
-            self.shift_left.clone(),
-            shift_left_shift_in_index,
-        );
+        //Carry In -> Signal gatekeepers & Signal gatekeepers & NOT(SUB) -> And -> Arithmetic gates
+        if let (Some(shift_left), Some(carry_in_and)) = (&self.shift_left, &self.shl_carry_in_and) {
+            connect_gates(
+                c_in_input_gate.clone(),
+                0,
+                self.carry_in_signal_gatekeepers[0].clone(),
+                0,
+            );
+
+            connect_gates(
+                self.carry_in_signal_gatekeepers[0].clone(),
+                0,
+                carry_in_and.clone(),
+                0,
+            );
+
+            connect_gates(
+                self.shift_carry_in_not.clone(),
+                0,
+                carry_in_and.clone(),
+                1,
+            );
+
+
+            let shift_left_shift_in_index = shift_left.lock().unwrap().get_index_from_tag("S_IN");
+            connect_gates(
+                carry_in_and.clone(),
+                0,
+                shift_left.clone(),
+                shift_left_shift_in_index,
+            );
+        }
+
+
+        if let (Some(shift_right), Some(carry_in_and)) = (&self.shift_right, &self.shr_carry_in_and) {
+            connect_gates(
+                c_in_input_gate.clone(),
+                1,
+                self.carry_in_signal_gatekeepers[1].clone(),
+                0,
+            );
+
+            connect_gates(
+                self.carry_in_signal_gatekeepers[1].clone(),
+                0,
+                carry_in_and.clone(),
+                0,
+            );
 
+            connect_gates(
+                self.shift_carry_in_not.clone(),
+                1,
+                carry_in_and.clone(),
+                1,
+            );
 
-        connect_gates(
-            c_in_input_gate.clone(),
-            0,
-            self.carry_in_signal_gatekeepers[1].clone(),
-            0,
-        );
 
+            let shift_right_shift_in_index = shift_right.lock().unwrap().get_index_from_tag("S_IN");
+            connect_gates(
+                carry_in_and.clone(),
+                0,
+                shift_right.clone(),
+                shift_right_shift_in_index,
+            );
+        }
 
-        let shift_right_shift_in_index = self.shift_right.lock().unwrap().get_index_from_tag("S_IN");
-        connect_gates(
-            self.carry_in_signal_gatekeepers[1].clone(),
-            0,
-            self.shift_right.clone(),
-            shift_right_shift_in_index,
-        );
 
+        if let Some(adder) = &self.adder {
+            connect_gates(
+                c_in_input_gate.clone(),
+                2,
+                self.carry_in_signal_gatekeepers[2].clone(),
+                0,
+            );
 
-        connect_gates(
-            c_in_input_gate.clone(),
-            0,
-            self.carry_in_signal_gatekeepers[2].clone(),
-            0,
-        );
 
+            let adder_carry_in_index = adder.lock().unwrap().get_index_from_tag("C_IN");
+            match &self.subtract_carry_in_or {
+                Some(carry_in_or) => {
+                    //Signal Gatekeeper & SUB -> Or -> Adder, subtracting forces the carry in HIGH.
+                    connect_gates(
+                        self.carry_in_signal_gatekeepers[2].clone(),
+                        0,
+                        carry_in_or.clone(),
+                        0,
+                    );
+
+                    connect_gates(
+                        sub_input_gate.clone(),
+                        0,
+                        carry_in_or.clone(),
+                        1,
+                    );
+
+                    connect_gates(
+                        carry_in_or.clone(),
+                        0,
+                        adder.clone(),
+                        adder_carry_in_index,
+                    );
+                }
+                None => {
+                    connect_gates(
+                        self.carry_in_signal_gatekeepers[2].clone(),
+                        0,
+                        adder.clone(),
+                        adder_carry_in_index,
+                    );
+                }
+            }
+        }
 
-        let adder_carry_in_index = self.adder.lock().unwrap().get_index_from_tag("C_IN");
-        connect_gates(
-            self.carry_in_signal_gatekeepers[2].clone(),
-            0,
-            self.adder.clone(),
-            adder_carry_in_index,
-        );
+        //SUB -> Multiplexer control line
+        if let Some(b_multiplexer) = &self.subtract_b_multiplexer {
+            let multiplexer_control_index = b_multiplexer.lock().unwrap().get_index_from_tag("C_0");
+            connect_gates(
+                sub_input_gate.clone(),
+                1,
+                b_multiplexer.clone(),
+                multiplexer_control_index,
+            );
+        }
 
 
         //Simple outputs
-        let xor_larger_output_index = self.xor_le.lock().unwrap().get_index_from_tag("L");
-        let a_larger_output_index = self.get_index_from_tag("A_L");
-        connect_gates(
-            self.xor_le.clone(),
-            xor_larger_output_index,
-            output_gates[a_larger_output_index].clone(),
-            0,
-        );
+        if let Some(xor_le) = &self.xor_le {
+            let xor_larger_output_index = xor_le.lock().unwrap().get_index_from_tag("L");
+            let a_larger_output_index = self.get_index_from_tag("A_L");
+            connect_gates(
+                xor_le.clone(),
+                xor_larger_output_index,
+                output_gates[a_larger_output_index].clone(),
+                0,
+            );
 
 
-        let xor_equal_output_index = self.xor_le.lock().unwrap().get_index_from_tag("E");
-        let equal_output_index = self.get_index_from_tag("EQ");
-        connect_gates(
-            self.xor_le.clone(),
-            xor_equal_output_index,
-            output_gates[equal_output_index].clone(),
-            0,
-        );
+            let xor_equal_output_index = xor_le.lock().unwrap().get_index_from_tag("E");
+            let equal_output_index = self.get_index_from_tag("EQ");
+            connect_gates(
+                xor_le.clone(),
+                xor_equal_output_index,
+                output_gates[equal_output_index].clone(),
+                0,
+            );
+        }
 
 
         let z_output_index = self.z.lock().unwrap().get_index_from_tag("O");
@@ -2621,33 +4783,138 @@ impl ArithmeticLogicUnit {
 
 
         //Shift/Carry out -> Controlled Buffers
-        let shl_shift_out_index = self.shift_left.lock().unwrap().get_index_from_tag("S_OUT");
+        // The shift out bits are also split off to the shift lost flag.
+        if let Some(shift_left) = &self.shift_left {
+            let shl_shift_out_index = shift_left.lock().unwrap().get_index_from_tag("S_OUT");
+            connect_gates(
+                shift_left.clone(),
+                shl_shift_out_index,
+                self.shl_shift_out_splitter.clone(),
+                0,
+            );
+
+            let splitter_output_index = self.shl_shift_out_splitter.lock().unwrap().get_index_for_output(
+                0, 0,
+            );
+            connect_gates(
+                self.shl_shift_out_splitter.clone(),
+                splitter_output_index,
+                self.shl_controlled_buffer.clone(),
+                0,
+            );
+        }
+
+
+        if let Some(shift_right) = &self.shift_right {
+            let shl_shift_out_index = shift_right.lock().unwrap().get_index_from_tag("S_OUT");
+            connect_gates(
+                shift_right.clone(),
+                shl_shift_out_index,
+                self.shr_shift_out_splitter.clone(),
+                0,
+            );
+
+            let splitter_output_index = self.shr_shift_out_splitter.lock().unwrap().get_index_for_output(
+                0, 0,
+            );
+            connect_gates(
+                self.shr_shift_out_splitter.clone(),
+                splitter_output_index,
+                self.shr_controlled_buffer.clone(),
+                0,
+            );
+        }
+
+
+        //Shift out -> Shift lost And gates -> Or -> Shift lost output
+        // The And gates are enabled by the shift decoder outputs so the flag is only HIGH when a set
+        // bit is shifted out during a shift operation.
+        if self.shift_left.is_some() {
+            let splitter_output_index = self.shl_shift_out_splitter.lock().unwrap().get_index_for_output(
+                0, 1,
+            );
+            connect_gates(
+                self.shl_shift_out_splitter.clone(),
+                splitter_output_index,
+                self.shl_shift_lost_and.clone(),
+                0,
+            );
+        }
+
+        if self.shift_right.is_some() {
+            let splitter_output_index = self.shr_shift_out_splitter.lock().unwrap().get_index_for_output(
+                0, 1,
+            );
+            connect_gates(
+                self.shr_shift_out_splitter.clone(),
+                splitter_output_index,
+                self.shr_shift_lost_and.clone(),
+                0,
+            );
+        }
+
         connect_gates(
-            self.shift_left.clone(),
-            shl_shift_out_index,
-            self.shl_controlled_buffer.clone(),
+            self.shl_shift_lost_and.clone(),
+            0,
+            self.shift_lost_or.clone(),
             0,
         );
 
-
-        let shl_shift_out_index = self.shift_right.lock().unwrap().get_index_from_tag("S_OUT");
         connect_gates(
-            self.shift_right.clone(),
-            shl_shift_out_index,
-            self.shr_controlled_buffer.clone(),
+            self.shr_shift_lost_and.clone(),
             0,
+            self.shift_lost_or.clone(),
+            1,
         );
 
-
-        let adder_carry_out_index = self.adder.lock().unwrap().get_index_from_tag("C_OUT");
+        let shift_lost_output_index = self.get_index_from_tag("SHIFT_LOST");
         connect_gates(
-            self.adder.clone(),
-            adder_carry_out_index,
-            self.adder_controlled_buffer.clone(),
+            self.shift_lost_or.clone(),
+            0,
+            output_gates[shift_lost_output_index].clone(),
             0,
         );
 
 
+        if let Some(adder) = &self.adder {
+            let adder_carry_out_index = adder.lock().unwrap().get_index_from_tag("C_OUT");
+            match &self.subtract_borrow_xor {
+                Some(borrow_xor) => {
+                    //Adder carry out & SUB -> XOr -> Controlled Buffer, when subtracting the carry
+                    // out is LOW exactly when the subtraction borrowed so it is inverted.
+                    connect_gates(
+                        adder.clone(),
+                        adder_carry_out_index,
+                        borrow_xor.clone(),
+                        0,
+                    );
+
+                    connect_gates(
+                        sub_input_gate.clone(),
+                        2,
+                        borrow_xor.clone(),
+                        1,
+                    );
+
+                    connect_gates(
+                        borrow_xor.clone(),
+                        0,
+                        self.adder_controlled_buffer.clone(),
+                        0,
+                    );
+                }
+                None => {
+                    connect_gates(
+                        adder.clone(),
+                        adder_carry_out_index,
+                        self.adder_controlled_buffer.clone(),
+                        0,
+                    );
+                }
+            }
+        }
+
+
         //Controlled Buffers -> Carry out output
         let alu_carry_output_index = self.get_index_from_tag("C_OUT");
         connect_gates(
@@ -2736,6 +5003,80 @@ impl ArithmeticLogicUnit {
         // Adder; Decoder input: 0; Enable Gate idx: 6; Splitter idx: 5; input_gatekeeper idx: 5 & 8; carry_gatekeeper idx:  2;
 
 
+        //Decoder output 7 is the None operation (HIGH, HIGH, HIGH), every other output is a real
+        // operation. Operations that were left out are not real operations either, so their decoder
+        // outputs are combined with the None operation before OP_VALID is inverted.
+        let splitter_ops_omitted = [
+            self.or.is_none(),
+            self.and.is_none(),
+            self.not.is_none(),
+            self.shift_left.is_none(),
+            self.shift_right.is_none(),
+            self.adder.is_none(),
+        ];
+
+        match &self.op_invalid_or {
+            Some(op_invalid_or) => {
+                connect_gates(
+                    self.decoder.clone(),
+                    7,
+                    op_invalid_or.clone(),
+                    0,
+                );
+
+                let mut op_invalid_or_input_index = 1;
+
+                //The enable gate of Xor has nothing to output when Xor is left out, so its decoder
+                // output is not needed there.
+                if self.xor_le.is_none() {
+                    connect_gates(
+                        self.decoder.clone(),
+                        6,
+                        op_invalid_or.clone(),
+                        op_invalid_or_input_index,
+                    );
+                    op_invalid_or_input_index += 1;
+                }
+
+                //Output 1 of each splitter is only used when the operation was built.
+                for (j, _) in splitter_ops_omitted.iter().enumerate().filter(|(_, &omitted)| omitted) {
+                    let decoder_splitter_output_index = self.decoder_splitters[j].lock().unwrap().get_index_for_output(
+                        0, 1,
+                    );
+                    connect_gates(
+                        self.decoder_splitters[j].clone(),
+                        decoder_splitter_output_index,
+                        op_invalid_or.clone(),
+                        op_invalid_or_input_index,
+                    );
+                    op_invalid_or_input_index += 1;
+                }
+
+                connect_gates(
+                    op_invalid_or.clone(),
+                    0,
+                    self.op_valid_not.clone(),
+                    0,
+                );
+            }
+            None => {
+                connect_gates(
+                    self.decoder.clone(),
+                    7,
+                    self.op_valid_not.clone(),
+                    0,
+                );
+            }
+        }
+
+        let op_valid_output_index = self.get_index_from_tag("OP_VALID");
+        connect_gates(
+            self.op_valid_not.clone(),
+            0,
+            output_gates[op_valid_output_index].clone(),
+            0,
+        );
+
         //Decoder -> Splitters
         for j in 0..6 {
             connect_gates(
@@ -2750,13 +5091,15 @@ impl ArithmeticLogicUnit {
 
 
         //Xor does not need a splitter
-        let enable_gate_enable_index = self.enable_gates[0].lock().unwrap().get_index_from_tag("E");
-        connect_gates(
-            self.decoder.clone(),
-            6,
-            self.enable_gates[0].clone(),
-            enable_gate_enable_index,
-        );
+        if self.xor_le.is_some() {
+            let enable_gate_enable_index = self.enable_gates[0].lock().unwrap().get_index_from_tag("E");
+            connect_gates(
+                self.decoder.clone(),
+                6,
+                self.enable_gates[0].clone(),
+                enable_gate_enable_index,
+            );
+        }
 
 
         //Or
@@ -2772,28 +5115,30 @@ impl ArithmeticLogicUnit {
         );
 
 
-        let decoder_splitter_output_index = self.decoder_splitters[0].lock().unwrap().get_index_for_output(
-            0, 1,
-        );
-        let gatekeeper_enable_index = self.input_signal_gatekeepers[0].lock().unwrap().get_index_from_tag("E");
-        connect_gates(
-            self.decoder_splitters[0].clone(),
-            decoder_splitter_output_index,
-            self.input_signal_gatekeepers[0].clone(),
-            gatekeeper_enable_index,
-        );
+        if self.or.is_some() {
+            let decoder_splitter_output_index = self.decoder_splitters[0].lock().unwrap().get_index_for_output(
+                0, 1,
+            );
+            let gatekeeper_enable_index = self.input_signal_gatekeepers[0].lock().unwrap().get_index_from_tag("E");
+            connect_gates(
+                self.decoder_splitters[0].clone(),
+                decoder_splitter_output_index,
+                self.input_signal_gatekeepers[0].clone(),
+                gatekeeper_enable_index,
+            );
 
 
-        let decoder_splitter_output_index = self.decoder_splitters[0].lock().unwrap().get_index_for_output(
-            0, 2,
-        );
-        let gatekeeper_enable_index = self.input_signal_gatekeepers[6].lock().unwrap().get_index_from_tag("E");
-        connect_gates(
-            self.decoder_splitters[0].clone(),
-            decoder_splitter_output_index,
-            self.input_signal_gatekeepers[6].clone(),
-            gatekeeper_enable_index,
-        );
+            let decoder_splitter_output_index = self.decoder_splitters[0].lock().unwrap().get_index_for_output(
+                0, 2,
+            );
+            let gatekeeper_enable_index = self.input_signal_gatekeepers[6].lock().unwrap().get_index_from_tag("E");
+            connect_gates(
+                self.decoder_splitters[0].clone(),
+                decoder_splitter_output_index,
+                self.input_signal_gatekeepers[6].clone(),
+                gatekeeper_enable_index,
+            );
+        }
 
 
         //And
@@ -2809,28 +5154,30 @@ impl ArithmeticLogicUnit {
         );
 
 
-        let decoder_splitter_output_index = self.decoder_splitters[1].lock().unwrap().get_index_for_output(
-            0, 1,
-        );
-        let gatekeeper_enable_index = self.input_signal_gatekeepers[1].lock().unwrap().get_index_from_tag("E");
-        connect_gates(
-            self.decoder_splitters[1].clone(),
-            decoder_splitter_output_index,
-            self.input_signal_gatekeepers[1].clone(),
-            gatekeeper_enable_index,
-        );
+        if self.and.is_some() {
+            let decoder_splitter_output_index = self.decoder_splitters[1].lock().unwrap().get_index_for_output(
+                0, 1,
+            );
+            let gatekeeper_enable_index = self.input_signal_gatekeepers[1].lock().unwrap().get_index_from_tag("E");
+            connect_gates(
+                self.decoder_splitters[1].clone(),
+                decoder_splitter_output_index,
+                self.input_signal_gatekeepers[1].clone(),
+                gatekeeper_enable_index,
+            );
 
 
-        let decoder_splitter_output_index = self.decoder_splitters[1].lock().unwrap().get_index_for_output(
-            0, 2,
-        );
-        let gatekeeper_enable_index = self.input_signal_gatekeepers[7].lock().unwrap().get_index_from_tag("E");
-        connect_gates(
-            self.decoder_splitters[1].clone(),
-            decoder_splitter_output_index,
-            self.input_signal_gatekeepers[7].clone(),
-            gatekeeper_enable_index,
-        );
+            let decoder_splitter_output_index = self.decoder_splitters[1].lock().unwrap().get_index_for_output(
+                0, 2,
+            );
+            let gatekeeper_enable_index = self.input_signal_gatekeepers[7].lock().unwrap().get_index_from_tag("E");
+            connect_gates(
+                self.decoder_splitters[1].clone(),
+                decoder_splitter_output_index,
+                self.input_signal_gatekeepers[7].clone(),
+                gatekeeper_enable_index,
+            );
+        }
 
 
         //Not
@@ -2846,16 +5193,18 @@ impl ArithmeticLogicUnit {
         );
 
 
-        let decoder_splitter_output_index = self.decoder_splitters[2].lock().unwrap().get_index_for_output(
-            0, 1,
-        );
-        let gatekeeper_enable_index = self.input_signal_gatekeepers[2].lock().unwrap().get_index_from_tag("E");
-        connect_gates(
-            self.decoder_splitters[2].clone(),
-            decoder_splitter_output_index,
-            self.input_signal_gatekeepers[2].clone(),
-            gatekeeper_enable_index,
-        );
+        if self.not.is_some() {
+            let decoder_splitter_output_index = self.decoder_splitters[2].lock().unwrap().get_index_for_output(
+                0, 1,
+            );
+            let gatekeeper_enable_index = self.input_signal_gatekeepers[2].lock().unwrap().get_index_from_tag("E");
+            connect_gates(
+                self.decoder_splitters[2].clone(),
+                decoder_splitter_output_index,
+                self.input_signal_gatekeepers[2].clone(),
+                gatekeeper_enable_index,
+            );
+        }
 
         //Shift Left
         let decoder_splitter_output_index = self.decoder_splitters[3].lock().unwrap().get_index_for_output(
@@ -2869,37 +5218,49 @@ impl ArithmeticLogicUnit {
             enable_gate_enable_index,
         );
 
-        let decoder_splitter_output_index = self.decoder_splitters[3].lock().unwrap().get_index_for_output(
-            0, 1,
-        );
-        let gatekeeper_enable_index = self.input_signal_gatekeepers[3].lock().unwrap().get_index_from_tag("E");
-        connect_gates(
-            self.decoder_splitters[3].clone(),
-            decoder_splitter_output_index,
-            self.input_signal_gatekeepers[3].clone(),
-            gatekeeper_enable_index,
-        );
+        if self.shift_left.is_some() {
+            let decoder_splitter_output_index = self.decoder_splitters[3].lock().unwrap().get_index_for_output(
+                0, 1,
+            );
+            let gatekeeper_enable_index = self.input_signal_gatekeepers[3].lock().unwrap().get_index_from_tag("E");
+            connect_gates(
+                self.decoder_splitters[3].clone(),
+                decoder_splitter_output_index,
+                self.input_signal_gatekeepers[3].clone(),
+                gatekeeper_enable_index,
+            );
+
+            let decoder_splitter_output_index = self.decoder_splitters[3].lock().unwrap().get_index_for_output(
+                0, 2,
+            );
+            let gatekeeper_enable_index = self.carry_in_signal_gatekeepers[0].lock().unwrap().get_index_from_tag("E");
+            connect_gates(
+                self.decoder_splitters[3].clone(),
+                decoder_splitter_output_index,
+                self.carry_in_signal_gatekeepers[0].clone(),
+                gatekeeper_enable_index,
+            );
+        }
 
         let decoder_splitter_output_index = self.decoder_splitters[3].lock().unwrap().get_index_for_output(
-            0, 2,
+            0, 3,
         );
-        let gatekeeper_enable_index = self.carry_in_signal_gatekeepers[0].lock().unwrap().get_index_from_tag("E");
+        let controlled_buffer_enable_index = self.shl_controlled_buffer.lock().unwrap().get_index_from_tag("E");
         connect_gates(
             self.decoder_splitters[3].clone(),
             decoder_splitter_output_index,
-            self.carry_in_signal_gatekeepers[0].clone(),
-            gatekeeper_enable_index,
+            self.shl_controlled_buffer.clone(),
+            controlled_buffer_enable_index,
         );
 
         let decoder_splitter_output_index = self.decoder_splitters[3].lock().unwrap().get_index_for_output(
-            0, 3,
+            0, 4,
         );
-        let controlled_buffer_enable_index = self.shl_controlled_buffer.lock().unwrap().get_index_from_tag("E");
         connect_gates(
             self.decoder_splitters[3].clone(),
             decoder_splitter_output_index,
-            self.shl_controlled_buffer.clone(),
-            controlled_buffer_enable_index,
+            self.shl_shift_lost_and.clone(),
+            1,
         );
 
         //Shift right
@@ -2914,84 +5275,98 @@ impl ArithmeticLogicUnit {
             enable_gate_enable_index,
         );
 
-        let decoder_splitter_output_index = self.decoder_splitters[4].lock().unwrap().get_index_for_output(
-            0, 1,
-        );
-        let gatekeeper_enable_index = self.input_signal_gatekeepers[4].lock().unwrap().get_index_from_tag("E");
-        connect_gates(
-            self.decoder_splitters[4].clone(),
-            decoder_splitter_output_index,
-            self.input_signal_gatekeepers[4].clone(),
-            gatekeeper_enable_index,
-        );
+        if self.shift_right.is_some() {
+            let decoder_splitter_output_index = self.decoder_splitters[4].lock().unwrap().get_index_for_output(
+                0, 1,
+            );
+            let gatekeeper_enable_index = self.input_signal_gatekeepers[4].lock().unwrap().get_index_from_tag("E");
+            connect_gates(
+                self.decoder_splitters[4].clone(),
+                decoder_splitter_output_index,
+                self.input_signal_gatekeepers[4].clone(),
+                gatekeeper_enable_index,
+            );
 
-        let decoder_splitter_output_index = self.decoder_splitters[4].lock().unwrap().get_index_for_output(
-            0, 2,
-        );
-        let gatekeeper_enable_index = self.carry_in_signal_gatekeepers[1].lock().unwrap().get_index_from_tag("E");
-        connect_gates(
-            self.decoder_splitters[4].clone(),
-            decoder_splitter_output_index,
-            self.carry_in_signal_gatekeepers[1].clone(),
-            gatekeeper_enable_index,
-        );
+            let decoder_splitter_output_index = self.decoder_splitters[4].lock().unwrap().get_index_for_output(
+                0, 2,
+            );
+            let gatekeeper_enable_index = self.carry_in_signal_gatekeepers[1].lock().unwrap().get_index_from_tag("E");
+            connect_gates(
+                self.decoder_splitters[4].clone(),
+                decoder_splitter_output_index,
+                self.carry_in_signal_gatekeepers[1].clone(),
+                gatekeeper_enable_index,
+            );
+        }
 
         let decoder_splitter_output_index = self.decoder_splitters[4].lock().unwrap().get_index_for_output(
-            0, 3,
-        );
-        let controlled_buffer_enable_index = self.shr_controlled_buffer.lock().unwrap().get_index_from_tag("E");
-        connect_gates(
-            self.decoder_splitters[4].clone(),
-            decoder_splitter_output_index,
-            self.shr_controlled_buffer.clone(),
-            controlled_buffer_enable_index,
-        );
-
-        //Adder
-        let decoder_splitter_output_index = self.decoder_splitters[5].lock().unwrap().get_index_for_output(
-            0, 0,
-        );
-        let enable_gate_enable_index = self.enable_gates[6].lock().unwrap().get_index_from_tag("E");
-        connect_gates(
-            self.decoder_splitters[5].clone(),
-            decoder_splitter_output_index,
-            self.enable_gates[6].clone(),
-            enable_gate_enable_index,
-        );
-
-        let decoder_splitter_output_index = self.decoder_splitters[5].lock().unwrap().get_index_for_output(
-            0, 1,
+            0, 3,
         );
-        let gatekeeper_enable_index = self.input_signal_gatekeepers[5].lock().unwrap().get_index_from_tag("E");
+        let controlled_buffer_enable_index = self.shr_controlled_buffer.lock().unwrap().get_index_from_tag("E");
         connect_gates(
-            self.decoder_splitters[5].clone(),
+            self.decoder_splitters[4].clone(),
             decoder_splitter_output_index,
-            self.input_signal_gatekeepers[5].clone(),
-            gatekeeper_enable_index,
+            self.shr_controlled_buffer.clone(),
+            controlled_buffer_enable_index,
         );
 
-        let decoder_splitter_output_index = self.decoder_splitters[5].lock().unwrap().get_index_for_output(
-            0, 2,
+        let decoder_splitter_output_index = self.decoder_splitters[4].lock().unwrap().get_index_for_output(
+            0, 4,
         );
-        let gatekeeper_enable_index = self.input_signal_gatekeepers[8].lock().unwrap().get_index_from_tag("E");
         connect_gates(
-            self.decoder_splitters[5].clone(),
+            self.decoder_splitters[4].clone(),
             decoder_splitter_output_index,
-            self.input_signal_gatekeepers[8].clone(),
-            gatekeeper_enable_index,
+            self.shr_shift_lost_and.clone(),
+            1,
         );
 
+        //Adder
         let decoder_splitter_output_index = self.decoder_splitters[5].lock().unwrap().get_index_for_output(
-            0, 3,
+            0, 0,
         );
-        let gatekeeper_enable_index = self.carry_in_signal_gatekeepers[2].lock().unwrap().get_index_from_tag("E");
+        let enable_gate_enable_index = self.enable_gates[6].lock().unwrap().get_index_from_tag("E");
         connect_gates(
             self.decoder_splitters[5].clone(),
             decoder_splitter_output_index,
-            self.carry_in_signal_gatekeepers[2].clone(),
-            gatekeeper_enable_index,
+            self.enable_gates[6].clone(),
+            enable_gate_enable_index,
         );
 
+        if self.adder.is_some() {
+            let decoder_splitter_output_index = self.decoder_splitters[5].lock().unwrap().get_index_for_output(
+                0, 1,
+            );
+            let gatekeeper_enable_index = self.input_signal_gatekeepers[5].lock().unwrap().get_index_from_tag("E");
+            connect_gates(
+                self.decoder_splitters[5].clone(),
+                decoder_splitter_output_index,
+                self.input_signal_gatekeepers[5].clone(),
+                gatekeeper_enable_index,
+            );
+
+            let decoder_splitter_output_index = self.decoder_splitters[5].lock().unwrap().get_index_for_output(
+                0, 2,
+            );
+            let gatekeeper_enable_index = self.input_signal_gatekeepers[8].lock().unwrap().get_index_from_tag("E");
+            connect_gates(
+                self.decoder_splitters[5].clone(),
+                decoder_splitter_output_index,
+                self.input_signal_gatekeepers[8].clone(),
+                gatekeeper_enable_index,
+            );
+
+            let decoder_splitter_output_index = self.decoder_splitters[5].lock().unwrap().get_index_for_output(
+                0, 3,
+            );
+            let gatekeeper_enable_index = self.carry_in_signal_gatekeepers[2].lock().unwrap().get_index_from_tag("E");
+            connect_gates(
+                self.decoder_splitters[5].clone(),
+                decoder_splitter_output_index,
+                self.carry_in_signal_gatekeepers[2].clone(),
+                gatekeeper_enable_index,
+            );
+        }
+
         let decoder_splitter_output_index = self.decoder_splitters[5].lock().unwrap().get_index_for_output(
             0, 4,
         );
@@ -3008,6 +5383,46 @@ impl ArithmeticLogicUnit {
             true,
         );
     }
+
+    //Caps the number of passes made through the circuit each time the output is fetched. When the
+    // ALU has not settled inside the budget, the output is only partially calculated and
+    // needs_more() returns true. Fetching the output again continues from where it stopped. None
+    // removes the cap.
+    pub fn set_settle_budget(&mut self, passes: Option<usize>) {
+        if let Some(passes) = passes {
+            assert_ne!(passes, 0);
+        }
+
+        self.settle_budget = passes;
+    }
+
+    pub fn needs_more(&self) -> bool {
+        !self.unsettled_gates.is_empty()
+    }
+
+    fn fetch_output_signals_within_settle_budget(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
+        let mut start_gates = self.complex_gate.input_gates.clone();
+        start_gates.append(&mut self.unsettled_gates);
+
+        let (_continue_clock, unsettled_gates) = run_circuit_with_pass_budget(
+            &start_gates,
+            &self.complex_gate.output_gates,
+            false,
+            self.settle_budget,
+            &mut |_clock_tick_inputs, _output_gates| {},
+        );
+
+        self.unsettled_gates = unsettled_gates;
+
+        //If a logic error occurred inside the circuit, the outputs are not valid.
+        if let Some(logic_error) = get_pending_logic_error() {
+            return Err(logic_error);
+        }
+
+        self.complex_gate.fetch_output_signals_no_calculate(
+            &self.get_tag(),
+        )
+    }
 }
 
 impl LogicGate for ArithmeticLogicUnit {
@@ -3031,9 +5446,15 @@ impl LogicGate for ArithmeticLogicUnit {
     fn fetch_output_signals_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
         let start = Instant::now();
 
-        let result = self.complex_gate.fetch_output_signals_calculate(
-            &self.get_tag(),
-        );
+        //Gates left over from a previous fetch that ran out of budget must still be propagated
+        // even if the budget has since been removed.
+        let result = if self.settle_budget.is_some() || self.needs_more() {
+            self.fetch_output_signals_within_settle_budget()
+        } else {
+            self.complex_gate.fetch_output_signals_calculate(
+                &self.get_tag(),
+            )
+        };
 
         unsafe {
             ALU_TIME += start.elapsed();
@@ -3072,6 +5493,14 @@ impl LogicGate for ArithmeticLogicUnit {
         self.complex_gate.get_index_from_tag(tag)
     }
 
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         self.complex_gate.remove_connected_input(input_index, connected_id);
     }
@@ -3084,9 +5513,29 @@ impl LogicGate for ArithmeticLogicUnit {
         self.complex_gate.simple_gate.number_child_gates
     }
 
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         self.complex_gate.input_gates.clone()
     }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
 }
 
 #[cfg(test)]
@@ -3095,7 +5544,9 @@ mod tests {
     use rand::Rng;
     use crate::logic::foundations::Signal;
     use crate::logic::foundations::Signal::{HIGH, LOW_, NONE};
-    use crate::test_stuff::run_multi_input_output_logic_gate;
+    use crate::logic::foundations::extract_signals_from_gate_output_states;
+    use crate::run_circuit::{feedback_cycles, gates_changed_between, run_circuit, topological_order, Snapshot};
+    use crate::test_stuff::{run_multi_input_output_logic_gate, run_multi_input_output_logic_gate_return, transistor_count};
     use super::*;
 
     fn test_half_adder(
@@ -3206,9 +5657,17 @@ mod tests {
     fn run_alu(
         num_bits: usize,
         opt: AluOperations,
-        mut gen_randoms_result: GenerateRandomReturns,
+        gen_randoms_result: GenerateRandomReturns,
     ) {
-        println!("result_num: {:#?}", gen_randoms_result);
+        run_alu_with_carry_in(num_bits, opt, gen_randoms_result, LOW_);
+    }
+
+    fn run_alu_with_carry_in(
+        num_bits: usize,
+        opt: AluOperations,
+        mut gen_randoms_result: GenerateRandomReturns,
+        carry_in: Signal,
+    ) -> Vec<Signal> {
         gen_randoms_result.output.push(
             convert_bool_to_signal(gen_randoms_result.a_input > gen_randoms_result.b_input)
         );  //A Larger  (A_L)
@@ -3221,17 +5680,18 @@ mod tests {
         gen_randoms_result.output.push(gen_randoms_result.carry_out); //Carry Out (C_OUT)
 
 
-        let alu = ArithmeticLogicUnit::new(num_bits);
+        let alu = ArithmeticLogicUnit::new(num_bits).unwrap();
 
 
         let alu_operation = AluOperations::get_vectors(opt);
 
+        let expected_output = vec![
+            gen_randoms_result.output //A_L, EQ, Z, C_OUT;
+        ];
 
-        run_multi_input_output_logic_gate(
+        let mut collected_output = run_multi_input_output_logic_gate_return(
             vec![],
-            vec![
-                gen_randoms_result.output //A_L, EQ, Z, C_OUT;
-            ],
+            &expected_output,
             HashMap::from(
                 [
                     ("a", vec![gen_randoms_result.a_input_signals]),
@@ -3239,11 +5699,16 @@ mod tests {
                     ("A", vec![alu_operation.a]),
                     ("B", vec![alu_operation.b]),
                     ("C", vec![alu_operation.c]),
-                    ("C_IN", vec![vec![LOW_]]),
+                    ("C_IN", vec![vec![carry_in]]),
+                    ("SUB", vec![alu_operation.sub]),
                 ]
             ),
             alu,
         );
+
+        assert_eq!(collected_output, expected_output);
+
+        collected_output.pop().unwrap()
     }
 
     fn generate_random_xor_le_inputs_outputs(
@@ -3410,6 +5875,36 @@ mod tests {
         )
     }
 
+    fn generate_rotate_through_carry_inputs_outputs(
+        num_bits: usize,
+        first_num: usize,
+        carry_in: bool,
+        left_shift: bool,
+    ) -> GenerateRandomReturns {
+        let high_bit = 1 << (num_bits - 1);
+        let carry_in_num = carry_in as usize;
+
+        let (result, carry_out) =
+            if left_shift {
+                (((first_num << 1) | carry_in_num) & ((high_bit << 1) - 1), first_num & high_bit != 0)
+            } else {
+                ((first_num >> 1) | (carry_in_num * high_bit), first_num & 1 != 0)
+            };
+
+        let first_binary = format!("{:0width$b}", first_num, width = num_bits);
+        let result_binary = format!("{:0width$b}", result, width = num_bits);
+
+        GenerateRandomReturns::new(
+            first_num,
+            0,
+            result,
+            convert_binary_to_vec(&first_binary),
+            vec![LOW_; num_bits],
+            convert_binary_to_vec(&result_binary),
+            convert_bool_to_signal(carry_out),
+        )
+    }
+
     fn generate_randoms_adder_inputs_outputs(num_bits: usize) -> GenerateRandomReturns {
         let high_number_range = usize::pow(2, num_bits as u32);
         let first_num = rand::thread_rng().gen_range(0..high_number_range);
@@ -3449,6 +5944,455 @@ mod tests {
         )
     }
 
+    fn generate_subtract_inputs_outputs(
+        num_bits: usize,
+        first_num: usize,
+        second_num: usize,
+    ) -> GenerateRandomReturns {
+        let high_number_range = usize::pow(2, num_bits as u32);
+        let difference = (first_num + high_number_range - second_num) % high_number_range;
+
+        let first_binary = format!("{:0width$b}", first_num, width = num_bits);
+        let second_binary = format!("{:0width$b}", second_num, width = num_bits);
+        let difference_binary = format!("{:0width$b}", difference, width = num_bits);
+
+        //Leave these here in case it fails the number will be reproducible.
+        println!("num_bits: {}", num_bits);
+        println!("{}", first_binary);
+        println!("{}", second_binary);
+        println!("{}", difference_binary);
+
+        GenerateRandomReturns::new(
+            first_num,
+            second_num,
+            difference,
+            convert_binary_to_vec(&first_binary),
+            convert_binary_to_vec(&second_binary),
+            convert_binary_to_vec(&difference_binary),
+            convert_bool_to_signal(second_num > first_num),
+        )
+    }
+
+    fn run_sign_magnitude_to_twos_complement(
+        num_bits: usize,
+        sign_magnitude: usize,
+        twos_complement: usize,
+    ) {
+        let number_to_signals = |number: usize| -> Vec<Signal> {
+            (0..num_bits)
+                .map(|i| if (number >> i) & 1 == 1 { HIGH } else { LOW_ })
+                .collect()
+        };
+
+        let converter = SignMagnitudeToTwosComplement::new(num_bits);
+
+        run_multi_input_output_logic_gate(
+            vec![number_to_signals(sign_magnitude)],
+            vec![number_to_signals(twos_complement)],
+            HashMap::new(),
+            converter,
+        );
+    }
+
+    #[test]
+    fn sign_magnitude_to_twos_complement_positive() {
+        run_sign_magnitude_to_twos_complement(4, 0b0000, 0b0000);
+        run_sign_magnitude_to_twos_complement(4, 0b0101, 0b0101);
+        run_sign_magnitude_to_twos_complement(4, 0b0111, 0b0111);
+        run_sign_magnitude_to_twos_complement(8, 0b0110_0001, 0b0110_0001);
+    }
+
+    #[test]
+    fn sign_magnitude_to_twos_complement_negative() {
+        //-1
+        run_sign_magnitude_to_twos_complement(4, 0b1001, 0b1111);
+        //-5
+        run_sign_magnitude_to_twos_complement(4, 0b1101, 0b1011);
+        //-7
+        run_sign_magnitude_to_twos_complement(4, 0b1111, 0b1001);
+        //-97
+        run_sign_magnitude_to_twos_complement(8, 0b1110_0001, 0b1001_1111);
+    }
+
+    #[test]
+    fn sign_magnitude_to_twos_complement_negative_zero() {
+        run_sign_magnitude_to_twos_complement(4, 0b1000, 0b0000);
+        run_sign_magnitude_to_twos_complement(8, 0b1000_0000, 0b0000_0000);
+    }
+
+    #[test]
+    fn variable_bit_adder_topological_order() {
+        let num_bits = 4;
+        let adder = VariableBitAdder::new(num_bits).unwrap();
+        let adder = adder.lock().unwrap();
+
+        let order = topological_order(&*adder);
+        assert!(feedback_cycles(&*adder).is_empty());
+
+        let position = |gate_id: UniqueID| {
+            order.iter().position(|&id| id == gate_id).unwrap()
+        };
+
+        let full_adder_positions: Vec<usize> = adder.full_adders
+            .iter()
+            .map(|full_adder| position(full_adder.lock().unwrap().get_unique_id()))
+            .collect();
+
+        for input_gate in adder.complex_gate.input_gates.iter() {
+            let input_position = position(input_gate.lock().unwrap().get_unique_id());
+            assert!(input_position < full_adder_positions[0]);
+        }
+
+        for i in 1..num_bits {
+            assert!(full_adder_positions[i - 1] < full_adder_positions[i]);
+        }
+
+        //Each sum output comes after its own full adder and the carry out comes after the last one.
+        for (i, output_gate) in adder.complex_gate.output_gates.iter().enumerate() {
+            let output_position = position(output_gate.lock().unwrap().get_unique_id());
+            assert!(full_adder_positions[i.min(num_bits - 1)] < output_position);
+        }
+    }
+
+    #[test]
+    fn half_adder_generated_logic_gate_impl() {
+        let half_adder = HalfAdder::new();
+        let mut half_adder = half_adder.lock().unwrap();
+
+        assert_eq!(half_adder.get_gate_type(), GateType::HalfAdderType);
+        assert_eq!(half_adder.get_unique_id(), half_adder.complex_gate.simple_gate.unique_id);
+        assert_eq!(half_adder.get_tags(GateTagType::Input), vec!["A", "B"]);
+        assert_eq!(half_adder.get_tags(GateTagType::Output), vec!["S", "C"]);
+        assert_eq!(half_adder.get_index_from_tag("B"), 1);
+        assert_eq!(half_adder.get_index_from_tag("C"), 1);
+        assert_eq!(half_adder.get_input_gates().len(), 2);
+
+        //Inputs A and B, the xor and and gates and outputs S and C.
+        assert_eq!(half_adder.num_children_gates(), 6);
+        assert_eq!(half_adder.num_unique_gates(), 6);
+
+        half_adder.set_tag("half_adder");
+        assert_eq!(half_adder.get_tag(), "half_adder");
+
+        let a_index = half_adder.get_index_from_tag("A");
+        let b_index = half_adder.get_index_from_tag("B");
+        half_adder.update_input_signal(GateInput::new(a_index, HIGH, UniqueID::zero_id()));
+        half_adder.update_input_signal(GateInput::new(b_index, HIGH, UniqueID::zero_id()));
+
+        let output = half_adder.fetch_output_signals_calculate().unwrap();
+        assert_eq!(extract_signals_from_gate_output_states(&output), vec![LOW_, HIGH]);
+
+        let output = half_adder.fetch_output_signals_no_calculate().unwrap();
+        assert_eq!(extract_signals_from_gate_output_states(&output), vec![LOW_, HIGH]);
+    }
+
+    #[test]
+    fn variable_bit_adder_gates_changed_between() {
+        let adder = VariableBitAdder::new(4).unwrap();
+        let mut adder = adder.lock().unwrap();
+
+        let before_tick = Snapshot::capture(&*adder);
+
+        //Setting a_0 only changes the sum of the first full adder, the carry stays LOW.
+        let a_0_index = adder.get_index_from_tag("a_0");
+        adder.update_input_signal(GateInput::new(a_0_index, HIGH, UniqueID::zero_id()));
+        adder.fetch_output_signals_calculate().unwrap();
+
+        let after_tick = Snapshot::capture(&*adder);
+
+        let o_0_index = adder.get_index_from_tag("o_0");
+        let mut expected_changed_gates = vec![
+            adder.complex_gate.input_gates[a_0_index].lock().unwrap().get_unique_id(),
+            adder.full_adders[0].lock().unwrap().get_unique_id(),
+            adder.complex_gate.output_gates[o_0_index].lock().unwrap().get_unique_id(),
+        ];
+        expected_changed_gates.sort_by_key(|gate_id| gate_id.id());
+
+        assert_eq!(gates_changed_between(&before_tick, &after_tick), expected_changed_gates);
+        assert!(gates_changed_between(&after_tick, &after_tick).is_empty());
+    }
+
+    //The zero detector is fed from the enable splitters rather than from the output bus itself. Drives
+    // random operations and operands through the alu and checks that Z is only HIGH when every
+    // output bit is LOW.
+    fn verify_z_flag_consistency(alu: &SharedMutex<ArithmeticLogicUnit>, num_samples: usize) {
+        let mut alu = alu.lock().unwrap();
+        //a, b, then A, B, C and C_IN.
+        let num_bits = (alu.num_inputs() - 4) / 2;
+        let mut rng = rand::thread_rng();
+
+        let set_input = |alu: &mut MutexGuard<ArithmeticLogicUnit>, tag: &str, signal: Signal| {
+            let input_index = alu.get_index_from_tag(tag);
+            alu.update_input_signal(GateInput::new(input_index, signal, UniqueID::zero_id()));
+        };
+
+        for _ in 0..num_samples {
+            //Only operations that drive the output bus.
+            let opt = match rng.gen_range(0..7) {
+                0 => AluOperations::XOrLe,
+                1 => AluOperations::Or,
+                2 => AluOperations::And,
+                3 => AluOperations::Not,
+                4 => AluOperations::Shl,
+                5 => AluOperations::Shr,
+                _ => AluOperations::Adder,
+            };
+
+            //Switching directly between two operations briefly has both enable gates driving Z, so
+            // the alu is turned off in between.
+            let alu_off = AluOperations::get_vectors(AluOperations::None);
+            set_input(&mut alu, "A", alu_off.a[0].clone());
+            set_input(&mut alu, "B", alu_off.b[0].clone());
+            set_input(&mut alu, "C", alu_off.c[0].clone());
+            alu.fetch_output_signals_calculate().unwrap();
+
+            let alu_operation = AluOperations::get_vectors(opt);
+            set_input(&mut alu, "A", alu_operation.a[0].clone());
+            set_input(&mut alu, "B", alu_operation.b[0].clone());
+            set_input(&mut alu, "C", alu_operation.c[0].clone());
+            set_input(&mut alu, "C_IN", convert_bool_to_signal(rng.gen()));
+
+            //Zero is weighted so that Z is HIGH often enough to be checked.
+            for input_name in ["a", "b"] {
+                let zero_operand = rng.gen_bool(0.25);
+                for i in 0..num_bits {
+                    let signal = convert_bool_to_signal(!zero_operand && rng.gen());
+                    set_input(&mut alu, format!("{}_{}", input_name, i).as_str(), signal);
+                }
+            }
+
+            let output = alu.fetch_output_signals_calculate().unwrap();
+            let output = extract_signals_from_gate_output_states(&output);
+
+            let output_bus: Vec<Signal> = (0..num_bits)
+                .map(|i| output[alu.get_index_from_tag(format!("o_{}", i).as_str())].clone())
+                .collect();
+            let z = output[alu.get_index_from_tag("Z")].clone();
+
+            assert_eq!(
+                z,
+                convert_bool_to_signal(output_bus.iter().all(|signal| *signal == LOW_)),
+                "output bus {:?}", output_bus
+            );
+        }
+    }
+
+    #[test]
+    fn alu_z_flag_matches_output_bus() {
+        let num_bits = rand::thread_rng().gen_range(2..9);
+        let alu = ArithmeticLogicUnit::new(num_bits).unwrap();
+
+        verify_z_flag_consistency(&alu, 64);
+    }
+
+    #[test]
+    fn alu_settle_budget() {
+        let num_bits = 16;
+        let alu = ArithmeticLogicUnit::new(num_bits).unwrap();
+        let mut alu = alu.lock().unwrap();
+
+        //A, B and C all LOW selects the adder. 0xFFFF + 1 carries through every bit.
+        for tag in ["A", "B", "C", "C_IN"] {
+            let input_index = alu.get_index_from_tag(tag);
+            alu.update_input_signal(GateInput::new(input_index, LOW_, UniqueID::zero_id()));
+        }
+        for i in 0..num_bits {
+            let a_index = alu.get_index_from_tag(format!("a_{}", i).as_str());
+            alu.update_input_signal(GateInput::new(a_index, HIGH, UniqueID::zero_id()));
+        }
+        let b_0_index = alu.get_index_from_tag("b_0");
+        alu.update_input_signal(GateInput::new(b_0_index, HIGH, UniqueID::zero_id()));
+
+        let c_out_index = alu.get_index_from_tag("C_OUT");
+
+        //A single pass only reaches the gates directly behind the inputs.
+        alu.set_settle_budget(Some(1));
+        let partial_output = extract_signals_from_gate_output_states(&alu.fetch_output_signals_calculate().unwrap());
+
+        assert!(alu.needs_more());
+        assert_ne!(partial_output[c_out_index], HIGH);
+
+        alu.set_settle_budget(Some(1000));
+        let output = extract_signals_from_gate_output_states(&alu.fetch_output_signals_calculate().unwrap());
+
+        assert!(!alu.needs_more());
+        assert_ne!(partial_output, output);
+        assert_eq!(output[c_out_index], HIGH);
+        for i in 0..num_bits {
+            let output_index = alu.get_index_from_tag(format!("o_{}", i).as_str());
+            assert_eq!(output[output_index], LOW_);
+        }
+    }
+
+    #[test]
+    fn cascadable_comparator_two_bits() {
+        let high_bit_comparator = CascadableComparator::new();
+        let low_bit_comparator = CascadableComparator::new();
+
+        for tag in ["EQUAL", "A_LARGER"] {
+            let output_index = high_bit_comparator.lock().unwrap().get_index_from_tag(format!("{}_OUT", tag).as_str());
+            let input_index = low_bit_comparator.lock().unwrap().get_index_from_tag(format!("{}_IN", tag).as_str());
+            connect_gates(
+                high_bit_comparator.clone(),
+                output_index,
+                low_bit_comparator.clone(),
+                input_index,
+            );
+        }
+
+        let set_input = |comparator: &SharedMutex<CascadableComparator>, tag: &str, signal: Signal| {
+            let idx = comparator.lock().unwrap().get_index_from_tag(tag);
+            comparator.lock().unwrap().update_input_signal(
+                GateInput::new(
+                    idx,
+                    signal,
+                    UniqueID::zero_id(),
+                )
+            );
+        };
+
+        //Nothing is above the most significant bit so it starts out equal.
+        set_input(&high_bit_comparator, "EQUAL_IN", HIGH);
+        set_input(&high_bit_comparator, "A_LARGER_IN", LOW_);
+
+        let input_gates: Vec<SharedMutex<dyn LogicGate>> = vec![high_bit_comparator.clone(), low_bit_comparator.clone()];
+        let output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
+        for a in 0..4 {
+            for b in 0..4 {
+                set_input(&high_bit_comparator, "A", convert_num_to_signal(a >> 1));
+                set_input(&high_bit_comparator, "B", convert_num_to_signal(b >> 1));
+                set_input(&low_bit_comparator, "A", convert_num_to_signal(a & 1));
+                set_input(&low_bit_comparator, "B", convert_num_to_signal(b & 1));
+
+                run_circuit(
+                    &input_gates,
+                    &output_gates,
+                    false,
+                    &mut |_clock_tick_inputs, _output_gates| {},
+                );
+
+                let output = low_bit_comparator.lock().unwrap().fetch_output_signals_no_calculate().unwrap();
+                let output = extract_signals_from_gate_output_states(&output);
+
+                //The high bit decides the result, for example 2 (10) is larger than 1 (01) even
+                // though the low bit of 1 is larger.
+                assert_eq!(
+                    output,
+                    vec![convert_bool_to_signal(a == b), convert_bool_to_signal(a > b)],
+                    "a {} b {}", a, b
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn full_adder_num_inputs_and_outputs() {
+        let full_adder = FullAdder::new();
+        let full_adder = full_adder.lock().unwrap();
+
+        //A, B and C_IN then S and C_OUT.
+        assert_eq!(full_adder.num_inputs(), 3);
+        assert_eq!(full_adder.num_outputs(), 2);
+
+        //The basic and nested complex gates inside report their own sizes.
+        let or_gate = full_adder.or_gate.lock().unwrap();
+        assert_eq!(or_gate.num_inputs(), 2);
+        assert_eq!(or_gate.num_outputs(), 1);
+
+        let half_adder = full_adder.input_half_added.lock().unwrap();
+        assert_eq!(half_adder.num_inputs(), 2);
+        assert_eq!(half_adder.num_outputs(), 2);
+    }
+
+    #[test]
+    fn full_adder_probe_half_adder_sum() {
+        let full_adder = FullAdder::new();
+
+        //The input half adder sums A and B, the carry half adder then adds C_IN to that.
+        run_multi_input_output_logic_gate(
+            vec![],
+            vec![
+                vec![LOW_, HIGH], //SUM, CARRY
+            ],
+            HashMap::from(
+                [
+                    ("A", vec![vec![HIGH]]),
+                    ("B", vec![vec![LOW_]]),
+                    ("C_IN", vec![vec![HIGH]]),
+                ]
+            ),
+            full_adder.clone(),
+        );
+
+        let full_adder = full_adder.lock().unwrap();
+        let (input_half_adder_id, sum_index) = {
+            let half_adder = full_adder.input_half_added.lock().unwrap();
+            (half_adder.get_unique_id(), half_adder.get_index_from_tag("S"))
+        };
+        let carry_half_adder_id = full_adder.carry_half_adder.lock().unwrap().get_unique_id();
+
+        assert_eq!(full_adder.complex_gate.probe_wire(input_half_adder_id, sum_index), Some(HIGH));
+        assert_eq!(full_adder.complex_gate.probe_wire(carry_half_adder_id, sum_index), Some(LOW_));
+
+        assert_eq!(full_adder.complex_gate.probe_wire(input_half_adder_id, 2), None);
+        assert_eq!(full_adder.complex_gate.probe_wire(full_adder.get_unique_id(), 0), None);
+    }
+
+    #[test]
+    fn full_adder_unique_gate_count() {
+        //Each HalfAdder is made of A, B, an XOr, an And, S and C.
+        let half_adder = HalfAdder::new();
+        assert_eq!(half_adder.lock().unwrap().num_unique_gates(), 6);
+
+        //A, B, C_IN, two HalfAdders, an Or, S and C_OUT.
+        let full_adder = FullAdder::new();
+        let full_adder = full_adder.lock().unwrap();
+        assert_eq!(full_adder.num_children_gates(), 8);
+        assert_eq!(full_adder.num_unique_gates(), 3 + 2 * 6 + 1 + 2);
+        assert_eq!(full_adder.complex_gate.count_unique_gates(), full_adder.num_unique_gates());
+    }
+
+    #[test]
+    fn half_adder_transistor_count() {
+        let half_adder = HalfAdder::new();
+        let xor_gate = XOr::new(2, 1);
+        let and_gate = And::new(2, 1);
+
+        let expected_count = transistor_count(&*xor_gate.lock().unwrap())
+            + transistor_count(&*and_gate.lock().unwrap());
+
+        assert_eq!(expected_count, 18);
+        assert_eq!(transistor_count(&*half_adder.lock().unwrap()), expected_count);
+    }
+
+    #[test]
+    fn half_adder_count_gates() {
+        let half_adder = HalfAdder::new();
+        let gate_counts = half_adder.lock().unwrap().count_gates();
+
+        assert_eq!(gate_counts.get(GateType::XOrType), 1);
+        assert_eq!(gate_counts.get(GateType::AndType), 1);
+        assert_eq!(gate_counts.get(GateType::SimpleInputType), 2);
+        assert_eq!(gate_counts.get(GateType::SimpleOutputType), 2);
+        assert_eq!(gate_counts.total, 6);
+    }
+
+    #[test]
+    fn arithmetic_logic_unit_count_gates() {
+        let num_bits = 8;
+        let alu = ArithmeticLogicUnit::new(num_bits).unwrap();
+        let gate_counts = alu.lock().unwrap().count_gates();
+
+        assert_ne!(gate_counts.total, 0);
+        assert_eq!(gate_counts.total, gate_counts.counts.values().sum::<usize>());
+        assert_eq!(gate_counts.total, alu.lock().unwrap().num_unique_gates());
+
+        //Counting again or counting a second ALU of the same size gives the same result.
+        assert_eq!(alu.lock().unwrap().count_gates(), gate_counts);
+        let other_alu = ArithmeticLogicUnit::new(num_bits).unwrap();
+        assert_eq!(other_alu.lock().unwrap().count_gates(), gate_counts);
+    }
+
     #[test]
     fn half_adder_low_low() {
         test_half_adder(
@@ -3591,13 +6535,180 @@ mod tests {
         vec
     }
 
+    fn run_saturating_subtractor(num_bits: usize, a: usize, b: usize) {
+        let difference = a.saturating_sub(b);
+
+        let mut output = convert_binary_to_vec(&format!("{:0width$b}", difference, width = num_bits));
+        output.push(convert_bool_to_signal(a < b)); //Borrow Out (B_OUT)
+
+        run_multi_input_output_logic_gate(
+            vec![],
+            vec![
+                output
+            ],
+            HashMap::from(
+                [
+                    ("a", vec![convert_binary_to_vec(&format!("{:0width$b}", a, width = num_bits))]),
+                    ("b", vec![convert_binary_to_vec(&format!("{:0width$b}", b, width = num_bits))]),
+                ]
+            ),
+            VariableBitSaturatingSubtractor::new(num_bits).unwrap(),
+        );
+    }
+
+    #[test]
+    fn saturating_subtractor_a_larger() {
+        run_saturating_subtractor(8, 200, 55);
+        run_saturating_subtractor(8, 255, 0);
+        run_saturating_subtractor(4, 9, 8);
+    }
+
+    #[test]
+    fn saturating_subtractor_clamps_to_zero() {
+        run_saturating_subtractor(8, 55, 200);
+        run_saturating_subtractor(8, 0, 255);
+        run_saturating_subtractor(4, 8, 9);
+    }
+
+    #[test]
+    fn saturating_subtractor_equal_inputs() {
+        run_saturating_subtractor(8, 0, 0);
+        run_saturating_subtractor(8, 170, 170);
+        run_saturating_subtractor(4, 15, 15);
+    }
+
+    fn run_divider(num_bits: usize, a: usize, b: usize) {
+        //Dividing by zero never subtracts anything, so every quotient bit is set and a is left over.
+        let quotient = a.checked_div(b).unwrap_or((1 << num_bits) - 1);
+        let remainder = a.checked_rem(b).unwrap_or(a);
+
+        let mut output = convert_binary_to_vec(&format!("{:0width$b}", quotient, width = num_bits));
+        output.extend(convert_binary_to_vec(&format!("{:0width$b}", remainder, width = num_bits)));
+        output.push(convert_bool_to_signal(b == 0)); //DIV_BY_ZERO
+
+        run_multi_input_output_logic_gate(
+            vec![],
+            vec![
+                output
+            ],
+            HashMap::from(
+                [
+                    ("a", vec![convert_binary_to_vec(&format!("{:0width$b}", a, width = num_bits))]),
+                    ("b", vec![convert_binary_to_vec(&format!("{:0width$b}", b, width = num_bits))]),
+                ]
+            ),
+            VariableBitDivider::new(num_bits).unwrap(),
+        );
+    }
+
+    #[test]
+    fn divider_random_nonzero_divisors() {
+        let mut rng = rand::thread_rng();
+        for num_bits in [2, 4, 8] {
+            for _ in 0..16 {
+                let a = rng.gen_range(0..(1 << num_bits));
+                let b = rng.gen_range(1..(1 << num_bits));
+                run_divider(num_bits, a, b);
+            }
+        }
+    }
+
+    fn run_multiplier(num_bits: usize, a: usize, b: usize) {
+        let product = a * b;
+
+        run_multi_input_output_logic_gate(
+            vec![],
+            vec![
+                convert_binary_to_vec(&format!("{:0width$b}", product, width = num_bits * 2))
+            ],
+            HashMap::from(
+                [
+                    ("a", vec![convert_binary_to_vec(&format!("{:0width$b}", a, width = num_bits))]),
+                    ("b", vec![convert_binary_to_vec(&format!("{:0width$b}", b, width = num_bits))]),
+                ]
+            ),
+            VariableBitMultiplier::new(num_bits).unwrap(),
+        );
+    }
+
+    #[test]
+    fn multiplier_random_operands() {
+        let mut rng = rand::thread_rng();
+        for num_bits in [4, 8] {
+            for _ in 0..16 {
+                let a = rng.gen_range(0..(1 << num_bits));
+                let b = rng.gen_range(0..(1 << num_bits));
+                run_multiplier(num_bits, a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn multiplier_zero_and_max_operands() {
+        for num_bits in [4, 8] {
+            let max = (1 << num_bits) - 1;
+            run_multiplier(num_bits, 0, 0);
+            run_multiplier(num_bits, 0, max);
+            run_multiplier(num_bits, max, 0);
+            run_multiplier(num_bits, max, max);
+            run_multiplier(num_bits, max, 1);
+        }
+    }
+
+    #[test]
+    fn divider_zero_divisor() {
+        run_divider(8, 0, 0);
+        run_divider(8, 200, 0);
+        run_divider(4, 9, 0);
+        run_divider(2, 3, 0);
+    }
+
+    fn run_count_leading_zeros(num_bits: usize, input: usize) {
+        let leading_zeros = (0..num_bits).rev().take_while(|i| (input >> i) & 1 == 0).count();
+        let count_bits = CountLeadingZeros::count_bits(num_bits);
+
+        let mut output = convert_binary_to_vec(&format!("{:0width$b}", leading_zeros, width = count_bits));
+        output.push(convert_bool_to_signal(input == 0)); //ALL_ZERO
+
+        run_multi_input_output_logic_gate(
+            vec![convert_binary_to_vec(&format!("{:0width$b}", input, width = num_bits))],
+            vec![output],
+            HashMap::new(),
+            CountLeadingZeros::new(num_bits).unwrap(),
+        );
+    }
+
+    #[test]
+    fn count_leading_zeros_patterns() {
+        run_count_leading_zeros(8, 0b0001_0110);
+        run_count_leading_zeros(8, 0b0100_0000);
+        run_count_leading_zeros(8, 0b0000_0001);
+        run_count_leading_zeros(5, 0b00111);
+        run_count_leading_zeros(4, 0b0010);
+    }
+
+    #[test]
+    fn count_leading_zeros_msb_set() {
+        run_count_leading_zeros(8, 0b1000_0000);
+        run_count_leading_zeros(8, 0b1111_1111);
+        run_count_leading_zeros(3, 0b101);
+        run_count_leading_zeros(1, 0b1);
+    }
+
+    #[test]
+    fn count_leading_zeros_all_zero() {
+        run_count_leading_zeros(8, 0);
+        run_count_leading_zeros(6, 0);
+        run_count_leading_zeros(1, 0);
+    }
+
     #[test]
     fn variable_bit_adder_tests() {
         for _ in 0..20 {
             let num_bits = rand::thread_rng().gen_range(2..16);
 
             let mut gen_randoms_result = generate_randoms_adder_inputs_outputs(num_bits);
-            let variable_bit_adder = VariableBitAdder::new(num_bits);
+            let variable_bit_adder = VariableBitAdder::new(num_bits).unwrap();
 
             gen_randoms_result.output.push(gen_randoms_result.carry_out);
 
@@ -3627,9 +6738,9 @@ mod tests {
 
             let variable_bit_left_shift: SharedMutex<dyn LogicGate> =
                 if left_shift {
-                    VariableBitShiftLeft::<true>::new(num_bits)
+                    VariableBitShiftLeft::<true>::new(num_bits).unwrap()
                 } else {
-                    VariableBitShiftLeft::<false>::new(num_bits)
+                    VariableBitShiftLeft::<false>::new(num_bits).unwrap()
                 };
 
             let mut gen_randoms_result = generate_randoms_shl_shr_inputs_outputs(
@@ -3660,7 +6771,7 @@ mod tests {
             let num_bits = rand::thread_rng().gen_range(1..16);
 
             let gen_randoms_result = generate_random_not_inputs_outputs(num_bits);
-            let variable_bit_not = VariableBitNot::new(num_bits);
+            let variable_bit_not = VariableBitNot::new(num_bits).unwrap();
 
             run_multi_input_output_logic_gate(
                 vec![
@@ -3677,6 +6788,48 @@ mod tests {
         }
     }
 
+    #[test]
+    fn mask_gate_masks_each_value() {
+        //The mask is only latched while S is HIGH, the mask inputs change afterwards to make sure
+        // they are ignored.
+        let mask = vec![LOW_, LOW_, HIGH, HIGH, LOW_, HIGH, LOW_, HIGH]; //0b1010_1100
+        run_multi_input_output_logic_gate(
+            vec![
+                vec![LOW_; 8],
+                vec![HIGH; 8],
+                vec![HIGH, HIGH, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_], //0x0F
+                vec![LOW_, LOW_, LOW_, LOW_, HIGH, HIGH, HIGH, HIGH], //0xF0
+                vec![HIGH, LOW_, HIGH, LOW_, HIGH, LOW_, HIGH, LOW_], //0x55
+            ],
+            vec![
+                vec![LOW_; 8],
+                mask.clone(),
+                vec![LOW_, LOW_, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_], //0x0C
+                vec![LOW_, LOW_, LOW_, LOW_, LOW_, HIGH, LOW_, HIGH], //0xA0
+                vec![LOW_, LOW_, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_], //0x04
+            ],
+            HashMap::from(
+                [
+                    (MaskGate::MASK, vec![
+                        mask,
+                        vec![HIGH; 8],
+                        vec![LOW_; 8],
+                        vec![HIGH; 8],
+                        vec![LOW_; 8],
+                    ]),
+                    ("S", vec![
+                        vec![HIGH],
+                        vec![LOW_],
+                        vec![LOW_],
+                        vec![LOW_],
+                        vec![LOW_],
+                    ]),
+                ]
+            ),
+            MaskGate::new(8).unwrap(),
+        );
+    }
+
     #[test]
     fn variable_bit_and_or_tests() {
         for _ in 0..20 {
@@ -3690,9 +6843,9 @@ mod tests {
 
             let variable_bit_not: SharedMutex<dyn LogicGate> =
                 if and_gate {
-                    VariableBitAnd::new(num_bits)
+                    VariableBitAnd::new(num_bits).unwrap()
                 } else {
-                    VariableBitOr::new(num_bits)
+                    VariableBitOr::new(num_bits).unwrap()
                 };
 
             run_multi_input_output_logic_gate(
@@ -3762,7 +6915,7 @@ mod tests {
                 convert_bool_to_signal(xor_le_returns.a_input == xor_le_returns.b_input),
             );
 
-            let variable_bit_xor_le = VariableBitXOrLE::new(num_bits);
+            let variable_bit_xor_le = VariableBitXOrLE::new(num_bits).unwrap();
             run_multi_input_output_logic_gate(
                 vec![],
                 vec![
@@ -3791,7 +6944,7 @@ mod tests {
                 output_vector[0] = LOW_;
             }
 
-            let variable_z = VariableBitZ::new(num_bits);
+            let variable_z = VariableBitZ::new(num_bits).unwrap();
 
             run_multi_input_output_logic_gate(
                 vec![
@@ -3837,7 +6990,7 @@ mod tests {
             println!("output {:?}", output);
             println!("enable {:?}", enable);
 
-            let variable_z = VariableBitEnable::new(num_bits);
+            let variable_z = VariableBitEnable::new(num_bits).unwrap();
 
             run_multi_input_output_logic_gate(
                 vec![
@@ -3854,6 +7007,78 @@ mod tests {
         }
     }
 
+    #[test]
+    fn variable_bit_enable_disabled_low_tests() {
+        let num_bits = rand::thread_rng().gen_range(2..16);
+        let input: Vec<Signal> = (0..num_bits)
+            .map(|i| if i % 2 == 0 { HIGH } else { LOW_ })
+            .collect();
+
+        run_multi_input_output_logic_gate(
+            vec![
+                input.clone(),
+                input.clone(),
+            ],
+            vec![
+                vec![LOW_; num_bits],
+                input,
+            ],
+            HashMap::from(
+                [("E", vec![vec![LOW_], vec![HIGH]])]
+            ),
+            VariableBitEnable::new_with_disabled_low(num_bits).unwrap(),
+        );
+    }
+
+    #[test]
+    fn variable_bit_enable_disabled_low_feeds_zero_detector() {
+        let num_bits = rand::thread_rng().gen_range(2..16);
+        let variable_bit_enable = VariableBitEnable::new_with_disabled_low(num_bits).unwrap();
+        let variable_bit_z = VariableBitZ::new(num_bits).unwrap();
+
+        for i in 0..num_bits {
+            connect_gates(
+                variable_bit_enable.clone(),
+                i,
+                variable_bit_z.clone(),
+                i,
+            );
+        }
+
+        let set_input = |tag: &str, signal: Signal| {
+            let idx = variable_bit_enable.lock().unwrap().get_index_from_tag(tag);
+            variable_bit_enable.lock().unwrap().update_input_signal(
+                GateInput::new(
+                    idx,
+                    signal,
+                    UniqueID::zero_id(),
+                )
+            );
+        };
+
+        //The data is never zero so only the enable can make the zero detector report zero.
+        for i in 0..num_bits {
+            set_input(format!("i_{}", i).as_str(), HIGH);
+        }
+
+        let input_gates: Vec<SharedMutex<dyn LogicGate>> = vec![variable_bit_enable.clone()];
+        let output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
+        for (enable, expected_zero) in [(LOW_, HIGH), (HIGH, LOW_), (LOW_, HIGH)] {
+            set_input("E", enable);
+
+            run_circuit(
+                &input_gates,
+                &output_gates,
+                false,
+                &mut |_clock_tick_inputs, _output_gates| {},
+            );
+
+            let z_output = variable_bit_z.lock().unwrap().fetch_output_signals_no_calculate().unwrap();
+            let z_output = extract_signals_from_gate_output_states(&z_output);
+            assert_eq!(z_output, vec![expected_zero]);
+        }
+    }
+
     #[test]
     fn arithmetic_logic_unit_off_test() {
         let num_bits = rand::thread_rng().gen_range(2..16);
@@ -3867,7 +7092,7 @@ mod tests {
         output.push(HIGH); //Zero      (Z)
         output.push(NONE); //Carry Out (C_OUT)
 
-        let alu = ArithmeticLogicUnit::new(num_bits);
+        let alu = ArithmeticLogicUnit::new(num_bits).unwrap();
         let alu_operation = AluOperations::get_vectors(AluOperations::None);
 
         run_multi_input_output_logic_gate(
@@ -3889,6 +7114,176 @@ mod tests {
         );
     }
 
+    #[test]
+    fn arithmetic_logic_unit_op_valid_test() {
+        let num_bits = rand::thread_rng().gen_range(2..16);
+
+        let mut none_output = vec![NONE; num_bits];
+        none_output.push(LOW_); //A Larger  (A_L)
+        none_output.push(HIGH); //Equal     (EQ)
+        none_output.push(HIGH); //Zero      (Z)
+        none_output.push(NONE); //Carry Out (C_OUT)
+        none_output.push(LOW_); //Valid     (OP_VALID)
+
+        let mut and_output = vec![HIGH; num_bits];
+        and_output.push(LOW_); //A Larger  (A_L)
+        and_output.push(HIGH); //Equal     (EQ)
+        and_output.push(LOW_); //Zero      (Z)
+        and_output.push(NONE); //Carry Out (C_OUT)
+        and_output.push(HIGH); //Valid     (OP_VALID)
+
+        let alu = ArithmeticLogicUnit::new(num_bits).unwrap();
+        let none_operation = AluOperations::get_vectors(AluOperations::None);
+        let and_operation = AluOperations::get_vectors(AluOperations::And);
+
+        run_multi_input_output_logic_gate(
+            vec![],
+            vec![
+                none_output, //A_L, EQ, Z, C_OUT, OP_VALID;
+                and_output,
+            ],
+            HashMap::from(
+                [
+                    ("a", vec![vec![HIGH; num_bits], vec![HIGH; num_bits]]),
+                    ("b", vec![vec![HIGH; num_bits], vec![HIGH; num_bits]]),
+                    ("A", vec![none_operation.a, and_operation.a]),
+                    ("B", vec![none_operation.b, and_operation.b]),
+                    ("C", vec![none_operation.c, and_operation.c]),
+                    ("C_IN", vec![vec![LOW_], vec![LOW_]]),
+                ]
+            ),
+            alu,
+        );
+    }
+
+    #[test]
+    fn arithmetic_logic_unit_shift_lost_test() {
+        let num_bits = 8;
+
+        let to_signals = |number: usize| -> Vec<Signal> {
+            (0..num_bits).map(|i| if (number >> i) & 1 == 1 { HIGH } else { LOW_ }).collect()
+        };
+
+        //The most significant bit is set so it is lost by the shift.
+        let mut msb_set_output = to_signals(0b00000010);
+        msb_set_output.push(HIGH); //A Larger   (A_L)
+        msb_set_output.push(LOW_); //Equal      (EQ)
+        msb_set_output.push(LOW_); //Zero       (Z)
+        msb_set_output.push(HIGH); //Carry Out  (C_OUT)
+        msb_set_output.push(HIGH); //Valid      (OP_VALID)
+        msb_set_output.push(HIGH); //Shift Lost (SHIFT_LOST)
+
+        let mut msb_clear_output = to_signals(0b10000010);
+        msb_clear_output.push(HIGH); //A Larger   (A_L)
+        msb_clear_output.push(LOW_); //Equal      (EQ)
+        msb_clear_output.push(LOW_); //Zero       (Z)
+        msb_clear_output.push(LOW_); //Carry Out  (C_OUT)
+        msb_clear_output.push(HIGH); //Valid      (OP_VALID)
+        msb_clear_output.push(LOW_); //Shift Lost (SHIFT_LOST)
+
+        //The flag is only set during shift operations.
+        let mut or_output = to_signals(0b10000001);
+        or_output.push(HIGH); //A Larger   (A_L)
+        or_output.push(LOW_); //Equal      (EQ)
+        or_output.push(LOW_); //Zero       (Z)
+        or_output.push(NONE); //Carry Out  (C_OUT)
+        or_output.push(HIGH); //Valid      (OP_VALID)
+        or_output.push(LOW_); //Shift Lost (SHIFT_LOST)
+
+        let alu = ArithmeticLogicUnit::new(num_bits).unwrap();
+        let shl_operation = AluOperations::get_vectors(AluOperations::Shl);
+        let or_operation = AluOperations::get_vectors(AluOperations::Or);
+
+        run_multi_input_output_logic_gate(
+            vec![],
+            vec![
+                msb_set_output, //A_L, EQ, Z, C_OUT, OP_VALID, SHIFT_LOST;
+                msb_clear_output,
+                or_output,
+            ],
+            HashMap::from(
+                [
+                    ("a", vec![to_signals(0b10000001), to_signals(0b01000001), to_signals(0b10000001)]),
+                    ("b", vec![to_signals(0), to_signals(0), to_signals(0)]),
+                    ("A", vec![shl_operation.a.clone(), shl_operation.a, or_operation.a]),
+                    ("B", vec![shl_operation.b.clone(), shl_operation.b, or_operation.b]),
+                    ("C", vec![shl_operation.c.clone(), shl_operation.c, or_operation.c]),
+                    ("C_IN", vec![vec![LOW_], vec![LOW_], vec![LOW_]]),
+                ]
+            ),
+            alu,
+        );
+    }
+
+    #[test]
+    fn arithmetic_logic_unit_with_ops_test() {
+        let num_bits = 4;
+
+        let to_signals = |number: usize| -> Vec<Signal> {
+            (0..num_bits).map(|i| if (number >> i) & 1 == 1 { HIGH } else { LOW_ }).collect()
+        };
+
+        //Xor_le is left out so A_L and EQ stay LOW.
+        let mut adder_output = to_signals(0b1000);
+        adder_output.push(LOW_); //A Larger   (A_L)
+        adder_output.push(LOW_); //Equal      (EQ)
+        adder_output.push(LOW_); //Zero       (Z)
+        adder_output.push(LOW_); //Carry Out  (C_OUT)
+        adder_output.push(HIGH); //Valid      (OP_VALID)
+        adder_output.push(LOW_); //Shift Lost (SHIFT_LOST)
+
+        let mut and_output = to_signals(0b0001);
+        and_output.push(LOW_); //A Larger   (A_L)
+        and_output.push(LOW_); //Equal      (EQ)
+        and_output.push(LOW_); //Zero       (Z)
+        and_output.push(NONE); //Carry Out  (C_OUT)
+        and_output.push(HIGH); //Valid      (OP_VALID)
+        and_output.push(LOW_); //Shift Lost (SHIFT_LOST)
+
+        //Or was left out so every output bit is LOW and it is not a valid operation.
+        let mut or_output = to_signals(0);
+        or_output.push(LOW_); //A Larger   (A_L)
+        or_output.push(LOW_); //Equal      (EQ)
+        or_output.push(HIGH); //Zero       (Z)
+        or_output.push(NONE); //Carry Out  (C_OUT)
+        or_output.push(LOW_); //Valid      (OP_VALID)
+        or_output.push(LOW_); //Shift Lost (SHIFT_LOST)
+
+        let alu = ArithmeticLogicUnit::new_with_ops(
+            num_bits,
+            &[AluOperations::Adder, AluOperations::And],
+        ).unwrap();
+        let full_alu = ArithmeticLogicUnit::new(num_bits).unwrap();
+
+        assert!(
+            alu.lock().unwrap().num_children_gates() < full_alu.lock().unwrap().num_children_gates()
+        );
+
+        let adder_operation = AluOperations::get_vectors(AluOperations::Adder);
+        let and_operation = AluOperations::get_vectors(AluOperations::And);
+        let or_operation = AluOperations::get_vectors(AluOperations::Or);
+
+        run_multi_input_output_logic_gate(
+            vec![],
+            vec![
+                adder_output, //A_L, EQ, Z, C_OUT, OP_VALID, SHIFT_LOST;
+                and_output,
+                or_output,
+            ],
+            HashMap::from(
+                [
+                    ("a", vec![to_signals(0b0101), to_signals(0b0101), to_signals(0b0101)]),
+                    ("b", vec![to_signals(0b0011), to_signals(0b0011), to_signals(0b0011)]),
+                    ("A", vec![adder_operation.a, and_operation.a, or_operation.a]),
+                    ("B", vec![adder_operation.b, and_operation.b, or_operation.b]),
+                    ("C", vec![adder_operation.c, and_operation.c, or_operation.c]),
+                    ("C_IN", vec![vec![LOW_], vec![LOW_], vec![LOW_]]),
+                ]
+            ),
+            alu,
+        );
+    }
+
     #[test]
     fn arithmetic_logic_unit_zero_test() {
         let num_bits = rand::thread_rng().gen_range(2..16);
@@ -3902,7 +7297,7 @@ mod tests {
         output.push(HIGH); //Zero      (Z)
         output.push(NONE); //Carry Out (C_OUT)
 
-        let alu = ArithmeticLogicUnit::new(num_bits);
+        let alu = ArithmeticLogicUnit::new(num_bits).unwrap();
 
         let alu_operation = AluOperations::get_vectors(AluOperations::Or);
 
@@ -3992,6 +7387,123 @@ mod tests {
         }
     }
 
+    #[test]
+    fn arithmetic_logic_unit_rotate_through_carry_test() {
+        let num_bits = 8;
+        let pattern = 0b1001_0110;
+
+        for carry_in in [false, true] {
+            for left_shift in [false, true] {
+                let gen_result = generate_rotate_through_carry_inputs_outputs(
+                    num_bits,
+                    pattern,
+                    carry_in,
+                    left_shift,
+                );
+
+                let opt =
+                    if left_shift {
+                        AluOperations::Rcl
+                    } else {
+                        AluOperations::Rcr
+                    };
+
+                run_alu_with_carry_in(num_bits, opt, gen_result, convert_bool_to_signal(carry_in));
+            }
+        }
+    }
+
+    #[test]
+    fn arithmetic_logic_unit_shift_ignores_carry_in_test() {
+        let num_bits = 8;
+        let pattern = 0b1001_0110;
+
+        //With C_IN HIGH the shifts still shift in LOW, only the rotates use the carry.
+        for left_shift in [false, true] {
+            let gen_result = generate_rotate_through_carry_inputs_outputs(
+                num_bits,
+                pattern,
+                false,
+                left_shift,
+            );
+
+            let opt =
+                if left_shift {
+                    AluOperations::Shl
+                } else {
+                    AluOperations::Shr
+                };
+
+            run_alu_with_carry_in(num_bits, opt, gen_result, HIGH);
+        }
+    }
+
+    #[test]
+    fn arithmetic_logic_unit_rotate_through_carry_chain_test() {
+        let num_bits = 8;
+
+        for left_shift in [false, true] {
+            //The shifted out bit of the first rotate must become the shifted in bit of the second.
+            let first_num = 0b1000_0001;
+            let first_result = generate_rotate_through_carry_inputs_outputs(
+                num_bits,
+                first_num,
+                false,
+                left_shift,
+            );
+            let expected_first_num = first_result.result_num;
+
+            let opt =
+                if left_shift {
+                    AluOperations::Rcl
+                } else {
+                    AluOperations::Rcr
+                };
+
+            let first_output = run_alu_with_carry_in(num_bits, opt, first_result, LOW_);
+
+            let carry_out = first_output.last().unwrap().clone();
+            assert_eq!(carry_out, HIGH);
+
+            let second_num = first_output[..num_bits]
+                .iter()
+                .enumerate()
+                .fold(0, |num, (i, signal)| {
+                    if *signal == HIGH {
+                        num | (1 << i)
+                    } else {
+                        num
+                    }
+                });
+            assert_eq!(second_num, expected_first_num);
+
+            let second_result = generate_rotate_through_carry_inputs_outputs(
+                num_bits,
+                second_num,
+                true,
+                left_shift,
+            );
+
+            //The carry from the first rotate fills the vacated bit of the second rotate.
+            let expected_second_num =
+                if left_shift {
+                    0b0000_0101
+                } else {
+                    0b1010_0000
+                };
+            assert_eq!(second_result.result_num, expected_second_num);
+
+            let opt =
+                if left_shift {
+                    AluOperations::Rcl
+                } else {
+                    AluOperations::Rcr
+                };
+
+            run_alu_with_carry_in(num_bits, opt, second_result, carry_out);
+        }
+    }
+
     #[test]
     fn arithmetic_logic_unit_adder_test() {
         for _ in 0..20 {
@@ -4002,4 +7514,54 @@ mod tests {
             run_alu(num_bits, AluOperations::Adder, gen_randoms_result);
         }
     }
+
+    #[test]
+    fn arithmetic_logic_unit_subtract_test() {
+        let num_bits = 8;
+
+        //The last three borrow, 0 - 1 wraps around to the largest value.
+        let mut operand_pairs = vec![
+            (200, 55),
+            (77, 77),
+            (3, 5),
+            (0, 1),
+            (1, 255),
+        ];
+
+        for _ in 0..5 {
+            operand_pairs.push((
+                rand::thread_rng().gen_range(0..256),
+                rand::thread_rng().gen_range(0..256),
+            ));
+        }
+
+        for (first_num, second_num) in operand_pairs {
+            let gen_result = generate_subtract_inputs_outputs(num_bits, first_num, second_num);
+
+            run_alu(num_bits, AluOperations::Subtract, gen_result);
+        }
+    }
+
+    #[test]
+    fn variable_bit_gates_reject_zero_width() {
+        let zero_width = Some(GateBuildError::ZeroWidth);
+
+        assert_eq!(VariableBitAdder::new(0).err(), zero_width);
+        assert_eq!(VariableBitShiftLeft::<true>::new(0).err(), zero_width);
+        assert_eq!(VariableBitShiftLeft::<false>::new(0).err(), zero_width);
+        assert_eq!(VariableBitNot::new(0).err(), zero_width);
+        assert_eq!(VariableBitAnd::new(0).err(), zero_width);
+        assert_eq!(MaskGate::new(0).err(), zero_width);
+        assert_eq!(VariableBitOr::new(0).err(), zero_width);
+        assert_eq!(VariableBitXOrLE::new(0).err(), zero_width);
+        assert_eq!(VariableBitZ::new(0).err(), zero_width);
+        assert_eq!(VariableBitEnable::new(0).err(), zero_width);
+        assert_eq!(VariableBitEnable::new_with_disabled_low(0).err(), zero_width);
+        assert_eq!(ArithmeticLogicUnit::new(0).err(), zero_width);
+        assert_eq!(VariableBitSaturatingSubtractor::new(0).err(), zero_width);
+        assert_eq!(VariableBitDivider::new(0).err(), zero_width);
+        assert_eq!(VariableBitMultiplier::new(0).err(), zero_width);
+
+        assert!(VariableBitAnd::new(1).is_ok());
+    }
 }
@@ -1,7 +1,33 @@
+use std::collections::HashMap;
 use crate::logic::foundations::{GateInput, GateOutputState, LogicGate, UniqueID, GateLogicError, GateType, GateLogic, BasicGateMembers, InputSignalReturn, ConnectedOutput, calculate_input_signals_from_all_inputs, Signal, calculate_input_signal_from_single_inputs};
 use crate::logic::foundations::Signal::{HIGH, LOW_, NONE};
 use crate::shared_mutex::{new_shared_mutex, SharedMutex};
 
+//And and Or gates only need to find a single dominant signal (LOW_ for And, HIGH for Or) to
+// know their output. This resolves the inputs one at a time and returns as soon as the dominant
+// signal is found instead of resolving every input first. Because of this, multiple valid signals
+// on an input after the dominant one are not reported. The second value returned is the number of
+// inputs that were resolved.
+pub fn calculate_output_with_early_exit(
+    input_signals: &[HashMap<UniqueID, Signal>],
+    dominant_signal: Signal,
+) -> Result<(Signal, usize), GateLogicError> {
+    for (i, input) in input_signals.iter().enumerate() {
+        if calculate_input_signal_from_single_inputs(input)? == dominant_signal {
+            return Ok((dominant_signal, i + 1));
+        }
+    }
+
+    let output_signal =
+        if dominant_signal == HIGH {
+            LOW_
+        } else {
+            HIGH
+        };
+
+    Ok((output_signal, input_signals.len()))
+}
+
 pub struct Or {
     pub members: BasicGateMembers,
 }
@@ -70,6 +96,10 @@ impl LogicGate for Or {
         self.members.tag = tag.to_string()
     }
 
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.members.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         self.members.remove_connected_input(input_index, connected_id);
     }
@@ -82,6 +112,18 @@ impl LogicGate for Or {
         self.members.number_child_gates
     }
 
+    fn num_unique_gates(&self) -> usize {
+        1
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.members.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.members.num_outputs()
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         panic!("Basic gates do not have input gates");
     }
@@ -155,6 +197,10 @@ impl LogicGate for And {
         self.members.tag = tag.to_string()
     }
 
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.members.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         self.members.remove_connected_input(input_index, connected_id);
     }
@@ -167,6 +213,18 @@ impl LogicGate for And {
        self.members.number_child_gates
     }
 
+    fn num_unique_gates(&self) -> usize {
+        1
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.members.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.members.num_outputs()
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         panic!("Basic gates do not have input gates");
     }
@@ -240,6 +298,10 @@ impl LogicGate for Not {
         self.members.tag = tag.to_string()
     }
 
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.members.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         self.members.remove_connected_input(input_index, connected_id);
     }
@@ -252,6 +314,18 @@ impl LogicGate for Not {
         self.members.number_child_gates
     }
 
+    fn num_unique_gates(&self) -> usize {
+        1
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.members.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.members.num_outputs()
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         panic!("Basic gates do not have input gates");
     }
@@ -325,6 +399,10 @@ impl LogicGate for Nor {
         self.members.tag = tag.to_string()
     }
 
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.members.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         self.members.remove_connected_input(input_index, connected_id);
     }
@@ -337,6 +415,18 @@ impl LogicGate for Nor {
         self.members.number_child_gates
     }
 
+    fn num_unique_gates(&self) -> usize {
+        1
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.members.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.members.num_outputs()
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         panic!("Basic gates do not have input gates");
     }
@@ -410,6 +500,10 @@ impl LogicGate for Nand {
         self.members.tag = tag.to_string()
     }
 
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.members.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         self.members.remove_connected_input(input_index, connected_id);
     }
@@ -422,6 +516,18 @@ impl LogicGate for Nand {
         self.members.number_child_gates
     }
 
+    fn num_unique_gates(&self) -> usize {
+        1
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.members.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.members.num_outputs()
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         panic!("Basic gates do not have input gates");
     }
@@ -494,6 +600,112 @@ impl LogicGate for XOr {
         self.members.tag = tag.to_string()
     }
 
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.members.is_input_connected(input_index)
+    }
+
+    fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
+        self.members.remove_connected_input(input_index, connected_id);
+    }
+
+    fn toggle_print_each_input_output_gate(&mut self, print_each_input_output_gate: bool) {
+        self.members.toggle_print_each_input_output_gate(print_each_input_output_gate);
+    }
+
+    fn num_children_gates(&self) -> usize {
+        self.members.number_child_gates
+    }
+
+    fn num_unique_gates(&self) -> usize {
+        1
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.members.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.members.num_outputs()
+    }
+
+    fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
+        panic!("Basic gates do not have input gates");
+    }
+}
+
+#[allow(dead_code)]
+pub struct Xnor {
+    pub members: BasicGateMembers,
+}
+
+#[allow(dead_code)]
+impl Xnor {
+    pub fn new(input_num: usize, output_num: usize) -> SharedMutex<Self> {
+        let xnor_gate = Xnor {
+            members: BasicGateMembers::new(
+                input_num,
+                output_num,
+                GateType::XnorType,
+                0,
+                None,
+            )
+        };
+        new_shared_mutex(
+            xnor_gate.get_unique_id().id(),
+            xnor_gate,
+        )
+    }
+}
+
+impl LogicGate for Xnor {
+    fn internal_connect_output(&mut self, current_gate_output_key: usize, next_gate_input_key: usize, next_gate: SharedMutex<dyn LogicGate>) -> Signal {
+        self.members.connect_output(
+            current_gate_output_key,
+            next_gate_input_key,
+            next_gate,
+        )
+    }
+
+    fn internal_update_index_to_id(&mut self, sending_id: UniqueID, gate_input_index: usize, signal: Signal) {
+        self.members.internal_update_index_to_id(sending_id, gate_input_index, signal);
+    }
+
+    fn update_input_signal(&mut self, input: GateInput) -> InputSignalReturn {
+        self.members.update_input_signal(input)
+    }
+
+    fn fetch_output_signals_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
+        GateLogic::fetch_output_signals_calculate_basic_gate(&mut self.members)
+    }
+
+    fn fetch_output_signals_no_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
+        GateLogic::fetch_output_signals_no_calculate_basic_gate(&mut self.members)
+    }
+
+    fn get_gate_type(&self) -> GateType {
+        self.members.gate_type
+    }
+
+    fn get_unique_id(&self) -> UniqueID {
+        self.members.unique_id
+    }
+
+    fn toggle_output_printing(&mut self, print_output: bool) {
+        self.members.should_print_output = print_output;
+    }
+
+    fn get_tag(&self) -> String {
+        self.members.tag.clone()
+    }
+
+    fn set_tag(&mut self, tag: &str) {
+        self.members.tag = tag.to_string()
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.members.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         self.members.remove_connected_input(input_index, connected_id);
     }
@@ -506,6 +718,18 @@ impl LogicGate for XOr {
         self.members.number_child_gates
     }
 
+    fn num_unique_gates(&self) -> usize {
+        1
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.members.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.members.num_outputs()
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         panic!("Basic gates do not have input gates");
     }
@@ -657,6 +881,172 @@ impl LogicGate for Splitter {
         self.members.get_index_from_tag(tag)
     }
 
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.members.is_input_connected(input_index)
+    }
+
+    fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
+        self.members.remove_connected_input(input_index, connected_id);
+    }
+
+    fn toggle_print_each_input_output_gate(&mut self, print_each_input_output_gate: bool) {
+        self.members.toggle_print_each_input_output_gate(print_each_input_output_gate);
+    }
+
+    fn num_children_gates(&self) -> usize {
+        self.members.number_child_gates
+    }
+
+    fn num_unique_gates(&self) -> usize {
+        1
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.members.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.members.num_outputs()
+    }
+
+    fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
+        panic!("Basic gates do not have input gates");
+    }
+}
+
+//Copies a bus to several identical output buses. Unlike Splitter, the outputs are grouped by copy
+// so each copy is a contiguous bus of num_bits outputs.
+pub struct FanoutSplitter {
+    pub members: BasicGateMembers,
+    num_bits: usize,
+    num_copies: usize,
+}
+
+#[allow(dead_code)]
+impl FanoutSplitter {
+    pub fn new(num_bits: usize, num_copies: usize) -> SharedMutex<Self> {
+        assert_ne!(num_bits, 0);
+        assert_ne!(num_copies, 0);
+        let fanout_splitter = FanoutSplitter {
+            members: BasicGateMembers::new(
+                num_bits,
+                num_bits * num_copies,
+                GateType::FanoutSplitterType,
+                0,
+                Some(LOW_),
+            ),
+            num_bits,
+            num_copies,
+        };
+        new_shared_mutex(
+            fanout_splitter.get_unique_id().id(),
+            fanout_splitter,
+        )
+    }
+
+    pub fn get_index_for_copy(&self, copy: usize, bit: usize) -> usize {
+        assert!(copy < self.num_copies);
+        assert!(bit < self.num_bits);
+
+        copy * self.num_bits + bit
+    }
+
+    fn fetch_output_signals(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
+        let input_signals = calculate_input_signals_from_all_inputs(&self.members.input_signals)?;
+
+        for (i, output) in self.members.output_states.iter_mut().enumerate() {
+            let input_signal = input_signals[i % self.num_bits].clone();
+            match output {
+                GateOutputState::NotConnected(signal) => {
+                    *signal = input_signal;
+                }
+                GateOutputState::Connected(connected_output) => {
+                    connected_output.throughput.signal = input_signal;
+                }
+            }
+        }
+
+        if self.members.should_print_output {
+            GateLogic::print_gate_output(
+                &self.members.gate_type,
+                &self.members.unique_id,
+                "",
+                &self.members.input_signals,
+                &self.members.output_states,
+            );
+        }
+
+        Ok(self.members.output_states.clone())
+    }
+}
+
+impl LogicGate for FanoutSplitter {
+    //current_gate_output_key is meant to be extracted from FanoutSplitter::get_index_for_copy()
+    fn internal_connect_output(&mut self, current_gate_output_key: usize, next_gate_input_key: usize, next_gate: SharedMutex<dyn LogicGate>) -> Signal {
+        //When gates are being connected, there should be no issues with this error.
+        let output_signal = calculate_input_signal_from_single_inputs(
+            &self.members.input_signals[current_gate_output_key % self.num_bits]
+        ).unwrap();
+
+        GateLogic::connect_output_no_calculate(
+            self.get_unique_id(),
+            &mut self.members.output_states,
+            current_gate_output_key,
+            next_gate_input_key,
+            next_gate,
+            output_signal.clone(),
+            self.members.gate_type,
+            &self.members.tag,
+            self.members.should_print_output,
+        );
+
+        output_signal
+    }
+
+    fn internal_update_index_to_id(&mut self, sending_id: UniqueID, gate_input_index: usize, signal: Signal) {
+        self.members.internal_update_index_to_id(sending_id, gate_input_index, signal);
+    }
+
+    fn update_input_signal(&mut self, input: GateInput) -> InputSignalReturn {
+        self.members.update_input_signal(input)
+    }
+
+    fn fetch_output_signals_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
+        self.fetch_output_signals()
+    }
+
+    fn fetch_output_signals_no_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
+        self.fetch_output_signals()
+    }
+
+    fn get_gate_type(&self) -> GateType {
+        self.members.gate_type
+    }
+
+    fn get_unique_id(&self) -> UniqueID {
+        self.members.unique_id
+    }
+
+    fn toggle_output_printing(&mut self, print_output: bool) {
+        self.members.should_print_output = print_output;
+    }
+
+    fn get_tag(&self) -> String {
+        self.members.tag.clone()
+    }
+
+    fn set_tag(&mut self, tag: &str) {
+        self.members.tag = tag.to_string()
+    }
+
+    fn get_index_from_tag(&self, tag: &str) -> usize {
+        self.members.get_index_from_tag(tag)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.members.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         self.members.remove_connected_input(input_index, connected_id);
     }
@@ -665,8 +1055,20 @@ impl LogicGate for Splitter {
         self.members.toggle_print_each_input_output_gate(print_each_input_output_gate);
     }
 
-    fn num_children_gates(&self) -> usize {
-        self.members.number_child_gates
+    fn num_children_gates(&self) -> usize {
+        self.members.number_child_gates
+    }
+
+    fn num_unique_gates(&self) -> usize {
+        1
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.members.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.members.num_outputs()
     }
 
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
@@ -674,8 +1076,18 @@ impl LogicGate for Splitter {
     }
 }
 
+//Faults used for fault simulation. The enable line is ignored and the buffer behaves as if E were
+// permanently HIGH (StuckEnabled) or LOW (StuckDisabled).
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ControlledBufferFault {
+    StuckEnabled,
+    StuckDisabled,
+}
+
 pub struct ControlledBuffer {
     pub members: BasicGateMembers,
+    fault: Option<ControlledBufferFault>,
 }
 
 #[allow(dead_code)]
@@ -688,7 +1100,8 @@ impl ControlledBuffer {
                 GateType::ControlledBufferType,
                 0,
                 Some(NONE),
-            )
+            ),
+            fault: None,
         };
         new_shared_mutex(
             controlled_buffer.get_unique_id().id(),
@@ -696,11 +1109,32 @@ impl ControlledBuffer {
         )
     }
 
+    //The fault overrides the E input until clear_fault() is called. The outputs are not updated
+    // until the buffer is next calculated.
+    pub fn inject_fault(&mut self, fault: ControlledBufferFault) {
+        self.fault = Some(fault);
+    }
+
+    pub fn clear_fault(&mut self) {
+        self.fault = None;
+    }
+
+    pub fn get_fault(&self) -> Option<ControlledBufferFault> {
+        self.fault
+    }
+
+    fn is_enabled(&self, input_signals: &[Signal]) -> bool {
+        match self.fault {
+            Some(ControlledBufferFault::StuckEnabled) => true,
+            Some(ControlledBufferFault::StuckDisabled) => false,
+            None => input_signals[self.get_index_from_tag("E")] == HIGH,
+        }
+    }
+
     fn fetch_output_signals(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
         //When input index 0 is HIGH, allow the signal through, otherwise return NotConnected.
         let input_signals = calculate_input_signals_from_all_inputs(&self.members.input_signals)?;
-        let enable_index = self.get_index_from_tag("E");
-        let output = if input_signals[enable_index] == HIGH {
+        let output = if self.is_enabled(&input_signals) {
             //input_signals and output_states are the same length.
             for (i, output) in self.members.output_states.iter_mut().enumerate() {
                 match output {
@@ -761,11 +1195,10 @@ impl LogicGate for ControlledBuffer {
         next_gate_input_key: usize,
         next_gate: SharedMutex<dyn LogicGate>,
     ) -> Signal {
-        let enable_index = self.get_index_from_tag("E");
         //When gates are being connected, there should be no issues with this error.
         let input_signals =
             calculate_input_signals_from_all_inputs(&self.members.input_signals).unwrap();
-        let output_signal = if input_signals[enable_index] == HIGH {
+        let output_signal = if self.is_enabled(&input_signals) {
             input_signals[current_gate_output_key].clone()
         } else {
             NONE
@@ -830,6 +1263,10 @@ impl LogicGate for ControlledBuffer {
         }
     }
 
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.members.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         self.members.remove_connected_input(input_index, connected_id);
     }
@@ -842,6 +1279,18 @@ impl LogicGate for ControlledBuffer {
         self.members.number_child_gates
     }
 
+    fn num_unique_gates(&self) -> usize {
+        1
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.members.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.members.num_outputs()
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         panic!("Basic gates do not have input gates");
     }
@@ -851,12 +1300,12 @@ impl LogicGate for ControlledBuffer {
 mod tests {
     use rand::Rng;
     use crate::globals::CLOCK_TICK_NUMBER;
-    use crate::logic::foundations::{ComplexGateMembers, connect_gates, Signal};
-    use crate::logic::foundations::Signal::{HIGH, LOW_};
-    use crate::logic::input_gates::{AutomaticInput, SimpleInput};
+    use crate::logic::foundations::{ComplexGateMembers, connect_gates, connect_gates_live, Signal};
+    use crate::logic::foundations::Signal::{HIGH, LOW_, NONE};
+    use crate::logic::input_gates::{AutomaticInput, DynamicInput, SimpleInput};
     use crate::logic::output_gates::{LogicGateAndOutputGate, SimpleOutput};
     use crate::run_circuit::run_circuit;
-    use crate::test_stuff::{collect_outputs_from_output_gates, test_simple_gate};
+    use crate::test_stuff::{assert_truth_table, collect_outputs_from_output_gates, test_simple_gate};
     use super::*;
 
     fn test_controlled_buffer(
@@ -1177,6 +1626,142 @@ mod tests {
         );
     }
 
+    #[test]
+    fn and_gate_truth_table() {
+        assert_truth_table(
+            || And::new(2, 1),
+            &[
+                (&[LOW_, LOW_], &[LOW_]),
+                (&[LOW_, HIGH], &[LOW_]),
+                (&[HIGH, LOW_], &[LOW_]),
+                (&[HIGH, HIGH], &[HIGH]),
+            ],
+        );
+    }
+
+    #[test]
+    fn nand_gate_truth_table() {
+        //A NONE input is floating, so only LOW inputs pull the And low.
+        assert_truth_table(
+            || Nand::new(2, 2),
+            &[
+                (&[NONE, LOW_], &[HIGH, HIGH]),
+                (&[NONE, HIGH], &[LOW_, LOW_]),
+                (&[NONE, NONE], &[LOW_, LOW_]),
+            ],
+        );
+
+        assert_truth_table(
+            || Nand::new(3, 1),
+            &[
+                (&[LOW_, LOW_, LOW_], &[HIGH]),
+                (&[HIGH, LOW_, HIGH], &[HIGH]),
+                (&[HIGH, HIGH, LOW_], &[HIGH]),
+                (&[HIGH, HIGH, HIGH], &[LOW_]),
+                (&[HIGH, NONE, HIGH], &[LOW_]),
+                (&[NONE, LOW_, HIGH], &[HIGH]),
+            ],
+        );
+    }
+
+    #[test]
+    fn nor_gate_truth_table() {
+        assert_truth_table(
+            || Nor::new(2, 2),
+            &[
+                (&[LOW_, LOW_], &[HIGH, HIGH]),
+                (&[LOW_, HIGH], &[LOW_, LOW_]),
+                (&[HIGH, LOW_], &[LOW_, LOW_]),
+                (&[HIGH, HIGH], &[LOW_, LOW_]),
+            ],
+        );
+
+        assert_truth_table(
+            || Nor::new(4, 1),
+            &[
+                (&[LOW_, LOW_, LOW_, LOW_], &[HIGH]),
+                (&[LOW_, HIGH, LOW_, HIGH], &[LOW_]),
+                (&[HIGH, HIGH, HIGH, HIGH], &[LOW_]),
+            ],
+        );
+    }
+
+    #[test]
+    fn nor_gate_single_high_input() {
+        let num_inputs = 4;
+
+        //Any one HIGH input forces the output LOW.
+        for high_index in 0..num_inputs {
+            let mut inputs = vec![LOW_; num_inputs];
+            inputs[high_index] = HIGH;
+
+            assert_truth_table(
+                || Nor::new(num_inputs, 1),
+                &[(&inputs, &[LOW_])],
+            );
+        }
+    }
+
+    #[test]
+    fn xnor_gate_truth_table() {
+        assert_truth_table(
+            || Xnor::new(2, 2),
+            &[
+                (&[LOW_, LOW_], &[HIGH, HIGH]),
+                (&[LOW_, HIGH], &[LOW_, LOW_]),
+                (&[HIGH, LOW_], &[LOW_, LOW_]),
+                (&[HIGH, HIGH], &[HIGH, HIGH]),
+            ],
+        );
+
+        assert_truth_table(
+            || Xnor::new(3, 1),
+            &[
+                (&[LOW_, LOW_, LOW_], &[HIGH]),
+                (&[HIGH, LOW_, LOW_], &[LOW_]),
+                (&[HIGH, HIGH, LOW_], &[LOW_]),
+                (&[HIGH, HIGH, HIGH], &[HIGH]),
+            ],
+        );
+    }
+
+    #[test]
+    fn xnor_gate_none_matches_xor() {
+        let signals = [LOW_, HIGH, NONE];
+
+        for first in signals.iter() {
+            for second in signals.iter() {
+                for third in signals.iter() {
+                    let inputs = [first.clone(), second.clone(), third.clone()];
+                    let input_signals = generate_wide_inputs(&inputs);
+
+                    let xor_signal = GateLogic::calculate_output_from_inputs(&GateType::XOrType, &input_signals).unwrap();
+                    let expected_signal = match xor_signal {
+                        NONE => NONE,
+                        HIGH => LOW_,
+                        LOW_ => HIGH,
+                    };
+
+                    assert_truth_table(
+                        || Xnor::new(3, 1),
+                        &[(&inputs, &[expected_signal])],
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn not_gate_truth_table() {
+        assert_truth_table(
+            || Not::new(1),
+            &[
+                (&[LOW_], &[HIGH]),
+                (&[HIGH], &[LOW_]),
+            ],
+        );
+    }
+
     #[test]
     fn test_controlled_buffer_initialization() {
         let output_gate = SimpleOutput::new("OUT");
@@ -1219,6 +1804,79 @@ mod tests {
         test_controlled_buffer(LOW_);
     }
 
+    fn run_faulty_controlled_buffer(
+        fault: ControlledBufferFault,
+        signal: Signal,
+        enable: Signal,
+    ) -> Signal {
+        let output_gate = SimpleOutput::new("OUT");
+        let controlled_buffer = ControlledBuffer::new(1);
+
+        connect_gates(
+            controlled_buffer.clone(),
+            0,
+            output_gate.clone(),
+            0,
+        );
+
+        controlled_buffer.lock().unwrap().inject_fault(fault);
+
+        controlled_buffer.lock().unwrap().update_input_signal(
+            GateInput::new(
+                0,
+                signal,
+                UniqueID::zero_id(),
+            )
+        );
+
+        let enable_index = controlled_buffer.lock().unwrap().get_index_from_tag("E");
+        controlled_buffer.lock().unwrap().update_input_signal(
+            GateInput::new(
+                enable_index,
+                enable,
+                UniqueID::zero_id(),
+            )
+        );
+
+        let output = controlled_buffer.lock().unwrap().fetch_output_signals_calculate().unwrap();
+
+        assert_eq!(output.len(), 1);
+        match &output[0] {
+            GateOutputState::NotConnected(_) => panic!("Output should be connected."),
+            GateOutputState::Connected(connected_output) => connected_output.throughput.signal.clone(),
+        }
+    }
+
+    #[test]
+    fn test_controlled_buffer_stuck_enabled() {
+        for enable in [HIGH, LOW_, NONE] {
+            for signal in [HIGH, LOW_] {
+                let output = run_faulty_controlled_buffer(
+                    ControlledBufferFault::StuckEnabled,
+                    signal.clone(),
+                    enable.clone(),
+                );
+
+                assert_eq!(output, signal);
+            }
+        }
+    }
+
+    #[test]
+    fn test_controlled_buffer_stuck_disabled() {
+        for enable in [HIGH, LOW_, NONE] {
+            for signal in [HIGH, LOW_] {
+                let output = run_faulty_controlled_buffer(
+                    ControlledBufferFault::StuckDisabled,
+                    signal,
+                    enable.clone(),
+                );
+
+                assert_eq!(output, NONE);
+            }
+        }
+    }
+
     #[test]
     fn test_none_signal_working() {
         let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
@@ -1313,6 +1971,56 @@ mod tests {
         assert_eq!(collected_output, output_signal);
     }
 
+    #[test]
+    fn dynamic_input_drives_counter_pattern() {
+        //The two outputs count up in binary, so the And is only HIGH when both bits are set.
+        let counter_input = DynamicInput::new(
+            Box::new(|tick| {
+                vec![
+                    if tick & 1 == 1 { HIGH } else { LOW_ },
+                    if tick & 2 == 2 { HIGH } else { LOW_ },
+                ]
+            }),
+            "COUNTER",
+        );
+        let and_gate = And::new(2, 1);
+        let output_gate = SimpleOutput::new("OUT");
+
+        connect_gates(counter_input.clone(), 0, and_gate.clone(), 0);
+        connect_gates(counter_input.clone(), 1, and_gate.clone(), 1);
+        connect_gates(and_gate.clone(), 0, output_gate.clone(), 0);
+
+        let input_gates: Vec<SharedMutex<dyn LogicGate>> = vec![counter_input];
+        let output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = vec![output_gate];
+
+        let mut collected_output: Vec<Vec<Signal>> = Vec::new();
+        let mut propagate_signal_through_circuit = true;
+        for _ in 0..8 {
+            unsafe {
+                CLOCK_TICK_NUMBER += 1;
+            }
+
+            run_circuit(
+                &input_gates,
+                &output_gates,
+                propagate_signal_through_circuit,
+                &mut |_clock_tick_inputs, output_gates: &Vec<SharedMutex<dyn LogicGateAndOutputGate>>| {
+                    collect_output_for_run_circuit(&mut collected_output, &output_gates);
+                },
+            );
+
+            propagate_signal_through_circuit = false;
+        }
+
+        assert_eq!(
+            collected_output,
+            vec![
+                vec![LOW_], vec![LOW_], vec![LOW_], vec![HIGH],
+                vec![LOW_], vec![LOW_], vec![LOW_], vec![HIGH],
+            ]
+        );
+    }
+
     #[test]
     #[should_panic]
     fn test_controlled_buffer_multiple_inputs() {
@@ -1498,6 +2206,10 @@ mod tests {
                 self.complex_gate.get_index_from_tag(tag)
             }
 
+            fn is_input_connected(&self, input_index: usize) -> bool {
+                self.complex_gate.is_input_connected(input_index)
+            }
+
             fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
                 self.complex_gate.remove_connected_input(input_index, connected_id);
             }
@@ -1510,6 +2222,18 @@ mod tests {
                 self.complex_gate.simple_gate.number_child_gates
             }
 
+            fn num_unique_gates(&self) -> usize {
+                self.complex_gate.count_unique_gates()
+            }
+
+            fn num_inputs(&self) -> usize {
+                self.complex_gate.num_inputs()
+            }
+
+            fn num_outputs(&self) -> usize {
+                self.complex_gate.num_outputs()
+            }
+
             fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
                 self.complex_gate.input_gates.clone()
             }
@@ -1556,6 +2280,60 @@ mod tests {
         assert_eq!(collected_output, output_signal);
     }
 
+    #[test]
+    fn connect_gates_live_propagates_to_primed_circuit() {
+        let source_input = SimpleInput::new(1, "SOURCE");
+        let not_gate = Not::new(1);
+        let output_gate = SimpleOutput::new("OUT");
+
+        connect_gates(
+            not_gate.clone(),
+            0,
+            output_gate.clone(),
+            0,
+        );
+
+        not_gate.lock().unwrap().update_input_signal(
+            GateInput::new(
+                0,
+                LOW_,
+                UniqueID::zero_id(),
+            )
+        );
+
+        source_input.lock().unwrap().update_input_signal(
+            GateInput::new(
+                0,
+                HIGH,
+                UniqueID::zero_id(),
+            )
+        );
+
+        let input_gates: Vec<SharedMutex<dyn LogicGate>> = vec![not_gate.clone(), source_input.clone()];
+        let output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = vec![output_gate.clone()];
+        run_circuit(
+            &input_gates,
+            &output_gates,
+            true,
+            &mut |_clock_tick_inputs, _output_gates| {},
+        );
+
+        let mut collected_output = Vec::new();
+        collect_outputs_from_output_gates(&&output_gates, &mut collected_output);
+        assert_eq!(collected_output, vec![HIGH]);
+
+        connect_gates_live(
+            source_input.clone(),
+            0,
+            not_gate.clone(),
+            0,
+        );
+
+        let mut collected_output = Vec::new();
+        collect_outputs_from_output_gates(&&output_gates, &mut collected_output);
+        assert_eq!(collected_output, vec![LOW_]);
+    }
+
     #[test]
     fn splitter_properly_splits() {
         let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
@@ -1636,4 +2414,159 @@ mod tests {
         println!("{:#?}", collected_output);
         assert_eq!(collected_output, output_signal);
     }
+
+    #[test]
+    fn fanout_splitter_copies_bus() {
+        let num_bits = 4;
+        let num_copies = 3;
+        let input_signals = vec![HIGH, LOW_, HIGH, HIGH];
+
+        let fanout_splitter = FanoutSplitter::new(num_bits, num_copies);
+
+        let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+        for (i, signal) in input_signals.iter().enumerate() {
+            let input_tag = format!("IN_{}", i);
+            let input_gate = AutomaticInput::new(vec![signal.clone()], 1, input_tag.as_str());
+
+            connect_gates(
+                input_gate.clone(),
+                0,
+                fanout_splitter.clone(),
+                i,
+            );
+
+            input_gates.push(input_gate);
+        }
+
+        let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
+        for copy in 0..num_copies {
+            for bit in 0..num_bits {
+                let output_tag = format!("OUT_{}_{}", copy, bit);
+                let output_gate = SimpleOutput::new(output_tag.as_str());
+                let splitter_output = fanout_splitter.lock().unwrap().get_index_for_copy(copy, bit);
+                connect_gates(
+                    fanout_splitter.clone(),
+                    splitter_output,
+                    output_gate.clone(),
+                    0,
+                );
+
+                output_gates.push(output_gate);
+            }
+        }
+
+        let mut collected_output: Vec<Vec<Signal>> = Vec::new();
+
+        run_circuit(
+            &input_gates,
+            &output_gates,
+            false,
+            &mut |_clock_tick_inputs, output_gates: &Vec<SharedMutex<dyn LogicGateAndOutputGate>>| {
+                let mut single_collected_output = Vec::new();
+                collect_outputs_from_output_gates(&output_gates, &mut single_collected_output);
+
+                collected_output.push(single_collected_output);
+            },
+        );
+
+        //Each copy is a contiguous bus holding the input.
+        let expected_output: Vec<Signal> = (0..num_copies).flat_map(|_| input_signals.clone()).collect();
+        assert_eq!(collected_output, vec![expected_output]);
+    }
+
+    fn generate_wide_inputs(input_signals: &[Signal]) -> Vec<HashMap<UniqueID, Signal>> {
+        input_signals
+            .iter()
+            .map(|signal| HashMap::from([(UniqueID::generate(), signal.clone())]))
+            .collect()
+    }
+
+    #[test]
+    fn wide_and_or_early_exit_matches_full_scan() {
+        for _ in 0..50 {
+            let num_inputs = rand::thread_rng().gen_range(2..64);
+            let signals: Vec<Signal> = (0..num_inputs)
+                .map(|_| {
+                    match rand::thread_rng().gen_range(0..10) {
+                        0 => LOW_,
+                        1 => NONE,
+                        _ => HIGH,
+                    }
+                })
+                .collect();
+
+            let input_signals = generate_wide_inputs(&signals);
+            let full_scan_signals = calculate_input_signals_from_all_inputs(&input_signals).unwrap();
+
+            let (and_signal, _) = calculate_output_with_early_exit(&input_signals, LOW_).unwrap();
+            assert_eq!(and_signal, GateLogic::calculate_output_for_and(&full_scan_signals));
+
+            let (or_signal, _) = calculate_output_with_early_exit(&input_signals, HIGH).unwrap();
+            assert_eq!(or_signal, GateLogic::calculate_output_for_or(&full_scan_signals));
+
+            for gate_type in [GateType::AndType, GateType::OrType, GateType::NandType, GateType::NorType] {
+                let expected_signal = match gate_type {
+                    GateType::AndType => GateLogic::calculate_output_for_and(&full_scan_signals),
+                    GateType::OrType => GateLogic::calculate_output_for_or(&full_scan_signals),
+                    GateType::NandType => GateLogic::calculate_output_for_nand(&full_scan_signals),
+                    _ => GateLogic::calculate_output_for_nor(&full_scan_signals),
+                };
+
+                assert_eq!(
+                    GateLogic::calculate_output_from_inputs(&gate_type, &input_signals).unwrap(),
+                    expected_signal
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn wide_and_early_exit_stops_at_dominant_input() {
+        let num_inputs = 64;
+        let dominant_index = 10;
+        let mut signals = vec![HIGH; num_inputs];
+        signals[dominant_index] = LOW_;
+
+        let input_signals = generate_wide_inputs(&signals);
+
+        let full_scan_signals = calculate_input_signals_from_all_inputs(&input_signals).unwrap();
+        assert_eq!(full_scan_signals.len(), num_inputs);
+        assert_eq!(GateLogic::calculate_output_for_and(&full_scan_signals), LOW_);
+
+        let (output_signal, num_resolved) = calculate_output_with_early_exit(&input_signals, LOW_).unwrap();
+        assert_eq!(output_signal, LOW_);
+        assert_eq!(num_resolved, dominant_index + 1);
+
+        //An Or finds its dominant HIGH on the first input.
+        let (output_signal, num_resolved) = calculate_output_with_early_exit(&input_signals, HIGH).unwrap();
+        assert_eq!(output_signal, HIGH);
+        assert_eq!(num_resolved, 1);
+
+        //Without a dominant signal every input must be resolved.
+        let input_signals = generate_wide_inputs(&vec![HIGH; num_inputs]);
+        let (output_signal, num_resolved) = calculate_output_with_early_exit(&input_signals, LOW_).unwrap();
+        assert_eq!(output_signal, HIGH);
+        assert_eq!(num_resolved, num_inputs);
+    }
+
+    #[test]
+    fn wide_and_early_exit_only_reports_contention_before_dominant_input() {
+        let mut input_signals = generate_wide_inputs(&[HIGH, LOW_, HIGH]);
+        input_signals[0].insert(UniqueID::generate(), LOW_);
+
+        for gate_type in [GateType::AndType, GateType::OrType, GateType::NandType, GateType::NorType] {
+            assert_eq!(
+                GateLogic::calculate_output_from_inputs(&gate_type, &input_signals),
+                Err(GateLogicError::MultipleValidSignalsWhenCalculating)
+            );
+        }
+
+        let mut input_signals = generate_wide_inputs(&[LOW_, HIGH, HIGH]);
+        input_signals[2].insert(UniqueID::generate(), LOW_);
+
+        assert_eq!(
+            calculate_output_with_early_exit(&input_signals, LOW_),
+            Ok((LOW_, 1))
+        );
+    }
 }
@@ -1,15 +1,18 @@
-use crate::logic::basic_gates::{And, Nand, Not, Or, Splitter};
+use crate::logic::basic_gates::{And, ControlledBuffer, Nand, Not, Or, Splitter, XOr};
 
 #[allow(unused_imports)]
-use crate::logic::foundations::{BasicGateMembers, build_simple_inputs_and_outputs, build_simple_inputs_and_outputs_with_and, calculate_input_signals_from_all_inputs, ComplexGateMembers, GateInput, GateLogicError, GateOutputState, GateType, InputSignalReturn, LogicGate, Signal, UniqueID};
+use crate::logic::foundations::{BasicGateMembers, build_simple_inputs_and_outputs, build_simple_inputs_and_outputs_with_and, calculate_input_signals_from_all_inputs, ComplexGateMembers, GateInput, GateLogicError, GateOutputState, GateCounts, GateType, InputSignalReturn, LogicGate, Signal, UniqueID, GateTagType};
+use crate::logic::foundations::impl_complex_logic_gate;
 use crate::logic::foundations::connect_gates;
 
 use crate::logic::input_gates::SimpleInput;
 use crate::logic::output_gates::{LogicGateAndOutputGate, SimpleOutput};
 
 #[allow(unused_imports)]
-use crate::logic::foundations::Signal::{LOW_, HIGH};
+use crate::logic::foundations::Signal::{LOW_, HIGH, NONE};
 use crate::logic::memory_gates::OneBitMemoryCell;
+use crate::logic::processor_components::VariableDecoder;
+use crate::logic::netlist::Netlist;
 use crate::shared_mutex::{new_shared_mutex, SharedMutex};
 
 pub struct VariableOutputStepper {
@@ -383,6 +386,14 @@ impl LogicGate for VariableOutputStepper {
         self.complex_gate.get_index_from_tag(tag)
     }
 
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         self.complex_gate.remove_connected_input(input_index, connected_id);
     }
@@ -395,9 +406,29 @@ impl LogicGate for VariableOutputStepper {
         self.complex_gate.simple_gate.number_child_gates
     }
 
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         self.complex_gate.input_gates.clone()
     }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
 }
 
 pub struct VariableBitCPUEnable {
@@ -537,6 +568,14 @@ impl LogicGate for VariableBitCPUEnable {
         self.complex_gate.get_index_from_tag(tag)
     }
 
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         self.complex_gate.remove_connected_input(input_index, connected_id);
     }
@@ -549,9 +588,29 @@ impl LogicGate for VariableBitCPUEnable {
         self.complex_gate.simple_gate.number_child_gates
     }
 
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         self.complex_gate.input_gates.clone()
     }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
 }
 
 //Note that this is not a 'real' logic gate. Its purpose is to stop the signal from propagating to
@@ -704,6 +763,14 @@ impl LogicGate for SignalGatekeeper {
         self.complex_gate.get_index_from_tag(tag)
     }
 
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         self.complex_gate.remove_connected_input(input_index, connected_id);
     }
@@ -716,9 +783,29 @@ impl LogicGate for SignalGatekeeper {
         self.complex_gate.simple_gate.number_child_gates
     }
 
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         self.complex_gate.input_gates.clone()
     }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
 }
 
 pub struct MasterSlaveJKFlipFlop {
@@ -1041,6 +1128,14 @@ impl LogicGate for MasterSlaveJKFlipFlop {
         self.complex_gate.get_index_from_tag(tag)
     }
 
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         self.complex_gate.remove_connected_input(input_index, connected_id);
     }
@@ -1053,250 +1148,149 @@ impl LogicGate for MasterSlaveJKFlipFlop {
         self.complex_gate.simple_gate.number_child_gates
     }
 
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         self.complex_gate.input_gates.clone()
     }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
 }
 
-pub struct FourCycleClockHookup {
+//Toggles Q on each rising edge of CLK_IN while T is HIGH and holds Q while T is LOW. This is a
+// MasterSlaveJKFlipFlop with J and K tied together. The MasterSlaveJKFlipFlop changes its output
+// when the clock goes LOW, so the clock is inverted to change on the rising edge instead.
+pub struct TFlipFlop {
     complex_gate: ComplexGateMembers,
     flip_flop: SharedMutex<MasterSlaveJKFlipFlop>,
-    q_splitter: SharedMutex<Splitter>,
-    q_input_and: SharedMutex<And>,
-    q_not_input_nand: SharedMutex<Nand>,
-    flip_flop_clk_in_not: SharedMutex<Not>,
+    clk_in_not_gate: SharedMutex<Not>,
 }
 
 #[allow(dead_code)]
-impl FourCycleClockHookup {
+impl TFlipFlop {
     //Inputs
+    pub const T: &'static str = "T";
     pub const CLK_IN: &'static str = "CLK_IN";
 
     //Outputs
-    pub const CLK_OUT: &'static str = "CLK_OUT";
-    pub const CLKS: &'static str = "CLKS";
-    pub const CLKE: &'static str = "CLKE";
+    pub const Q: &'static str = "Q";
+    pub const NOT_Q: &'static str = "NOT_Q";
 
     pub fn new() -> SharedMutex<Self> {
         let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
         let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
         let mut output_gates_logic: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
 
-        let input_gate = SimpleInput::new(3, FourCycleClockHookup::CLK_IN);
-
-        //Order of input gates is important here to force the circuit into a deterministic state.
-        input_gates.push(input_gate.clone());
+        input_gates.push(SimpleInput::new(2, TFlipFlop::T));
+        input_gates.push(SimpleInput::new(1, TFlipFlop::CLK_IN));
 
         let mut store_output = |gate: SharedMutex<SimpleOutput>| {
             output_gates.push(gate.clone());
             output_gates_logic.push(gate.clone());
         };
 
-        store_output(SimpleOutput::new(FourCycleClockHookup::CLK_OUT));
-        store_output(SimpleOutput::new(FourCycleClockHookup::CLKS));
-        store_output(SimpleOutput::new(FourCycleClockHookup::CLKE));
+        store_output(SimpleOutput::new(TFlipFlop::Q));
+        store_output(SimpleOutput::new(TFlipFlop::NOT_Q));
 
-        let mut clock_hookup = FourCycleClockHookup {
+        let mut flip_flop = TFlipFlop {
             complex_gate: ComplexGateMembers::new(
-                1,
-                3,
-                GateType::FourCycleClockHookupType,
+                2,
+                2,
+                GateType::TFlipFlopType,
                 input_gates,
                 output_gates,
             ),
             flip_flop: MasterSlaveJKFlipFlop::new(),
-            q_splitter: Splitter::new(1, 2),
-            q_input_and: And::new(2, 1),
-            q_not_input_nand: Nand::new(2, 1),
-            flip_flop_clk_in_not: Not::new(1),
+            clk_in_not_gate: Not::new(1),
         };
 
-        clock_hookup.flip_flop.lock().unwrap().set_tag("flip_flop");
-        clock_hookup.q_splitter.lock().unwrap().set_tag("q_splitter");
-        clock_hookup.q_input_and.lock().unwrap().set_tag("q_input_and");
-        clock_hookup.q_not_input_nand.lock().unwrap().set_tag("q_not_input_nand");
-        clock_hookup.flip_flop_clk_in_not.lock().unwrap().set_tag("flip_flop_clk_in_not");
+        flip_flop.flip_flop.lock().unwrap().set_tag("flip_flop");
+        flip_flop.clk_in_not_gate.lock().unwrap().set_tag("clk_in_not_gate");
 
-        clock_hookup.build_and_prime_circuit(
-            output_gates_logic
-        );
+        flip_flop.build_and_prime_circuit(output_gates_logic);
 
-        new_shared_mutex(clock_hookup.get_unique_id().id(), clock_hookup)
+        new_shared_mutex(flip_flop.get_unique_id().id(), flip_flop)
     }
 
     fn build_and_prime_circuit(
         &mut self,
         output_gates: Vec<SharedMutex<dyn LogicGate>>,
     ) {
+        let t_input_gate = self.complex_gate.input_gates[self.get_index_from_tag(TFlipFlop::T)].clone();
+        let clk_input_gate = self.complex_gate.input_gates[self.get_index_from_tag(TFlipFlop::CLK_IN)].clone();
 
-        //Set J to high
         let j_input_index = self.flip_flop.lock().unwrap().get_index_from_tag(MasterSlaveJKFlipFlop::J);
-        self.flip_flop.lock().unwrap().update_input_signal(
-            GateInput::new(
-                j_input_index,
-                HIGH,
-                UniqueID::zero_id(),
-            )
-        );
-
-        //Set K to high
         let k_input_index = self.flip_flop.lock().unwrap().get_index_from_tag(MasterSlaveJKFlipFlop::K);
-        self.flip_flop.lock().unwrap().update_input_signal(
-            GateInput::new(
-                k_input_index,
-                HIGH,
-                UniqueID::zero_id(),
-            )
-        );
+        let clk_input_index = self.flip_flop.lock().unwrap().get_index_from_tag(MasterSlaveJKFlipFlop::CLK_IN);
+        let q_output_index = self.flip_flop.lock().unwrap().get_index_from_tag(MasterSlaveJKFlipFlop::Q);
+        let not_q_output_index = self.flip_flop.lock().unwrap().get_index_from_tag(MasterSlaveJKFlipFlop::NOT_Q);
 
-        //CLK_IN input
-        let clk_input_index = self.get_index_from_tag(FourCycleClockHookup::CLK_IN);
-        let clk_input_gate = self.complex_gate.input_gates[clk_input_index].clone();
         connect_gates(
-            clk_input_gate.clone(),
-            0,
-            self.q_input_and.clone(),
+            t_input_gate.clone(),
             0,
+            self.flip_flop.clone(),
+            j_input_index,
         );
 
         connect_gates(
-            clk_input_gate.clone(),
-            1,
-            self.q_not_input_nand.clone(),
+            t_input_gate.clone(),
             1,
+            self.flip_flop.clone(),
+            k_input_index,
         );
 
         connect_gates(
             clk_input_gate.clone(),
-            2,
-            self.flip_flop_clk_in_not.clone(),
+            0,
+            self.clk_in_not_gate.clone(),
             0,
         );
 
-        let clk_input_index = self.flip_flop.lock().unwrap().get_index_from_tag(MasterSlaveJKFlipFlop::CLK_IN);
         connect_gates(
-            self.flip_flop_clk_in_not.clone(),
+            self.clk_in_not_gate.clone(),
             0,
             self.flip_flop.clone(),
             clk_input_index,
         );
 
-        let q_output_index = self.flip_flop.lock().unwrap().get_index_from_tag(MasterSlaveJKFlipFlop::Q);
         connect_gates(
             self.flip_flop.clone(),
             q_output_index,
-            self.q_splitter.clone(),
+            output_gates[self.get_index_from_tag(TFlipFlop::Q)].clone(),
             0,
         );
 
-        let output_index = self.q_splitter.lock().unwrap().get_index_for_output(0, 0);
-        connect_gates(
-            self.q_splitter.clone(),
-            output_index,
-            self.q_input_and.clone(),
-            1,
-        );
-
-        let output_index = self.get_index_from_tag(FourCycleClockHookup::CLK_OUT);
-        let splitter_output_index = self.q_splitter.lock().unwrap().get_index_for_output(0, 1);
-        connect_gates(
-            self.q_splitter.clone(),
-            splitter_output_index,
-            output_gates[output_index].clone(),
-            1,
-        );
-
-        let not_q_output_index = self.flip_flop.lock().unwrap().get_index_from_tag(MasterSlaveJKFlipFlop::NOT_Q);
         connect_gates(
             self.flip_flop.clone(),
             not_q_output_index,
-            self.q_not_input_nand.clone(),
-            0,
-        );
-
-        let output_index = self.get_index_from_tag(FourCycleClockHookup::CLKS);
-        connect_gates(
-            self.q_input_and.clone(),
-            0,
-            output_gates[output_index].clone(),
+            output_gates[self.get_index_from_tag(TFlipFlop::NOT_Q)].clone(),
             0,
         );
 
-        let output_index = self.get_index_from_tag(FourCycleClockHookup::CLKE);
-        connect_gates(
-            self.q_not_input_nand.clone(),
-            0,
-            output_gates[output_index].clone(),
-            0,
-        );
-
-        #[cfg(feature = "high_restriction")]
-        self.check_output();
-
-        let mut input_signal = LOW_;
-        for i in 0..4 {
-            if input_signal == LOW_ {
-                input_signal = HIGH;
-            } else {
-                input_signal = LOW_;
-            }
-
-            clk_input_gate.lock().unwrap().update_input_signal(
-                GateInput::new(
-                    0,
-                    input_signal.clone(),
-                    UniqueID::zero_id(),
-                )
-            );
-
-            self.complex_gate.calculate_output_from_inputs_and_set_child_count(
-                if i == 0 { true } else { false },
-            );
-        }
-
-        clk_input_gate.lock().unwrap().update_input_signal(
-            GateInput::new(
-                0,
-                LOW_,
-                UniqueID::zero_id(),
-            )
+        //Prime gates
+        self.complex_gate.calculate_output_from_inputs_and_set_child_count(
+            true,
         );
     }
-
-    #[cfg(feature = "high_restriction")]
-    fn check_output(&self) {
-        println!("Running high_restriction!");
-        fn check_output(simple_gate: &BasicGateMembers) {
-            for o in simple_gate.output_states.iter() {
-                if let GateOutputState::NotConnected(_) = o {
-                    panic!("A gate output for type {} id {} was not set\n{:#?}", simple_gate.gate_type, simple_gate.unique_id.id(), simple_gate.output_states);
-                }
-            }
-
-            for i in simple_gate.input_signals.iter() {
-                if i.len() == 0 {
-                    panic!("A gate input for type {} id {} was empty", simple_gate.gate_type, simple_gate.unique_id.id());
-                } else if i.len() == 1 {
-                    let (id, _signal) = i.iter().next().unwrap();
-                    if id.id() == 0 {
-                        panic!("A gate input for type {} id {} was empty\n{:#?}", simple_gate.gate_type, simple_gate.unique_id.id(), simple_gate.input_signals);
-                    }
-                } else {
-                    panic!("Multiple inputs connected for the same gate")
-                }
-            }
-        }
-
-        //J and K on the flip flop are tied high
-        // check_output(&self.flip_flop.lock().unwrap().complex_gate.simple_gate);
-        check_output(&self.q_splitter.lock().unwrap().members);
-        check_output(&self.q_input_and.lock().unwrap().members);
-        check_output(&self.q_not_input_nand.lock().unwrap().members);
-        check_output(&self.flip_flop_clk_in_not.lock().unwrap().members);
-    }
 }
 
-impl LogicGate for FourCycleClockHookup {
+impl LogicGate for TFlipFlop {
     fn internal_connect_output(&mut self, current_gate_output_key: usize, next_gate_input_key: usize, next_gate: SharedMutex<dyn LogicGate>) -> Signal {
         self.complex_gate.connect_output(
             self.get_unique_id(),
@@ -1311,8 +1305,6 @@ impl LogicGate for FourCycleClockHookup {
     }
 
     fn update_input_signal(&mut self, input: GateInput) -> InputSignalReturn {
-        //ActiveLowSRLatch has an `invalid` state of LOW LOW. However, this is not being enforced by
-        // assertions because it may be an intermediate state.
         self.complex_gate.update_input_signal(input)
     }
 
@@ -1352,6 +1344,14 @@ impl LogicGate for FourCycleClockHookup {
         self.complex_gate.get_index_from_tag(tag)
     }
 
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         self.complex_gate.remove_connected_input(input_index, connected_id);
     }
@@ -1364,241 +1364,129 @@ impl LogicGate for FourCycleClockHookup {
         self.complex_gate.simple_gate.number_child_gates
     }
 
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         self.complex_gate.input_gates.clone()
     }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
 }
 
-pub struct VariableBitMultiplexer {
+//Each TFlipFlop halves the frequency of the clock driving it, so they are chained together with
+// the Q output of one flip flop driving the clock of the next.
+pub struct ClockDivider {
     complex_gate: ComplexGateMembers,
-    input_and_gates: Vec<SharedMutex<And>>,
-    input_or_gates: Vec<SharedMutex<Or>>,
-    control_lines: Vec<SharedMutex<Not>>,
+    t_flip_flops: Vec<SharedMutex<TFlipFlop>>,
 }
 
 #[allow(dead_code)]
-impl VariableBitMultiplexer {
-    pub fn new(bus_size: usize, number_inputs: usize) -> SharedMutex<Self> {
-        assert_ne!(bus_size, 0);
-        assert_ne!(number_inputs, 0);
+impl ClockDivider {
+    //Inputs
+    pub const CLK_IN: &'static str = "CLK_IN";
 
-        let num_control_lines = number_inputs.ilog2() as usize;
+    //Outputs
+    pub const CLK_OUT: &'static str = "CLK_OUT";
 
-        assert_eq!(usize::pow(2, num_control_lines as u32), number_inputs);
+    pub fn new(divisor: usize) -> SharedMutex<Self> {
+        assert!(divisor >= 2 && divisor.is_power_of_two());
 
         let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
         let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
         let mut output_gates_logic: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
 
-        let mut input_and_gates: Vec<SharedMutex<And>> = Vec::new();
-        let mut input_or_gates: Vec<SharedMutex<Or>> = Vec::new();
-        let mut control_lines: Vec<SharedMutex<Not>> = Vec::new();
-
-
-        let loop_num = bus_size * number_inputs;
-        for i in 0..loop_num {
-            let and_gate = And::new(num_control_lines + 1, 1);
-            and_gate.lock().unwrap().set_tag(format!("and_{}", i).as_str());
-            input_and_gates.push(and_gate);
-        }
-
-        for i in 0..bus_size {
-            let or_gate = Or::new(number_inputs, 1);
-            or_gate.lock().unwrap().set_tag(format!("or_{}", i).as_str());
-            input_or_gates.push(or_gate);
-        }
-
-        for i in 0..number_inputs {
-            for j in 0..bus_size {
-                let input_tag = format!("I_{}_bit_{}", i, j);
-                input_gates.push(SimpleInput::new(1, input_tag.as_str()));
-            }
-        }
-
-        for i in 0..num_control_lines {
-            let not_gate = Not::new((number_inputs * bus_size) / 2);
-            not_gate.lock().unwrap().set_tag(format!("not_{}", i).as_str());
-            control_lines.push(not_gate);
-
-            let input_tag = format!("C_{}", i);
-            input_gates.push(SimpleInput::new((number_inputs * bus_size) / 2 + 1, input_tag.as_str()));
-        }
+        input_gates.push(SimpleInput::new(1, ClockDivider::CLK_IN));
 
-        let mut store_output = |gate: SharedMutex<SimpleOutput>| {
-            output_gates.push(gate.clone());
-            output_gates_logic.push(gate.clone());
-        };
+        let output_gate = SimpleOutput::new(ClockDivider::CLK_OUT);
+        output_gates.push(output_gate.clone());
+        output_gates_logic.push(output_gate);
 
-        for i in 0..bus_size {
-            let output_tag = format!("o_{}", i);
-            store_output(SimpleOutput::new(output_tag.as_str()));
+        let mut t_flip_flops = Vec::new();
+        for i in 0..divisor.trailing_zeros() {
+            let t_flip_flop = TFlipFlop::new();
+            t_flip_flop.lock().unwrap().set_tag(format!("t_flip_flop_{}", i).as_str());
+            t_flip_flops.push(t_flip_flop);
         }
 
-        let mut multiplexer = VariableBitMultiplexer {
+        let mut clock_divider = ClockDivider {
             complex_gate: ComplexGateMembers::new(
-                num_control_lines + bus_size * number_inputs,
-                bus_size,
-                GateType::VariableBitMultiplexerType,
+                1,
+                1,
+                GateType::ClockDividerType,
                 input_gates,
                 output_gates,
             ),
-            input_and_gates,
-            input_or_gates,
-            control_lines,
+            t_flip_flops,
         };
 
-        multiplexer.build_and_prime_circuit(
-            bus_size,
-            number_inputs,
-            num_control_lines,
-            output_gates_logic,
-        );
+        clock_divider.build_and_prime_circuit(output_gates_logic);
 
-        new_shared_mutex(multiplexer.get_unique_id().id(), multiplexer)
+        new_shared_mutex(clock_divider.get_unique_id().id(), clock_divider)
     }
 
     fn build_and_prime_circuit(
         &mut self,
-        bus_size: usize,
-        number_inputs: usize,
-        num_control_lines: usize,
         output_gates: Vec<SharedMutex<dyn LogicGate>>,
     ) {
-        let mut not_current_index = vec![0; num_control_lines];
-        let mut normal_current_index = vec![0; num_control_lines];
+        let t_input_index = self.t_flip_flops[0].lock().unwrap().get_index_from_tag(TFlipFlop::T);
+        let clk_input_index = self.t_flip_flops[0].lock().unwrap().get_index_from_tag(TFlipFlop::CLK_IN);
+        let q_output_index = self.t_flip_flops[0].lock().unwrap().get_index_from_tag(TFlipFlop::Q);
 
-        //Connect input control lines to not gates.
-        for i in 0..num_control_lines {
-            let control_input_tag = format!("C_{}", i);
-            let control_index = self.get_index_from_tag(control_input_tag.as_str());
-            let control_input_gate = self.complex_gate.input_gates[control_index].clone();
+        let mut previous_gate: SharedMutex<dyn LogicGate> = self.complex_gate.input_gates[self.get_index_from_tag(ClockDivider::CLK_IN)].clone();
+        let mut previous_output_index = 0;
 
-            connect_gates(
-                control_input_gate.clone(),
-                normal_current_index[i],
-                self.control_lines[i].clone(),
-                0,
+        for t_flip_flop in self.t_flip_flops.iter() {
+            //Set T to high so the flip flop toggles on every clock.
+            t_flip_flop.lock().unwrap().update_input_signal(
+                GateInput::new(
+                    t_input_index,
+                    HIGH,
+                    UniqueID::zero_id(),
+                )
             );
 
-            normal_current_index[i] += 1;
-        }
-
-        //Connect inputs, control lines, and not gates to and gates.
-        for i in 0..number_inputs {
-            let binary_number = format!("{:0width$b}", i, width = num_control_lines);
-            assert_eq!(binary_number.len(), num_control_lines);
-            for j in 0..bus_size {
-                let input_tag = format!("I_{}_bit_{}", i, j);
-                let input_index = self.get_index_from_tag(input_tag.as_str());
-                let input_gate = self.complex_gate.input_gates[input_index].clone();
-
-                let and_gate_index = j * number_inputs + i;
-                connect_gates(
-                    input_gate.clone(),
-                    0,
-                    self.input_and_gates[and_gate_index].clone(),
-                    0,
-                );
-
-                for (k, c) in binary_number.chars().enumerate() {
-                    if c == '0' {
-                        //Not input to and gate.
-                        connect_gates(
-                            self.control_lines[k].clone(),
-                            not_current_index[k],
-                            self.input_and_gates[and_gate_index].clone(),
-                            k + 1,
-                        );
-                        not_current_index[k] += 1;
-                    } else {
-                        //Normal input to and gate
-                        let control_input_tag = format!("C_{}", k);
-                        let control_index = self.get_index_from_tag(control_input_tag.as_str());
-                        let control_input_gate = self.complex_gate.input_gates[control_index].clone();
-
-                        connect_gates(
-                            control_input_gate.clone(),
-                            normal_current_index[k],
-                            self.input_and_gates[and_gate_index].clone(),
-                            k + 1,
-                        );
-
-                        normal_current_index[k] += 1;
-                    }
-                }
-            }
-        }
-
-        for i in 0..self.input_and_gates.len() {
-            let or_gate_index = i / number_inputs;
-            let next_gate_input_key = i % number_inputs;
-
             connect_gates(
-                self.input_and_gates[i].clone(),
-                0,
-                self.input_or_gates[or_gate_index].clone(),
-                next_gate_input_key,
+                previous_gate,
+                previous_output_index,
+                t_flip_flop.clone(),
+                clk_input_index,
             );
-        };
 
-        for i in 0..bus_size {
-            let output_tag = format!("o_{}", i);
-            let output_index = self.get_index_from_tag(output_tag.as_str());
-            connect_gates(
-                self.input_or_gates[i].clone(),
-                0,
-                output_gates[output_index].clone(),
-                0,
-            );
+            previous_gate = t_flip_flop.clone();
+            previous_output_index = q_output_index;
         }
 
-        #[cfg(feature = "high_restriction")]
-        self.check_output();
+        connect_gates(
+            previous_gate,
+            previous_output_index,
+            output_gates[self.get_index_from_tag(ClockDivider::CLK_OUT)].clone(),
+            0,
+        );
 
+        //Prime gates
         self.complex_gate.calculate_output_from_inputs_and_set_child_count(
             true,
         );
     }
-
-    #[cfg(feature = "high_restriction")]
-    fn check_output(&self) {
-        println!("Running high_restriction!");
-        fn check_output(simple_gate: &BasicGateMembers) {
-            for o in simple_gate.output_states.iter() {
-                if let GateOutputState::NotConnected(_) = o {
-                    panic!("A gate output for type {} id {} was not set\n{:#?}", simple_gate.gate_type, simple_gate.unique_id.id(), simple_gate.output_states);
-                }
-            }
-
-            for i in simple_gate.input_signals.iter() {
-                if i.len() == 0 {
-                    panic!("A gate input for type {} id {} was empty", simple_gate.gate_type, simple_gate.unique_id.id());
-                } else if i.len() == 1 {
-                    let (id, _signal) = i.iter().next().unwrap();
-                    if id.id() == 0 {
-                        panic!("A gate input for type {} id {} was empty\n{:#?}", simple_gate.gate_type, simple_gate.unique_id.id(), simple_gate.input_signals);
-                    }
-                } else {
-                    panic!("Multiple inputs connected for the same gate")
-                }
-            }
-        }
-
-        for i in self.input_and_gates.iter() {
-            check_output(&i.lock().unwrap().members);
-        }
-
-        for i in self.input_or_gates.iter() {
-            check_output(&i.lock().unwrap().members);
-        }
-
-        for i in self.control_lines.iter() {
-            check_output(&i.lock().unwrap().members);
-        }
-    }
 }
 
-impl LogicGate for VariableBitMultiplexer {
+impl LogicGate for ClockDivider {
     fn internal_connect_output(&mut self, current_gate_output_key: usize, next_gate_input_key: usize, next_gate: SharedMutex<dyn LogicGate>) -> Signal {
         self.complex_gate.connect_output(
             self.get_unique_id(),
@@ -1613,8 +1501,6 @@ impl LogicGate for VariableBitMultiplexer {
     }
 
     fn update_input_signal(&mut self, input: GateInput) -> InputSignalReturn {
-        //ActiveLowSRLatch has an `invalid` state of LOW LOW. However, this is not being enforced by
-        // assertions because it may be an intermediate state.
         self.complex_gate.update_input_signal(input)
     }
 
@@ -1654,6 +1540,14 @@ impl LogicGate for VariableBitMultiplexer {
         self.complex_gate.get_index_from_tag(tag)
     }
 
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         self.complex_gate.remove_connected_input(input_index, connected_id);
     }
@@ -1666,70 +1560,92 @@ impl LogicGate for VariableBitMultiplexer {
         self.complex_gate.simple_gate.number_child_gates
     }
 
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         self.complex_gate.input_gates.clone()
     }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
 }
 
-pub struct VariableBitCounter {
+pub struct FourCycleClockHookup {
     complex_gate: ComplexGateMembers,
-    flip_flops: Vec<SharedMutex<MasterSlaveJKFlipFlop>>,
-    flip_flop_clk_in_not_gates: Vec<SharedMutex<Not>>,
-    flip_flop_output_splitter: SharedMutex<Splitter>,
+    flip_flop: SharedMutex<MasterSlaveJKFlipFlop>,
+    q_splitter: SharedMutex<Splitter>,
+    q_input_and: SharedMutex<And>,
+    q_not_input_nand: SharedMutex<Nand>,
+    flip_flop_clk_in_not: SharedMutex<Not>,
 }
 
 #[allow(dead_code)]
-impl VariableBitCounter {
+impl FourCycleClockHookup {
     //Inputs
     pub const CLK_IN: &'static str = "CLK_IN";
 
-    pub fn new(num_output_pins: usize) -> SharedMutex<Self> {
+    //Outputs
+    pub const CLK_OUT: &'static str = "CLK_OUT";
+    pub const CLKS: &'static str = "CLKS";
+    pub const CLKE: &'static str = "CLKE";
+
+    pub fn new() -> SharedMutex<Self> {
         let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
         let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
         let mut output_gates_logic: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
 
-        let input_gate = SimpleInput::new(1, Self::CLK_IN);
+        let input_gate = SimpleInput::new(3, FourCycleClockHookup::CLK_IN);
 
         //Order of input gates is important here to force the circuit into a deterministic state.
         input_gates.push(input_gate.clone());
 
-        let mut flip_flops = Vec::new();
-        let mut flip_flop_clk_in_not_gates = Vec::new();
-
         let mut store_output = |gate: SharedMutex<SimpleOutput>| {
             output_gates.push(gate.clone());
             output_gates_logic.push(gate.clone());
         };
 
-        for i in 0..num_output_pins {
-            let output_tag = format!("o_{}", i);
-            store_output(SimpleOutput::new(output_tag.as_str()));
-
-            flip_flops.push(MasterSlaveJKFlipFlop::new());
-            flip_flop_clk_in_not_gates.push(Not::new(1));
-        }
+        store_output(SimpleOutput::new(FourCycleClockHookup::CLK_OUT));
+        store_output(SimpleOutput::new(FourCycleClockHookup::CLKS));
+        store_output(SimpleOutput::new(FourCycleClockHookup::CLKE));
 
-        let mut clock_hookup = VariableBitCounter {
+        let mut clock_hookup = FourCycleClockHookup {
             complex_gate: ComplexGateMembers::new(
                 1,
-                num_output_pins,
-                GateType::VariableBitCounterType,
+                3,
+                GateType::FourCycleClockHookupType,
                 input_gates,
                 output_gates,
             ),
-            flip_flops,
-            flip_flop_clk_in_not_gates,
-            flip_flop_output_splitter: Splitter::new(num_output_pins, 2),
-        };
-
-        for i in 0..num_output_pins {
-            clock_hookup.flip_flops[i].lock().unwrap().set_tag(format!("flip_flop_{}", i).as_str());
-            clock_hookup.flip_flop_clk_in_not_gates[i].lock().unwrap().set_tag(format!("flip_flop_clk_in_not_{}", i).as_str());
-        }
+            flip_flop: MasterSlaveJKFlipFlop::new(),
+            q_splitter: Splitter::new(1, 2),
+            q_input_and: And::new(2, 1),
+            q_not_input_nand: Nand::new(2, 1),
+            flip_flop_clk_in_not: Not::new(1),
+        };
+
+        clock_hookup.flip_flop.lock().unwrap().set_tag("flip_flop");
+        clock_hookup.q_splitter.lock().unwrap().set_tag("q_splitter");
+        clock_hookup.q_input_and.lock().unwrap().set_tag("q_input_and");
+        clock_hookup.q_not_input_nand.lock().unwrap().set_tag("q_not_input_nand");
+        clock_hookup.flip_flop_clk_in_not.lock().unwrap().set_tag("flip_flop_clk_in_not");
 
         clock_hookup.build_and_prime_circuit(
-            output_gates_logic,
-            num_output_pins,
+            output_gates_logic
         );
 
         new_shared_mutex(clock_hookup.get_unique_id().id(), clock_hookup)
@@ -1738,73 +1654,139 @@ impl VariableBitCounter {
     fn build_and_prime_circuit(
         &mut self,
         output_gates: Vec<SharedMutex<dyn LogicGate>>,
-        num_output_pins: usize,
     ) {
-        for i in 0..num_output_pins {
-            //Set J to high
-            let j_input_index = self.flip_flops[i].lock().unwrap().get_index_from_tag(MasterSlaveJKFlipFlop::J);
-            self.flip_flops[i].lock().unwrap().update_input_signal(
-                GateInput::new(
-                    j_input_index,
-                    HIGH,
-                    UniqueID::zero_id(),
-                )
-            );
 
-            //Set K to high
-            let k_input_index = self.flip_flops[i].lock().unwrap().get_index_from_tag(MasterSlaveJKFlipFlop::K);
-            self.flip_flops[i].lock().unwrap().update_input_signal(
-                GateInput::new(
-                    k_input_index,
-                    HIGH,
-                    UniqueID::zero_id(),
-                )
-            );
+        //Set J to high
+        let j_input_index = self.flip_flop.lock().unwrap().get_index_from_tag(MasterSlaveJKFlipFlop::J);
+        self.flip_flop.lock().unwrap().update_input_signal(
+            GateInput::new(
+                j_input_index,
+                HIGH,
+                UniqueID::zero_id(),
+            )
+        );
 
-            let not_q_output_index = self.flip_flops[i].lock().unwrap().get_index_from_tag(MasterSlaveJKFlipFlop::NOT_Q);
-            connect_gates(
-                self.flip_flops[i].clone(),
-                not_q_output_index,
-                self.flip_flop_output_splitter.clone(),
-                i,
-            );
+        //Set K to high
+        let k_input_index = self.flip_flop.lock().unwrap().get_index_from_tag(MasterSlaveJKFlipFlop::K);
+        self.flip_flop.lock().unwrap().update_input_signal(
+            GateInput::new(
+                k_input_index,
+                HIGH,
+                UniqueID::zero_id(),
+            )
+        );
 
-            if i == 0 {
-                //CLK_IN input
-                let clk_input_index = self.get_index_from_tag(Self::CLK_IN);
-                let clk_input_gate = self.complex_gate.input_gates[clk_input_index].clone();
+        //CLK_IN input
+        let clk_input_index = self.get_index_from_tag(FourCycleClockHookup::CLK_IN);
+        let clk_input_gate = self.complex_gate.input_gates[clk_input_index].clone();
+        connect_gates(
+            clk_input_gate.clone(),
+            0,
+            self.q_input_and.clone(),
+            0,
+        );
 
-                let clk_input_index = self.flip_flops[i].lock().unwrap().get_index_from_tag(MasterSlaveJKFlipFlop::CLK_IN);
-                connect_gates(
-                    clk_input_gate.clone(),
-                    0,
-                    self.flip_flops[i].clone(),
-                    clk_input_index,
-                );
+        connect_gates(
+            clk_input_gate.clone(),
+            1,
+            self.q_not_input_nand.clone(),
+            1,
+        );
+
+        connect_gates(
+            clk_input_gate.clone(),
+            2,
+            self.flip_flop_clk_in_not.clone(),
+            0,
+        );
+
+        let clk_input_index = self.flip_flop.lock().unwrap().get_index_from_tag(MasterSlaveJKFlipFlop::CLK_IN);
+        connect_gates(
+            self.flip_flop_clk_in_not.clone(),
+            0,
+            self.flip_flop.clone(),
+            clk_input_index,
+        );
+
+        let q_output_index = self.flip_flop.lock().unwrap().get_index_from_tag(MasterSlaveJKFlipFlop::Q);
+        connect_gates(
+            self.flip_flop.clone(),
+            q_output_index,
+            self.q_splitter.clone(),
+            0,
+        );
+
+        let output_index = self.q_splitter.lock().unwrap().get_index_for_output(0, 0);
+        connect_gates(
+            self.q_splitter.clone(),
+            output_index,
+            self.q_input_and.clone(),
+            1,
+        );
+
+        let output_index = self.get_index_from_tag(FourCycleClockHookup::CLK_OUT);
+        let splitter_output_index = self.q_splitter.lock().unwrap().get_index_for_output(0, 1);
+        connect_gates(
+            self.q_splitter.clone(),
+            splitter_output_index,
+            output_gates[output_index].clone(),
+            1,
+        );
+
+        let not_q_output_index = self.flip_flop.lock().unwrap().get_index_from_tag(MasterSlaveJKFlipFlop::NOT_Q);
+        connect_gates(
+            self.flip_flop.clone(),
+            not_q_output_index,
+            self.q_not_input_nand.clone(),
+            0,
+        );
+
+        let output_index = self.get_index_from_tag(FourCycleClockHookup::CLKS);
+        connect_gates(
+            self.q_input_and.clone(),
+            0,
+            output_gates[output_index].clone(),
+            0,
+        );
+
+        let output_index = self.get_index_from_tag(FourCycleClockHookup::CLKE);
+        connect_gates(
+            self.q_not_input_nand.clone(),
+            0,
+            output_gates[output_index].clone(),
+            0,
+        );
+
+        #[cfg(feature = "high_restriction")]
+        self.check_output();
+
+        let mut input_signal = LOW_;
+        for i in 0..4 {
+            if input_signal == LOW_ {
+                input_signal = HIGH;
             } else {
-                let clk_input_index = self.flip_flops[i].lock().unwrap().get_index_from_tag(MasterSlaveJKFlipFlop::CLK_IN);
-                let splitter_output_index = self.flip_flop_output_splitter.lock().unwrap().get_index_for_output(i - 1, 1);
-                connect_gates(
-                    self.flip_flop_output_splitter.clone(),
-                    splitter_output_index,
-                    self.flip_flops[i].clone(),
-                    clk_input_index,
-                );
+                input_signal = LOW_;
             }
 
-            let splitter_output_index = self.flip_flop_output_splitter.lock().unwrap().get_index_for_output(i, 0);
-            let output_tag = format!("o_{}", i);
-            let output_index = self.get_index_from_tag(output_tag.as_str());
-            connect_gates(
-                self.flip_flop_output_splitter.clone(),
-                splitter_output_index,
-                output_gates[output_index].clone(),
-                0,
+            clk_input_gate.lock().unwrap().update_input_signal(
+                GateInput::new(
+                    0,
+                    input_signal.clone(),
+                    UniqueID::zero_id(),
+                )
+            );
+
+            self.complex_gate.calculate_output_from_inputs_and_set_child_count(
+                if i == 0 { true } else { false },
             );
         }
 
-        self.complex_gate.calculate_output_from_inputs_and_set_child_count(
-            true,
+        clk_input_gate.lock().unwrap().update_input_signal(
+            GateInput::new(
+                0,
+                LOW_,
+                UniqueID::zero_id(),
+            )
         );
     }
 
@@ -1820,11 +1802,11 @@ impl VariableBitCounter {
 
             for i in simple_gate.input_signals.iter() {
                 if i.len() == 0 {
-                    panic!("A gate input for type {} id {} tag {} was empty", simple_gate.gate_type, simple_gate.unique_id.id(), simple_gate.tag);
+                    panic!("A gate input for type {} id {} was empty", simple_gate.gate_type, simple_gate.unique_id.id());
                 } else if i.len() == 1 {
                     let (id, _signal) = i.iter().next().unwrap();
                     if id.id() == 0 {
-                        panic!("A gate input for type {} id {} tag {} was empty\n{:#?}", simple_gate.gate_type, simple_gate.unique_id.id(), simple_gate.tag, simple_gate.input_signals);
+                        panic!("A gate input for type {} id {} was empty\n{:#?}", simple_gate.gate_type, simple_gate.unique_id.id(), simple_gate.input_signals);
                     }
                 } else {
                     panic!("Multiple inputs connected for the same gate")
@@ -1832,21 +1814,2297 @@ impl VariableBitCounter {
             }
         }
 
-        for i in 0..self.flip_flops.len() {
-            check_output(&self.flip_flops[i].lock().unwrap().complex_gate.simple_gate);
-            check_output(&self.flip_flop_clk_in_not_gates[i].lock().unwrap().members);
+        //J and K on the flip flop are tied high
+        // check_output(&self.flip_flop.lock().unwrap().complex_gate.simple_gate);
+        check_output(&self.q_splitter.lock().unwrap().members);
+        check_output(&self.q_input_and.lock().unwrap().members);
+        check_output(&self.q_not_input_nand.lock().unwrap().members);
+        check_output(&self.flip_flop_clk_in_not.lock().unwrap().members);
+    }
+}
+
+impl LogicGate for FourCycleClockHookup {
+    fn internal_connect_output(&mut self, current_gate_output_key: usize, next_gate_input_key: usize, next_gate: SharedMutex<dyn LogicGate>) -> Signal {
+        self.complex_gate.connect_output(
+            self.get_unique_id(),
+            current_gate_output_key,
+            next_gate_input_key,
+            next_gate,
+        )
+    }
+
+    fn internal_update_index_to_id(&mut self, sending_id: UniqueID, gate_input_index: usize, signal: Signal) {
+        self.complex_gate.internal_update_index_to_id(sending_id, gate_input_index, signal);
+    }
+
+    fn update_input_signal(&mut self, input: GateInput) -> InputSignalReturn {
+        //ActiveLowSRLatch has an `invalid` state of LOW LOW. However, this is not being enforced by
+        // assertions because it may be an intermediate state.
+        self.complex_gate.update_input_signal(input)
+    }
+
+    fn fetch_output_signals_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
+        self.complex_gate.fetch_output_signals_calculate(
+            &self.get_tag(),
+        )
+    }
+
+    fn fetch_output_signals_no_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
+        self.complex_gate.fetch_output_signals_no_calculate(
+            &self.get_tag(),
+        )
+    }
+
+    fn get_gate_type(&self) -> GateType {
+        self.complex_gate.simple_gate.gate_type
+    }
+
+    fn get_unique_id(&self) -> UniqueID {
+        self.complex_gate.simple_gate.unique_id
+    }
+
+    fn toggle_output_printing(&mut self, print_output: bool) {
+        self.complex_gate.simple_gate.should_print_output = print_output;
+    }
+
+    fn get_tag(&self) -> String {
+        self.complex_gate.simple_gate.tag.clone()
+    }
+
+    fn set_tag(&mut self, tag: &str) {
+        self.complex_gate.simple_gate.tag = tag.to_string();
+    }
+
+    fn get_index_from_tag(&self, tag: &str) -> usize {
+        self.complex_gate.get_index_from_tag(tag)
+    }
+
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
+    fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
+        self.complex_gate.remove_connected_input(input_index, connected_id);
+    }
+
+    fn toggle_print_each_input_output_gate(&mut self, print_each_input_output_gate: bool) {
+        self.complex_gate.toggle_print_each_input_output_gate(print_each_input_output_gate);
+    }
+
+    fn num_children_gates(&self) -> usize {
+        self.complex_gate.simple_gate.number_child_gates
+    }
+
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
+    fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
+        self.complex_gate.input_gates.clone()
+    }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
+}
+
+pub struct VariableBitMultiplexer {
+    complex_gate: ComplexGateMembers,
+    input_and_gates: Vec<SharedMutex<And>>,
+    input_or_gates: Vec<SharedMutex<Or>>,
+    control_lines: Vec<SharedMutex<Not>>,
+}
+
+#[allow(dead_code)]
+impl VariableBitMultiplexer {
+    pub fn new(bus_size: usize, number_inputs: usize) -> SharedMutex<Self> {
+        assert_ne!(bus_size, 0);
+        assert_ne!(number_inputs, 0);
+
+        let num_control_lines = number_inputs.ilog2() as usize;
+
+        assert_eq!(usize::pow(2, num_control_lines as u32), number_inputs);
+
+        let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+        let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
+        let mut output_gates_logic: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+
+        let mut input_and_gates: Vec<SharedMutex<And>> = Vec::new();
+        let mut input_or_gates: Vec<SharedMutex<Or>> = Vec::new();
+        let mut control_lines: Vec<SharedMutex<Not>> = Vec::new();
+
+
+        let loop_num = bus_size * number_inputs;
+        for i in 0..loop_num {
+            let and_gate = And::new(num_control_lines + 1, 1);
+            and_gate.lock().unwrap().set_tag(format!("and_{}", i).as_str());
+            input_and_gates.push(and_gate);
+        }
+
+        for i in 0..bus_size {
+            let or_gate = Or::new(number_inputs, 1);
+            or_gate.lock().unwrap().set_tag(format!("or_{}", i).as_str());
+            input_or_gates.push(or_gate);
+        }
+
+        for i in 0..number_inputs {
+            for j in 0..bus_size {
+                let input_tag = format!("I_{}_bit_{}", i, j);
+                input_gates.push(SimpleInput::new(1, input_tag.as_str()));
+            }
+        }
+
+        for i in 0..num_control_lines {
+            let not_gate = Not::new((number_inputs * bus_size) / 2);
+            not_gate.lock().unwrap().set_tag(format!("not_{}", i).as_str());
+            control_lines.push(not_gate);
+
+            let input_tag = format!("C_{}", i);
+            input_gates.push(SimpleInput::new((number_inputs * bus_size) / 2 + 1, input_tag.as_str()));
+        }
+
+        let mut store_output = |gate: SharedMutex<SimpleOutput>| {
+            output_gates.push(gate.clone());
+            output_gates_logic.push(gate.clone());
+        };
+
+        for i in 0..bus_size {
+            let output_tag = format!("o_{}", i);
+            store_output(SimpleOutput::new(output_tag.as_str()));
+        }
+
+        let mut multiplexer = VariableBitMultiplexer {
+            complex_gate: ComplexGateMembers::new(
+                num_control_lines + bus_size * number_inputs,
+                bus_size,
+                GateType::VariableBitMultiplexerType,
+                input_gates,
+                output_gates,
+            ),
+            input_and_gates,
+            input_or_gates,
+            control_lines,
+        };
+
+        multiplexer.build_and_prime_circuit(
+            bus_size,
+            number_inputs,
+            num_control_lines,
+            output_gates_logic,
+        );
+
+        new_shared_mutex(multiplexer.get_unique_id().id(), multiplexer)
+    }
+
+    fn build_and_prime_circuit(
+        &mut self,
+        bus_size: usize,
+        number_inputs: usize,
+        num_control_lines: usize,
+        output_gates: Vec<SharedMutex<dyn LogicGate>>,
+    ) {
+        let mut not_current_index = vec![0; num_control_lines];
+        let mut normal_current_index = vec![0; num_control_lines];
+
+        //Connect input control lines to not gates.
+        for i in 0..num_control_lines {
+            let control_input_tag = format!("C_{}", i);
+            let control_index = self.get_index_from_tag(control_input_tag.as_str());
+            let control_input_gate = self.complex_gate.input_gates[control_index].clone();
+
+            connect_gates(
+                control_input_gate.clone(),
+                normal_current_index[i],
+                self.control_lines[i].clone(),
+                0,
+            );
+
+            normal_current_index[i] += 1;
+        }
+
+        //Connect inputs, control lines, and not gates to and gates.
+        for i in 0..number_inputs {
+            let binary_number = format!("{:0width$b}", i, width = num_control_lines);
+            assert_eq!(binary_number.len(), num_control_lines);
+            for j in 0..bus_size {
+                let input_tag = format!("I_{}_bit_{}", i, j);
+                let input_index = self.get_index_from_tag(input_tag.as_str());
+                let input_gate = self.complex_gate.input_gates[input_index].clone();
+
+                let and_gate_index = j * number_inputs + i;
+                connect_gates(
+                    input_gate.clone(),
+                    0,
+                    self.input_and_gates[and_gate_index].clone(),
+                    0,
+                );
+
+                for (k, c) in binary_number.chars().enumerate() {
+                    if c == '0' {
+                        //Not input to and gate.
+                        connect_gates(
+                            self.control_lines[k].clone(),
+                            not_current_index[k],
+                            self.input_and_gates[and_gate_index].clone(),
+                            k + 1,
+                        );
+                        not_current_index[k] += 1;
+                    } else {
+                        //Normal input to and gate
+                        let control_input_tag = format!("C_{}", k);
+                        let control_index = self.get_index_from_tag(control_input_tag.as_str());
+                        let control_input_gate = self.complex_gate.input_gates[control_index].clone();
+
+                        connect_gates(
+                            control_input_gate.clone(),
+                            normal_current_index[k],
+                            self.input_and_gates[and_gate_index].clone(),
+                            k + 1,
+                        );
+
+                        normal_current_index[k] += 1;
+                    }
+                }
+            }
+        }
+
+        for i in 0..self.input_and_gates.len() {
+            let or_gate_index = i / number_inputs;
+            let next_gate_input_key = i % number_inputs;
+
+            connect_gates(
+                self.input_and_gates[i].clone(),
+                0,
+                self.input_or_gates[or_gate_index].clone(),
+                next_gate_input_key,
+            );
+        };
+
+        for i in 0..bus_size {
+            let output_tag = format!("o_{}", i);
+            let output_index = self.get_index_from_tag(output_tag.as_str());
+            connect_gates(
+                self.input_or_gates[i].clone(),
+                0,
+                output_gates[output_index].clone(),
+                0,
+            );
+        }
+
+        #[cfg(feature = "high_restriction")]
+        self.check_output();
+
+        self.complex_gate.calculate_output_from_inputs_and_set_child_count(
+            true,
+        );
+    }
+
+    #[cfg(feature = "high_restriction")]
+    fn check_output(&self) {
+        println!("Running high_restriction!");
+        fn check_output(simple_gate: &BasicGateMembers) {
+            for o in simple_gate.output_states.iter() {
+                if let GateOutputState::NotConnected(_) = o {
+                    panic!("A gate output for type {} id {} was not set\n{:#?}", simple_gate.gate_type, simple_gate.unique_id.id(), simple_gate.output_states);
+                }
+            }
+
+            for i in simple_gate.input_signals.iter() {
+                if i.len() == 0 {
+                    panic!("A gate input for type {} id {} was empty", simple_gate.gate_type, simple_gate.unique_id.id());
+                } else if i.len() == 1 {
+                    let (id, _signal) = i.iter().next().unwrap();
+                    if id.id() == 0 {
+                        panic!("A gate input for type {} id {} was empty\n{:#?}", simple_gate.gate_type, simple_gate.unique_id.id(), simple_gate.input_signals);
+                    }
+                } else {
+                    panic!("Multiple inputs connected for the same gate")
+                }
+            }
+        }
+
+        for i in self.input_and_gates.iter() {
+            check_output(&i.lock().unwrap().members);
+        }
+
+        for i in self.input_or_gates.iter() {
+            check_output(&i.lock().unwrap().members);
+        }
+
+        for i in self.control_lines.iter() {
+            check_output(&i.lock().unwrap().members);
+        }
+    }
+}
+
+impl LogicGate for VariableBitMultiplexer {
+    fn internal_connect_output(&mut self, current_gate_output_key: usize, next_gate_input_key: usize, next_gate: SharedMutex<dyn LogicGate>) -> Signal {
+        self.complex_gate.connect_output(
+            self.get_unique_id(),
+            current_gate_output_key,
+            next_gate_input_key,
+            next_gate,
+        )
+    }
+
+    fn internal_update_index_to_id(&mut self, sending_id: UniqueID, gate_input_index: usize, signal: Signal) {
+        self.complex_gate.internal_update_index_to_id(sending_id, gate_input_index, signal);
+    }
+
+    fn update_input_signal(&mut self, input: GateInput) -> InputSignalReturn {
+        //ActiveLowSRLatch has an `invalid` state of LOW LOW. However, this is not being enforced by
+        // assertions because it may be an intermediate state.
+        self.complex_gate.update_input_signal(input)
+    }
+
+    fn fetch_output_signals_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
+        self.complex_gate.fetch_output_signals_calculate(
+            &self.get_tag(),
+        )
+    }
+
+    fn fetch_output_signals_no_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
+        self.complex_gate.fetch_output_signals_no_calculate(
+            &self.get_tag(),
+        )
+    }
+
+    fn get_gate_type(&self) -> GateType {
+        self.complex_gate.simple_gate.gate_type
+    }
+
+    fn get_unique_id(&self) -> UniqueID {
+        self.complex_gate.simple_gate.unique_id
+    }
+
+    fn toggle_output_printing(&mut self, print_output: bool) {
+        self.complex_gate.simple_gate.should_print_output = print_output;
+    }
+
+    fn get_tag(&self) -> String {
+        self.complex_gate.simple_gate.tag.clone()
+    }
+
+    fn set_tag(&mut self, tag: &str) {
+        self.complex_gate.simple_gate.tag = tag.to_string();
+    }
+
+    fn get_index_from_tag(&self, tag: &str) -> usize {
+        self.complex_gate.get_index_from_tag(tag)
+    }
+
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
+    fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
+        self.complex_gate.remove_connected_input(input_index, connected_id);
+    }
+
+    fn toggle_print_each_input_output_gate(&mut self, print_each_input_output_gate: bool) {
+        self.complex_gate.toggle_print_each_input_output_gate(print_each_input_output_gate);
+    }
+
+    fn num_children_gates(&self) -> usize {
+        self.complex_gate.simple_gate.number_child_gates
+    }
+
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
+    fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
+        self.complex_gate.input_gates.clone()
+    }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
+}
+
+//Selects one of 2^num_select_lines input buses and passes it to the output. The select lines are
+// read as a binary number with S_0 as the least significant bit, so setting S to 2 will output the
+// bus i_2_0..i_2_n. A select line that is NONE enables the inputs on both sides of it, so the
+// output will be every matching input bus OR'ed together.
+pub struct Multiplexer {
+    complex_gate: ComplexGateMembers,
+    input_and_gates: Vec<SharedMutex<And>>,
+    output_or_gates: Vec<SharedMutex<Or>>,
+    select_not_gates: Vec<SharedMutex<Not>>,
+}
+
+#[allow(dead_code)]
+impl Multiplexer {
+    pub fn new(num_bits: usize, num_select_lines: usize) -> SharedMutex<Self> {
+        assert_ne!(num_bits, 0);
+
+        let num_inputs = usize::pow(2, num_select_lines as u32);
+
+        //Each select line is HIGH for exactly half of the input buses.
+        let num_select_fan_out = (num_inputs * num_bits) / 2;
+
+        let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+        let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
+        let mut output_gates_logic: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+
+        let mut input_and_gates: Vec<SharedMutex<And>> = Vec::new();
+        let mut output_or_gates: Vec<SharedMutex<Or>> = Vec::new();
+        let mut select_not_gates: Vec<SharedMutex<Not>> = Vec::new();
+
+        for i in 0..num_inputs {
+            for j in 0..num_bits {
+                let input_tag = format!("i_{}_{}", i, j);
+                input_gates.push(SimpleInput::new(1, input_tag.as_str()));
+
+                let and_gate = And::new(num_select_lines + 1, 1);
+                and_gate.lock().unwrap().set_tag(format!("and_{}_{}", i, j).as_str());
+                input_and_gates.push(and_gate);
+            }
+        }
+
+        for i in 0..num_select_lines {
+            let input_tag = format!("S_{}", i);
+            input_gates.push(SimpleInput::new(num_select_fan_out + 1, input_tag.as_str()));
+
+            let not_gate = Not::new(num_select_fan_out);
+            not_gate.lock().unwrap().set_tag(format!("not_{}", i).as_str());
+            select_not_gates.push(not_gate);
+        }
+
+        for i in 0..num_bits {
+            let output_tag = format!("o_{}", i);
+            let output_gate = SimpleOutput::new(output_tag.as_str());
+            output_gates.push(output_gate.clone());
+            output_gates_logic.push(output_gate);
+
+            let or_gate = Or::new(num_inputs, 1);
+            or_gate.lock().unwrap().set_tag(format!("or_{}", i).as_str());
+            output_or_gates.push(or_gate);
+        }
+
+        let mut multiplexer = Multiplexer {
+            complex_gate: ComplexGateMembers::new(
+                num_inputs * num_bits + num_select_lines,
+                num_bits,
+                GateType::MultiplexerType,
+                input_gates,
+                output_gates,
+            ),
+            input_and_gates,
+            output_or_gates,
+            select_not_gates,
+        };
+
+        multiplexer.build_and_prime_circuit(
+            num_bits,
+            num_inputs,
+            num_select_lines,
+            output_gates_logic,
+        );
+
+        new_shared_mutex(multiplexer.get_unique_id().id(), multiplexer)
+    }
+
+    fn build_and_prime_circuit(
+        &mut self,
+        num_bits: usize,
+        num_inputs: usize,
+        num_select_lines: usize,
+        output_gates: Vec<SharedMutex<dyn LogicGate>>,
+    ) {
+        let select_input_gates: Vec<SharedMutex<dyn LogicGate>> = (0..num_select_lines)
+            .map(|i| {
+                let select_index = self.get_index_from_tag(format!("S_{}", i).as_str());
+                self.complex_gate.input_gates[select_index].clone()
+            })
+            .collect();
+
+        //Output 0 of each select input always drives its Not gate.
+        let mut select_output_index = vec![1; num_select_lines];
+        let mut not_output_index = vec![0; num_select_lines];
+
+        for (i, select_input_gate) in select_input_gates.iter().enumerate() {
+            connect_gates(
+                select_input_gate.clone(),
+                0,
+                self.select_not_gates[i].clone(),
+                0,
+            );
+        }
+
+        for i in 0..num_inputs {
+            for j in 0..num_bits {
+                let and_gate = self.input_and_gates[i * num_bits + j].clone();
+
+                let input_index = self.get_index_from_tag(format!("i_{}_{}", i, j).as_str());
+                connect_gates(
+                    self.complex_gate.input_gates[input_index].clone(),
+                    0,
+                    and_gate.clone(),
+                    0,
+                );
+
+                //The and gate for input i is only enabled when the select lines spell out i.
+                for k in 0..num_select_lines {
+                    if (i >> k) & 1 == 1 {
+                        connect_gates(
+                            select_input_gates[k].clone(),
+                            select_output_index[k],
+                            and_gate.clone(),
+                            k + 1,
+                        );
+                        select_output_index[k] += 1;
+                    } else {
+                        connect_gates(
+                            self.select_not_gates[k].clone(),
+                            not_output_index[k],
+                            and_gate.clone(),
+                            k + 1,
+                        );
+                        not_output_index[k] += 1;
+                    }
+                }
+
+                connect_gates(
+                    and_gate,
+                    0,
+                    self.output_or_gates[j].clone(),
+                    i,
+                );
+            }
+        }
+
+        for (i, or_gate) in self.output_or_gates.iter().enumerate() {
+            let output_index = self.get_index_from_tag(format!("o_{}", i).as_str());
+            connect_gates(
+                or_gate.clone(),
+                0,
+                output_gates[output_index].clone(),
+                0,
+            );
+        }
+
+        //Prime gates
+        self.complex_gate.calculate_output_from_inputs_and_set_child_count(
+            true,
+        );
+    }
+}
+
+impl_complex_logic_gate!(Multiplexer);
+
+//Routes the input bus to one of 2^num_select_lines output buses, every other output bus is LOW. The
+// select lines are read as a binary number with S_0 as the least significant bit, so setting S to 2
+// will send i_0..i_n to o_2_0..o_2_n.
+pub struct Demultiplexer {
+    complex_gate: ComplexGateMembers,
+    output_and_gates: Vec<SharedMutex<And>>,
+    select_not_gates: Vec<SharedMutex<Not>>,
+}
+
+#[allow(dead_code)]
+impl Demultiplexer {
+    pub fn new(num_bits: usize, num_select_lines: usize) -> SharedMutex<Self> {
+        assert_ne!(num_bits, 0);
+
+        let num_outputs = usize::pow(2, num_select_lines as u32);
+
+        //Each select line is HIGH for exactly half of the output buses.
+        let num_select_fan_out = (num_outputs * num_bits) / 2;
+
+        let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+        let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
+        let mut output_gates_logic: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+
+        let mut output_and_gates: Vec<SharedMutex<And>> = Vec::new();
+        let mut select_not_gates: Vec<SharedMutex<Not>> = Vec::new();
+
+        for i in 0..num_bits {
+            let input_tag = format!("i_{}", i);
+            input_gates.push(SimpleInput::new(num_outputs, input_tag.as_str()));
+        }
+
+        for i in 0..num_select_lines {
+            let input_tag = format!("S_{}", i);
+            input_gates.push(SimpleInput::new(num_select_fan_out + 1, input_tag.as_str()));
+
+            let not_gate = Not::new(num_select_fan_out);
+            not_gate.lock().unwrap().set_tag(format!("not_{}", i).as_str());
+            select_not_gates.push(not_gate);
+        }
+
+        for i in 0..num_outputs {
+            for j in 0..num_bits {
+                let output_tag = format!("o_{}_{}", i, j);
+                let output_gate = SimpleOutput::new(output_tag.as_str());
+                output_gates.push(output_gate.clone());
+                output_gates_logic.push(output_gate);
+
+                let and_gate = And::new(num_select_lines + 1, 1);
+                and_gate.lock().unwrap().set_tag(format!("and_{}_{}", i, j).as_str());
+                output_and_gates.push(and_gate);
+            }
+        }
+
+        let mut demultiplexer = Demultiplexer {
+            complex_gate: ComplexGateMembers::new(
+                num_bits + num_select_lines,
+                num_outputs * num_bits,
+                GateType::DemultiplexerType,
+                input_gates,
+                output_gates,
+            ),
+            output_and_gates,
+            select_not_gates,
+        };
+
+        demultiplexer.build_and_prime_circuit(
+            num_bits,
+            num_outputs,
+            num_select_lines,
+            output_gates_logic,
+        );
+
+        new_shared_mutex(demultiplexer.get_unique_id().id(), demultiplexer)
+    }
+
+    fn build_and_prime_circuit(
+        &mut self,
+        num_bits: usize,
+        num_outputs: usize,
+        num_select_lines: usize,
+        output_gates: Vec<SharedMutex<dyn LogicGate>>,
+    ) {
+        let select_input_gates: Vec<SharedMutex<dyn LogicGate>> = (0..num_select_lines)
+            .map(|i| {
+                let select_index = self.get_index_from_tag(format!("S_{}", i).as_str());
+                self.complex_gate.input_gates[select_index].clone()
+            })
+            .collect();
+
+        //Output 0 of each select input always drives its Not gate.
+        let mut select_output_index = vec![1; num_select_lines];
+        let mut not_output_index = vec![0; num_select_lines];
+
+        for (i, select_input_gate) in select_input_gates.iter().enumerate() {
+            connect_gates(
+                select_input_gate.clone(),
+                0,
+                self.select_not_gates[i].clone(),
+                0,
+            );
+        }
+
+        for i in 0..num_outputs {
+            for j in 0..num_bits {
+                let and_gate = self.output_and_gates[i * num_bits + j].clone();
+
+                let input_index = self.get_index_from_tag(format!("i_{}", j).as_str());
+                connect_gates(
+                    self.complex_gate.input_gates[input_index].clone(),
+                    i,
+                    and_gate.clone(),
+                    0,
+                );
+
+                //The and gate for output i is only enabled when the select lines spell out i.
+                for k in 0..num_select_lines {
+                    if (i >> k) & 1 == 1 {
+                        connect_gates(
+                            select_input_gates[k].clone(),
+                            select_output_index[k],
+                            and_gate.clone(),
+                            k + 1,
+                        );
+                        select_output_index[k] += 1;
+                    } else {
+                        connect_gates(
+                            self.select_not_gates[k].clone(),
+                            not_output_index[k],
+                            and_gate.clone(),
+                            k + 1,
+                        );
+                        not_output_index[k] += 1;
+                    }
+                }
+
+                let output_index = self.get_index_from_tag(format!("o_{}_{}", i, j).as_str());
+                connect_gates(
+                    and_gate,
+                    0,
+                    output_gates[output_index].clone(),
+                    0,
+                );
+            }
+        }
+
+        //Prime gates
+        self.complex_gate.calculate_output_from_inputs_and_set_child_count(
+            true,
+        );
+    }
+}
+
+impl_complex_logic_gate!(Demultiplexer);
+
+//A lookup table where table[i] is the output for the inputs read as the binary number i, with i_0
+// as the least significant bit. The decoder enables the single row of controlled buffers matching
+// the inputs, every row has its data inputs tied to the values from the table.
+pub struct Lut {
+    complex_gate: ComplexGateMembers,
+    decoder: SharedMutex<VariableDecoder>,
+    row_buffers: Vec<SharedMutex<ControlledBuffer>>,
+}
+
+#[allow(dead_code)]
+impl Lut {
+    pub fn new(num_inputs: usize, table: Vec<Vec<Signal>>) -> SharedMutex<Self> {
+        assert_ne!(num_inputs, 0);
+        assert_eq!(table.len(), usize::pow(2, num_inputs as u32));
+
+        let num_outputs = table[0].len();
+        assert_ne!(num_outputs, 0);
+        assert!(table.iter().all(|row| row.len() == num_outputs));
+
+        let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+        let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
+        let mut output_gates_logic: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+
+        for i in 0..num_inputs {
+            let input_tag = format!("i_{}", i);
+            input_gates.push(SimpleInput::new(1, input_tag.as_str()));
+        }
+
+        for i in 0..num_outputs {
+            let output_tag = format!("o_{}", i);
+            let output_gate = SimpleOutput::new(output_tag.as_str());
+            output_gates.push(output_gate.clone());
+            output_gates_logic.push(output_gate);
+        }
+
+        let mut row_buffers = Vec::new();
+        for i in 0..table.len() {
+            let controlled_buffer = ControlledBuffer::new(num_outputs);
+            controlled_buffer.lock().unwrap().set_tag(format!("row_{}", i).as_str());
+            row_buffers.push(controlled_buffer);
+        }
+
+        let mut lut = Lut {
+            complex_gate: ComplexGateMembers::new(
+                num_inputs,
+                num_outputs,
+                GateType::LutType,
+                input_gates,
+                output_gates,
+            ),
+            decoder: VariableDecoder::new(num_inputs),
+            row_buffers,
+        };
+
+        lut.build_and_prime_circuit(
+            num_inputs,
+            table,
+            output_gates_logic,
+        );
+
+        new_shared_mutex(lut.get_unique_id().id(), lut)
+    }
+
+    fn build_and_prime_circuit(
+        &mut self,
+        num_inputs: usize,
+        table: Vec<Vec<Signal>>,
+        output_gates: Vec<SharedMutex<dyn LogicGate>>,
+    ) {
+        for i in 0..num_inputs {
+            let input_tag = format!("i_{}", i);
+            let input_index = self.get_index_from_tag(input_tag.as_str());
+            let decoder_input_index = self.decoder.lock().unwrap().get_index_from_tag(input_tag.as_str());
+            connect_gates(
+                self.complex_gate.input_gates[input_index].clone(),
+                0,
+                self.decoder.clone(),
+                decoder_input_index,
+            );
+        }
+
+        for (i, row) in table.into_iter().enumerate() {
+            let controlled_buffer = self.row_buffers[i].clone();
+
+            let decoder_output_index = self.decoder.lock().unwrap().get_index_from_tag(format!("o_{}", i).as_str());
+            let enable_index = controlled_buffer.lock().unwrap().get_index_from_tag("E");
+            connect_gates(
+                self.decoder.clone(),
+                decoder_output_index,
+                controlled_buffer.clone(),
+                enable_index,
+            );
+
+            for (j, signal) in row.into_iter().enumerate() {
+                controlled_buffer.lock().unwrap().update_input_signal(
+                    GateInput::new(
+                        j,
+                        signal,
+                        UniqueID::zero_id(),
+                    )
+                );
+
+                //Only the enabled row drives the outputs, every other row outputs NONE.
+                let output_index = self.get_index_from_tag(format!("o_{}", j).as_str());
+                connect_gates(
+                    controlled_buffer.clone(),
+                    j,
+                    output_gates[output_index].clone(),
+                    0,
+                );
+            }
+        }
+
+        //Prime gates
+        self.complex_gate.calculate_output_from_inputs_and_set_child_count(
+            true,
+        );
+    }
+}
+
+impl_complex_logic_gate!(Lut);
+
+pub struct VariableBitCounter {
+    complex_gate: ComplexGateMembers,
+    flip_flops: Vec<SharedMutex<MasterSlaveJKFlipFlop>>,
+    flip_flop_clk_in_not_gates: Vec<SharedMutex<Not>>,
+    flip_flop_output_splitter: SharedMutex<Splitter>,
+}
+
+#[allow(dead_code)]
+impl VariableBitCounter {
+    //Inputs
+    pub const CLK_IN: &'static str = "CLK_IN";
+
+    pub fn new(num_output_pins: usize) -> SharedMutex<Self> {
+        let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+        let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
+        let mut output_gates_logic: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+
+        let input_gate = SimpleInput::new(1, Self::CLK_IN);
+
+        //Order of input gates is important here to force the circuit into a deterministic state.
+        input_gates.push(input_gate.clone());
+
+        let mut flip_flops = Vec::new();
+        let mut flip_flop_clk_in_not_gates = Vec::new();
+
+        let mut store_output = |gate: SharedMutex<SimpleOutput>| {
+            output_gates.push(gate.clone());
+            output_gates_logic.push(gate.clone());
+        };
+
+        for i in 0..num_output_pins {
+            let output_tag = format!("o_{}", i);
+            store_output(SimpleOutput::new(output_tag.as_str()));
+
+            flip_flops.push(MasterSlaveJKFlipFlop::new());
+            flip_flop_clk_in_not_gates.push(Not::new(1));
+        }
+
+        let mut clock_hookup = VariableBitCounter {
+            complex_gate: ComplexGateMembers::new(
+                1,
+                num_output_pins,
+                GateType::VariableBitCounterType,
+                input_gates,
+                output_gates,
+            ),
+            flip_flops,
+            flip_flop_clk_in_not_gates,
+            flip_flop_output_splitter: Splitter::new(num_output_pins, 2),
+        };
+
+        for i in 0..num_output_pins {
+            clock_hookup.flip_flops[i].lock().unwrap().set_tag(format!("flip_flop_{}", i).as_str());
+            clock_hookup.flip_flop_clk_in_not_gates[i].lock().unwrap().set_tag(format!("flip_flop_clk_in_not_{}", i).as_str());
+        }
+
+        clock_hookup.build_and_prime_circuit(
+            output_gates_logic,
+            num_output_pins,
+        );
+
+        new_shared_mutex(clock_hookup.get_unique_id().id(), clock_hookup)
+    }
+
+    fn build_and_prime_circuit(
+        &mut self,
+        output_gates: Vec<SharedMutex<dyn LogicGate>>,
+        num_output_pins: usize,
+    ) {
+        for i in 0..num_output_pins {
+            //Set J to high
+            let j_input_index = self.flip_flops[i].lock().unwrap().get_index_from_tag(MasterSlaveJKFlipFlop::J);
+            self.flip_flops[i].lock().unwrap().update_input_signal(
+                GateInput::new(
+                    j_input_index,
+                    HIGH,
+                    UniqueID::zero_id(),
+                )
+            );
+
+            //Set K to high
+            let k_input_index = self.flip_flops[i].lock().unwrap().get_index_from_tag(MasterSlaveJKFlipFlop::K);
+            self.flip_flops[i].lock().unwrap().update_input_signal(
+                GateInput::new(
+                    k_input_index,
+                    HIGH,
+                    UniqueID::zero_id(),
+                )
+            );
+
+            let not_q_output_index = self.flip_flops[i].lock().unwrap().get_index_from_tag(MasterSlaveJKFlipFlop::NOT_Q);
+            connect_gates(
+                self.flip_flops[i].clone(),
+                not_q_output_index,
+                self.flip_flop_output_splitter.clone(),
+                i,
+            );
+
+            if i == 0 {
+                //CLK_IN input
+                let clk_input_index = self.get_index_from_tag(Self::CLK_IN);
+                let clk_input_gate = self.complex_gate.input_gates[clk_input_index].clone();
+
+                let clk_input_index = self.flip_flops[i].lock().unwrap().get_index_from_tag(MasterSlaveJKFlipFlop::CLK_IN);
+                connect_gates(
+                    clk_input_gate.clone(),
+                    0,
+                    self.flip_flops[i].clone(),
+                    clk_input_index,
+                );
+            } else {
+                let clk_input_index = self.flip_flops[i].lock().unwrap().get_index_from_tag(MasterSlaveJKFlipFlop::CLK_IN);
+                let splitter_output_index = self.flip_flop_output_splitter.lock().unwrap().get_index_for_output(i - 1, 1);
+                connect_gates(
+                    self.flip_flop_output_splitter.clone(),
+                    splitter_output_index,
+                    self.flip_flops[i].clone(),
+                    clk_input_index,
+                );
+            }
+
+            let splitter_output_index = self.flip_flop_output_splitter.lock().unwrap().get_index_for_output(i, 0);
+            let output_tag = format!("o_{}", i);
+            let output_index = self.get_index_from_tag(output_tag.as_str());
+            connect_gates(
+                self.flip_flop_output_splitter.clone(),
+                splitter_output_index,
+                output_gates[output_index].clone(),
+                0,
+            );
+        }
+
+        self.complex_gate.calculate_output_from_inputs_and_set_child_count(
+            true,
+        );
+    }
+
+    #[cfg(feature = "high_restriction")]
+    fn check_output(&self) {
+        println!("Running high_restriction!");
+        fn check_output(simple_gate: &BasicGateMembers) {
+            for o in simple_gate.output_states.iter() {
+                if let GateOutputState::NotConnected(_) = o {
+                    panic!("A gate output for type {} id {} was not set\n{:#?}", simple_gate.gate_type, simple_gate.unique_id.id(), simple_gate.output_states);
+                }
+            }
+
+            for i in simple_gate.input_signals.iter() {
+                if i.len() == 0 {
+                    panic!("A gate input for type {} id {} tag {} was empty", simple_gate.gate_type, simple_gate.unique_id.id(), simple_gate.tag);
+                } else if i.len() == 1 {
+                    let (id, _signal) = i.iter().next().unwrap();
+                    if id.id() == 0 {
+                        panic!("A gate input for type {} id {} tag {} was empty\n{:#?}", simple_gate.gate_type, simple_gate.unique_id.id(), simple_gate.tag, simple_gate.input_signals);
+                    }
+                } else {
+                    panic!("Multiple inputs connected for the same gate")
+                }
+            }
+        }
+
+        for i in 0..self.flip_flops.len() {
+            check_output(&self.flip_flops[i].lock().unwrap().complex_gate.simple_gate);
+            check_output(&self.flip_flop_clk_in_not_gates[i].lock().unwrap().members);
+        }
+    }
+}
+
+impl LogicGate for VariableBitCounter {
+    fn internal_connect_output(&mut self, current_gate_output_key: usize, next_gate_input_key: usize, next_gate: SharedMutex<dyn LogicGate>) -> Signal {
+        self.complex_gate.connect_output(
+            self.get_unique_id(),
+            current_gate_output_key,
+            next_gate_input_key,
+            next_gate,
+        )
+    }
+
+    fn internal_update_index_to_id(&mut self, sending_id: UniqueID, gate_input_index: usize, signal: Signal) {
+        self.complex_gate.internal_update_index_to_id(sending_id, gate_input_index, signal);
+    }
+
+    fn update_input_signal(&mut self, input: GateInput) -> InputSignalReturn {
+        //ActiveLowSRLatch has an `invalid` state of LOW LOW. However, this is not being enforced by
+        // assertions because it may be an intermediate state.
+        self.complex_gate.update_input_signal(input)
+    }
+
+    fn fetch_output_signals_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
+        self.complex_gate.fetch_output_signals_calculate(
+            &self.get_tag(),
+        )
+    }
+
+    fn fetch_output_signals_no_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
+        self.complex_gate.fetch_output_signals_no_calculate(
+            &self.get_tag(),
+        )
+    }
+
+    fn get_gate_type(&self) -> GateType {
+        self.complex_gate.simple_gate.gate_type
+    }
+
+    fn get_unique_id(&self) -> UniqueID {
+        self.complex_gate.simple_gate.unique_id
+    }
+
+    fn toggle_output_printing(&mut self, print_output: bool) {
+        self.complex_gate.simple_gate.should_print_output = print_output;
+    }
+
+    fn get_tag(&self) -> String {
+        self.complex_gate.simple_gate.tag.clone()
+    }
+
+    fn set_tag(&mut self, tag: &str) {
+        self.complex_gate.simple_gate.tag = tag.to_string();
+    }
+
+    fn get_index_from_tag(&self, tag: &str) -> usize {
+        self.complex_gate.get_index_from_tag(tag)
+    }
+
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
+    fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
+        self.complex_gate.remove_connected_input(input_index, connected_id);
+    }
+
+    fn toggle_print_each_input_output_gate(&mut self, print_each_input_output_gate: bool) {
+        self.complex_gate.toggle_print_each_input_output_gate(print_each_input_output_gate);
+    }
+
+    fn num_children_gates(&self) -> usize {
+        self.complex_gate.simple_gate.number_child_gates
+    }
+
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
+    fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
+        self.complex_gate.input_gates.clone()
+    }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
+}
+
+//Converts a binary number to Gray code. Index 0 is the least significant bit. Each Gray code bit
+// is the XOr of the matching binary bit and the next more significant binary bit.
+pub struct BinaryToGray {
+    complex_gate: ComplexGateMembers,
+    xor_gates: Vec<SharedMutex<XOr>>,
+}
+
+#[allow(dead_code)]
+impl BinaryToGray {
+    pub fn new(number_bits: usize) -> SharedMutex<Self> {
+        assert_ne!(number_bits, 0);
+
+        let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+        let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
+        let mut output_gates_logic: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+        let mut xor_gates = Vec::new();
+
+        for i in 0..number_bits {
+            //Every bit except the least significant bit is also used by the next lower Gray code
+            // bit.
+            let number_outputs = if i == 0 { 1 } else { 2 };
+
+            let input_tag = format!("i_{}", i);
+            input_gates.push(SimpleInput::new(number_outputs, input_tag.as_str()));
+
+            let output_tag = format!("o_{}", i);
+            let output_gate = SimpleOutput::new(output_tag.as_str());
+            output_gates.push(output_gate.clone());
+            output_gates_logic.push(output_gate);
+        }
+
+        //The most significant bit passes straight through so it does not need an XOr gate.
+        for _ in 0..(number_bits - 1) {
+            xor_gates.push(XOr::new(2, 1));
+        }
+
+        let mut binary_to_gray = BinaryToGray {
+            complex_gate: ComplexGateMembers::new(
+                number_bits,
+                number_bits,
+                GateType::BinaryToGrayType,
+                input_gates,
+                output_gates,
+            ),
+            xor_gates,
+        };
+
+        binary_to_gray.build_and_prime_circuit(number_bits, output_gates_logic);
+
+        new_shared_mutex(binary_to_gray.get_unique_id().id(), binary_to_gray)
+    }
+
+    fn build_and_prime_circuit(
+        &mut self,
+        number_bits: usize,
+        output_gates: Vec<SharedMutex<dyn LogicGate>>,
+    ) {
+        for (i, xor_gate) in self.xor_gates.iter().enumerate() {
+            connect_gates(
+                self.complex_gate.input_gates[i].clone(),
+                0,
+                xor_gate.clone(),
+                0,
+            );
+
+            connect_gates(
+                self.complex_gate.input_gates[i + 1].clone(),
+                1,
+                xor_gate.clone(),
+                1,
+            );
+
+            connect_gates(
+                xor_gate.clone(),
+                0,
+                output_gates[i].clone(),
+                0,
+            );
+        }
+
+        connect_gates(
+            self.complex_gate.input_gates[number_bits - 1].clone(),
+            0,
+            output_gates[number_bits - 1].clone(),
+            0,
+        );
+
+        //Prime gates
+        self.complex_gate.calculate_output_from_inputs_and_set_child_count(
+            true,
+        );
+    }
+}
+
+impl LogicGate for BinaryToGray {
+    fn internal_connect_output(&mut self, current_gate_output_key: usize, next_gate_input_key: usize, next_gate: SharedMutex<dyn LogicGate>) -> Signal {
+        self.complex_gate.connect_output(
+            self.get_unique_id(),
+            current_gate_output_key,
+            next_gate_input_key,
+            next_gate,
+        )
+    }
+
+    fn internal_update_index_to_id(&mut self, sending_id: UniqueID, gate_input_index: usize, signal: Signal) {
+        self.complex_gate.internal_update_index_to_id(sending_id, gate_input_index, signal);
+    }
+
+    fn update_input_signal(&mut self, input: GateInput) -> InputSignalReturn {
+        self.complex_gate.update_input_signal(input)
+    }
+
+    fn fetch_output_signals_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
+        self.complex_gate.fetch_output_signals_calculate(
+            &self.get_tag(),
+        )
+    }
+
+    fn fetch_output_signals_no_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
+        self.complex_gate.fetch_output_signals_no_calculate(
+            &self.get_tag(),
+        )
+    }
+
+    fn get_gate_type(&self) -> GateType {
+        self.complex_gate.simple_gate.gate_type
+    }
+
+    fn get_unique_id(&self) -> UniqueID {
+        self.complex_gate.simple_gate.unique_id
+    }
+
+    fn toggle_output_printing(&mut self, print_output: bool) {
+        self.complex_gate.simple_gate.should_print_output = print_output;
+    }
+
+    fn get_tag(&self) -> String {
+        self.complex_gate.simple_gate.tag.clone()
+    }
+
+    fn set_tag(&mut self, tag: &str) {
+        self.complex_gate.simple_gate.tag = tag.to_string();
+    }
+
+    fn get_index_from_tag(&self, tag: &str) -> usize {
+        self.complex_gate.get_index_from_tag(tag)
+    }
+
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
+    fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
+        self.complex_gate.remove_connected_input(input_index, connected_id);
+    }
+
+    fn toggle_print_each_input_output_gate(&mut self, print_each_input_output_gate: bool) {
+        self.complex_gate.toggle_print_each_input_output_gate(print_each_input_output_gate);
+    }
+
+    fn num_children_gates(&self) -> usize {
+        self.complex_gate.simple_gate.number_child_gates
+    }
+
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
+    fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
+        self.complex_gate.input_gates.clone()
+    }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
+}
+
+//Converts a Gray code number back to binary. Index 0 is the least significant bit. Each binary bit
+// is the XOr of the matching Gray code bit and the next more significant binary bit, so the
+// XOr gates form a chain starting at the most significant bit.
+pub struct GrayToBinary {
+    complex_gate: ComplexGateMembers,
+    xor_gates: Vec<SharedMutex<XOr>>,
+}
+
+#[allow(dead_code)]
+impl GrayToBinary {
+    pub fn new(number_bits: usize) -> SharedMutex<Self> {
+        assert_ne!(number_bits, 0);
+
+        let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+        let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
+        let mut output_gates_logic: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+        let mut xor_gates = Vec::new();
+
+        for i in 0..number_bits {
+            //The most significant bit is both an output and the start of the XOr chain.
+            let number_outputs = if i == number_bits - 1 && number_bits > 1 { 2 } else { 1 };
+
+            let input_tag = format!("i_{}", i);
+            input_gates.push(SimpleInput::new(number_outputs, input_tag.as_str()));
+
+            let output_tag = format!("o_{}", i);
+            let output_gate = SimpleOutput::new(output_tag.as_str());
+            output_gates.push(output_gate.clone());
+            output_gates_logic.push(output_gate);
+        }
+
+        for i in 0..(number_bits - 1) {
+            //Every binary bit except the least significant is also fed into the next XOr gate
+            // down the chain.
+            let number_outputs = if i == 0 { 1 } else { 2 };
+            xor_gates.push(XOr::new(2, number_outputs));
+        }
+
+        let mut gray_to_binary = GrayToBinary {
+            complex_gate: ComplexGateMembers::new(
+                number_bits,
+                number_bits,
+                GateType::GrayToBinaryType,
+                input_gates,
+                output_gates,
+            ),
+            xor_gates,
+        };
+
+        gray_to_binary.build_and_prime_circuit(number_bits, output_gates_logic);
+
+        new_shared_mutex(gray_to_binary.get_unique_id().id(), gray_to_binary)
+    }
+
+    fn build_and_prime_circuit(
+        &mut self,
+        number_bits: usize,
+        output_gates: Vec<SharedMutex<dyn LogicGate>>,
+    ) {
+        connect_gates(
+            self.complex_gate.input_gates[number_bits - 1].clone(),
+            0,
+            output_gates[number_bits - 1].clone(),
+            0,
+        );
+
+        for i in (0..(number_bits - 1)).rev() {
+            connect_gates(
+                self.complex_gate.input_gates[i].clone(),
+                0,
+                self.xor_gates[i].clone(),
+                0,
+            );
+
+            if i == number_bits - 2 {
+                connect_gates(
+                    self.complex_gate.input_gates[i + 1].clone(),
+                    1,
+                    self.xor_gates[i].clone(),
+                    1,
+                );
+            } else {
+                connect_gates(
+                    self.xor_gates[i + 1].clone(),
+                    1,
+                    self.xor_gates[i].clone(),
+                    1,
+                );
+            }
+
+            connect_gates(
+                self.xor_gates[i].clone(),
+                0,
+                output_gates[i].clone(),
+                0,
+            );
+        }
+
+        //Prime gates
+        self.complex_gate.calculate_output_from_inputs_and_set_child_count(
+            true,
+        );
+    }
+}
+
+impl LogicGate for GrayToBinary {
+    fn internal_connect_output(&mut self, current_gate_output_key: usize, next_gate_input_key: usize, next_gate: SharedMutex<dyn LogicGate>) -> Signal {
+        self.complex_gate.connect_output(
+            self.get_unique_id(),
+            current_gate_output_key,
+            next_gate_input_key,
+            next_gate,
+        )
+    }
+
+    fn internal_update_index_to_id(&mut self, sending_id: UniqueID, gate_input_index: usize, signal: Signal) {
+        self.complex_gate.internal_update_index_to_id(sending_id, gate_input_index, signal);
+    }
+
+    fn update_input_signal(&mut self, input: GateInput) -> InputSignalReturn {
+        self.complex_gate.update_input_signal(input)
+    }
+
+    fn fetch_output_signals_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
+        self.complex_gate.fetch_output_signals_calculate(
+            &self.get_tag(),
+        )
+    }
+
+    fn fetch_output_signals_no_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
+        self.complex_gate.fetch_output_signals_no_calculate(
+            &self.get_tag(),
+        )
+    }
+
+    fn get_gate_type(&self) -> GateType {
+        self.complex_gate.simple_gate.gate_type
+    }
+
+    fn get_unique_id(&self) -> UniqueID {
+        self.complex_gate.simple_gate.unique_id
+    }
+
+    fn toggle_output_printing(&mut self, print_output: bool) {
+        self.complex_gate.simple_gate.should_print_output = print_output;
+    }
+
+    fn get_tag(&self) -> String {
+        self.complex_gate.simple_gate.tag.clone()
+    }
+
+    fn set_tag(&mut self, tag: &str) {
+        self.complex_gate.simple_gate.tag = tag.to_string();
+    }
+
+    fn get_index_from_tag(&self, tag: &str) -> usize {
+        self.complex_gate.get_index_from_tag(tag)
+    }
+
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
+    fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
+        self.complex_gate.remove_connected_input(input_index, connected_id);
+    }
+
+    fn toggle_print_each_input_output_gate(&mut self, print_each_input_output_gate: bool) {
+        self.complex_gate.toggle_print_each_input_output_gate(print_each_input_output_gate);
+    }
+
+    fn num_children_gates(&self) -> usize {
+        self.complex_gate.simple_gate.number_child_gates
+    }
+
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
+    fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
+        self.complex_gate.input_gates.clone()
+    }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
+}
+
+//Outputs HIGH only when the inputs exactly equal the pattern it was built with. Index 0 is the
+// least significant bit. Bits that must be LOW are inverted by a Not gate and every bit is then
+// fed into a single And gate, this is the standard way to decode an address.
+pub struct PatternMatch {
+    complex_gate: ComplexGateMembers,
+    pattern: Vec<Signal>,
+    not_gates: Vec<SharedMutex<Not>>,
+    and_gate: SharedMutex<And>,
+}
+
+#[allow(dead_code)]
+impl PatternMatch {
+    pub fn new(pattern: &[Signal]) -> SharedMutex<Self> {
+        assert_ne!(pattern.len(), 0);
+        assert!(
+            pattern.iter().all(|signal| *signal == HIGH || *signal == LOW_),
+            "Pattern can only contain HIGH and LOW_ signals."
+        );
+
+        let number_bits = pattern.len();
+
+        let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+        let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
+        let mut not_gates = Vec::new();
+
+        for i in 0..number_bits {
+            let input_tag = format!("i_{}", i);
+            input_gates.push(SimpleInput::new(1, input_tag.as_str()));
+        }
+
+        for signal in pattern.iter() {
+            if *signal == LOW_ {
+                not_gates.push(Not::new(1));
+            }
+        }
+
+        let output_gate = SimpleOutput::new("o");
+        output_gates.push(output_gate.clone());
+
+        let mut pattern_match = PatternMatch {
+            complex_gate: ComplexGateMembers::new(
+                number_bits,
+                1,
+                GateType::PatternMatchType,
+                input_gates,
+                output_gates,
+            ),
+            pattern: pattern.to_vec(),
+            not_gates,
+            and_gate: And::new(number_bits, 1),
+        };
+
+        pattern_match.build_and_prime_circuit(output_gate);
+
+        new_shared_mutex(pattern_match.get_unique_id().id(), pattern_match)
+    }
+
+    pub fn pattern(&self) -> Vec<Signal> {
+        self.pattern.clone()
+    }
+
+    fn build_and_prime_circuit(
+        &mut self,
+        output_gate: SharedMutex<dyn LogicGate>,
+    ) {
+        let mut not_gates = self.not_gates.iter();
+        for (i, signal) in self.pattern.iter().enumerate() {
+            if *signal == LOW_ {
+                let not_gate = not_gates.next().unwrap();
+
+                connect_gates(
+                    self.complex_gate.input_gates[i].clone(),
+                    0,
+                    not_gate.clone(),
+                    0,
+                );
+
+                connect_gates(
+                    not_gate.clone(),
+                    0,
+                    self.and_gate.clone(),
+                    i,
+                );
+            } else {
+                connect_gates(
+                    self.complex_gate.input_gates[i].clone(),
+                    0,
+                    self.and_gate.clone(),
+                    i,
+                );
+            }
+        }
+
+        connect_gates(
+            self.and_gate.clone(),
+            0,
+            output_gate,
+            0,
+        );
+
+        //Prime gates
+        self.complex_gate.calculate_output_from_inputs_and_set_child_count(
+            true,
+        );
+    }
+}
+
+impl LogicGate for PatternMatch {
+    fn internal_connect_output(&mut self, current_gate_output_key: usize, next_gate_input_key: usize, next_gate: SharedMutex<dyn LogicGate>) -> Signal {
+        self.complex_gate.connect_output(
+            self.get_unique_id(),
+            current_gate_output_key,
+            next_gate_input_key,
+            next_gate,
+        )
+    }
+
+    fn internal_update_index_to_id(&mut self, sending_id: UniqueID, gate_input_index: usize, signal: Signal) {
+        self.complex_gate.internal_update_index_to_id(sending_id, gate_input_index, signal);
+    }
+
+    fn update_input_signal(&mut self, input: GateInput) -> InputSignalReturn {
+        self.complex_gate.update_input_signal(input)
+    }
+
+    fn fetch_output_signals_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
+        self.complex_gate.fetch_output_signals_calculate(
+            &self.get_tag(),
+        )
+    }
+
+    fn fetch_output_signals_no_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
+        self.complex_gate.fetch_output_signals_no_calculate(
+            &self.get_tag(),
+        )
+    }
+
+    fn get_gate_type(&self) -> GateType {
+        self.complex_gate.simple_gate.gate_type
+    }
+
+    fn get_unique_id(&self) -> UniqueID {
+        self.complex_gate.simple_gate.unique_id
+    }
+
+    fn toggle_output_printing(&mut self, print_output: bool) {
+        self.complex_gate.simple_gate.should_print_output = print_output;
+    }
+
+    fn get_tag(&self) -> String {
+        self.complex_gate.simple_gate.tag.clone()
+    }
+
+    fn set_tag(&mut self, tag: &str) {
+        self.complex_gate.simple_gate.tag = tag.to_string();
+    }
+
+    fn get_index_from_tag(&self, tag: &str) -> usize {
+        self.complex_gate.get_index_from_tag(tag)
+    }
+
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
+    fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
+        self.complex_gate.remove_connected_input(input_index, connected_id);
+    }
+
+    fn toggle_print_each_input_output_gate(&mut self, print_each_input_output_gate: bool) {
+        self.complex_gate.toggle_print_each_input_output_gate(print_each_input_output_gate);
+    }
+
+    fn num_children_gates(&self) -> usize {
+        self.complex_gate.simple_gate.number_child_gates
+    }
+
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
+    fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
+        self.complex_gate.input_gates.clone()
+    }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
+}
+
+//Circulates a single HIGH through num_stages master slave stages, advancing one stage each time
+// CLK falls and wrapping from the last stage back to the first. Every memory cell starts LOW, so
+// the first stage stores its value inverted. This makes o_0 the only HIGH output after priming
+// without needing a reset input.
+pub struct RingCounter {
+    complex_gate: ComplexGateMembers,
+    master_cells: Vec<SharedMutex<OneBitMemoryCell>>,
+    slave_cells: Vec<SharedMutex<OneBitMemoryCell>>,
+    clk_not: SharedMutex<Not>,
+    first_stage_not: SharedMutex<Not>,
+    wrap_not: SharedMutex<Not>,
+}
+
+#[allow(dead_code)]
+impl RingCounter {
+    pub fn new(num_stages: usize) -> SharedMutex<Self> {
+        //A single stage would always be HIGH.
+        assert!(num_stages > 1);
+
+        let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+        let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
+        let mut output_gates_logic: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+        let mut master_cells = Vec::new();
+        let mut slave_cells = Vec::new();
+
+        for i in 0..num_stages {
+            let output_tag = format!("o_{}", i);
+            let output_gate = SimpleOutput::new(output_tag.as_str());
+            output_gates.push(output_gate.clone());
+            output_gates_logic.push(output_gate);
+
+            master_cells.push(OneBitMemoryCell::new(1));
+
+            //The first slave only feeds first_stage_not.
+            let slave_outputs = if i == 0 { 1 } else { 2 };
+            slave_cells.push(OneBitMemoryCell::new(slave_outputs));
+        }
+
+        //Each master cell and the Not gate that drives the slave cells.
+        input_gates.push(SimpleInput::new(num_stages + 1, "CLK"));
+
+        let mut ring_counter = RingCounter {
+            complex_gate: ComplexGateMembers::new(
+                1,
+                num_stages,
+                GateType::RingCounterType,
+                input_gates,
+                output_gates,
+            ),
+            master_cells,
+            slave_cells,
+            clk_not: Not::new(num_stages),
+            first_stage_not: Not::new(2),
+            wrap_not: Not::new(1),
+        };
+
+        ring_counter.build_and_prime_circuit(num_stages, output_gates_logic);
+
+        new_shared_mutex(ring_counter.get_unique_id().id(), ring_counter)
+    }
+
+    fn build_and_prime_circuit(
+        &mut self,
+        num_stages: usize,
+        output_gates: Vec<SharedMutex<dyn LogicGate>>,
+    ) {
+        let clk_input = self.complex_gate.input_gates[self.get_index_from_tag("CLK")].clone();
+
+        connect_gates(
+            clk_input.clone(),
+            num_stages,
+            self.clk_not.clone(),
+            0,
+        );
+
+        for (i, output_gate) in output_gates.iter().enumerate() {
+            let master_enable_index = self.master_cells[i].lock().unwrap().get_index_from_tag("E");
+            let master_set_index = self.master_cells[i].lock().unwrap().get_index_from_tag("S");
+            let master_output_index = self.master_cells[i].lock().unwrap().get_index_from_tag("Q");
+            let slave_enable_index = self.slave_cells[i].lock().unwrap().get_index_from_tag("E");
+            let slave_set_index = self.slave_cells[i].lock().unwrap().get_index_from_tag("S");
+
+            //Masters load while CLK is HIGH and the slaves copy them once CLK is LOW.
+            connect_gates(
+                clk_input.clone(),
+                i,
+                self.master_cells[i].clone(),
+                master_enable_index,
+            );
+
+            connect_gates(
+                self.clk_not.clone(),
+                i,
+                self.slave_cells[i].clone(),
+                slave_enable_index,
+            );
+
+            connect_gates(
+                self.master_cells[i].clone(),
+                master_output_index,
+                self.slave_cells[i].clone(),
+                slave_set_index,
+            );
+
+            //The previous stage feeds this master. The first stage stores the inverse of the last
+            // stage.
+            if i == 0 {
+                connect_gates(
+                    self.wrap_not.clone(),
+                    0,
+                    self.master_cells[i].clone(),
+                    master_set_index,
+                );
+            } else if i == 1 {
+                connect_gates(
+                    self.first_stage_not.clone(),
+                    1,
+                    self.master_cells[i].clone(),
+                    master_set_index,
+                );
+            } else {
+                let previous_slave_output_index = self.slave_cells[i - 1].lock().unwrap().get_index_from_tag("Q_1");
+                connect_gates(
+                    self.slave_cells[i - 1].clone(),
+                    previous_slave_output_index,
+                    self.master_cells[i].clone(),
+                    master_set_index,
+                );
+            }
+
+            let slave_output_index = self.slave_cells[i].lock().unwrap().get_index_from_tag("Q");
+            if i == 0 {
+                connect_gates(
+                    self.slave_cells[i].clone(),
+                    slave_output_index,
+                    self.first_stage_not.clone(),
+                    0,
+                );
+
+                connect_gates(
+                    self.first_stage_not.clone(),
+                    0,
+                    output_gate.clone(),
+                    0,
+                );
+            } else {
+                connect_gates(
+                    self.slave_cells[i].clone(),
+                    slave_output_index,
+                    output_gate.clone(),
+                    0,
+                );
+            }
+        }
+
+        let last_slave_output_index = self.slave_cells[num_stages - 1].lock().unwrap().get_index_from_tag("Q_1");
+        connect_gates(
+            self.slave_cells[num_stages - 1].clone(),
+            last_slave_output_index,
+            self.wrap_not.clone(),
+            0,
+        );
+
+        //Prime gates
+        self.complex_gate.calculate_output_from_inputs_and_set_child_count(
+            true,
+        );
+    }
+}
+
+impl LogicGate for RingCounter {
+    fn internal_connect_output(&mut self, current_gate_output_key: usize, next_gate_input_key: usize, next_gate: SharedMutex<dyn LogicGate>) -> Signal {
+        self.complex_gate.connect_output(
+            self.get_unique_id(),
+            current_gate_output_key,
+            next_gate_input_key,
+            next_gate,
+        )
+    }
+
+    fn internal_update_index_to_id(&mut self, sending_id: UniqueID, gate_input_index: usize, signal: Signal) {
+        self.complex_gate.internal_update_index_to_id(sending_id, gate_input_index, signal);
+    }
+
+    fn update_input_signal(&mut self, input: GateInput) -> InputSignalReturn {
+        self.complex_gate.update_input_signal(input)
+    }
+
+    fn fetch_output_signals_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
+        self.complex_gate.fetch_output_signals_calculate(
+            &self.get_tag(),
+        )
+    }
+
+    fn fetch_output_signals_no_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
+        self.complex_gate.fetch_output_signals_no_calculate(
+            &self.get_tag(),
+        )
+    }
+
+    fn get_gate_type(&self) -> GateType {
+        self.complex_gate.simple_gate.gate_type
+    }
+
+    fn get_unique_id(&self) -> UniqueID {
+        self.complex_gate.simple_gate.unique_id
+    }
+
+    fn toggle_output_printing(&mut self, print_output: bool) {
+        self.complex_gate.simple_gate.should_print_output = print_output;
+    }
+
+    fn get_tag(&self) -> String {
+        self.complex_gate.simple_gate.tag.clone()
+    }
+
+    fn set_tag(&mut self, tag: &str) {
+        self.complex_gate.simple_gate.tag = tag.to_string();
+    }
+
+    fn get_index_from_tag(&self, tag: &str) -> usize {
+        self.complex_gate.get_index_from_tag(tag)
+    }
+
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
+    fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
+        self.complex_gate.remove_connected_input(input_index, connected_id);
+    }
+
+    fn toggle_print_each_input_output_gate(&mut self, print_each_input_output_gate: bool) {
+        self.complex_gate.toggle_print_each_input_output_gate(print_each_input_output_gate);
+    }
+
+    fn num_children_gates(&self) -> usize {
+        self.complex_gate.simple_gate.number_child_gates
+    }
+
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
+    fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
+        self.complex_gate.input_gates.clone()
+    }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
+}
+
+//Joins two buses into a single wider bus. The low bus becomes the least significant bits of the
+// output and the high bus is placed directly above it. There are no gates inside, only wiring.
+pub struct BusConcat {
+    complex_gate: ComplexGateMembers,
+}
+
+#[allow(dead_code)]
+impl BusConcat {
+    pub const LOW: &'static str = "l";
+    pub const HIGH: &'static str = "h";
+
+    pub fn new(low_bits: usize, high_bits: usize) -> SharedMutex<Self> {
+        assert_ne!(low_bits + high_bits, 0);
+
+        let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+        let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
+        let mut output_gates_logic: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+
+        for i in 0..low_bits {
+            let input_tag = format!("{}_{}", BusConcat::LOW, i);
+            input_gates.push(SimpleInput::new(1, input_tag.as_str()));
+        }
+
+        for i in 0..high_bits {
+            let input_tag = format!("{}_{}", BusConcat::HIGH, i);
+            input_gates.push(SimpleInput::new(1, input_tag.as_str()));
+        }
+
+        for i in 0..(low_bits + high_bits) {
+            let output_tag = format!("o_{}", i);
+            let output_gate = SimpleOutput::new(output_tag.as_str());
+            output_gates.push(output_gate.clone());
+            output_gates_logic.push(output_gate);
+        }
+
+        let mut bus_concat = BusConcat {
+            complex_gate: ComplexGateMembers::new(
+                low_bits + high_bits,
+                low_bits + high_bits,
+                GateType::BusConcatType,
+                input_gates,
+                output_gates,
+            ),
+        };
+
+        bus_concat.build_and_prime_circuit(output_gates_logic);
+
+        new_shared_mutex(bus_concat.get_unique_id().id(), bus_concat)
+    }
+
+    fn build_and_prime_circuit(
+        &mut self,
+        output_gates: Vec<SharedMutex<dyn LogicGate>>,
+    ) {
+        //The low bus inputs come first so input i always maps to output i.
+        for (i, output_gate) in output_gates.iter().enumerate() {
+            connect_gates(
+                self.complex_gate.input_gates[i].clone(),
+                0,
+                output_gate.clone(),
+                0,
+            );
+        }
+
+        //Prime gates
+        self.complex_gate.calculate_output_from_inputs_and_set_child_count(
+            true,
+        );
+    }
+}
+
+impl LogicGate for BusConcat {
+    fn internal_connect_output(&mut self, current_gate_output_key: usize, next_gate_input_key: usize, next_gate: SharedMutex<dyn LogicGate>) -> Signal {
+        self.complex_gate.connect_output(
+            self.get_unique_id(),
+            current_gate_output_key,
+            next_gate_input_key,
+            next_gate,
+        )
+    }
+
+    fn internal_update_index_to_id(&mut self, sending_id: UniqueID, gate_input_index: usize, signal: Signal) {
+        self.complex_gate.internal_update_index_to_id(sending_id, gate_input_index, signal);
+    }
+
+    fn update_input_signal(&mut self, input: GateInput) -> InputSignalReturn {
+        self.complex_gate.update_input_signal(input)
+    }
+
+    fn fetch_output_signals_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
+        self.complex_gate.fetch_output_signals_calculate(
+            &self.get_tag(),
+        )
+    }
+
+    fn fetch_output_signals_no_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
+        self.complex_gate.fetch_output_signals_no_calculate(
+            &self.get_tag(),
+        )
+    }
+
+    fn get_gate_type(&self) -> GateType {
+        self.complex_gate.simple_gate.gate_type
+    }
+
+    fn get_unique_id(&self) -> UniqueID {
+        self.complex_gate.simple_gate.unique_id
+    }
+
+    fn toggle_output_printing(&mut self, print_output: bool) {
+        self.complex_gate.simple_gate.should_print_output = print_output;
+    }
+
+    fn get_tag(&self) -> String {
+        self.complex_gate.simple_gate.tag.clone()
+    }
+
+    fn set_tag(&mut self, tag: &str) {
+        self.complex_gate.simple_gate.tag = tag.to_string();
+    }
+
+    fn get_index_from_tag(&self, tag: &str) -> usize {
+        self.complex_gate.get_index_from_tag(tag)
+    }
+
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
+    fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
+        self.complex_gate.remove_connected_input(input_index, connected_id);
+    }
+
+    fn toggle_print_each_input_output_gate(&mut self, print_each_input_output_gate: bool) {
+        self.complex_gate.toggle_print_each_input_output_gate(print_each_input_output_gate);
+    }
+
+    fn num_children_gates(&self) -> usize {
+        self.complex_gate.simple_gate.number_child_gates
+    }
+
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
+    fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
+        self.complex_gate.input_gates.clone()
+    }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
+}
+
+//Holds an undriven bus at a weak default. Each input is passed straight through to its output
+// unless it is NONE, in which case the output is the default instead. Any ControlledBuffer that is
+// enabled on the bus overrides the default. Like SignalGatekeeper this is not a 'real' logic gate,
+// it stands in for the pull-up or pull-down resistors on a bus.
+pub struct BusKeeper {
+    complex_gate: ComplexGateMembers,
+    default: Signal,
+}
+
+#[allow(dead_code)]
+impl BusKeeper {
+    pub fn new(number_bits: usize, default: Signal) -> SharedMutex<Self> {
+        assert_ne!(number_bits, 0);
+        assert_ne!(default, NONE);
+
+        let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+        let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
+        let mut output_gates_logic: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+
+        build_simple_inputs_and_outputs(
+            number_bits,
+            &mut input_gates,
+            &mut output_gates,
+            &mut output_gates_logic,
+        );
+
+        let mut bus_keeper = BusKeeper {
+            complex_gate: ComplexGateMembers::new(
+                number_bits,
+                number_bits,
+                GateType::BusKeeperType,
+                input_gates,
+                output_gates,
+            ),
+            default,
+        };
+
+        bus_keeper.build_and_prime_circuit(output_gates_logic);
+
+        new_shared_mutex(bus_keeper.get_unique_id().id(), bus_keeper)
+    }
+
+    fn build_and_prime_circuit(
+        &mut self,
+        output_gates: Vec<SharedMutex<dyn LogicGate>>,
+    ) {
+        for (i, output_gate) in output_gates.iter().enumerate() {
+            connect_gates(
+                self.complex_gate.input_gates[i].clone(),
+                0,
+                output_gate.clone(),
+                0,
+            );
+        }
+
+        //Prime gates
+        self.complex_gate.calculate_output_from_inputs_and_set_child_count(
+            true,
+        );
+    }
+
+    fn keep_signal(&self, signal: Signal) -> Signal {
+        if signal == NONE {
+            self.default.clone()
+        } else {
+            signal
+        }
+    }
+
+    fn keep_output_states(&self, mut output_states: Vec<GateOutputState>) -> Vec<GateOutputState> {
+        for output_state in output_states.iter_mut() {
+            match output_state {
+                GateOutputState::NotConnected(signal) => {
+                    *signal = self.keep_signal(signal.clone());
+                }
+                GateOutputState::Connected(connected_output) => {
+                    connected_output.throughput.signal = self.keep_signal(connected_output.throughput.signal.clone());
+                }
+            }
         }
+        output_states
     }
 }
 
-impl LogicGate for VariableBitCounter {
+impl LogicGate for BusKeeper {
     fn internal_connect_output(&mut self, current_gate_output_key: usize, next_gate_input_key: usize, next_gate: SharedMutex<dyn LogicGate>) -> Signal {
-        self.complex_gate.connect_output(
+        let signal = self.complex_gate.connect_output(
             self.get_unique_id(),
             current_gate_output_key,
             next_gate_input_key,
             next_gate,
-        )
+        );
+        self.keep_signal(signal)
     }
 
     fn internal_update_index_to_id(&mut self, sending_id: UniqueID, gate_input_index: usize, signal: Signal) {
@@ -1854,21 +4112,21 @@ impl LogicGate for VariableBitCounter {
     }
 
     fn update_input_signal(&mut self, input: GateInput) -> InputSignalReturn {
-        //ActiveLowSRLatch has an `invalid` state of LOW LOW. However, this is not being enforced by
-        // assertions because it may be an intermediate state.
         self.complex_gate.update_input_signal(input)
     }
 
     fn fetch_output_signals_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
-        self.complex_gate.fetch_output_signals_calculate(
+        let output_states = self.complex_gate.fetch_output_signals_calculate(
             &self.get_tag(),
-        )
+        )?;
+        Ok(self.keep_output_states(output_states))
     }
 
     fn fetch_output_signals_no_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
-        self.complex_gate.fetch_output_signals_no_calculate(
+        let output_states = self.complex_gate.fetch_output_signals_no_calculate(
             &self.get_tag(),
-        )
+        )?;
+        Ok(self.keep_output_states(output_states))
     }
 
     fn get_gate_type(&self) -> GateType {
@@ -1895,6 +4153,14 @@ impl LogicGate for VariableBitCounter {
         self.complex_gate.get_index_from_tag(tag)
     }
 
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         self.complex_gate.remove_connected_input(input_index, connected_id);
     }
@@ -1907,17 +4173,41 @@ impl LogicGate for VariableBitCounter {
         self.complex_gate.simple_gate.number_child_gates
     }
 
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         self.complex_gate.input_gates.clone()
     }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
-    use crate::logic::foundations::Signal::{HIGH, LOW_};
+    use crate::logic::foundations::Signal::{HIGH, LOW_, NONE};
     use rand::Rng;
-    use crate::test_stuff::run_multi_input_output_logic_gate;
+    use crate::logic::foundations::extract_signals_from_gate_output_states;
+    use crate::globals::CLOCK_TICK_NUMBER;
+    use crate::logic::input_gates::AutomaticInput;
+    use crate::run_circuit::run_circuit;
+    use crate::test_stuff::{collect_outputs_from_output_gates, run_multi_input_output_logic_gate, run_multi_input_output_logic_gate_return};
     use super::*;
 
     #[test]
@@ -2016,6 +4306,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn ring_counter_full_cycle() {
+        let num_stages = 4;
+
+        let mut clock_signals = Vec::new();
+        let mut output_signals = Vec::new();
+
+        //Two full trips around the ring. The HIGH stage advances each time CLK goes LOW.
+        for step in 0..(2 * num_stages + 1) {
+            let mut stage_output = vec![LOW_; num_stages];
+            stage_output[step % num_stages] = HIGH;
+
+            clock_signals.push(vec![LOW_]);
+            output_signals.push(stage_output.clone());
+
+            clock_signals.push(vec![HIGH]);
+            output_signals.push(stage_output);
+        }
+
+        let collected_output = run_multi_input_output_logic_gate_return(
+            vec![],
+            &output_signals,
+            HashMap::from([("CLK", clock_signals)]),
+            RingCounter::new(num_stages),
+        );
+
+        for output in collected_output.iter() {
+            assert_eq!(output.iter().filter(|signal| **signal == HIGH).count(), 1);
+        }
+
+        assert_eq!(collected_output, output_signals);
+    }
+
     #[test]
     fn master_slave_jk_flip_flop_initialization() {
         let flip_flop = MasterSlaveJKFlipFlop::new();
@@ -2060,6 +4383,86 @@ mod tests {
         );
     }
 
+    #[test]
+    fn t_flip_flop_run() {
+        run_multi_input_output_logic_gate(
+            vec![],
+            vec![
+                vec![HIGH, LOW_], //Q ~Q starts HIGH like the MasterSlaveJKFlipFlop
+                vec![LOW_, HIGH], //Q ~Q toggle
+                vec![LOW_, HIGH], //Q ~Q
+                vec![HIGH, LOW_], //Q ~Q toggle
+                vec![HIGH, LOW_], //Q ~Q
+                vec![LOW_, HIGH], //Q ~Q toggle
+                vec![LOW_, HIGH], //Q ~Q
+                vec![LOW_, HIGH], //Q ~Q hold
+                vec![LOW_, HIGH], //Q ~Q
+                vec![LOW_, HIGH], //Q ~Q hold
+                vec![LOW_, HIGH], //Q ~Q
+                vec![HIGH, LOW_], //Q ~Q toggle
+            ],
+            HashMap::from(
+                [
+                    (TFlipFlop::CLK_IN, vec![
+                        vec![LOW_], vec![HIGH], vec![LOW_], vec![HIGH], vec![LOW_], vec![HIGH],
+                        vec![LOW_], vec![HIGH], vec![LOW_], vec![HIGH], vec![LOW_], vec![HIGH],
+                    ]),
+                    (TFlipFlop::T, vec![
+                        vec![HIGH], vec![HIGH], vec![HIGH], vec![HIGH], vec![HIGH], vec![HIGH],
+                        vec![LOW_], vec![LOW_], vec![LOW_], vec![LOW_], vec![HIGH], vec![HIGH],
+                    ]),
+                ]
+            ),
+            TFlipFlop::new(),
+        );
+    }
+
+    fn run_clock_divider(divisor: usize) {
+        let number_ticks = divisor * 8;
+        let clock_divider = ClockDivider::new(divisor);
+
+        //Each flip flop after the first is clocked by the one before it while the circuit is being
+        // primed, so the starting value depends on the number of flip flops.
+        let initial_output = clock_divider.lock().unwrap().fetch_output_signals_calculate().unwrap();
+        let initial_output = match initial_output.first().unwrap() {
+            GateOutputState::NotConnected(signal) => signal.clone(),
+            GateOutputState::Connected(_) => panic!("Clock divider should not be connected to anything"),
+        };
+        let toggled_output = if initial_output == HIGH { LOW_ } else { HIGH };
+
+        //The input clock alternates starting LOW so every odd tick is a rising edge. CLK_OUT toggles
+        // every divisor input edges.
+        let mut input_signals = Vec::new();
+        let mut output_signals = Vec::new();
+        for tick in 0..number_ticks {
+            input_signals.push(vec![if tick % 2 == 0 { LOW_ } else { HIGH }]);
+
+            let number_toggles = (tick + 1) / divisor;
+            output_signals.push(vec![if number_toggles.is_multiple_of(2) { initial_output.clone() } else { toggled_output.clone() }]);
+        }
+
+        run_multi_input_output_logic_gate(
+            vec![],
+            output_signals,
+            HashMap::from(
+                [
+                    (ClockDivider::CLK_IN, input_signals),
+                ]
+            ),
+            clock_divider,
+        );
+    }
+
+    #[test]
+    fn clock_divider_divide_by_two() {
+        run_clock_divider(2);
+    }
+
+    #[test]
+    fn clock_divider_divide_by_four() {
+        run_clock_divider(4);
+    }
+
     #[test]
     fn four_cycle_clock_hookup_initialization() {
         let clock = FourCycleClockHookup::new();
@@ -2174,6 +4577,198 @@ mod tests {
         multi.lock().unwrap().toggle_output_printing(true);
     }
 
+    #[test]
+    fn multiplexer_one_bit_two_to_one() {
+        run_multi_input_output_logic_gate(
+            vec![],
+            vec![
+                vec![HIGH],
+                vec![LOW_],
+                vec![HIGH],
+                vec![LOW_],
+            ],
+            HashMap::from(
+                [
+                    ("i_0_0", vec![vec![HIGH], vec![HIGH], vec![LOW_], vec![LOW_]]),
+                    ("i_1_0", vec![vec![LOW_], vec![LOW_], vec![HIGH], vec![HIGH]]),
+                    ("S_0", vec![vec![LOW_], vec![HIGH], vec![HIGH], vec![LOW_]]),
+                ]
+            ),
+            Multiplexer::new(1, 1),
+        );
+    }
+
+    #[test]
+    fn multiplexer_eight_bit_two_to_one() {
+        let input_0 = vec![HIGH, LOW_, HIGH, HIGH, LOW_, LOW_, HIGH, LOW_];
+        let input_1 = vec![LOW_, HIGH, LOW_, HIGH, HIGH, HIGH, LOW_, LOW_];
+
+        run_multi_input_output_logic_gate(
+            vec![],
+            vec![
+                input_0.clone(),
+                input_1.clone(),
+                input_0.clone(),
+            ],
+            HashMap::from(
+                [
+                    ("i_0", vec![input_0.clone(), input_0.clone(), input_0.clone()]),
+                    ("i_1", vec![input_1.clone(), input_1.clone(), input_1.clone()]),
+                    ("S_0", vec![vec![LOW_], vec![HIGH], vec![LOW_]]),
+                ]
+            ),
+            Multiplexer::new(8, 1),
+        );
+    }
+
+    #[test]
+    fn multiplexer_four_to_one() {
+        let inputs = [
+            vec![LOW_, LOW_],
+            vec![HIGH, LOW_],
+            vec![LOW_, HIGH],
+            vec![HIGH, HIGH],
+        ];
+
+        //S_0 is the least significant select line.
+        let select = vec![
+            vec![LOW_, LOW_],
+            vec![HIGH, LOW_],
+            vec![LOW_, HIGH],
+            vec![HIGH, HIGH],
+            vec![HIGH, LOW_],
+        ];
+
+        run_multi_input_output_logic_gate(
+            vec![],
+            vec![
+                inputs[0].clone(),
+                inputs[1].clone(),
+                inputs[2].clone(),
+                inputs[3].clone(),
+                inputs[1].clone(),
+            ],
+            HashMap::from(
+                [
+                    ("i_0", vec![inputs[0].clone(); select.len()]),
+                    ("i_1", vec![inputs[1].clone(); select.len()]),
+                    ("i_2", vec![inputs[2].clone(); select.len()]),
+                    ("i_3", vec![inputs[3].clone(); select.len()]),
+                    ("S", select.clone()),
+                ]
+            ),
+            Multiplexer::new(2, 2),
+        );
+    }
+
+    #[test]
+    fn multiplexer_select_none() {
+        //With S_1 NONE, both i_0 and i_2 are selected and OR'ed together.
+        run_multi_input_output_logic_gate(
+            vec![],
+            vec![
+                vec![HIGH, HIGH],
+                vec![HIGH, LOW_],
+                vec![LOW_, HIGH],
+            ],
+            HashMap::from(
+                [
+                    ("i_0", vec![vec![HIGH, LOW_], vec![HIGH, LOW_], vec![LOW_, LOW_]]),
+                    ("i_1", vec![vec![LOW_, HIGH], vec![LOW_, HIGH], vec![LOW_, HIGH]]),
+                    ("i_2", vec![vec![LOW_, HIGH], vec![LOW_, LOW_], vec![LOW_, LOW_]]),
+                    ("i_3", vec![vec![LOW_, LOW_], vec![LOW_, LOW_], vec![LOW_, LOW_]]),
+                    ("S", vec![vec![LOW_, NONE], vec![LOW_, NONE], vec![HIGH, LOW_]]),
+                ]
+            ),
+            Multiplexer::new(2, 2),
+        );
+    }
+
+    #[test]
+    fn demultiplexer_one_to_two() {
+        let input = vec![HIGH, LOW_, HIGH, HIGH];
+        let low = vec![LOW_; 4];
+
+        run_multi_input_output_logic_gate(
+            vec![],
+            vec![
+                [input.clone(), low.clone()].concat(),
+                [low.clone(), input.clone()].concat(),
+                [low.clone(), vec![LOW_, HIGH, LOW_, LOW_]].concat(),
+            ],
+            HashMap::from(
+                [
+                    ("i", vec![input.clone(), input.clone(), vec![LOW_, HIGH, LOW_, LOW_]]),
+                    ("S_0", vec![vec![LOW_], vec![HIGH], vec![HIGH]]),
+                ]
+            ),
+            Demultiplexer::new(4, 1),
+        );
+    }
+
+    #[test]
+    fn demultiplexer_one_to_four() {
+        let input = vec![HIGH, HIGH];
+        let low = vec![LOW_; 2];
+
+        //S_0 is the least significant select line.
+        run_multi_input_output_logic_gate(
+            vec![],
+            vec![
+                [input.clone(), low.clone(), low.clone(), low.clone()].concat(),
+                [low.clone(), input.clone(), low.clone(), low.clone()].concat(),
+                [low.clone(), low.clone(), input.clone(), low.clone()].concat(),
+                [low.clone(), low.clone(), low.clone(), input.clone()].concat(),
+                [low.clone(), low.clone(), low.clone(), low.clone()].concat(),
+            ],
+            HashMap::from(
+                [
+                    ("i", vec![input.clone(), input.clone(), input.clone(), input.clone(), low.clone()]),
+                    ("S", vec![
+                        vec![LOW_, LOW_],
+                        vec![HIGH, LOW_],
+                        vec![LOW_, HIGH],
+                        vec![HIGH, HIGH],
+                        vec![HIGH, HIGH],
+                    ]),
+                ]
+            ),
+            Demultiplexer::new(2, 2),
+        );
+    }
+
+    #[test]
+    fn lut_matches_xor() {
+        let lut = Lut::new(
+            2,
+            vec![
+                vec![LOW_],
+                vec![HIGH],
+                vec![HIGH],
+                vec![LOW_],
+            ],
+        );
+        let xor = XOr::new(2, 1);
+
+        for (a, b) in [(LOW_, LOW_), (HIGH, LOW_), (LOW_, HIGH), (HIGH, HIGH)] {
+            let mut lut = lut.lock().unwrap();
+            let mut xor = xor.lock().unwrap();
+
+            for (i, signal) in [a, b].into_iter().enumerate() {
+                lut.update_input_signal(GateInput::new(i, signal.clone(), UniqueID::zero_id()));
+                xor.update_input_signal(GateInput::new(i, signal, UniqueID::zero_id()));
+            }
+
+            let lut_output = lut.fetch_output_signals_calculate().unwrap();
+            let xor_output = xor.fetch_output_signals_calculate().unwrap();
+
+            assert_eq!(
+                extract_signals_from_gate_output_states(&lut_output),
+                extract_signals_from_gate_output_states(&xor_output),
+            );
+        }
+    }
+
     #[test]
     fn variable_bit_counter_initialization() {
         let counter = VariableBitCounter::new(4);
@@ -2246,4 +4841,232 @@ mod tests {
             counter,
         );
     }
-}
\ No newline at end of file
+
+    //Index 0 is the least significant bit.
+    fn number_to_signals(number: usize, number_bits: usize) -> Vec<Signal> {
+        (0..number_bits)
+            .map(|i| if (number >> i) & 1 == 1 { HIGH } else { LOW_ })
+            .collect()
+    }
+
+    fn run_gray_code_gate(
+        gate: SharedMutex<dyn LogicGate>,
+        input_signals: Vec<Vec<Signal>>,
+        number_bits: usize,
+    ) -> Vec<Vec<Signal>> {
+        //Only the width of the output is used when collecting.
+        let output_signals = vec![vec![LOW_; number_bits]; input_signals.len()];
+
+        run_multi_input_output_logic_gate_return(
+            input_signals,
+            &output_signals,
+            HashMap::new(),
+            gate,
+        )
+    }
+
+    #[test]
+    fn binary_to_gray_run() {
+        for number_bits in 1..=4 {
+            let possible_numbers = usize::pow(2, number_bits as u32);
+
+            let input_signals: Vec<Vec<Signal>> = (0..possible_numbers)
+                .map(|i| number_to_signals(i, number_bits))
+                .collect();
+
+            let output_signals: Vec<Vec<Signal>> = (0..possible_numbers)
+                .map(|i| number_to_signals(i ^ (i >> 1), number_bits))
+                .collect();
+
+            run_multi_input_output_logic_gate(
+                input_signals,
+                output_signals,
+                HashMap::new(),
+                BinaryToGray::new(number_bits),
+            );
+        }
+    }
+
+    #[test]
+    fn gray_to_binary_run() {
+        for number_bits in 1..=4 {
+            let possible_numbers = usize::pow(2, number_bits as u32);
+
+            let input_signals: Vec<Vec<Signal>> = (0..possible_numbers)
+                .map(|i| number_to_signals(i ^ (i >> 1), number_bits))
+                .collect();
+
+            let output_signals: Vec<Vec<Signal>> = (0..possible_numbers)
+                .map(|i| number_to_signals(i, number_bits))
+                .collect();
+
+            run_multi_input_output_logic_gate(
+                input_signals,
+                output_signals,
+                HashMap::new(),
+                GrayToBinary::new(number_bits),
+            );
+        }
+    }
+
+    #[test]
+    fn bus_concat_run() {
+        let low_numbers = [0b0000, 0b1010, 0b0001, 0b1111, 0b0110];
+        let high_numbers = [0b0000, 0b0011, 0b1000, 0b0000, 0b1111];
+
+        let output_signals: Vec<Vec<Signal>> = low_numbers
+            .iter()
+            .zip(high_numbers.iter())
+            .map(|(low, high)| number_to_signals(low | (high << 4), 8))
+            .collect();
+
+        run_multi_input_output_logic_gate(
+            vec![],
+            output_signals,
+            HashMap::from(
+                [
+                    (BusConcat::LOW, low_numbers.iter().map(|i| number_to_signals(*i, 4)).collect()),
+                    (BusConcat::HIGH, high_numbers.iter().map(|i| number_to_signals(*i, 4)).collect()),
+                ]
+            ),
+            BusConcat::new(4, 4),
+        );
+    }
+
+    #[test]
+    fn pattern_match_run() {
+        let number_bits = 4;
+        let pattern_number = 0b1010;
+        let pattern = number_to_signals(pattern_number, number_bits);
+
+        let input_numbers = vec![0b0000, 0b1010, 0b1011, 0b0010, 0b1010, 0b1110, 0b0101, 0b1111, 0b1010];
+
+        let input_signals: Vec<Vec<Signal>> = input_numbers
+            .iter()
+            .map(|i| number_to_signals(*i, number_bits))
+            .collect();
+
+        let output_signals: Vec<Vec<Signal>> = input_numbers
+            .iter()
+            .map(|i| if *i == pattern_number { vec![HIGH] } else { vec![LOW_] })
+            .collect();
+
+        run_multi_input_output_logic_gate(
+            input_signals,
+            output_signals,
+            HashMap::new(),
+            PatternMatch::new(&pattern),
+        );
+    }
+
+    #[test]
+    fn binary_to_gray_round_trip() {
+        for number_bits in 1..=4 {
+            let possible_numbers = usize::pow(2, number_bits as u32);
+
+            let binary_signals: Vec<Vec<Signal>> = (0..possible_numbers)
+                .map(|i| number_to_signals(i, number_bits))
+                .collect();
+
+            let gray_signals = run_gray_code_gate(
+                BinaryToGray::new(number_bits),
+                binary_signals.clone(),
+                number_bits,
+            );
+
+            //Adjacent binary values (including the wrap around) must differ by a single Gray code
+            // bit.
+            for i in 0..possible_numbers {
+                let next = &gray_signals[(i + 1) % possible_numbers];
+                let num_different_bits = gray_signals[i]
+                    .iter()
+                    .zip(next.iter())
+                    .filter(|(first, second)| first != second)
+                    .count();
+
+                assert_eq!(num_different_bits, 1, "number_bits {} value {}", number_bits, i);
+            }
+
+            let round_trip_signals = run_gray_code_gate(
+                GrayToBinary::new(number_bits),
+                gray_signals,
+                number_bits,
+            );
+
+            assert_eq!(round_trip_signals, binary_signals);
+        }
+    }
+
+    #[test]
+    fn bus_keeper_undriven_bus() {
+        //Two ControlledBuffers share a two bit bus. The bus is only driven on ticks 1 and 3.
+        let buffer_data = [
+            [vec![LOW_; 4], vec![HIGH; 4]],
+            [vec![LOW_; 4], vec![LOW_; 4]],
+        ];
+        let buffer_enables = [
+            vec![LOW_, HIGH, LOW_, LOW_],
+            vec![LOW_, LOW_, LOW_, HIGH],
+        ];
+
+        let bus_keeper = BusKeeper::new(2, HIGH);
+        let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+        let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
+
+        for (data, enable) in buffer_data.into_iter().zip(buffer_enables) {
+            let controlled_buffer = ControlledBuffer::new(2);
+
+            for (i, bit) in data.into_iter().enumerate() {
+                let data_input = AutomaticInput::new(bit, 1, "Data");
+                connect_gates(data_input.clone(), 0, controlled_buffer.clone(), i);
+                connect_gates(controlled_buffer.clone(), i, bus_keeper.clone(), i);
+                input_gates.push(data_input);
+            }
+
+            let enable_index = controlled_buffer.lock().unwrap().get_index_from_tag("E");
+            let enable_input = AutomaticInput::new(enable, 1, "Enable");
+            connect_gates(enable_input.clone(), 0, controlled_buffer.clone(), enable_index);
+            input_gates.push(enable_input);
+        }
+
+        for i in 0..2 {
+            let output_gate = SimpleOutput::new(format!("OUT_{}", i).as_str());
+            connect_gates(bus_keeper.clone(), i, output_gate.clone(), 0);
+            output_gates.push(output_gate);
+        }
+
+        let mut collected_output: Vec<Vec<Signal>> = Vec::new();
+        let mut propagate_signal_through_circuit = true;
+        let mut continue_clock = true;
+
+        while continue_clock {
+            unsafe {
+                CLOCK_TICK_NUMBER += 1;
+            }
+
+            continue_clock = run_circuit(
+                &input_gates,
+                &output_gates,
+                propagate_signal_through_circuit,
+                &mut |_clock_tick_inputs, output_gates: &Vec<SharedMutex<dyn LogicGateAndOutputGate>>| {
+                    let mut single_collected_output = Vec::new();
+                    collect_outputs_from_output_gates(&output_gates, &mut single_collected_output);
+                    collected_output.push(single_collected_output);
+                },
+            );
+
+            propagate_signal_through_circuit = false;
+        }
+
+        //Nothing drives the bus on ticks 0 and 2 so it reads the keeper's default instead of NONE.
+        assert_eq!(
+            collected_output,
+            vec![
+                vec![HIGH, HIGH],
+                vec![LOW_, HIGH],
+                vec![HIGH, HIGH],
+                vec![LOW_, LOW_],
+            ]
+        );
+    }
+}
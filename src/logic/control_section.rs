@@ -4,16 +4,26 @@ use crate::logic::basic_gates::{And, Not, Or, Splitter};
 use crate::logic::complex_logic::VariableOutputStepper;
 
 #[allow(unused_imports)]
-use crate::logic::foundations::{BasicGateMembers, ComplexGateMembers, GateInput, GateLogicError, GateOutputState, GateType, InputSignalReturn, LogicGate, Signal, UniqueID};
-use crate::logic::foundations::connect_gates;
-use crate::logic::input_gates::SimpleInput;
+use crate::logic::foundations::{BasicGateMembers, ComplexGateMembers, GateInput, GateLogicError, GateOutputState, GateCounts, GateType, InputSignalReturn, LogicGate, Signal, UniqueID, GateTagType};
+use crate::logic::foundations::{connect_gates, extract_signals_from_gate_output_states};
+use crate::logic::input_gates::{AutomaticInput, SimpleInput};
+use crate::logic::memory_gates::OneBitMemoryCell;
 use crate::logic::output_gates::{LogicGateAndOutputGate, SimpleOutput};
 
 #[allow(unused_imports)]
 use crate::logic::foundations::Signal::{LOW_, HIGH};
 use crate::logic::processor_components::VariableDecoder;
+use crate::logic::netlist::Netlist;
+use crate::run_circuit::start_clock;
 use crate::shared_mutex::{new_shared_mutex, SharedMutex};
 
+//Instructions::EXTENDED_PREFIX selects the extended instruction table for the instruction after
+// it. The prefix is fetched and does nothing during steps 4-6, then the next instruction is
+// fetched as normal and decoded from the extended table. The extended table is
+//  0000 aa bb MOV (copy reg_a to reg_b)
+//  0001 aa bb SWAP (exchange reg_a and reg_b, TMP and ACC are overwritten)
+//  0010 00 bb LDI (load the immediate in the next RAM cell into reg_b, ACC is overwritten)
+//  1 ooo aa bb the ALU instruction ooo with the stored carry flag as its carry in (ADC for ADD)
 pub struct ControlSection {
     complex_gate: ComplexGateMembers,
     clk_and: SharedMutex<And>,
@@ -66,15 +76,22 @@ pub struct ControlSection {
     r3_s_or: SharedMutex<Or>,
     r3_s_and: SharedMutex<And>,
     r_s_decoder: SharedMutex<VariableDecoder>,
+    r0_s_reg_a_and: SharedMutex<And>,
+    r1_s_reg_a_and: SharedMutex<And>,
+    r2_s_reg_a_and: SharedMutex<And>,
+    r3_s_reg_a_and: SharedMutex<And>,
+    r_s_reg_a_decoder: SharedMutex<VariableDecoder>,
     ir_s_or: SharedMutex<Or>,
     ir_s_and: SharedMutex<And>,
     tmp_s_or: SharedMutex<Or>,
     tmp_s_and: SharedMutex<And>,
+    tmp_s_outer_or: SharedMutex<Or>,
     reg_b_e_or: SharedMutex<Or>,
     reg_a_or: SharedMutex<Or>,
     alu_0_and: SharedMutex<And>,
     alu_1_and: SharedMutex<And>,
     alu_2_and: SharedMutex<And>,
+    alu_2_or: SharedMutex<Or>,
     flags_s_or: SharedMutex<Or>,
     flags_s_and: SharedMutex<And>,
     flags_s_outer_or: SharedMutex<Or>,
@@ -119,6 +136,36 @@ pub struct ControlSection {
     alu_input_or: SharedMutex<Or>,
     add_and: SharedMutex<And>,
     add_not: SharedMutex<Not>,
+    step_reset_and: SharedMutex<And>,
+    step_reset_not: SharedMutex<Not>,
+    prefix_and: SharedMutex<And>,
+    prefix_and_not_loc_6: SharedMutex<Not>,
+    prefix_and_not_loc_7: SharedMutex<Not>,
+    prefix_not: SharedMutex<Not>,
+    extended_latch: SharedMutex<OneBitMemoryCell>,
+    extended_not: SharedMutex<Not>,
+    extended_alu_or: SharedMutex<Or>,
+    c_out_and: SharedMutex<And>,
+    stepper_4_splitter: SharedMutex<Splitter>,
+    stepper_5_splitter: SharedMutex<Splitter>,
+    stepper_6_splitter: SharedMutex<Splitter>,
+    base_step_4_and: SharedMutex<And>,
+    base_step_5_and: SharedMutex<And>,
+    base_step_6_and: SharedMutex<And>,
+    extended_opcode_or: SharedMutex<Or>,
+    extended_opcode_not: SharedMutex<Not>,
+    extended_move_not: SharedMutex<Not>,
+    extended_move_and: SharedMutex<And>,
+    extended_swap_and: SharedMutex<And>,
+    extended_swap_step_4_and: SharedMutex<And>,
+    extended_swap_step_5_and: SharedMutex<And>,
+    extended_swap_step_6_and: SharedMutex<And>,
+    extended_ldi_opcode_or: SharedMutex<Or>,
+    extended_ldi_opcode_not: SharedMutex<Not>,
+    extended_ldi_and: SharedMutex<And>,
+    extended_ldi_step_4_and: SharedMutex<And>,
+    extended_ldi_step_5_and: SharedMutex<And>,
+    extended_ldi_step_6_and: SharedMutex<And>,
 }
 
 #[allow(dead_code)]
@@ -162,10 +209,28 @@ impl ControlSection {
     pub const FLAG_S: &'static str = "FLAG_S";
     pub const IO_CLK_S: &'static str = "IO_CLK_S";
     pub const IO_CLK_E: &'static str = "IO_CLK_E";
+    //HIGH on the ALU step of an extended ALU instruction. The cpu ANDs this with the carry flag
+    // latched in c_tmp to drive the ALU C_IN, so the base ALU instructions have no carry in.
     pub const C_OUT: &'static str = "C_OUT";
     pub const END: &'static str = "END";
     pub const IO: &'static str = "IO";
     pub const DA: &'static str = "DA";
+    //HIGH for the single clock tick where the stepper returns to step 1 (the tick after the last
+    // step of an instruction ends). It is also HIGH after power on because the stepper starts at
+    // step 1.
+    pub const STEP_RESET: &'static str = "STEP_RESET";
+
+    //Outputs that drive a value onto the bus. At most one of these can be HIGH at a time.
+    pub const BUS_ENABLE_OUTPUTS: [&'static str; 8] = [
+        ControlSection::RAM_E,
+        ControlSection::ACC_E,
+        ControlSection::IAR_E,
+        ControlSection::R0_E,
+        ControlSection::R1_E,
+        ControlSection::R2_E,
+        ControlSection::R3_E,
+        ControlSection::IO_CLK_E,
+    ];
 
     pub fn new(bus_width: usize) -> SharedMutex<Self> {
         assert!(bus_width > 7);
@@ -174,14 +239,14 @@ impl ControlSection {
         let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
         let mut output_gates_logic: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
 
-        input_gates.push(SimpleInput::new(4, "IR_0"));
-        input_gates.push(SimpleInput::new(4, "IR_1"));
-        input_gates.push(SimpleInput::new(4, "IR_2"));
-        input_gates.push(SimpleInput::new(6, "IR_3"));
-        input_gates.push(SimpleInput::new(4, "IR_4"));
-        input_gates.push(SimpleInput::new(4, "IR_5"));
-        input_gates.push(SimpleInput::new(4, "IR_6"));
-        input_gates.push(SimpleInput::new(8, "IR_7"));
+        input_gates.push(SimpleInput::new(5, "IR_0"));
+        input_gates.push(SimpleInput::new(5, "IR_1"));
+        input_gates.push(SimpleInput::new(6, "IR_2"));
+        input_gates.push(SimpleInput::new(8, "IR_3"));
+        input_gates.push(SimpleInput::new(8, "IR_4"));
+        input_gates.push(SimpleInput::new(7, "IR_5"));
+        input_gates.push(SimpleInput::new(7, "IR_6"));
+        input_gates.push(SimpleInput::new(12, "IR_7"));
 
         for i in 8..bus_width {
             let input_tag = format!("IR_{}", i);
@@ -190,16 +255,16 @@ impl ControlSection {
 
         let mut stepper_splitters = Vec::new();
 
-        stepper_splitters.push(Splitter::new(1, 1));
+        stepper_splitters.push(Splitter::new(1, 2));
         stepper_splitters.push(Splitter::new(1, 2));
         stepper_splitters.push(Splitter::new(1, 2));
         stepper_splitters.push(Splitter::new(1, 9));
         stepper_splitters.push(Splitter::new(1, 10));
         stepper_splitters.push(Splitter::new(1, 3));
 
-        input_gates.push(SimpleInput::new(13, ControlSection::CLOCK_SET));
+        input_gates.push(SimpleInput::new(17, ControlSection::CLOCK_SET));
         input_gates.push(SimpleInput::new(1, ControlSection::CLOCK));
-        input_gates.push(SimpleInput::new(12, ControlSection::CLOCK_ENABLE));
+        input_gates.push(SimpleInput::new(13, ControlSection::CLOCK_ENABLE));
         input_gates.push(SimpleInput::new(1, ControlSection::HIGH_LVL_MARS));
         input_gates.push(SimpleInput::new(11, ControlSection::HIGH_LVL_RESET));
         input_gates.push(SimpleInput::new(2, ControlSection::HIGH_LVL_LOAD));
@@ -241,11 +306,12 @@ impl ControlSection {
         store_output(SimpleOutput::new(ControlSection::END));
         store_output(SimpleOutput::new(ControlSection::IO));
         store_output(SimpleOutput::new(ControlSection::DA));
+        store_output(SimpleOutput::new(ControlSection::STEP_RESET));
 
         let mut control_section = ControlSection {
             complex_gate: ComplexGateMembers::new(
                 bus_width + 10,
-                28,
+                29,
                 GateType::ControlSectionType,
                 input_gates,
                 output_gates,
@@ -256,13 +322,13 @@ impl ControlSection {
             stepper: VariableOutputStepper::new(6),
             stepper_splitters,
             stepper_1_and: And::new(3, 4),
-            bus_1_or: Or::new(4, 1),
+            bus_1_or: Or::new(5, 1),
             ram_e_and: And::new(2, 1),
-            ram_e_or: Or::new(5, 1),
+            ram_e_or: Or::new(6, 1),
             acc_e_and: And::new(2, 1),
-            acc_e_or: Or::new(4, 1),
+            acc_e_or: Or::new(6, 1),
             iar_e_and: And::new(2, 1),
-            iar_e_or: Or::new(4, 1),
+            iar_e_or: Or::new(5, 1),
             io_clk_e_and: And::new(2, 1),
             io_clks_s_and: And::new(2, 1),
             r0_e_or: Or::new(2, 1),
@@ -281,38 +347,45 @@ impl ControlSection {
             r_e_reg_a_decoder: VariableDecoder::new(2),
             mar_s_or: Or::new(3, 1),
             mar_s_and: And::new(2, 1),
-            mar_s_outer_or: Or::new(6, 1),
+            mar_s_outer_or: Or::new(7, 1),
             ram_s_or: Or::new(2, 1),
             ram_s_load_and: And::new(2, 1),
             ram_s_and: And::new(2, 1),
             acc_s_or: Or::new(2, 1),
             acc_s_and: And::new(2, 1),
-            acc_s_outer_or: Or::new(4, 1),
+            acc_s_outer_or: Or::new(6, 1),
             iar_s_or: Or::new(2, 1),
             iar_s_and: And::new(2, 1),
-            iar_s_outer_or: Or::new(6, 1),
-            r0_s_or: Or::new(2, 1),
+            iar_s_outer_or: Or::new(7, 1),
+            r0_s_or: Or::new(3, 1),
             r0_s_and: And::new(3, 1),
-            r1_s_or: Or::new(2, 1),
+            r1_s_or: Or::new(3, 1),
             r1_s_and: And::new(3, 1),
-            r2_s_or: Or::new(2, 1),
+            r2_s_or: Or::new(3, 1),
             r2_s_and: And::new(3, 1),
-            r3_s_or: Or::new(2, 1),
+            r3_s_or: Or::new(3, 1),
             r3_s_and: And::new(3, 1),
             r_s_decoder: VariableDecoder::new(2),
+            r0_s_reg_a_and: And::new(3, 1),
+            r1_s_reg_a_and: And::new(3, 1),
+            r2_s_reg_a_and: And::new(3, 1),
+            r3_s_reg_a_and: And::new(3, 1),
+            r_s_reg_a_decoder: VariableDecoder::new(2),
             ir_s_or: Or::new(2, 1),
             ir_s_and: And::new(2, 1),
             tmp_s_or: Or::new(2, 1),
             tmp_s_and: And::new(2, 1),
-            reg_b_e_or: Or::new(4, 4),
-            reg_a_or: Or::new(3, 4),
+            tmp_s_outer_or: Or::new(2, 1),
+            reg_b_e_or: Or::new(5, 4),
+            reg_a_or: Or::new(5, 4),
             alu_0_and: And::new(3, 1),
             alu_1_and: And::new(3, 1),
             alu_2_and: And::new(3, 1),
+            alu_2_or: Or::new(2, 1),
             flags_s_or: Or::new(2, 1),
             flags_s_and: And::new(2, 1),
             flags_s_outer_or: Or::new(2, 1),
-            reg_b_s_or: Or::new(4, 4),
+            reg_b_s_or: Or::new(7, 4),
             load_store_instr_not: Not::new(8),
             load_store_instr_decoder: VariableDecoder::new(3),
             load_store_instr_0_top_and: And::new(2, 2),
@@ -353,6 +426,36 @@ impl ControlSection {
             alu_input_or: Or::new(4, 1),
             add_and: And::new(3, 1),
             add_not: Not::new(1),
+            step_reset_and: And::new(2, 2),
+            step_reset_not: Not::new(1),
+            prefix_and: And::new(8, 2),
+            prefix_and_not_loc_6: Not::new(1),
+            prefix_and_not_loc_7: Not::new(1),
+            prefix_not: Not::new(3),
+            extended_latch: OneBitMemoryCell::new(5),
+            extended_not: Not::new(1),
+            extended_alu_or: Or::new(2, 3),
+            c_out_and: And::new(2, 1),
+            stepper_4_splitter: Splitter::new(1, 4),
+            stepper_5_splitter: Splitter::new(1, 3),
+            stepper_6_splitter: Splitter::new(1, 3),
+            base_step_4_and: And::new(3, 1),
+            base_step_5_and: And::new(3, 1),
+            base_step_6_and: And::new(3, 1),
+            extended_opcode_or: Or::new(3, 1),
+            extended_opcode_not: Not::new(2),
+            extended_move_not: Not::new(1),
+            extended_move_and: And::new(4, 2),
+            extended_swap_and: And::new(3, 3),
+            extended_swap_step_4_and: And::new(2, 4),
+            extended_swap_step_5_and: And::new(2, 5),
+            extended_swap_step_6_and: And::new(2, 2),
+            extended_ldi_opcode_or: Or::new(3, 1),
+            extended_ldi_opcode_not: Not::new(1),
+            extended_ldi_and: And::new(3, 3),
+            extended_ldi_step_4_and: And::new(2, 4),
+            extended_ldi_step_5_and: And::new(2, 2),
+            extended_ldi_step_6_and: And::new(2, 2),
         };
 
         control_section.clk_and.lock().unwrap().set_tag("clk_and");
@@ -410,15 +513,22 @@ impl ControlSection {
         control_section.r3_s_or.lock().unwrap().set_tag("r3_s_or");
         control_section.r3_s_and.lock().unwrap().set_tag("r3_s_and");
         control_section.r_s_decoder.lock().unwrap().set_tag("r_s_decoder");
+        control_section.r0_s_reg_a_and.lock().unwrap().set_tag("r0_s_reg_a_and");
+        control_section.r1_s_reg_a_and.lock().unwrap().set_tag("r1_s_reg_a_and");
+        control_section.r2_s_reg_a_and.lock().unwrap().set_tag("r2_s_reg_a_and");
+        control_section.r3_s_reg_a_and.lock().unwrap().set_tag("r3_s_reg_a_and");
+        control_section.r_s_reg_a_decoder.lock().unwrap().set_tag("r_s_reg_a_decoder");
         control_section.ir_s_or.lock().unwrap().set_tag("ir_s_or");
         control_section.ir_s_and.lock().unwrap().set_tag("ir_s_and");
         control_section.tmp_s_or.lock().unwrap().set_tag("tmp_s_or");
         control_section.tmp_s_and.lock().unwrap().set_tag("tmp_s_and");
+        control_section.tmp_s_outer_or.lock().unwrap().set_tag("tmp_s_outer_or");
         control_section.reg_b_e_or.lock().unwrap().set_tag("reg_b_e_or");
         control_section.reg_a_or.lock().unwrap().set_tag("reg_a_or");
         control_section.alu_0_and.lock().unwrap().set_tag("alu_0_and");
         control_section.alu_1_and.lock().unwrap().set_tag("alu_1_and");
         control_section.alu_2_and.lock().unwrap().set_tag("alu_2_and");
+        control_section.alu_2_or.lock().unwrap().set_tag("alu_2_or");
         control_section.flags_s_or.lock().unwrap().set_tag("flags_s_or");
         control_section.flags_s_and.lock().unwrap().set_tag("flags_s_and");
         control_section.flags_s_outer_or.lock().unwrap().set_tag("flags_s_outer_or");
@@ -463,6 +573,36 @@ impl ControlSection {
         control_section.alu_input_or.lock().unwrap().set_tag("alu_input_or");
         control_section.add_and.lock().unwrap().set_tag("add_and");
         control_section.add_not.lock().unwrap().set_tag("add_not");
+        control_section.step_reset_and.lock().unwrap().set_tag("step_reset_and");
+        control_section.step_reset_not.lock().unwrap().set_tag("step_reset_not");
+        control_section.prefix_and.lock().unwrap().set_tag("prefix_and");
+        control_section.prefix_and_not_loc_6.lock().unwrap().set_tag("prefix_and_not_loc_6");
+        control_section.prefix_and_not_loc_7.lock().unwrap().set_tag("prefix_and_not_loc_7");
+        control_section.prefix_not.lock().unwrap().set_tag("prefix_not");
+        control_section.extended_latch.lock().unwrap().set_tag("extended_latch");
+        control_section.extended_not.lock().unwrap().set_tag("extended_not");
+        control_section.extended_alu_or.lock().unwrap().set_tag("extended_alu_or");
+        control_section.c_out_and.lock().unwrap().set_tag("c_out_and");
+        control_section.stepper_4_splitter.lock().unwrap().set_tag("stepper_4_splitter");
+        control_section.stepper_5_splitter.lock().unwrap().set_tag("stepper_5_splitter");
+        control_section.stepper_6_splitter.lock().unwrap().set_tag("stepper_6_splitter");
+        control_section.base_step_4_and.lock().unwrap().set_tag("base_step_4_and");
+        control_section.base_step_5_and.lock().unwrap().set_tag("base_step_5_and");
+        control_section.base_step_6_and.lock().unwrap().set_tag("base_step_6_and");
+        control_section.extended_opcode_or.lock().unwrap().set_tag("extended_opcode_or");
+        control_section.extended_opcode_not.lock().unwrap().set_tag("extended_opcode_not");
+        control_section.extended_move_not.lock().unwrap().set_tag("extended_move_not");
+        control_section.extended_move_and.lock().unwrap().set_tag("extended_move_and");
+        control_section.extended_swap_and.lock().unwrap().set_tag("extended_swap_and");
+        control_section.extended_swap_step_4_and.lock().unwrap().set_tag("extended_swap_step_4_and");
+        control_section.extended_swap_step_5_and.lock().unwrap().set_tag("extended_swap_step_5_and");
+        control_section.extended_swap_step_6_and.lock().unwrap().set_tag("extended_swap_step_6_and");
+        control_section.extended_ldi_opcode_or.lock().unwrap().set_tag("extended_ldi_opcode_or");
+        control_section.extended_ldi_opcode_not.lock().unwrap().set_tag("extended_ldi_opcode_not");
+        control_section.extended_ldi_and.lock().unwrap().set_tag("extended_ldi_and");
+        control_section.extended_ldi_step_4_and.lock().unwrap().set_tag("extended_ldi_step_4_and");
+        control_section.extended_ldi_step_5_and.lock().unwrap().set_tag("extended_ldi_step_5_and");
+        control_section.extended_ldi_step_6_and.lock().unwrap().set_tag("extended_ldi_step_6_and");
 
         control_section.stepper.lock().unwrap().toggle_print_each_input_output_gate(false);
 
@@ -555,15 +695,22 @@ impl ControlSection {
         self.r3_s_or_connect(&output_gates);
         self.r3_s_and_connect();
         self.r_s_decoder_connect();
+        self.r0_s_reg_a_and_connect();
+        self.r1_s_reg_a_and_connect();
+        self.r2_s_reg_a_and_connect();
+        self.r3_s_reg_a_and_connect();
+        self.r_s_reg_a_decoder_connect();
         self.ir_s_or_connect(&output_gates);
         self.ir_s_and_connect();
         self.tmp_s_or_connect(&output_gates);
         self.tmp_s_and_connect();
+        self.tmp_s_outer_or_connect();
         self.reg_b_e_or_connect();
         self.reg_a_or_connect();
         self.alu_0_and_connect(&output_gates);
         self.alu_1_and_connect(&output_gates);
-        self.alu_2_and_connect(&output_gates);
+        self.alu_2_and_connect();
+        self.alu_2_or_connect(&output_gates);
         self.flags_s_or_connect(&output_gates);
         self.flags_s_and_connect();
         self.flags_s_outer_or_connect();
@@ -587,7 +734,7 @@ impl ControlSection {
         self.stepper_out_4_6_and_connect();
         self.stepper_out_4_7_and_connect();
         self.stepper_out_4_8_and_connect();
-        self.stepper_out_5_top_0_and_connect(&output_gates);
+        self.stepper_out_5_top_0_and_connect();
         self.stepper_out_5_1_and_connect();
         self.stepper_out_5_2_and_connect();
         self.stepper_out_5_3_and_connect();
@@ -608,6 +755,34 @@ impl ControlSection {
         self.alu_input_or_connect();
         self.add_and_connect();
         self.add_not_connect();
+        self.step_reset_and_connect(&output_gates);
+        self.step_reset_not_connect();
+        self.prefix_and_connect();
+        self.prefix_and_not_loc_6_connect();
+        self.prefix_and_not_loc_7_connect();
+        self.prefix_not_connect();
+        self.extended_latch_connect();
+        self.extended_not_connect();
+        self.extended_alu_or_connect();
+        self.c_out_and_connect(&output_gates);
+        self.stepper_4_splitter_connect();
+        self.stepper_5_splitter_connect();
+        self.stepper_6_splitter_connect();
+        self.base_step_and_connect();
+        self.extended_opcode_or_connect();
+        self.extended_opcode_not_connect();
+        self.extended_move_not_connect();
+        self.extended_move_and_connect();
+        self.extended_swap_and_connect();
+        self.extended_swap_step_4_and_connect();
+        self.extended_swap_step_5_and_connect();
+        self.extended_swap_step_6_and_connect();
+        self.extended_ldi_opcode_or_connect();
+        self.extended_ldi_opcode_not_connect();
+        self.extended_ldi_and_connect();
+        self.extended_ldi_step_4_and_connect();
+        self.extended_ldi_step_5_and_connect();
+        self.extended_ldi_step_6_and_connect();
 
         #[cfg(feature = "high_restriction")]
         self.check_output();
@@ -696,15 +871,22 @@ impl ControlSection {
         check_output(&self.r3_s_or.lock().unwrap().members);
         check_output(&self.r3_s_and.lock().unwrap().members);
         check_output(&self.r_s_decoder.lock().unwrap().complex_gate.simple_gate);
+        check_output(&self.r0_s_reg_a_and.lock().unwrap().members);
+        check_output(&self.r1_s_reg_a_and.lock().unwrap().members);
+        check_output(&self.r2_s_reg_a_and.lock().unwrap().members);
+        check_output(&self.r3_s_reg_a_and.lock().unwrap().members);
+        check_output(&self.r_s_reg_a_decoder.lock().unwrap().complex_gate.simple_gate);
         check_output(&self.ir_s_or.lock().unwrap().members);
         check_output(&self.ir_s_and.lock().unwrap().members);
         check_output(&self.tmp_s_or.lock().unwrap().members);
         check_output(&self.tmp_s_and.lock().unwrap().members);
+        check_output(&self.tmp_s_outer_or.lock().unwrap().members);
         check_output(&self.reg_b_e_or.lock().unwrap().members);
         check_output(&self.reg_a_or.lock().unwrap().members);
         check_output(&self.alu_0_and.lock().unwrap().members);
         check_output(&self.alu_1_and.lock().unwrap().members);
         check_output(&self.alu_2_and.lock().unwrap().members);
+        check_output(&self.alu_2_or.lock().unwrap().members);
         check_output(&self.flags_s_or.lock().unwrap().members);
         check_output(&self.flags_s_and.lock().unwrap().members);
         check_output(&self.flags_s_outer_or.lock().unwrap().members);
@@ -749,10 +931,41 @@ impl ControlSection {
         check_output(&self.alu_input_or.lock().unwrap().members);
         check_output(&self.add_and.lock().unwrap().members);
         check_output(&self.add_not.lock().unwrap().members);
+        check_output(&self.step_reset_and.lock().unwrap().members);
+        check_output(&self.step_reset_not.lock().unwrap().members);
+        check_output(&self.prefix_and.lock().unwrap().members);
+        check_output(&self.prefix_and_not_loc_6.lock().unwrap().members);
+        check_output(&self.prefix_and_not_loc_7.lock().unwrap().members);
+        check_output(&self.prefix_not.lock().unwrap().members);
+        check_output(&self.extended_not.lock().unwrap().members);
+        check_output(&self.extended_alu_or.lock().unwrap().members);
+        check_output(&self.c_out_and.lock().unwrap().members);
+        check_output(&self.stepper_4_splitter.lock().unwrap().members);
+        check_output(&self.stepper_5_splitter.lock().unwrap().members);
+        check_output(&self.stepper_6_splitter.lock().unwrap().members);
+        check_output(&self.base_step_4_and.lock().unwrap().members);
+        check_output(&self.base_step_5_and.lock().unwrap().members);
+        check_output(&self.base_step_6_and.lock().unwrap().members);
+        check_output(&self.extended_opcode_or.lock().unwrap().members);
+        check_output(&self.extended_opcode_not.lock().unwrap().members);
+        check_output(&self.extended_move_not.lock().unwrap().members);
+        check_output(&self.extended_move_and.lock().unwrap().members);
+        check_output(&self.extended_swap_and.lock().unwrap().members);
+        check_output(&self.extended_swap_step_4_and.lock().unwrap().members);
+        check_output(&self.extended_swap_step_5_and.lock().unwrap().members);
+        check_output(&self.extended_swap_step_6_and.lock().unwrap().members);
+        check_output(&self.extended_ldi_opcode_or.lock().unwrap().members);
+        check_output(&self.extended_ldi_opcode_not.lock().unwrap().members);
+        check_output(&self.extended_ldi_and.lock().unwrap().members);
+        check_output(&self.extended_ldi_step_4_and.lock().unwrap().members);
+        check_output(&self.extended_ldi_step_5_and.lock().unwrap().members);
+        check_output(&self.extended_ldi_step_6_and.lock().unwrap().members);
     }
 
     fn connect_stepper_to_splitter(&mut self) {
-        for i in 0..self.stepper_splitters.len() {
+        //Steps 1-3 fetch the instruction and are always used. Steps 4-6 pass through the base_step
+        // gates first so they can be blocked while an extended instruction runs.
+        for i in 0..3 {
             connect_gates(
                 self.stepper.clone(),
                 i,
@@ -760,6 +973,27 @@ impl ControlSection {
                 0,
             );
         }
+
+        connect_gates(
+            self.stepper.clone(),
+            3,
+            self.stepper_4_splitter.clone(),
+            0,
+        );
+
+        connect_gates(
+            self.stepper.clone(),
+            4,
+            self.stepper_5_splitter.clone(),
+            0,
+        );
+
+        connect_gates(
+            self.stepper.clone(),
+            5,
+            self.stepper_6_splitter.clone(),
+            0,
+        );
     }
 
     fn connect_clk_input(&mut self) {
@@ -863,6 +1097,13 @@ impl ControlSection {
             self.r3_e_reg_a_and.clone(),
             0,
         );
+
+        connect_gates(
+            clke_input.clone(),
+            12,
+            self.step_reset_not.clone(),
+            0,
+        );
     }
 
     fn connect_clks_input(&mut self) {
@@ -960,6 +1201,34 @@ impl ControlSection {
             self.r3_s_and.clone(),
             0,
         );
+
+        connect_gates(
+            clks_input.clone(),
+            13,
+            self.r0_s_reg_a_and.clone(),
+            0,
+        );
+
+        connect_gates(
+            clks_input.clone(),
+            14,
+            self.r1_s_reg_a_and.clone(),
+            0,
+        );
+
+        connect_gates(
+            clks_input.clone(),
+            15,
+            self.r2_s_reg_a_and.clone(),
+            0,
+        );
+
+        connect_gates(
+            clks_input.clone(),
+            16,
+            self.r3_s_reg_a_and.clone(),
+            0,
+        );
     }
 
     fn connect_high_level_mars_input(&mut self) {
@@ -1160,6 +1429,13 @@ impl ControlSection {
             self.r_s_decoder.clone(),
             0,
         );
+
+        connect_gates(
+            input_gate.clone(),
+            4,
+            self.prefix_and.clone(),
+            0,
+        );
     }
 
     fn connect_ir_1_input(&mut self) {
@@ -1192,6 +1468,13 @@ impl ControlSection {
             self.r_s_decoder.clone(),
             1,
         );
+
+        connect_gates(
+            input_gate.clone(),
+            4,
+            self.prefix_and.clone(),
+            1,
+        );
     }
 
     fn connect_ir_2_input(
@@ -1228,6 +1511,20 @@ impl ControlSection {
             output_gates[io_index].clone(),
             0,
         );
+
+        connect_gates(
+            input_gate.clone(),
+            4,
+            self.prefix_and.clone(),
+            2,
+        );
+
+        connect_gates(
+            input_gate.clone(),
+            5,
+            self.r_s_reg_a_decoder.clone(),
+            0,
+        );
     }
 
     fn connect_ir_3_input(
@@ -1278,6 +1575,20 @@ impl ControlSection {
             self.stepper_out_5_6_not.clone(),
             0,
         );
+
+        connect_gates(
+            input_gate.clone(),
+            6,
+            self.prefix_and.clone(),
+            3,
+        );
+
+        connect_gates(
+            input_gate.clone(),
+            7,
+            self.r_s_reg_a_decoder.clone(),
+            1,
+        );
     }
 
     fn connect_ir_4_input(&mut self) {
@@ -1310,6 +1621,34 @@ impl ControlSection {
             self.add_and.clone(),
             0,
         );
+
+        connect_gates(
+            input_gate.clone(),
+            4,
+            self.prefix_and.clone(),
+            4,
+        );
+
+        connect_gates(
+            input_gate.clone(),
+            5,
+            self.extended_move_not.clone(),
+            0,
+        );
+
+        connect_gates(
+            input_gate.clone(),
+            6,
+            self.extended_swap_and.clone(),
+            1,
+        );
+
+        connect_gates(
+            input_gate.clone(),
+            7,
+            self.extended_ldi_opcode_or.clone(),
+            0,
+        );
     }
 
     fn connect_ir_5_input(&mut self) {
@@ -1342,6 +1681,27 @@ impl ControlSection {
             self.add_and.clone(),
             1,
         );
+
+        connect_gates(
+            input_gate.clone(),
+            4,
+            self.prefix_and.clone(),
+            5,
+        );
+
+        connect_gates(
+            input_gate.clone(),
+            5,
+            self.extended_opcode_or.clone(),
+            0,
+        );
+
+        connect_gates(
+            input_gate.clone(),
+            6,
+            self.extended_ldi_and.clone(),
+            1,
+        );
     }
 
     fn connect_ir_6_input(&mut self) {
@@ -1374,6 +1734,27 @@ impl ControlSection {
             self.add_and.clone(),
             2,
         );
+
+        connect_gates(
+            input_gate.clone(),
+            4,
+            self.prefix_and_not_loc_6.clone(),
+            0,
+        );
+
+        connect_gates(
+            input_gate.clone(),
+            5,
+            self.extended_opcode_or.clone(),
+            1,
+        );
+
+        connect_gates(
+            input_gate.clone(),
+            6,
+            self.extended_ldi_opcode_or.clone(),
+            1,
+        );
     }
 
     fn connect_ir_7_input(&mut self) {
@@ -1434,6 +1815,34 @@ impl ControlSection {
             self.stepper_out_6_top_0_and.clone(),
             1,
         );
+
+        connect_gates(
+            input_gate.clone(),
+            8,
+            self.prefix_and_not_loc_7.clone(),
+            0,
+        );
+
+        connect_gates(
+            input_gate.clone(),
+            9,
+            self.extended_opcode_or.clone(),
+            2,
+        );
+
+        connect_gates(
+            input_gate.clone(),
+            10,
+            self.extended_ldi_opcode_or.clone(),
+            2,
+        );
+
+        connect_gates(
+            input_gate.clone(),
+            11,
+            self.extended_alu_or.clone(),
+            1,
+        );
     }
 
     fn clk_and_connect(&mut self) {
@@ -1485,6 +1894,13 @@ impl ControlSection {
             self.stepper_1_and.clone(),
             1,
         );
+
+        connect_gates(
+            self.stepper_splitters[0].clone(),
+            1,
+            self.step_reset_and.clone(),
+            0,
+        );
     }
 
     fn stepper_splitters_2_connect(&mut self) {
@@ -2240,6 +2656,72 @@ impl ControlSection {
         );
     }
 
+    fn r0_s_reg_a_and_connect(&mut self) {
+        connect_gates(
+            self.r0_s_reg_a_and.clone(),
+            0,
+            self.r0_s_or.clone(),
+            2,
+        );
+    }
+
+    fn r1_s_reg_a_and_connect(&mut self) {
+        connect_gates(
+            self.r1_s_reg_a_and.clone(),
+            0,
+            self.r1_s_or.clone(),
+            2,
+        );
+    }
+
+    fn r2_s_reg_a_and_connect(&mut self) {
+        connect_gates(
+            self.r2_s_reg_a_and.clone(),
+            0,
+            self.r2_s_or.clone(),
+            2,
+        );
+    }
+
+    fn r3_s_reg_a_and_connect(&mut self) {
+        connect_gates(
+            self.r3_s_reg_a_and.clone(),
+            0,
+            self.r3_s_or.clone(),
+            2,
+        );
+    }
+
+    fn r_s_reg_a_decoder_connect(&mut self) {
+        connect_gates(
+            self.r_s_reg_a_decoder.clone(),
+            0,
+            self.r0_s_reg_a_and.clone(),
+            2,
+        );
+
+        connect_gates(
+            self.r_s_reg_a_decoder.clone(),
+            1,
+            self.r1_s_reg_a_and.clone(),
+            2,
+        );
+
+        connect_gates(
+            self.r_s_reg_a_decoder.clone(),
+            2,
+            self.r2_s_reg_a_and.clone(),
+            2,
+        );
+
+        connect_gates(
+            self.r_s_reg_a_decoder.clone(),
+            3,
+            self.r3_s_reg_a_and.clone(),
+            2,
+        );
+    }
+
     fn ir_s_or_connect(
         &mut self,
         output_gates: &Vec<SharedMutex<dyn LogicGate>>,
@@ -2284,6 +2766,15 @@ impl ControlSection {
         );
     }
 
+    fn tmp_s_outer_or_connect(&mut self) {
+        connect_gates(
+            self.tmp_s_outer_or.clone(),
+            0,
+            self.tmp_s_and.clone(),
+            1,
+        );
+    }
+
     fn reg_b_e_or_connect(&mut self) {
         connect_gates(
             self.reg_b_e_or.clone(),
@@ -2370,13 +2861,22 @@ impl ControlSection {
         );
     }
 
-    fn alu_2_and_connect(
+    fn alu_2_and_connect(&mut self) {
+        connect_gates(
+            self.alu_2_and.clone(),
+            0,
+            self.alu_2_or.clone(),
+            0,
+        );
+    }
+
+    fn alu_2_or_connect(
         &mut self,
         output_gates: &Vec<SharedMutex<dyn LogicGate>>,
     ) {
         let alu_2_index = self.get_index_from_tag(ControlSection::ALU_2);
         connect_gates(
-            self.alu_2_and.clone(),
+            self.alu_2_or.clone(),
             0,
             output_gates[alu_2_index].clone(),
             0,
@@ -2699,8 +3199,8 @@ impl ControlSection {
         connect_gates(
             self.stepper_out_4_top_0_and.clone(),
             1,
-            self.tmp_s_and.clone(),
-            1,
+            self.tmp_s_outer_or.clone(),
+            0,
         );
     }
 
@@ -2860,10 +3360,7 @@ impl ControlSection {
         );
     }
 
-    fn stepper_out_5_top_0_and_connect(
-        &mut self,
-        output_gates: &Vec<SharedMutex<dyn LogicGate>>,
-    ) {
+    fn stepper_out_5_top_0_and_connect(&mut self) {
         connect_gates(
             self.stepper_out_5_top_0_and.clone(),
             0,
@@ -2885,11 +3382,10 @@ impl ControlSection {
             3,
         );
 
-        let c_out_index = self.get_index_from_tag(ControlSection::C_OUT);
         connect_gates(
             self.stepper_out_5_top_0_and.clone(),
             3,
-            output_gates[c_out_index].clone(),
+            self.c_out_and.clone(),
             0,
         );
     }
@@ -3133,6 +3629,8 @@ impl ControlSection {
     }
 
     fn add_not_connect(&mut self) {
+        //add_and is HIGH when the ALU operation bits are 111 (CMP). CMP only sets the flags so
+        // this blocks step 6 from enabling ACC and setting the reg_b register.
         connect_gates(
             self.add_not.clone(),
             0,
@@ -3140,20 +3638,599 @@ impl ControlSection {
             2,
         );
     }
-}
-
-impl LogicGate for ControlSection {
-    fn internal_connect_output(&mut self, current_gate_output_key: usize, next_gate_input_key: usize, next_gate: SharedMutex<dyn LogicGate>) -> Signal {
-        self.complex_gate.connect_output(
-            self.get_unique_id(),
-            current_gate_output_key,
-            next_gate_input_key,
-            next_gate,
-        )
-    }
 
-    fn internal_update_index_to_id(&mut self, sending_id: UniqueID, gate_input_index: usize, signal: Signal) {
-        self.complex_gate.internal_update_index_to_id(sending_id, gate_input_index, signal);
+    //The stepper moves to the next step when CLK goes LOW, CLKE is only LOW for that same tick.
+    fn step_reset_and_connect(
+        &mut self,
+        output_gates: &[SharedMutex<dyn LogicGate>],
+    ) {
+        let step_reset_index = self.get_index_from_tag(ControlSection::STEP_RESET);
+        connect_gates(
+            self.step_reset_and.clone(),
+            0,
+            output_gates[step_reset_index].clone(),
+            0,
+        );
+
+        //The instruction that just completed is still inside the IR when the stepper wraps, so this
+        // is where the extended latch decides if the next instruction is extended.
+        let enable_index = self.extended_latch.lock().unwrap().get_index_from_tag("E");
+        connect_gates(
+            self.step_reset_and.clone(),
+            1,
+            self.extended_latch.clone(),
+            enable_index,
+        );
+    }
+
+    fn step_reset_not_connect(&mut self) {
+        connect_gates(
+            self.step_reset_not.clone(),
+            0,
+            self.step_reset_and.clone(),
+            1,
+        );
+    }
+
+    fn prefix_and_connect(&mut self) {
+        let set_index = self.extended_latch.lock().unwrap().get_index_from_tag("S");
+        connect_gates(
+            self.prefix_and.clone(),
+            0,
+            self.extended_latch.clone(),
+            set_index,
+        );
+
+        connect_gates(
+            self.prefix_and.clone(),
+            1,
+            self.prefix_not.clone(),
+            0,
+        );
+    }
+
+    fn prefix_and_not_loc_6_connect(&mut self) {
+        connect_gates(
+            self.prefix_and_not_loc_6.clone(),
+            0,
+            self.prefix_and.clone(),
+            6,
+        );
+    }
+
+    fn prefix_and_not_loc_7_connect(&mut self) {
+        connect_gates(
+            self.prefix_and_not_loc_7.clone(),
+            0,
+            self.prefix_and.clone(),
+            7,
+        );
+    }
+
+    //The prefix itself does nothing during steps 4-6.
+    fn prefix_not_connect(&mut self) {
+        connect_gates(
+            self.prefix_not.clone(),
+            0,
+            self.base_step_4_and.clone(),
+            2,
+        );
+
+        connect_gates(
+            self.prefix_not.clone(),
+            1,
+            self.base_step_5_and.clone(),
+            2,
+        );
+
+        connect_gates(
+            self.prefix_not.clone(),
+            2,
+            self.base_step_6_and.clone(),
+            2,
+        );
+    }
+
+    fn extended_latch_connect(&mut self) {
+        connect_gates(
+            self.extended_latch.clone(),
+            0,
+            self.extended_not.clone(),
+            0,
+        );
+
+        connect_gates(
+            self.extended_latch.clone(),
+            1,
+            self.extended_move_and.clone(),
+            1,
+        );
+
+        connect_gates(
+            self.extended_latch.clone(),
+            2,
+            self.extended_swap_and.clone(),
+            0,
+        );
+
+        connect_gates(
+            self.extended_latch.clone(),
+            3,
+            self.extended_ldi_and.clone(),
+            0,
+        );
+
+        connect_gates(
+            self.extended_latch.clone(),
+            4,
+            self.c_out_and.clone(),
+            1,
+        );
+    }
+
+    fn extended_not_connect(&mut self) {
+        connect_gates(
+            self.extended_not.clone(),
+            0,
+            self.extended_alu_or.clone(),
+            0,
+        );
+    }
+
+    //An extended instruction does not use the base instruction table during steps 4-6 unless it is
+    // an ALU instruction (IR_7 HIGH). Extended ALU instructions run the same steps as the base ones.
+    fn extended_alu_or_connect(&mut self) {
+        connect_gates(
+            self.extended_alu_or.clone(),
+            0,
+            self.base_step_4_and.clone(),
+            1,
+        );
+
+        connect_gates(
+            self.extended_alu_or.clone(),
+            1,
+            self.base_step_5_and.clone(),
+            1,
+        );
+
+        connect_gates(
+            self.extended_alu_or.clone(),
+            2,
+            self.base_step_6_and.clone(),
+            1,
+        );
+    }
+
+    //Only an extended ALU instruction takes the stored carry flag as its carry in.
+    fn c_out_and_connect(
+        &mut self,
+        output_gates: &[SharedMutex<dyn LogicGate>],
+    ) {
+        let c_out_index = self.get_index_from_tag(ControlSection::C_OUT);
+        connect_gates(
+            self.c_out_and.clone(),
+            0,
+            output_gates[c_out_index].clone(),
+            0,
+        );
+    }
+
+    fn stepper_4_splitter_connect(&mut self) {
+        connect_gates(
+            self.stepper_4_splitter.clone(),
+            0,
+            self.base_step_4_and.clone(),
+            0,
+        );
+
+        connect_gates(
+            self.stepper_4_splitter.clone(),
+            1,
+            self.extended_move_and.clone(),
+            0,
+        );
+
+        connect_gates(
+            self.stepper_4_splitter.clone(),
+            2,
+            self.extended_swap_step_4_and.clone(),
+            0,
+        );
+
+        connect_gates(
+            self.stepper_4_splitter.clone(),
+            3,
+            self.extended_ldi_step_4_and.clone(),
+            0,
+        );
+    }
+
+    fn stepper_5_splitter_connect(&mut self) {
+        connect_gates(
+            self.stepper_5_splitter.clone(),
+            0,
+            self.base_step_5_and.clone(),
+            0,
+        );
+
+        connect_gates(
+            self.stepper_5_splitter.clone(),
+            1,
+            self.extended_swap_step_5_and.clone(),
+            0,
+        );
+
+        connect_gates(
+            self.stepper_5_splitter.clone(),
+            2,
+            self.extended_ldi_step_5_and.clone(),
+            0,
+        );
+    }
+
+    fn stepper_6_splitter_connect(&mut self) {
+        connect_gates(
+            self.stepper_6_splitter.clone(),
+            0,
+            self.base_step_6_and.clone(),
+            0,
+        );
+
+        connect_gates(
+            self.stepper_6_splitter.clone(),
+            1,
+            self.extended_swap_step_6_and.clone(),
+            0,
+        );
+
+        connect_gates(
+            self.stepper_6_splitter.clone(),
+            2,
+            self.extended_ldi_step_6_and.clone(),
+            0,
+        );
+    }
+
+    fn base_step_and_connect(&mut self) {
+        connect_gates(
+            self.base_step_4_and.clone(),
+            0,
+            self.stepper_splitters[3].clone(),
+            0,
+        );
+
+        connect_gates(
+            self.base_step_5_and.clone(),
+            0,
+            self.stepper_splitters[4].clone(),
+            0,
+        );
+
+        connect_gates(
+            self.base_step_6_and.clone(),
+            0,
+            self.stepper_splitters[5].clone(),
+            0,
+        );
+    }
+
+    fn extended_opcode_or_connect(&mut self) {
+        connect_gates(
+            self.extended_opcode_or.clone(),
+            0,
+            self.extended_opcode_not.clone(),
+            0,
+        );
+    }
+
+    //HIGH when IR_5-IR_7 are LOW, IR_4 then picks between MOV and SWAP.
+    fn extended_opcode_not_connect(&mut self) {
+        connect_gates(
+            self.extended_opcode_not.clone(),
+            0,
+            self.extended_move_and.clone(),
+            2,
+        );
+
+        connect_gates(
+            self.extended_opcode_not.clone(),
+            1,
+            self.extended_swap_and.clone(),
+            2,
+        );
+    }
+
+    fn extended_move_not_connect(&mut self) {
+        connect_gates(
+            self.extended_move_not.clone(),
+            0,
+            self.extended_move_and.clone(),
+            3,
+        );
+    }
+
+    //MOV is extended opcode 0000. Step 4 enables reg_a and sets reg_b.
+    fn extended_move_and_connect(&mut self) {
+        connect_gates(
+            self.extended_move_and.clone(),
+            0,
+            self.reg_a_or.clone(),
+            3,
+        );
+
+        connect_gates(
+            self.extended_move_and.clone(),
+            1,
+            self.reg_b_s_or.clone(),
+            4,
+        );
+    }
+
+    fn extended_swap_and_connect(&mut self) {
+        connect_gates(
+            self.extended_swap_and.clone(),
+            0,
+            self.extended_swap_step_4_and.clone(),
+            1,
+        );
+
+        connect_gates(
+            self.extended_swap_and.clone(),
+            1,
+            self.extended_swap_step_5_and.clone(),
+            1,
+        );
+
+        connect_gates(
+            self.extended_swap_and.clone(),
+            2,
+            self.extended_swap_step_6_and.clone(),
+            1,
+        );
+    }
+
+    //SWAP is extended opcode 0001. Step 4 enables reg_a and sets TMP. ACC is set during the same
+    // step with the ALU doing AND, because A (the bus) and B (TMP) are both reg_a this leaves a
+    // copy of reg_a inside ACC.
+    fn extended_swap_step_4_and_connect(&mut self) {
+        connect_gates(
+            self.extended_swap_step_4_and.clone(),
+            0,
+            self.reg_a_or.clone(),
+            4,
+        );
+
+        connect_gates(
+            self.extended_swap_step_4_and.clone(),
+            1,
+            self.tmp_s_outer_or.clone(),
+            1,
+        );
+
+        connect_gates(
+            self.extended_swap_step_4_and.clone(),
+            2,
+            self.acc_s_outer_or.clone(),
+            4,
+        );
+
+        connect_gates(
+            self.extended_swap_step_4_and.clone(),
+            3,
+            self.alu_2_or.clone(),
+            1,
+        );
+    }
+
+    //Step 5 enables reg_b and sets reg_a.
+    fn extended_swap_step_5_and_connect(&mut self) {
+        connect_gates(
+            self.extended_swap_step_5_and.clone(),
+            0,
+            self.reg_b_e_or.clone(),
+            4,
+        );
+
+        connect_gates(
+            self.extended_swap_step_5_and.clone(),
+            1,
+            self.r0_s_reg_a_and.clone(),
+            1,
+        );
+
+        connect_gates(
+            self.extended_swap_step_5_and.clone(),
+            2,
+            self.r1_s_reg_a_and.clone(),
+            1,
+        );
+
+        connect_gates(
+            self.extended_swap_step_5_and.clone(),
+            3,
+            self.r2_s_reg_a_and.clone(),
+            1,
+        );
+
+        connect_gates(
+            self.extended_swap_step_5_and.clone(),
+            4,
+            self.r3_s_reg_a_and.clone(),
+            1,
+        );
+    }
+
+    //Step 6 enables ACC (the old reg_a) and sets reg_b.
+    fn extended_swap_step_6_and_connect(&mut self) {
+        connect_gates(
+            self.extended_swap_step_6_and.clone(),
+            0,
+            self.acc_e_or.clone(),
+            4,
+        );
+
+        connect_gates(
+            self.extended_swap_step_6_and.clone(),
+            1,
+            self.reg_b_s_or.clone(),
+            5,
+        );
+    }
+
+    fn extended_ldi_opcode_or_connect(&mut self) {
+        connect_gates(
+            self.extended_ldi_opcode_or.clone(),
+            0,
+            self.extended_ldi_opcode_not.clone(),
+            0,
+        );
+    }
+
+    //HIGH when IR_4, IR_6 and IR_7 are LOW, IR_5 is checked by extended_ldi_and.
+    fn extended_ldi_opcode_not_connect(&mut self) {
+        connect_gates(
+            self.extended_ldi_opcode_not.clone(),
+            0,
+            self.extended_ldi_and.clone(),
+            2,
+        );
+    }
+
+    fn extended_ldi_and_connect(&mut self) {
+        connect_gates(
+            self.extended_ldi_and.clone(),
+            0,
+            self.extended_ldi_step_4_and.clone(),
+            1,
+        );
+
+        connect_gates(
+            self.extended_ldi_and.clone(),
+            1,
+            self.extended_ldi_step_5_and.clone(),
+            1,
+        );
+
+        connect_gates(
+            self.extended_ldi_and.clone(),
+            2,
+            self.extended_ldi_step_6_and.clone(),
+            1,
+        );
+    }
+
+    //LDI is extended opcode 0010. The RAM can only be read from the address inside MAR, so the
+    // immediate is fetched the same way DATA fetches it. Step 4 sends IAR (which already points at
+    // the immediate) to MAR and IAR + 1 into ACC.
+    fn extended_ldi_step_4_and_connect(&mut self) {
+        connect_gates(
+            self.extended_ldi_step_4_and.clone(),
+            0,
+            self.iar_e_or.clone(),
+            4,
+        );
+
+        connect_gates(
+            self.extended_ldi_step_4_and.clone(),
+            1,
+            self.mar_s_outer_or.clone(),
+            6,
+        );
+
+        connect_gates(
+            self.extended_ldi_step_4_and.clone(),
+            2,
+            self.bus_1_or.clone(),
+            4,
+        );
+
+        connect_gates(
+            self.extended_ldi_step_4_and.clone(),
+            3,
+            self.acc_s_outer_or.clone(),
+            5,
+        );
+    }
+
+    //Step 5 enables RAM (the immediate) and sets reg_b.
+    fn extended_ldi_step_5_and_connect(&mut self) {
+        connect_gates(
+            self.extended_ldi_step_5_and.clone(),
+            0,
+            self.ram_e_or.clone(),
+            5,
+        );
+
+        connect_gates(
+            self.extended_ldi_step_5_and.clone(),
+            1,
+            self.reg_b_s_or.clone(),
+            6,
+        );
+    }
+
+    //Step 6 moves IAR past the immediate.
+    fn extended_ldi_step_6_and_connect(&mut self) {
+        connect_gates(
+            self.extended_ldi_step_6_and.clone(),
+            0,
+            self.acc_e_or.clone(),
+            5,
+        );
+
+        connect_gates(
+            self.extended_ldi_step_6_and.clone(),
+            1,
+            self.iar_s_outer_or.clone(),
+            6,
+        );
+    }
+
+    //Returns the index of the active stepper output (0 is step 1).
+    pub fn get_current_step(&self) -> Option<usize> {
+        let stepper = self.stepper.lock().unwrap();
+        let step_signals = extract_signals_from_gate_output_states(
+            &stepper.complex_gate.simple_gate.output_states
+        );
+
+        step_signals.iter().position(|signal| *signal == HIGH)
+    }
+
+    //Each element of control_outputs is every output of the control section for a single clock
+    // tick. Panics if more than one of the enable_tags outputs is HIGH during the same tick.
+    pub fn assert_bus_exclusivity(&self, enable_tags: &[&str], control_outputs: &[Vec<Signal>]) {
+        let enable_indices: Vec<(&str, usize)> = enable_tags
+            .iter()
+            .map(|&tag| (tag, self.get_index_from_tag(tag)))
+            .collect();
+
+        for (clock_tick, output) in control_outputs.iter().enumerate() {
+            let high_tags: Vec<&str> = enable_indices
+                .iter()
+                .filter(|(_, idx)| output[*idx] == HIGH)
+                .map(|(tag, _)| *tag)
+                .collect();
+
+            if high_tags.len() > 1 {
+                panic!("Multiple outputs enabled onto the bus during clock tick {}: {:?}", clock_tick, high_tags);
+            }
+        }
+    }
+}
+
+impl LogicGate for ControlSection {
+    fn internal_connect_output(&mut self, current_gate_output_key: usize, next_gate_input_key: usize, next_gate: SharedMutex<dyn LogicGate>) -> Signal {
+        self.complex_gate.connect_output(
+            self.get_unique_id(),
+            current_gate_output_key,
+            next_gate_input_key,
+            next_gate,
+        )
+    }
+
+    fn internal_update_index_to_id(&mut self, sending_id: UniqueID, gate_input_index: usize, signal: Signal) {
+        self.complex_gate.internal_update_index_to_id(sending_id, gate_input_index, signal);
     }
 
     fn update_input_signal(&mut self, input: GateInput) -> InputSignalReturn {
@@ -3206,6 +4283,14 @@ impl LogicGate for ControlSection {
         self.complex_gate.get_index_from_tag(tag)
     }
 
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         self.complex_gate.remove_connected_input(input_index, connected_id);
     }
@@ -3218,9 +4303,152 @@ impl LogicGate for ControlSection {
         self.complex_gate.simple_gate.number_child_gates
     }
 
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         self.complex_gate.input_gates.clone()
     }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
+}
+
+//The control outputs that are HIGH at any point during one stepper step of an instruction. Step 1
+// is the first step of the fetch.
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct MicroStep {
+    pub opcode: usize,
+    pub step: usize,
+    pub asserted_outputs: Vec<String>,
+}
+
+//Runs every opcode through all six stepper steps and records the control outputs asserted on each
+// step. Only IR_0 to IR_7 are decoded so those are the bits of the opcode, IR_0 is the least
+// significant bit. The flag inputs are held LOW so conditional jumps are recorded as not taken.
+// The control section must be newly built, it is left connected to the inputs used here.
+#[allow(dead_code)]
+pub fn extract_microcode(control_section: &SharedMutex<ControlSection>) -> Vec<MicroStep> {
+    let opcodes: Vec<usize> = (0..1 << 8).collect();
+    extract_microcode_for_opcodes(control_section, &opcodes)
+}
+
+//Same as extract_microcode() except only the opcodes passed in are run, in the order passed in.
+// Every opcode takes 24 clock ticks so running all of them is slow.
+#[allow(dead_code)]
+pub fn extract_microcode_for_opcodes(control_section: &SharedMutex<ControlSection>, opcodes: &[usize]) -> Vec<MicroStep> {
+    const NUM_STEPS: usize = 6;
+    const OPCODE_BITS: usize = 8;
+
+    assert_ne!(opcodes.len(), 0);
+
+    let ticks_per_instruction = NUM_STEPS * 4;
+    let num_ticks = opcodes.len() * ticks_per_instruction;
+
+    //Each step is a full clock cycle of four ticks.
+    let repeat_cycle = |cycle: [Signal; 4]| -> Vec<Signal> {
+        cycle.iter().cycle().take(num_ticks).cloned().collect()
+    };
+
+    let mut tagged_inputs = vec![
+        (ControlSection::CLOCK.to_string(), repeat_cycle([LOW_, HIGH, HIGH, LOW_])),
+        (ControlSection::CLOCK_ENABLE.to_string(), repeat_cycle([HIGH, HIGH, HIGH, LOW_])),
+        (ControlSection::CLOCK_SET.to_string(), repeat_cycle([LOW_, HIGH, LOW_, LOW_])),
+    ];
+
+    for bit in 0..OPCODE_BITS {
+        let ir_signals = (0..num_ticks)
+            .map(|tick| {
+                let opcode = opcodes[tick / ticks_per_instruction];
+                if (opcode >> bit) & 1 == 1 { HIGH } else { LOW_ }
+            })
+            .collect();
+
+        tagged_inputs.push((format!("IR_{}", bit), ir_signals));
+    }
+
+    let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+    for (tag, signals) in tagged_inputs.into_iter() {
+        let input_gate = AutomaticInput::new(signals, 1, tag.as_str());
+        let input_index = control_section.lock().unwrap().get_index_from_tag(tag.as_str());
+
+        connect_gates(
+            input_gate.clone(),
+            0,
+            control_section.clone(),
+            input_index,
+        );
+
+        input_gates.push(input_gate);
+    }
+
+    let output_tags = control_section.lock().unwrap().get_tags(GateTagType::Output);
+    let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
+    for (i, tag) in output_tags.iter().enumerate() {
+        let output_gate = SimpleOutput::new(tag.as_str());
+
+        connect_gates(
+            control_section.clone(),
+            i,
+            output_gate.clone(),
+            0,
+        );
+
+        output_gates.push(output_gate);
+    }
+
+    let mut collected_outputs: Vec<Vec<Signal>> = Vec::new();
+    start_clock(
+        &input_gates,
+        &output_gates,
+        |_clock_tick_inputs, output_gates: &Vec<SharedMutex<dyn LogicGateAndOutputGate>>| {
+            let signals = output_gates
+                .iter()
+                .map(|output_gate| {
+                    let output = output_gate.lock().unwrap().fetch_output_signals_calculate().unwrap();
+                    extract_signals_from_gate_output_states(&output)[0].clone()
+                })
+                .collect();
+
+            collected_outputs.push(signals);
+        },
+    );
+
+    assert_eq!(collected_outputs.len(), num_ticks);
+
+    collected_outputs
+        .chunks(4)
+        .enumerate()
+        .map(|(step_number, step_outputs)| {
+            let asserted_outputs = output_tags
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| step_outputs.iter().any(|tick_output| tick_output[*i] == HIGH))
+                .map(|(_, tag)| tag.clone())
+                .collect();
+
+            MicroStep {
+                opcode: opcodes[step_number / NUM_STEPS],
+                step: step_number % NUM_STEPS + 1,
+                asserted_outputs,
+            }
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -3229,17 +4457,99 @@ mod tests {
     use crate::logic::foundations::Signal::{HIGH, LOW_};
     use crate::logic::input_gates::AutomaticInput;
     use crate::run_circuit::run_circuit;
-    use crate::test_stuff::{extract_output_tags_sorted_by_index, run_multi_input_output_logic_gate_return};
+    use crate::test_stuff::{build_clock_schedule, collect_outputs_from_output_gates, extract_output_tags_sorted_by_index, run_multi_input_output_logic_gate_return};
     use super::*;
 
-    //This uses a 4 cycle clock, so the number of clock cycles it will advance will be
-    // 4 * advance_to_splitter_num.
-    fn test_control_section(
-        output_signals_map: HashMap<&str, Vec<Signal>>,
-        input_signals_map: HashMap<&str, Vec<Vec<Signal>>>,
+    //This uses a 4 cycle clock, so the number of clock cycles it will advance will be
+    // 4 * advance_to_splitter_num. The control outputs do not depend on bus_width, it only adds
+    // extra IR inputs.
+    fn test_control_section(
+        bus_width: usize,
+        output_signals_map: HashMap<&str, Vec<Signal>>,
+        input_signals_map: HashMap<&str, Vec<Vec<Signal>>>,
+        start_at_splitter_num: usize,
+    ) {
+        assert_ne!(output_signals_map.len(), 0);
+
+        let length = output_signals_map.iter().next().unwrap().1.len();
+        for (_tag, v) in output_signals_map.iter() {
+            assert_eq!(v.len(), length);
+        }
+
+        let (control_section, collected_output) = run_control_section(
+            bus_width,
+            input_signals_map,
+            start_at_splitter_num,
+            length,
+        );
+
+        let mut output_signals = Vec::new();
+
+        let mut current_idx = 0;
+        while current_idx < length {
+            let mut output = vec![LOW_; control_section.lock().unwrap().complex_gate.output_gates.len()];
+            for (tag, v) in output_signals_map.iter() {
+                let idx = control_section.lock().unwrap().get_index_from_tag(tag);
+                output[idx] = v[current_idx].clone();
+            }
+            current_idx += 1;
+            output_signals.push(output);
+        }
+
+        assert_eq!(output_signals.len(), collected_output.len());
+
+        println!("Ran for {} clock ticks", output_signals.len());
+        let tags_sorted_by_index = extract_output_tags_sorted_by_index(&control_section.lock().unwrap().complex_gate);
+
+        let mut failed = false;
+        for i in 0..output_signals.len() {
+            let mut failed_map = HashMap::new();
+
+            for j in 0..output_signals[i].len() {
+                if (tags_sorted_by_index[j].clone(), output_signals[i][j].clone()) != (tags_sorted_by_index[j].clone(), collected_output[i][j].clone()) {
+                    failed_map.insert(tags_sorted_by_index[j].clone(), (output_signals[i][j].clone(), collected_output[i][j].clone()));
+                    failed = true;
+                };
+            }
+
+            if !failed_map.is_empty() {
+                println!("Clock tick {}\nfailed (passed, collected): {:?}", i, failed_map);
+            }
+        }
+
+        assert!(!failed);
+    }
+
+    //Advances a new ControlSection to start_at_splitter_num then runs the inputs and returns the
+    // output collected on each clock tick.
+    fn run_control_section(
+        bus_width: usize,
+        input_signals_map: HashMap<&str, Vec<Vec<Signal>>>,
+        start_at_splitter_num: usize,
+        number_clock_ticks: usize,
+    ) -> (SharedMutex<ControlSection>, Vec<Vec<Signal>>) {
+        let control_section = advance_control_section(bus_width, start_at_splitter_num);
+
+        println!("Running inputs.");
+        //Only the size of the output is used when collecting.
+        let number_outputs = control_section.lock().unwrap().complex_gate.output_gates.len();
+        let output_signals = vec![vec![LOW_; number_outputs]; number_clock_ticks];
+
+        let collected_output = run_multi_input_output_logic_gate_return(
+            vec![],
+            &output_signals,
+            input_signals_map,
+            control_section.clone(),
+        );
+
+        (control_section, collected_output)
+    }
+
+    //Returns a new ControlSection advanced to start_at_splitter_num with its clock disconnected.
+    fn advance_control_section(
+        bus_width: usize,
         start_at_splitter_num: usize,
-    ) {
-        assert_ne!(output_signals_map.len(), 0);
+    ) -> SharedMutex<ControlSection> {
         //Splitters only go [1-6].
         assert!(start_at_splitter_num > 0);
         assert!(start_at_splitter_num < 7);
@@ -3248,24 +4558,7 @@ mod tests {
         // The outer minus 1 is to get to the spot right before the splitter is initialized.
         let clock_ticks_to_advance: i32 = ((start_at_splitter_num as i32 - 1) * 4) - 1;
 
-        let length = output_signals_map.iter().next().unwrap().1.len();
-        for (_tag, v) in output_signals_map.iter() {
-            assert_eq!(v.len(), length);
-        }
-
-        let control_section = ControlSection::new(8);
-        let mut output_signals = Vec::new();
-
-        let mut current_idx = 0;
-        while current_idx < length {
-            let mut output = vec![LOW_; control_section.lock().unwrap().complex_gate.output_gates.len()];
-            for (tag, v) in output_signals_map.iter() {
-                let idx = control_section.lock().unwrap().get_index_from_tag(tag);
-                output[idx] = v[current_idx].clone();
-            }
-            current_idx += 1;
-            output_signals.push(output);
-        }
+        let control_section = ControlSection::new(bus_width);
 
         if clock_ticks_to_advance > 1 {
             //The splitter changes on the negative edge, so transitioning from HIGH->LOW.
@@ -3312,37 +4605,9 @@ mod tests {
             );
         }
 
-        println!("Running inputs.");
-        let collected_output = run_multi_input_output_logic_gate_return(
-            vec![],
-            &output_signals,
-            input_signals_map,
-            control_section.clone(),
-        );
-
-        assert_eq!(output_signals.len(), collected_output.len());
-
         println!("Advanced for {} clock ticks", clock_ticks_to_advance);
-        println!("Ran for {} clock ticks", output_signals.len());
-        let tags_sorted_by_index = extract_output_tags_sorted_by_index(&control_section.lock().unwrap().complex_gate);
-
-        let mut failed = false;
-        for i in 0..output_signals.len() {
-            let mut failed_map = HashMap::new();
-
-            for j in 0..output_signals[i].len() {
-                if (tags_sorted_by_index[j].clone(), output_signals[i][j].clone()) != (tags_sorted_by_index[j].clone(), collected_output[i][j].clone()) {
-                    failed_map.insert(tags_sorted_by_index[j].clone(), (output_signals[i][j].clone(), collected_output[i][j].clone()));
-                    failed = true;
-                };
-            }
 
-            if !failed_map.is_empty() {
-                println!("Clock tick {}\nfailed (passed, collected): {:?}", i, failed_map);
-            }
-        }
-
-        assert!(!failed);
+        control_section
     }
 
     struct ClockTickRounds {
@@ -3352,31 +4617,12 @@ mod tests {
     }
 
     fn get_clock_cycles(num_cycles: usize) -> ClockTickRounds {
-        let mut clock_vec = Vec::new();
-        let mut clock_enable_vec = Vec::new();
-        let mut clock_set_vec = Vec::new();
-
-        for _ in 0..num_cycles {
-            clock_vec.push(vec![LOW_]);
-            clock_vec.push(vec![HIGH]);
-            clock_vec.push(vec![HIGH]);
-            clock_vec.push(vec![LOW_]);
-
-            clock_enable_vec.push(vec![HIGH]);
-            clock_enable_vec.push(vec![HIGH]);
-            clock_enable_vec.push(vec![HIGH]);
-            clock_enable_vec.push(vec![LOW_]);
-
-            clock_set_vec.push(vec![LOW_]);
-            clock_set_vec.push(vec![HIGH]);
-            clock_set_vec.push(vec![LOW_]);
-            clock_set_vec.push(vec![LOW_]);
-        }
+        let schedule = build_clock_schedule(num_cycles, 1);
 
         ClockTickRounds {
-            clock: (ControlSection::CLOCK, clock_vec),
-            clock_enable: (ControlSection::CLOCK_ENABLE, clock_enable_vec),
-            clock_set: (ControlSection::CLOCK_SET, clock_set_vec),
+            clock: (ControlSection::CLOCK, schedule.clock),
+            clock_enable: (ControlSection::CLOCK_ENABLE, schedule.clock_enable),
+            clock_set: (ControlSection::CLOCK_SET, schedule.clock_set),
         }
     }
 
@@ -3390,6 +4636,8 @@ mod tests {
 
         let mut generated_output = vec![LOW_; tags_sorted_by_index.len()];
         generated_output[0] = HIGH; //Set BUS_1 high
+        //The stepper starts at step 1.
+        generated_output[control_section.lock().unwrap().get_index_from_tag(ControlSection::STEP_RESET)] = HIGH;
 
         let collected_output: Vec<Signal> = collected_output.into_iter().map(
             |out| {
@@ -3411,10 +4659,10 @@ mod tests {
         return_vec
     }
 
-    #[test]
-    fn control_section_fetch_instructions() {
+    fn fetch_instructions_with_bus_width(bus_width: usize) {
         let clock_tick_rounds = get_clock_cycles(3);
         test_control_section(
+            bus_width,
             HashMap::from(
                 [
                     (ControlSection::BUS_1, vec![HIGH, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
@@ -3438,16 +4686,49 @@ mod tests {
         );
     }
 
+    #[test]
+    fn control_section_fetch_instructions() {
+        fetch_instructions_with_bus_width(8);
+    }
+
+    #[test]
+    fn control_section_fetch_instructions_wide_bus() {
+        fetch_instructions_with_bus_width(16);
+
+        //Only the IR width changes with the bus, so both widths must produce identical control
+        // outputs on every clock tick.
+        let number_clock_ticks = 12;
+        let run_fetch = |bus_width: usize| {
+            let clock_tick_rounds = get_clock_cycles(3);
+            let (_control_section, collected_output) = run_control_section(
+                bus_width,
+                HashMap::from(
+                    [
+                        clock_tick_rounds.clock,
+                        clock_tick_rounds.clock_set,
+                        clock_tick_rounds.clock_enable
+                    ]
+                ),
+                1,
+                number_clock_ticks,
+            );
+            collected_output
+        };
+
+        assert_eq!(run_fetch(8), run_fetch(16));
+    }
+
     #[test]
     fn control_section_add() {
         let clock_tick_rounds = get_clock_cycles(3);
         test_control_section(
+            8,
             HashMap::from(
                 [
                     (ControlSection::BUS_1, vec![LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, HIGH]),
+                    (ControlSection::STEP_RESET, vec![LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, HIGH]),
                     (ControlSection::TMP_S, vec![LOW_, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::R2_E, vec![HIGH, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
-                    (ControlSection::C_OUT, vec![LOW_, LOW_, LOW_, HIGH, HIGH, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::IO, vec![HIGH, HIGH, HIGH, HIGH, HIGH, HIGH, HIGH, HIGH, HIGH, HIGH, HIGH, HIGH]),
                     (ControlSection::R1_E, vec![LOW_, LOW_, LOW_, LOW_, HIGH, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::ACC_S, vec![LOW_, LOW_, LOW_, LOW_, LOW_, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
@@ -3471,16 +4752,349 @@ mod tests {
         );
     }
 
+    //The cpu latches the carry flag into c_tmp on TMP_S and ANDs it with C_OUT to drive the ALU
+    // C_IN, see VariableBitCPU. This wires the same latch to a ControlSection running two
+    // instructions, first_ir then an ADD, and returns the ALU C_IN on each clock tick.
+    fn run_add_alu_carry_in(first_ir: [Signal; 8], carry_flag: Signal) -> Vec<Signal> {
+        let number_clock_ticks = 2 * 6 * 4;
+        let control_section = advance_control_section(8, 1);
+
+        let c_tmp = OneBitMemoryCell::new(1);
+        let c_tmp_and = And::new(2, 1);
+        let alu_c_in = SimpleOutput::new("ALU_C_IN");
+
+        let tmp_s_index = control_section.lock().unwrap().get_index_from_tag(ControlSection::TMP_S);
+        let c_tmp_e_index = c_tmp.lock().unwrap().get_index_from_tag("E");
+        connect_gates(
+            control_section.clone(),
+            tmp_s_index,
+            c_tmp.clone(),
+            c_tmp_e_index,
+        );
+
+        let c_out_index = control_section.lock().unwrap().get_index_from_tag(ControlSection::C_OUT);
+        connect_gates(
+            control_section.clone(),
+            c_out_index,
+            c_tmp_and.clone(),
+            0,
+        );
+
+        let c_tmp_q_index = c_tmp.lock().unwrap().get_index_from_tag("Q");
+        connect_gates(
+            c_tmp.clone(),
+            c_tmp_q_index,
+            c_tmp_and.clone(),
+            1,
+        );
+
+        connect_gates(
+            c_tmp_and.clone(),
+            0,
+            alu_c_in.clone(),
+            0,
+        );
+
+        let schedule = build_clock_schedule(12, 1);
+        let add_ir = [LOW_, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, HIGH]; // R1+R2=R2
+
+        //The IR changes when IR_S is set during step 2 of the second instruction.
+        let ir_set_tick = 29;
+
+        let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+        let mut connect_input = |signals: Vec<Signal>, gate: SharedMutex<dyn LogicGate>, input_index: usize| {
+            let input_gate = AutomaticInput::new(signals, 1, "Start");
+            connect_gates(
+                input_gate.clone(),
+                0,
+                gate,
+                input_index,
+            );
+            input_gates.push(input_gate);
+        };
+
+        for (tag, signals) in [
+            (ControlSection::CLOCK, &schedule.clock),
+            (ControlSection::CLOCK_ENABLE, &schedule.clock_enable),
+            (ControlSection::CLOCK_SET, &schedule.clock_set),
+        ] {
+            let input_index = control_section.lock().unwrap().get_index_from_tag(tag);
+            connect_input(signals.iter().map(|signal| signal[0].clone()).collect(), control_section.clone(), input_index);
+        }
+
+        for i in 0..8 {
+            let mut signals = vec![first_ir[i].clone(); ir_set_tick];
+            signals.append(&mut vec![add_ir[i].clone(); number_clock_ticks - ir_set_tick]);
+            let input_index = control_section.lock().unwrap().get_index_from_tag(format!("IR_{}", i).as_str());
+            connect_input(signals, control_section.clone(), input_index);
+        }
+
+        let c_tmp_s_index = c_tmp.lock().unwrap().get_index_from_tag("S");
+        connect_input(vec![carry_flag; number_clock_ticks], c_tmp.clone(), c_tmp_s_index);
+
+        let output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = vec![alu_c_in];
+        let mut collected_output = Vec::new();
+        let mut propagate_signal = true;
+        let mut continue_clock = true;
+        while continue_clock {
+            continue_clock = run_circuit(
+                &input_gates,
+                &output_gates,
+                propagate_signal,
+                &mut |_clock_tick_inputs, output_gates| {
+                    let mut single_collected_output = Vec::new();
+                    collect_outputs_from_output_gates(&output_gates, &mut single_collected_output);
+                    collected_output.push(single_collected_output[0].clone());
+                },
+            );
+            propagate_signal = false;
+        }
+
+        collected_output
+    }
+
+    #[test]
+    fn control_section_carry_flag_drives_alu_carry_in() {
+        let prefix = [HIGH, HIGH, HIGH, HIGH, HIGH, HIGH, LOW_, LOW_];
+        let clf = [LOW_, LOW_, LOW_, LOW_, LOW_, HIGH, HIGH, LOW_];
+
+        //ADC is the prefix followed by ADD. Its ALU step (step 5) is where C_OUT is HIGH.
+        let mut expected = vec![LOW_; 2 * 6 * 4];
+        for tick in 39..43 {
+            expected[tick] = HIGH;
+        }
+        assert_eq!(run_add_alu_carry_in(prefix.clone(), HIGH), expected);
+        assert_eq!(run_add_alu_carry_in(prefix, LOW_), vec![LOW_; 2 * 6 * 4]);
+
+        //A plain ADD never takes in the carry flag.
+        assert_eq!(run_add_alu_carry_in(clf, HIGH), vec![LOW_; 2 * 6 * 4]);
+    }
+
+    #[test]
+    fn control_section_step_reset() {
+        //Every step is 4 clock ticks, run all 6 steps of the instruction.
+        let number_clock_ticks = 6 * 4;
+        let clock_tick_rounds = get_clock_cycles(6);
+
+        let (control_section, collected_output) = run_control_section(
+            8,
+            HashMap::from(
+                [
+                    clock_tick_rounds.clock,
+                    clock_tick_rounds.clock_enable,
+                    clock_tick_rounds.clock_set,
+                    ("IR", copy_input_n_times(
+                        vec![LOW_, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, HIGH], // R1+R2=R2
+                        number_clock_ticks,
+                    ))
+                ]
+            ),
+            1,
+            number_clock_ticks,
+        );
+
+        let step_reset_index = control_section.lock().unwrap().get_index_from_tag(ControlSection::STEP_RESET);
+        let step_reset: Vec<Signal> = collected_output
+            .iter()
+            .map(|output| output[step_reset_index].clone())
+            .collect();
+
+        //Only the final tick, the stepper wraps back to step 1 as the instruction completes.
+        let mut expected_step_reset = vec![LOW_; number_clock_ticks];
+        expected_step_reset[number_clock_ticks - 1] = HIGH;
+
+        assert_eq!(step_reset, expected_step_reset);
+        assert_eq!(control_section.lock().unwrap().get_current_step(), Some(0));
+    }
+
+    #[test]
+    fn control_section_extended_move() {
+        //Two full instructions, the prefix then MOV R1 R2 from the extended table.
+        let number_clock_ticks = 2 * 6 * 4;
+        let clock_tick_rounds = get_clock_cycles(12);
+
+        let high_at = |ticks: &[usize]| {
+            let mut signals = vec![LOW_; number_clock_ticks];
+            for tick in ticks {
+                signals[*tick] = HIGH;
+            }
+            signals
+        };
+
+        //The IR changes when IR_S is set during step 2 of the second instruction, the same as it
+        // would inside the cpu.
+        let ir_set_tick = 29;
+        let mut ir_input = copy_input_n_times(
+            vec![HIGH, HIGH, HIGH, HIGH, HIGH, HIGH, LOW_, LOW_], // Prefix
+            ir_set_tick,
+        );
+        ir_input.append(&mut copy_input_n_times(
+            vec![LOW_, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_], // MOV R1 R2
+            number_clock_ticks - ir_set_tick,
+        ));
+
+        test_control_section(
+            8,
+            HashMap::from(
+                [
+                    (ControlSection::BUS_1, high_at(&[0, 1, 2, 23, 24, 25, 26, 47])),
+                    (ControlSection::IAR_E, high_at(&[0, 1, 2, 24, 25, 26])),
+                    (ControlSection::MAR_S, high_at(&[1, 25])),
+                    (ControlSection::ACC_S, high_at(&[1, 25])),
+                    (ControlSection::RAM_E, high_at(&[4, 5, 6, 28, 29, 30])),
+                    (ControlSection::IR_S, high_at(&[5, 29])),
+                    (ControlSection::ACC_E, high_at(&[8, 9, 10, 32, 33, 34])),
+                    (ControlSection::IAR_S, high_at(&[9, 33])),
+                    (ControlSection::IO, vec![HIGH; number_clock_ticks]),
+                    (ControlSection::DA, high_at(&(0..ir_set_tick).collect::<Vec<usize>>())),
+                    (ControlSection::STEP_RESET, high_at(&[23, 47])),
+                    //Step 4 of MOV, nothing else is set during steps 4-6 of either instruction.
+                    (ControlSection::R1_E, high_at(&[36, 37, 38])),
+                    (ControlSection::R2_S, high_at(&[37])),
+                ]
+            ),
+            HashMap::from(
+                [
+                    clock_tick_rounds.clock,
+                    clock_tick_rounds.clock_enable,
+                    clock_tick_rounds.clock_set,
+                    ("IR", ir_input),
+                ]
+            ),
+            1,
+        );
+    }
+
+    #[test]
+    fn control_section_extended_swap() {
+        //Two full instructions, the prefix then SWAP R1 R2 from the extended table.
+        let number_clock_ticks = 2 * 6 * 4;
+        let clock_tick_rounds = get_clock_cycles(12);
+
+        let high_at = |ticks: &[usize]| {
+            let mut signals = vec![LOW_; number_clock_ticks];
+            for tick in ticks {
+                signals[*tick] = HIGH;
+            }
+            signals
+        };
+
+        let ir_set_tick = 29;
+        let mut ir_input = copy_input_n_times(
+            vec![HIGH, HIGH, HIGH, HIGH, HIGH, HIGH, LOW_, LOW_], // Prefix
+            ir_set_tick,
+        );
+        ir_input.append(&mut copy_input_n_times(
+            vec![LOW_, HIGH, HIGH, LOW_, HIGH, LOW_, LOW_, LOW_], // SWAP R1 R2
+            number_clock_ticks - ir_set_tick,
+        ));
+
+        test_control_section(
+            8,
+            HashMap::from(
+                [
+                    (ControlSection::BUS_1, high_at(&[0, 1, 2, 23, 24, 25, 26, 47])),
+                    (ControlSection::IAR_E, high_at(&[0, 1, 2, 24, 25, 26])),
+                    (ControlSection::MAR_S, high_at(&[1, 25])),
+                    (ControlSection::RAM_E, high_at(&[4, 5, 6, 28, 29, 30])),
+                    (ControlSection::IR_S, high_at(&[5, 29])),
+                    (ControlSection::IAR_S, high_at(&[9, 33])),
+                    (ControlSection::IO, vec![HIGH; number_clock_ticks]),
+                    (ControlSection::DA, high_at(&(0..ir_set_tick).collect::<Vec<usize>>())),
+                    (ControlSection::STEP_RESET, high_at(&[23, 47])),
+                    //Step 4, R1 is parked inside TMP and ANDed with itself into ACC.
+                    (ControlSection::R1_E, high_at(&[36, 37, 38])),
+                    (ControlSection::TMP_S, high_at(&[37])),
+                    (ControlSection::ACC_S, high_at(&[1, 25, 37])),
+                    (ControlSection::ALU_2, high_at(&[35, 36, 37, 38])),
+                    //Step 5, R2 is copied into R1.
+                    (ControlSection::R2_E, high_at(&[40, 41, 42])),
+                    (ControlSection::R1_S, high_at(&[41])),
+                    //Step 6, ACC (the old R1) is copied into R2.
+                    (ControlSection::ACC_E, high_at(&[8, 9, 10, 32, 33, 34, 44, 45, 46])),
+                    (ControlSection::R2_S, high_at(&[45])),
+                ]
+            ),
+            HashMap::from(
+                [
+                    clock_tick_rounds.clock,
+                    clock_tick_rounds.clock_enable,
+                    clock_tick_rounds.clock_set,
+                    ("IR", ir_input),
+                ]
+            ),
+            1,
+        );
+    }
+
+    #[test]
+    fn control_section_extended_ldi() {
+        //Two full instructions, the prefix then LDI R2 from the extended table.
+        let number_clock_ticks = 2 * 6 * 4;
+        let clock_tick_rounds = get_clock_cycles(12);
+
+        let high_at = |ticks: &[usize]| {
+            let mut signals = vec![LOW_; number_clock_ticks];
+            for tick in ticks {
+                signals[*tick] = HIGH;
+            }
+            signals
+        };
+
+        let ir_set_tick = 29;
+        let mut ir_input = copy_input_n_times(
+            vec![HIGH, HIGH, HIGH, HIGH, HIGH, HIGH, LOW_, LOW_], // Prefix
+            ir_set_tick,
+        );
+        ir_input.append(&mut copy_input_n_times(
+            vec![LOW_, HIGH, LOW_, LOW_, LOW_, HIGH, LOW_, LOW_], // LDI R2
+            number_clock_ticks - ir_set_tick,
+        ));
+
+        test_control_section(
+            8,
+            HashMap::from(
+                [
+                    (ControlSection::IR_S, high_at(&[5, 29])),
+                    (ControlSection::IO, high_at(&(0..ir_set_tick).collect::<Vec<usize>>())),
+                    (ControlSection::DA, high_at(&(0..ir_set_tick).collect::<Vec<usize>>())),
+                    (ControlSection::STEP_RESET, high_at(&[23, 47])),
+                    //Step 4, IAR points at the immediate, it is sent to MAR and IAR + 1 is set into ACC.
+                    (ControlSection::BUS_1, high_at(&[0, 1, 2, 23, 24, 25, 26, 35, 36, 37, 38, 47])),
+                    (ControlSection::IAR_E, high_at(&[0, 1, 2, 24, 25, 26, 36, 37, 38])),
+                    (ControlSection::MAR_S, high_at(&[1, 25, 37])),
+                    (ControlSection::ACC_S, high_at(&[1, 25, 37])),
+                    //Step 5, the immediate is read from RAM into R2.
+                    (ControlSection::RAM_E, high_at(&[4, 5, 6, 28, 29, 30, 40, 41, 42])),
+                    (ControlSection::R2_S, high_at(&[41])),
+                    //Step 6, IAR is moved past the immediate.
+                    (ControlSection::ACC_E, high_at(&[8, 9, 10, 32, 33, 34, 44, 45, 46])),
+                    (ControlSection::IAR_S, high_at(&[9, 33, 45])),
+                ]
+            ),
+            HashMap::from(
+                [
+                    clock_tick_rounds.clock,
+                    clock_tick_rounds.clock_enable,
+                    clock_tick_rounds.clock_set,
+                    ("IR", ir_input),
+                ]
+            ),
+            1,
+        );
+    }
+
     #[test]
     fn control_section_shift_right() {
         let clock_tick_rounds = get_clock_cycles(3);
         test_control_section(
+            8,
             HashMap::from(
                 [
                     (ControlSection::BUS_1, vec![LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, HIGH]),
+                    (ControlSection::STEP_RESET, vec![LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, HIGH]),
                     (ControlSection::TMP_S, vec![LOW_, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::R3_E, vec![HIGH, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
-                    (ControlSection::C_OUT, vec![LOW_, LOW_, LOW_, HIGH, HIGH, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::R0_E, vec![LOW_, LOW_, LOW_, LOW_, HIGH, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::ACC_S, vec![LOW_, LOW_, LOW_, LOW_, LOW_, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::FLAG_S, vec![LOW_, LOW_, LOW_, LOW_, LOW_, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
@@ -3508,12 +5122,13 @@ mod tests {
     fn control_section_shift_left() {
         let clock_tick_rounds = get_clock_cycles(3);
         test_control_section(
+            8,
             HashMap::from(
                 [
                     (ControlSection::BUS_1, vec![LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, HIGH]),
+                    (ControlSection::STEP_RESET, vec![LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, HIGH]),
                     (ControlSection::TMP_S, vec![LOW_, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::R0_E, vec![HIGH, HIGH, HIGH, LOW_, HIGH, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_]),
-                    (ControlSection::C_OUT, vec![LOW_, LOW_, LOW_, HIGH, HIGH, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::ACC_S, vec![LOW_, LOW_, LOW_, LOW_, LOW_, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::FLAG_S, vec![LOW_, LOW_, LOW_, LOW_, LOW_, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::ACC_E, vec![LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, HIGH, HIGH, HIGH, LOW_]),
@@ -3540,12 +5155,13 @@ mod tests {
     fn control_section_not() {
         let clock_tick_rounds = get_clock_cycles(3);
         test_control_section(
+            8,
             HashMap::from(
                 [
                     (ControlSection::BUS_1, vec![LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, HIGH]),
+                    (ControlSection::STEP_RESET, vec![LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, HIGH]),
                     (ControlSection::TMP_S, vec![LOW_, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::R1_E, vec![HIGH, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
-                    (ControlSection::C_OUT, vec![LOW_, LOW_, LOW_, HIGH, HIGH, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::R0_E, vec![LOW_, LOW_, LOW_, LOW_, HIGH, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::ACC_S, vec![LOW_, LOW_, LOW_, LOW_, LOW_, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::FLAG_S, vec![LOW_, LOW_, LOW_, LOW_, LOW_, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
@@ -3574,13 +5190,14 @@ mod tests {
     fn control_section_and() {
         let clock_tick_rounds = get_clock_cycles(3);
         test_control_section(
+            8,
             HashMap::from(
                 [
                     (ControlSection::BUS_1, vec![LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, HIGH]),
+                    (ControlSection::STEP_RESET, vec![LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, HIGH]),
                     (ControlSection::TMP_S, vec![LOW_, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::R0_E, vec![LOW_, LOW_, LOW_, LOW_, HIGH, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::R3_E, vec![HIGH, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
-                    (ControlSection::C_OUT, vec![LOW_, LOW_, LOW_, HIGH, HIGH, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::ACC_S, vec![LOW_, LOW_, LOW_, LOW_, LOW_, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::FLAG_S, vec![LOW_, LOW_, LOW_, LOW_, LOW_, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::ACC_E, vec![LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, HIGH, HIGH, HIGH, LOW_]),
@@ -3607,13 +5224,14 @@ mod tests {
     fn control_section_or() {
         let clock_tick_rounds = get_clock_cycles(3);
         test_control_section(
+            8,
             HashMap::from(
                 [
                     (ControlSection::BUS_1, vec![LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, HIGH]),
+                    (ControlSection::STEP_RESET, vec![LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, HIGH]),
                     (ControlSection::TMP_S, vec![LOW_, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::R0_E, vec![LOW_, LOW_, LOW_, LOW_, HIGH, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::R3_E, vec![HIGH, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
-                    (ControlSection::C_OUT, vec![LOW_, LOW_, LOW_, HIGH, HIGH, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::ACC_S, vec![LOW_, LOW_, LOW_, LOW_, LOW_, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::FLAG_S, vec![LOW_, LOW_, LOW_, LOW_, LOW_, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::ACC_E, vec![LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, HIGH, HIGH, HIGH, LOW_]),
@@ -3641,13 +5259,14 @@ mod tests {
     fn control_section_xor() {
         let clock_tick_rounds = get_clock_cycles(3);
         test_control_section(
+            8,
             HashMap::from(
                 [
                     (ControlSection::BUS_1, vec![LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, HIGH]),
+                    (ControlSection::STEP_RESET, vec![LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, HIGH]),
                     (ControlSection::TMP_S, vec![LOW_, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::R0_E, vec![LOW_, LOW_, LOW_, LOW_, HIGH, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::R3_E, vec![HIGH, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
-                    (ControlSection::C_OUT, vec![LOW_, LOW_, LOW_, HIGH, HIGH, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::ACC_S, vec![LOW_, LOW_, LOW_, LOW_, LOW_, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::FLAG_S, vec![LOW_, LOW_, LOW_, LOW_, LOW_, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::ACC_E, vec![LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, HIGH, HIGH, HIGH, LOW_]),
@@ -3675,13 +5294,14 @@ mod tests {
     fn control_section_cmp() {
         let clock_tick_rounds = get_clock_cycles(3);
         test_control_section(
+            8,
             HashMap::from(
                 [
                     (ControlSection::BUS_1, vec![LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, HIGH]),
+                    (ControlSection::STEP_RESET, vec![LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, HIGH]),
                     (ControlSection::TMP_S, vec![LOW_, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::R0_E, vec![LOW_, LOW_, LOW_, LOW_, HIGH, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::R3_E, vec![HIGH, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
-                    (ControlSection::C_OUT, vec![LOW_, LOW_, LOW_, HIGH, HIGH, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::ACC_S, vec![LOW_, LOW_, LOW_, LOW_, LOW_, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::FLAG_S, vec![LOW_, LOW_, LOW_, LOW_, LOW_, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::ALU_0, vec![LOW_, LOW_, LOW_, HIGH, HIGH, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_]),
@@ -3704,10 +5324,158 @@ mod tests {
         );
     }
 
+    #[test]
+    fn control_section_cmp_does_not_set_registers() {
+        let register_set_tags = [
+            ControlSection::R0_S,
+            ControlSection::R1_S,
+            ControlSection::R2_S,
+            ControlSection::R3_S,
+        ];
+
+        for reg_a in 0..4 {
+            for reg_b in 0..4 {
+                let bit = |num: usize, idx: usize| if (num >> idx) & 1 == 1 { HIGH } else { LOW_ };
+
+                let clock_tick_rounds = get_clock_cycles(3);
+                let (control_section, collected_output) = run_control_section(
+                    8,
+                    HashMap::from(
+                        [
+                            clock_tick_rounds.clock,
+                            clock_tick_rounds.clock_enable,
+                            clock_tick_rounds.clock_set,
+                            ("IR", copy_input_n_times(
+                                vec![bit(reg_b, 0), bit(reg_b, 1), bit(reg_a, 0), bit(reg_a, 1), HIGH, HIGH, HIGH, HIGH], // CMP reg_a reg_b
+                                12,
+                            ))
+                        ]
+                    ),
+                    4,
+                    12,
+                );
+
+                for tag in register_set_tags {
+                    let idx = control_section.lock().unwrap().get_index_from_tag(tag);
+                    assert!(
+                        collected_output.iter().all(|output| output[idx] == LOW_),
+                        "{} was set during CMP reg_a {} reg_b {}", tag, reg_a, reg_b,
+                    );
+                }
+
+                let flag_s_idx = control_section.lock().unwrap().get_index_from_tag(ControlSection::FLAG_S);
+                assert!(collected_output.iter().any(|output| output[flag_s_idx] == HIGH));
+            }
+        }
+    }
+
+    #[test]
+    fn control_section_bus_exclusivity() {
+        let instructions = [
+            ("LOAD", vec![LOW_, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_]),
+            ("STORE", vec![LOW_, HIGH, HIGH, LOW_, HIGH, LOW_, LOW_, LOW_]),
+            ("DATA", vec![HIGH, LOW_, LOW_, LOW_, LOW_, HIGH, LOW_, LOW_]),
+            ("JUMP_REGISTER", vec![LOW_, HIGH, LOW_, LOW_, HIGH, HIGH, LOW_, LOW_]),
+            ("JUMP", vec![LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, HIGH, LOW_]),
+            ("JUMP_IF", vec![HIGH, HIGH, HIGH, HIGH, HIGH, LOW_, HIGH, LOW_]),
+            ("CLEAR_FLAGS", vec![LOW_, LOW_, LOW_, LOW_, LOW_, HIGH, HIGH, LOW_]),
+            ("IO_INPUT", vec![HIGH, LOW_, LOW_, LOW_, HIGH, HIGH, HIGH, LOW_]),
+            ("IO_OUTPUT", vec![HIGH, LOW_, HIGH, HIGH, HIGH, HIGH, HIGH, LOW_]),
+            ("ADD", vec![LOW_, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, HIGH]),
+            ("SHR", vec![LOW_, HIGH, HIGH, LOW_, HIGH, LOW_, LOW_, HIGH]),
+            ("SHL", vec![LOW_, HIGH, HIGH, LOW_, LOW_, HIGH, LOW_, HIGH]),
+            ("NOT", vec![LOW_, HIGH, HIGH, LOW_, HIGH, HIGH, LOW_, HIGH]),
+            ("AND", vec![LOW_, HIGH, HIGH, LOW_, LOW_, LOW_, HIGH, HIGH]),
+            ("OR", vec![LOW_, HIGH, HIGH, LOW_, HIGH, LOW_, HIGH, HIGH]),
+            ("XOR", vec![LOW_, HIGH, HIGH, LOW_, LOW_, HIGH, HIGH, HIGH]),
+            ("CMP", vec![LOW_, HIGH, HIGH, LOW_, HIGH, HIGH, HIGH, HIGH]),
+        ];
+
+        //Every instruction is run through all six steps, starting with the fetch.
+        let number_clock_ticks = 24;
+        for (name, instruction) in instructions {
+            println!("Checking {}", name);
+            let clock_tick_rounds = get_clock_cycles(6);
+            let (control_section, collected_output) = run_control_section(
+                8,
+                HashMap::from(
+                    [
+                        clock_tick_rounds.clock,
+                        clock_tick_rounds.clock_enable,
+                        clock_tick_rounds.clock_set,
+                        ("IR", copy_input_n_times(instruction, number_clock_ticks)),
+                        (ControlSection::C_IN, copy_input_n_times(vec![HIGH], number_clock_ticks)),
+                        (ControlSection::A_L, copy_input_n_times(vec![HIGH], number_clock_ticks)),
+                        (ControlSection::EQ, copy_input_n_times(vec![HIGH], number_clock_ticks)),
+                        (ControlSection::Z, copy_input_n_times(vec![HIGH], number_clock_ticks)),
+                    ]
+                ),
+                1,
+                number_clock_ticks,
+            );
+
+            //The fetch always enables IAR then RAM onto the bus.
+            let iar_e_idx = control_section.lock().unwrap().get_index_from_tag(ControlSection::IAR_E);
+            let ram_e_idx = control_section.lock().unwrap().get_index_from_tag(ControlSection::RAM_E);
+            assert!(collected_output.iter().any(|output| output[iar_e_idx] == HIGH));
+            assert!(collected_output.iter().any(|output| output[ram_e_idx] == HIGH));
+
+            control_section.lock().unwrap().assert_bus_exclusivity(
+                &ControlSection::BUS_ENABLE_OUTPUTS,
+                &collected_output,
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Multiple outputs enabled onto the bus")]
+    fn control_section_bus_exclusivity_detects_conflict() {
+        let control_section = ControlSection::new(8);
+        let number_outputs = control_section.lock().unwrap().complex_gate.output_gates.len();
+
+        let mut output = vec![LOW_; number_outputs];
+        output[control_section.lock().unwrap().get_index_from_tag(ControlSection::RAM_E)] = HIGH;
+        output[control_section.lock().unwrap().get_index_from_tag(ControlSection::R2_E)] = HIGH;
+
+        control_section.lock().unwrap().assert_bus_exclusivity(
+            &ControlSection::BUS_ENABLE_OUTPUTS,
+            &[vec![LOW_; number_outputs], output],
+        );
+    }
+
+    #[test]
+    fn control_section_ir_bus_group() {
+        let control_section = ControlSection::new(8);
+        let ir_tags: Vec<String> = (0..8).map(|i| format!("IR_{}", i)).collect();
+        let ir_tags: Vec<&str> = ir_tags.iter().map(|tag| tag.as_str()).collect();
+
+        control_section.lock().unwrap().complex_gate.define_bus_group("IR", &ir_tags);
+
+        //Each value is checked after a different value so that every bit is driven both ways.
+        for opcode in [0b1111_0110, 0b0000_1001, 0b1010_0101] {
+            control_section.lock().unwrap().complex_gate.set_bus_group("IR", opcode);
+            control_section.lock().unwrap().fetch_output_signals_calculate().unwrap();
+
+            for (i, tag) in ir_tags.iter().enumerate() {
+                let expected_signal = if (opcode >> i) & 1 == 1 { HIGH } else { LOW_ };
+                let idx = control_section.lock().unwrap().get_index_from_tag(tag);
+                let input_gate = control_section.lock().unwrap().complex_gate.input_gates[idx].clone();
+                let input_output = input_gate.lock().unwrap().fetch_output_signals_no_calculate().unwrap();
+
+                let signals = extract_signals_from_gate_output_states(&input_output);
+                assert!(
+                    signals.iter().all(|signal| *signal == expected_signal),
+                    "opcode {:08b} {} expected {:?} collected {:?}", opcode, tag, expected_signal, signals,
+                );
+            }
+        }
+    }
+
     #[test]
     fn control_section_store() {
         let clock_tick_rounds = get_clock_cycles(2);
         test_control_section(
+            8,
             HashMap::from(
                 [
                     (ControlSection::R0_E, vec![HIGH, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_]),
@@ -3735,6 +5503,7 @@ mod tests {
     fn control_section_load() {
         let clock_tick_rounds = get_clock_cycles(2);
         test_control_section(
+            8,
             HashMap::from(
                 [
                     (ControlSection::R0_E, vec![HIGH, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_]),
@@ -3759,13 +5528,31 @@ mod tests {
     }
 
 
+    #[test]
+    fn extract_microcode_load() {
+        // Load R3 from RAM address inside R0.
+        let load_opcode = 0b0000_0011;
+        let control_section = ControlSection::new(8);
+        let load_steps = extract_microcode_for_opcodes(&control_section, &[load_opcode]);
+
+        assert_eq!(load_steps.len(), 6);
+        assert!(load_steps.iter().all(|micro_step| micro_step.opcode == load_opcode));
+        assert_eq!(load_steps[3].step, 4);
+        assert_eq!(load_steps[3].asserted_outputs, vec![ControlSection::R0_E, ControlSection::MAR_S]);
+        assert_eq!(load_steps[4].step, 5);
+        assert_eq!(load_steps[4].asserted_outputs, vec![ControlSection::RAM_E, ControlSection::R3_S]);
+    }
+
+
     #[test]
     fn control_section_data() {
         let clock_tick_rounds = get_clock_cycles(3);
         test_control_section(
+            8,
             HashMap::from(
                 [
                     (ControlSection::BUS_1, vec![HIGH, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, HIGH]),
+                    (ControlSection::STEP_RESET, vec![LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, HIGH]),
                     (ControlSection::IAR_E, vec![HIGH, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::MAR_S, vec![LOW_, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::ACC_S, vec![LOW_, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
@@ -3794,6 +5581,7 @@ mod tests {
     fn control_section_jump() {
         let clock_tick_rounds = get_clock_cycles(1);
         test_control_section(
+            8,
             HashMap::from(
                 [
                     (ControlSection::BUS_1, vec![LOW_, LOW_, LOW_, LOW_]),
@@ -3820,6 +5608,7 @@ mod tests {
     fn control_section_jump_addr() {
         let clock_tick_rounds = get_clock_cycles(2);
         test_control_section(
+            8,
             HashMap::from(
                 [
                     (ControlSection::IAR_E, vec![HIGH, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_]),
@@ -3847,9 +5636,11 @@ mod tests {
     fn control_section_jump_if_true() {
         let clock_tick_rounds = get_clock_cycles(3);
         test_control_section(
+            8,
             HashMap::from(
                 [
                     (ControlSection::BUS_1, vec![HIGH, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, HIGH]),
+                    (ControlSection::STEP_RESET, vec![LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, HIGH]),
                     (ControlSection::IAR_E, vec![HIGH, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::MAR_S, vec![LOW_, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::ACC_S, vec![LOW_, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
@@ -3878,9 +5669,11 @@ mod tests {
     fn control_section_jump_if_false() {
         let clock_tick_rounds = get_clock_cycles(3);
         test_control_section(
+            8,
             HashMap::from(
                 [
                     (ControlSection::BUS_1, vec![HIGH, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, HIGH]),
+                    (ControlSection::STEP_RESET, vec![LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, HIGH]),
                     (ControlSection::IAR_E, vec![HIGH, HIGH, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::MAR_S, vec![LOW_, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
                     (ControlSection::ACC_S, vec![LOW_, HIGH, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_, LOW_]),
@@ -3907,6 +5700,7 @@ mod tests {
     fn control_section_clear_flags() {
         let clock_tick_rounds = get_clock_cycles(1);
         test_control_section(
+            8,
             HashMap::from(
                 [
                     (ControlSection::BUS_1,  vec![HIGH, HIGH, HIGH, LOW_]),
@@ -1,14 +1,15 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::{fmt, thread};
 use std::fmt::Formatter;
 use std::hash::{Hash, Hasher};
 use std::sync::atomic::Ordering;
-use crate::globals::{get_clock_tick_number, MAX_INPUT_CHANGES, NEXT_UNIQUE_ID};
-use crate::logic::basic_gates::And;
+use crate::globals::{get_clock_tick_number, get_pending_logic_error, MAX_INPUT_CHANGES, NEXT_UNIQUE_ID, return_logic_errors, store_logic_error};
+use crate::logic::basic_gates::{And, calculate_output_with_early_exit, Nand, Nor};
 use crate::logic::foundations::Signal::{HIGH, LOW_, NONE};
 use crate::logic::input_gates::SimpleInput;
+use crate::logic::netlist::{Netlist, NetlistConnection, NetlistGate};
 use crate::logic::output_gates::{LogicGateAndOutputGate, SimpleOutput};
-use crate::run_circuit::{count_gates_in_circuit, run_circuit};
+use crate::run_circuit::{collect_gates_in_circuit, run_circuit, strongly_connected_components};
 use crate::shared_mutex::SharedMutex;
 
 //NONE includes some complications. For example when two connections are made to the same
@@ -26,10 +27,37 @@ pub enum Signal {
     HIGH,
 }
 
+impl fmt::Display for Signal {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let printable = match *self {
+            NONE => "NONE",
+            LOW_ => "LOW",
+            HIGH => "HIGH",
+        };
+        write!(f, "{}", printable)
+    }
+}
+
 #[derive(PartialEq, Debug, Clone)]
 pub enum GateLogicError {
+    /// An AutomaticInput has output every value it was given. run_circuit() uses this to stop.
     NoMoreAutomaticInputsRemaining,
+    /// An input has more than one valid (non NONE) signal connected to it. This can be a temporary
+    /// state, run_circuit() will delay the gate until it can be resolved.
     MultipleValidSignalsWhenCalculating,
+    /// An input changed MAX_INPUT_CHANGES times in a single clock tick. Only returned when
+    /// set_return_logic_errors() is enabled, otherwise this panics. Holds the gate description.
+    OscillationDetected(String),
+    /// Every gate left inside run_circuit() had multiple valid signals on an input so the state can
+    /// never be resolved. Only returned when set_return_logic_errors() is enabled, otherwise this
+    /// panics. Holds the gate descriptions.
+    UnresolvableContention(String),
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum GateBuildError {
+    /// A variable width gate was asked to build with zero bits.
+    ZeroWidth,
 }
 
 #[derive(Debug, Clone)]
@@ -39,6 +67,14 @@ pub struct GateInput {
     pub sending_id: UniqueID,
 }
 
+//Prints as in[{input_index}]={signal}. The sending id is left out, it is almost never needed when
+// reading through output.
+impl fmt::Display for GateInput {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "in[{}]={}", self.input_index, self.signal)
+    }
+}
+
 #[derive(Debug, Copy, Clone)]
 pub struct UniqueID {
     id: usize,
@@ -90,6 +126,31 @@ impl GateInput {
     }
 }
 
+//Returned by LogicGate::count_gates(). The total is the sum of every count.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GateCounts {
+    pub counts: HashMap<GateType, usize>,
+    pub total: usize,
+}
+
+#[allow(dead_code)]
+impl GateCounts {
+    pub fn add(&mut self, gate_type: GateType, count: usize) {
+        *self.counts.entry(gate_type).or_insert(0) += count;
+        self.total += count;
+    }
+
+    pub fn merge(&mut self, other: &GateCounts) {
+        for (gate_type, count) in other.counts.iter() {
+            self.add(*gate_type, *count);
+        }
+    }
+
+    pub fn get(&self, gate_type: GateType) -> usize {
+        self.counts.get(&gate_type).copied().unwrap_or(0)
+    }
+}
+
 #[derive(Debug)]
 pub struct InputSignalReturn {
     pub changed_count_this_tick: usize,
@@ -130,13 +191,45 @@ pub trait LogicGate: Send {
         panic!("Gate {} using tag {} id {} did not implement get_index_from_tag()", self.get_tag(), tag, self.get_unique_id().id)
     }
 
+    //Every input or output tag of the gate sorted by index. Gates without tags return nothing.
+    fn get_tags(&self, _tag_type: GateTagType) -> Vec<String> {
+        Vec::new()
+    }
+
+    //Returns true if another gate has been connected to the input. Inputs that were only set
+    // directly through update_input_signal() are not considered connected.
+    fn is_input_connected(&self, input_index: usize) -> bool;
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID);
 
     fn toggle_print_each_input_output_gate(&mut self, print_each_input_output_gate: bool);
 
     fn num_children_gates(&self) -> usize;
 
+    //The total number of gates that make up this gate including every nested gate. Basic gates
+    // count as a single gate.
+    fn num_unique_gates(&self) -> usize;
+
+    fn num_inputs(&self) -> usize;
+
+    fn num_outputs(&self) -> usize;
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>>;
+
+    //The gates directly inside this gate and how they are connected. Only complex gates have a
+    // netlist.
+    #[allow(dead_code)]
+    fn netlist(&self) -> Option<Netlist> {
+        None
+    }
+
+    //The number of basic gates of each GateType that make up this gate including every nested gate.
+    // A basic gate only counts itself.
+    fn count_gates(&self) -> GateCounts {
+        let mut gate_counts = GateCounts::default();
+        gate_counts.add(self.get_gate_type(), 1);
+        gate_counts
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -145,6 +238,23 @@ pub enum GateOutputState {
     Connected(ConnectedOutput),
 }
 
+#[allow(dead_code)]
+impl GateOutputState {
+    //A single line description such as out[2]->gate#7.in[0]=HIGH or out[2]=LOW. The connected gate
+    // is locked to get its id, so this must not be called while that gate is already locked.
+    pub fn describe(&self, output_index: usize) -> String {
+        match self {
+            GateOutputState::NotConnected(signal) => {
+                format!("out[{}]={}", output_index, signal)
+            }
+            GateOutputState::Connected(connected_output) => {
+                let gate_id = connected_output.gate.lock().unwrap().get_unique_id().id();
+                format!("out[{}]->gate#{}.{}", output_index, gate_id, connected_output.throughput)
+            }
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct ConnectedOutput {
     pub throughput: GateInput,
@@ -159,6 +269,79 @@ impl fmt::Debug for ConnectedOutput {
     }
 }
 
+pub fn extract_signals_from_gate_output_states(output_states: &[GateOutputState]) -> Vec<Signal> {
+    output_states
+        .iter()
+        .map(|output_state| {
+            match output_state {
+                GateOutputState::NotConnected(signal) => signal.clone(),
+                GateOutputState::Connected(connected_output) => connected_output.throughput.signal.clone(),
+            }
+        })
+        .collect()
+}
+
+//The bit order of a vector of signals. Little means index 0 holds the least significant bit, which
+// is the order used by every gate inside this crate. Big means index 0 holds the most significant bit.
+#[allow(dead_code)]
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Endianness {
+    Little,
+    Big,
+}
+
+//Only the first num_bits signals are used. None is returned if any of the bits are NONE.
+#[allow(dead_code)]
+pub fn signals_to_u64(signals: &[Signal], num_bits: usize, endianness: Endianness) -> Option<u64> {
+    assert!(num_bits <= u64::BITS as usize);
+    assert!(num_bits <= signals.len());
+
+    let mut value: u64 = 0;
+    for (i, signal) in signals.iter().take(num_bits).enumerate() {
+        let bit = match endianness {
+            Endianness::Little => i,
+            Endianness::Big => num_bits - 1 - i,
+        };
+
+        match signal {
+            HIGH => value |= 1 << bit,
+            LOW_ => {}
+            NONE => return None,
+        }
+    }
+
+    Some(value)
+}
+
+//Bits of value above num_bits are ignored.
+#[allow(dead_code)]
+pub fn u64_to_signals(value: u64, num_bits: usize, endianness: Endianness) -> Vec<Signal> {
+    assert!(num_bits <= u64::BITS as usize);
+
+    let mut signals: Vec<Signal> = (0..num_bits)
+        .map(|i| if (value >> i) & 1 == 1 { HIGH } else { LOW_ })
+        .collect();
+
+    if endianness == Endianness::Big {
+        signals.reverse();
+    }
+
+    signals
+}
+
+//Interprets the most significant bit (index num_bits - 1) as the sign bit of a two's complement
+// number.
+#[allow(dead_code)]
+pub fn signals_to_i64(signals: &[Signal], num_bits: usize) -> Option<i64> {
+    assert_ne!(num_bits, 0);
+
+    let value = signals_to_u64(signals, num_bits, Endianness::Little)?;
+
+    //Shifting the sign bit up to bit 63 then arithmetic shifting back down sign extends it.
+    let unused_bits = u64::BITS as usize - num_bits;
+    Some(((value << unused_bits) as i64) >> unused_bits)
+}
+
 pub fn extract_string_from_gate_output_states(connected_output: &Vec<GateOutputState>) -> String {
     let mut result_string = String::from("[\n");
     for output in connected_output.iter() {
@@ -245,12 +428,16 @@ impl OscillationDetection {
             self.changed_count_this_tick += 1;
 
             if self.changed_count_this_tick >= MAX_INPUT_CHANGES {
-                panic!(
-                    "Oscillation (a loop) was detected on the current {} gate id {} tag {}",
-                    gate_type,
-                    id.id,
-                    tag,
-                );
+                let gate_description = format!("{} gate id {} tag {}", gate_type, id.id, tag);
+
+                if return_logic_errors() {
+                    store_logic_error(GateLogicError::OscillationDetected(gate_description));
+                } else {
+                    panic!(
+                        "Oscillation (a loop) was detected on the current {}",
+                        gate_description,
+                    );
+                }
             }
         } else {
             self.current_tick = clock_tick_number;
@@ -261,7 +448,7 @@ impl OscillationDetection {
     }
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum GateType {
     #[allow(dead_code)]
     UnknownType,
@@ -273,11 +460,14 @@ pub enum GateType {
     NandType,
     XOrType,
     SplitterType,
+    FanoutSplitterType,
     ControlledBufferType,
     SignalGatekeeperType,
     ClockType,
     AutomaticInputType,
+    DynamicInputType,
     SimpleOutputType,
+    LatchedOutputType,
     SimpleInputType,
     SRLatchType,
     ActiveLowSRLatchType,
@@ -308,6 +498,27 @@ pub enum GateType {
     VariableOutputStepperType,
     ControlSectionType,
     VariableBitCPUType,
+    BinaryToGrayType,
+    GrayToBinaryType,
+    TFlipFlopType,
+    SignMagnitudeToTwosComplementType,
+    ClockDividerType,
+    DualPortRamType,
+    MaskType,
+    PatternMatchType,
+    VariableBitSaturatingSubtractorType,
+    RingCounterType,
+    BusConcatType,
+    CountLeadingZerosType,
+    CascadableComparatorType,
+    VariableBitDividerType,
+    XnorType,
+    MultiplexerType,
+    DemultiplexerType,
+    LutType,
+    VariableBitMultiplierType,
+    ShiftRegisterType,
+    BusKeeperType,
 }
 
 impl fmt::Display for GateType {
@@ -321,11 +532,14 @@ impl fmt::Display for GateType {
             GateType::NandType => "NAND",
             GateType::XOrType => "XOR",
             GateType::SplitterType => "SPLITTER",
+            GateType::FanoutSplitterType => "FANOUT_SPLITTER",
             GateType::ControlledBufferType => "CONTROLLED_BUFFER",
             GateType::SignalGatekeeperType => "SIGNAL_GATEKEEPER",
             GateType::ClockType => "CLOCK",
             GateType::AutomaticInputType => "AUTOMATIC_INPUT",
+            GateType::DynamicInputType => "DYNAMIC_INPUT",
             GateType::SimpleOutputType => "SIMPLE_OUTPUT",
+            GateType::LatchedOutputType => "LATCHED_OUTPUT",
             GateType::SimpleInputType => "SIMPLE_INPUT",
             GateType::SRLatchType => "SR_LATCH",
             GateType::ActiveLowSRLatchType => "ACTIVE_LOW_SR_LATCH",
@@ -356,13 +570,62 @@ impl fmt::Display for GateType {
             GateType::VariableOutputStepperType => "VARIABLE_OUTPUT_STEPPER",
             GateType::ControlSectionType => "CONTROL_SECTION",
             GateType::VariableBitCPUType => "VARIABLE_BIT_CPU",
+            GateType::BinaryToGrayType => "BINARY_TO_GRAY",
+            GateType::GrayToBinaryType => "GRAY_TO_BINARY",
+            GateType::TFlipFlopType => "T_FLIP_FLOP",
+            GateType::SignMagnitudeToTwosComplementType => "SIGN_MAGNITUDE_TO_TWOS_COMPLEMENT",
+            GateType::ClockDividerType => "CLOCK_DIVIDER",
+            GateType::DualPortRamType => "DUAL_PORT_RAM",
+            GateType::MaskType => "MASK",
+            GateType::PatternMatchType => "PATTERN_MATCH",
+            GateType::VariableBitSaturatingSubtractorType => "VARIABLE_BIT_SATURATING_SUBTRACTOR",
+            GateType::RingCounterType => "RING_COUNTER",
+            GateType::BusConcatType => "BUS_CONCAT",
+            GateType::CountLeadingZerosType => "COUNT_LEADING_ZEROS",
+            GateType::CascadableComparatorType => "CASCADABLE_COMPARATOR",
+            GateType::VariableBitDividerType => "VARIABLE_BIT_DIVIDER",
+            GateType::XnorType => "XNOR",
+            GateType::MultiplexerType => "MULTIPLEXER",
+            GateType::DemultiplexerType => "DEMULTIPLEXER",
+            GateType::LutType => "LUT",
+            GateType::VariableBitMultiplierType => "VARIABLE_BIT_MULTIPLIER",
+            GateType::ShiftRegisterType => "SHIFT_REGISTER",
+            GateType::BusKeeperType => "BUS_KEEPER",
         };
         write!(f, "{}", printable)
     }
 }
 
+impl GateType {
+    //Gates that hold a value through feedback inside of them. A loop that runs through one of these
+    // is how memory is built, so it is not a combinational loop.
+    pub fn is_stateful(&self) -> bool {
+        matches!(
+            self,
+            GateType::SRLatchType
+                | GateType::ActiveLowSRLatchType
+                | GateType::OneBitMemoryCellType
+                | GateType::VariableBitMemoryCellType
+                | GateType::MasterSlaveJKFlipFlopType
+                | GateType::VariableBitCounterType
+                | GateType::VariableBitRegisterType
+                | GateType::VariableSingleRAMCellType
+                | GateType::RAMUnitType
+                | GateType::VariableOutputStepperType
+                | GateType::TFlipFlopType
+                | GateType::ClockDividerType
+                | GateType::DualPortRamType
+                | GateType::RingCounterType
+                | GateType::LatchedOutputType
+        )
+    }
+}
+
 pub struct BasicGateMembers {
     pub input_signals: Vec<HashMap<UniqueID, Signal>>,
+    //The gate outputs connected to each input. This is kept apart from input_signals which holds the
+    // last signals sent to each input, not which gates are connected to it.
+    pub connected_inputs: Vec<HashSet<UniqueID>>,
     pub output_states: Vec<GateOutputState>,
     pub unique_id: UniqueID,
     pub oscillation_detection: OscillationDetection,
@@ -387,6 +650,7 @@ impl BasicGateMembers {
 
         let mut result = BasicGateMembers {
             input_signals: vec![HashMap::from([(UniqueID::zero_id(), LOW_)]); input_num],
+            connected_inputs: vec![HashSet::new(); input_num],
             output_states: Vec::with_capacity(output_num),
             unique_id: UniqueID::generate(),
             oscillation_detection: OscillationDetection::new(),
@@ -414,6 +678,16 @@ impl BasicGateMembers {
         result
     }
 
+    #[allow(dead_code)]
+    pub fn num_inputs(&self) -> usize {
+        self.input_signals.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn num_outputs(&self) -> usize {
+        self.output_states.len()
+    }
+
     pub fn update_input_signal(&mut self, input: GateInput) -> InputSignalReturn {
         let changed_count_this_tick = self.oscillation_detection.detect_oscillation(
             &self.gate_type,
@@ -422,9 +696,6 @@ impl BasicGateMembers {
             &input.sending_id,
         );
 
-        //todo delete
-        println!("update_input_signal() input_signals: [\n    {:#?}\n]", self.input_signals);
-
         let input_signal_updated = if self.input_signals[input.input_index][&input.sending_id] == input.signal {
             false
         } else {
@@ -438,6 +709,10 @@ impl BasicGateMembers {
         }
     }
 
+    pub fn is_input_connected(&self, input_index: usize) -> bool {
+        !self.connected_inputs[input_index].is_empty()
+    }
+
     pub fn connect_output(
         &mut self,
         current_gate_output_index: usize,
@@ -479,6 +754,10 @@ impl BasicGateMembers {
         //This is a temporary signal. When the input is updated afterwards, it will add it.
         self.input_signals[gate_input_index].insert(sending_id, signal);
 
+        if sending_id != UniqueID::zero_id() {
+            self.connected_inputs[gate_input_index].insert(sending_id);
+        }
+
         #[cfg(feature = "high_restriction")]
         if self.input_signals[gate_input_index].len() > 1 {
             panic!("A gate had multiple connections to the same input")
@@ -511,6 +790,25 @@ impl BasicGateMembers {
         if input_map.is_empty() {
             input_map.insert(UniqueID::zero_id(), returned_signal);
         }
+
+        self.connected_inputs[input_index].remove(&connected_id);
+    }
+
+    //The id of the gate output connected to input_index or None if nothing is connected. When
+    // multiple outputs are connected, the one driving a valid (non NONE) signal is preferred.
+    #[allow(dead_code)]
+    pub fn trace_input_source(&self, input_index: usize) -> Option<UniqueID> {
+        let input_map = &self.input_signals[input_index];
+
+        let mut connected_ids: Vec<(&UniqueID, &Signal)> = input_map
+            .iter()
+            .filter(|(id, _)| **id != UniqueID::zero_id())
+            .collect();
+
+        //Sorted so the result does not depend on the HashMap iteration order.
+        connected_ids.sort_by_key(|(id, signal)| (**signal == NONE, id.id()));
+
+        connected_ids.first().map(|(id, _)| **id)
     }
 
     pub fn toggle_print_each_input_output_gate(&mut self, print_each_input_output_gate: bool) {
@@ -518,7 +816,7 @@ impl BasicGateMembers {
     }
 }
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Clone, Copy)]
 pub enum GateTagType {
     Input,
     Output,
@@ -543,6 +841,11 @@ pub struct ComplexGateMembers {
     pub input_gates: Vec<SharedMutex<dyn LogicGate>>,
     pub output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>>,
     pub gate_tags_to_index: HashMap<String, GateTagInfo>,
+    //Named groups of input tags, the first tag in each group is the least significant bit.
+    #[allow(dead_code)]
+    pub bus_groups: HashMap<String, Vec<String>>,
+    //Set while priming, see count_unique_gates().
+    number_unique_gates: usize,
 }
 
 impl ComplexGateMembers {
@@ -595,6 +898,8 @@ impl ComplexGateMembers {
             input_gates,
             output_gates,
             gate_tags_to_index,
+            bus_groups: HashMap::new(),
+            number_unique_gates: 0,
         }
     }
 
@@ -632,9 +937,285 @@ impl ComplexGateMembers {
             propagate_signal_through_circuit
         );
 
-        self.simple_gate.number_child_gates = count_gates_in_circuit(
+        self.set_child_count();
+    }
+
+    fn set_child_count(&mut self) {
+        let child_gates = collect_gates_in_circuit(
             &self.input_gates
         );
+
+        self.simple_gate.number_child_gates = child_gates.len();
+        self.number_unique_gates = child_gates
+            .iter()
+            .map(|gate| gate.lock().unwrap().num_unique_gates())
+            .sum();
+    }
+
+    //Replaces every And or Or gate whose only output drives a Not gate with a single Nand or Nor
+    // gate. Only the gates directly inside this gate are checked, nested complex gates are left
+    // alone. The replaced gates are disconnected from the circuit, so any field that still holds
+    // them will no longer see the signals.
+    #[allow(dead_code)]
+    pub fn optimize_to_nand_nor(&mut self) {
+        let child_gates = collect_gates_in_circuit(
+            &self.input_gates
+        );
+
+        for gate in child_gates.iter() {
+            let (gate_type, gate_id, num_inputs, gate_tag) = {
+                let gate = gate.lock().unwrap();
+                (gate.get_gate_type(), gate.get_unique_id(), gate.num_inputs(), gate.get_tag())
+            };
+
+            if gate_type != GateType::AndType && gate_type != GateType::OrType {
+                continue;
+            }
+
+            let gate_output = gate.lock().unwrap().fetch_output_signals_no_calculate().unwrap();
+            let not_gate = match gate_output.as_slice() {
+                [GateOutputState::Connected(connected_output)]
+                if connected_output.gate.lock().unwrap().get_gate_type() == GateType::NotType => {
+                    connected_output.gate.clone()
+                }
+                _ => continue,
+            };
+
+            let (not_id, not_output) = {
+                let mut not_gate = not_gate.lock().unwrap();
+                (not_gate.get_unique_id(), not_gate.fetch_output_signals_no_calculate().unwrap())
+            };
+
+            let replacement_gate: SharedMutex<dyn LogicGate> =
+                if gate_type == GateType::AndType {
+                    Nand::new(num_inputs, not_output.len())
+                } else {
+                    Nor::new(num_inputs, not_output.len())
+                };
+            replacement_gate.lock().unwrap().set_tag(gate_tag.as_str());
+
+            //Move every connection driving the original gate onto the replacement.
+            for driving_gate in child_gates.iter() {
+                let driving_output = driving_gate.lock().unwrap().fetch_output_signals_no_calculate().unwrap();
+                for (output_index, output) in driving_output.into_iter().enumerate() {
+                    if let GateOutputState::Connected(connected_output) = output {
+                        if connected_output.gate.lock().unwrap().get_unique_id() == gate_id {
+                            connect_gates(
+                                driving_gate.clone(),
+                                output_index,
+                                replacement_gate.clone(),
+                                connected_output.throughput.input_index,
+                            );
+                        }
+                    }
+                }
+            }
+
+            //Move every connection driven by the Not gate onto the replacement.
+            for (output_index, output) in not_output.into_iter().enumerate() {
+                if let GateOutputState::Connected(connected_output) = output {
+                    let input_index = connected_output.throughput.input_index;
+                    connected_output.gate.lock().unwrap().remove_connected_input(input_index, not_id);
+                    connect_gates(
+                        replacement_gate.clone(),
+                        output_index,
+                        connected_output.gate.clone(),
+                        input_index,
+                    );
+                }
+            }
+        }
+
+        self.set_child_count();
+    }
+
+    //Returns the signal currently on output_index of the gate with gate_id. Only the gates directly
+    // inside this gate are searched. None is returned if the gate or the output does not exist.
+    #[allow(dead_code)]
+    pub fn probe_wire(&self, gate_id: UniqueID, output_index: usize) -> Option<Signal> {
+        let child_gates = collect_gates_in_circuit(
+            &self.input_gates
+        );
+
+        let gate = child_gates
+            .iter()
+            .find(|gate| gate.lock().unwrap().get_unique_id() == gate_id)?;
+
+        let gate_output = gate.lock().unwrap().fetch_output_signals_no_calculate().ok()?;
+
+        extract_signals_from_gate_output_states(&gate_output)
+            .get(output_index)
+            .cloned()
+    }
+
+    //Returns every loop among the gates directly inside this gate, each loop is the ids of the gates
+    // that form it. Loops through stateful gates are intended and are not returned. Only the
+    // connections are followed, nothing is calculated, so this can be called before priming.
+    #[allow(dead_code)]
+    pub fn detect_combinational_loops(&self) -> Vec<Vec<UniqueID>> {
+        let mut next_gates: HashMap<UniqueID, Vec<UniqueID>> = HashMap::new();
+        let mut gate_order = Vec::new();
+        let mut visited_gates = HashSet::new();
+        let mut gates_to_visit = self.input_gates.clone();
+
+        while let Some(gate) = gates_to_visit.pop() {
+            let (gate_id, is_stateful, gate_output) = {
+                let mut gate = gate.lock().unwrap();
+                (
+                    gate.get_unique_id(),
+                    gate.get_gate_type().is_stateful(),
+                    gate.fetch_output_signals_no_calculate().unwrap_or_default(),
+                )
+            };
+
+            if !visited_gates.insert(gate_id) {
+                continue;
+            }
+
+            let mut connected_ids = Vec::new();
+            for output in gate_output.into_iter() {
+                if let GateOutputState::Connected(next_gate_info) = output {
+                    connected_ids.push(next_gate_info.gate.lock().unwrap().get_unique_id());
+                    gates_to_visit.push(next_gate_info.gate);
+                }
+            }
+
+            //Stateful gates are left out of the graph so any loop through them is broken.
+            if !is_stateful {
+                gate_order.push(gate_id);
+                next_gates.insert(gate_id, connected_ids);
+            }
+        }
+
+        let gate_indices: HashMap<UniqueID, usize> = gate_order
+            .iter()
+            .enumerate()
+            .map(|(gate_index, &gate_id)| (gate_id, gate_index))
+            .collect();
+
+        //Stateful gates are not part of the graph.
+        let edges: Vec<Vec<usize>> = gate_order
+            .iter()
+            .map(|gate_id| {
+                next_gates[gate_id]
+                    .iter()
+                    .filter_map(|next_id| gate_indices.get(next_id).copied())
+                    .collect()
+            })
+            .collect();
+
+        //Every component with more than one gate, or a gate that is connected to itself, is a loop.
+        strongly_connected_components(&edges)
+            .into_iter()
+            .filter(|component| component.len() > 1 || edges[component[0]].contains(&component[0]))
+            .map(|component| {
+                let mut component: Vec<UniqueID> = component
+                    .into_iter()
+                    .map(|gate_index| gate_order[gate_index])
+                    .collect();
+                component.sort_by_key(|member_id| member_id.id());
+                component
+            })
+            .collect()
+    }
+
+    //Every gate directly inside this gate sorted by id along with the connections leaving it.
+    // Nothing is calculated, the connections are followed the same way as
+    // detect_combinational_loops().
+    #[allow(dead_code)]
+    pub fn netlist(&self) -> Netlist {
+        let mut gates = Vec::new();
+        let mut visited_gates = HashSet::new();
+        let mut gates_to_visit = self.input_gates.clone();
+
+        while let Some(gate) = gates_to_visit.pop() {
+            let mut gate = gate.lock().unwrap();
+            let gate_id = gate.get_unique_id();
+
+            if !visited_gates.insert(gate_id) {
+                continue;
+            }
+
+            let gate_output = gate.fetch_output_signals_no_calculate().unwrap_or_default();
+            let mut netlist_gate = NetlistGate {
+                id: gate_id.id(),
+                gate_type: gate.get_gate_type(),
+                tag: gate.get_tag(),
+                connections: Vec::new(),
+            };
+            drop(gate);
+
+            for (output_index, output) in gate_output.into_iter().enumerate() {
+                if let GateOutputState::Connected(next_gate_info) = output {
+                    netlist_gate.connections.push(
+                        NetlistConnection {
+                            output_index,
+                            gate_id: next_gate_info.gate.lock().unwrap().get_unique_id().id(),
+                            input_index: next_gate_info.throughput.input_index,
+                        }
+                    );
+                    gates_to_visit.push(next_gate_info.gate);
+                }
+            }
+
+            gates.push(netlist_gate);
+        }
+
+        gates.sort_by_key(|gate| gate.id);
+
+        Netlist {
+            id: self.simple_gate.unique_id.id(),
+            gate_type: self.simple_gate.gate_type,
+            tag: self.simple_gate.tag.clone(),
+            gates,
+        }
+    }
+
+    //The number of gates inside this gate, including the gates nested inside of complex child gates.
+    // This is counted once while priming so it is available without traversing the circuit again.
+    pub fn count_unique_gates(&self) -> usize {
+        self.number_unique_gates
+    }
+
+    //Unlike count_unique_gates() this traverses the circuit every time it is called. The
+    // connections are followed the same way as netlist() and nothing is calculated.
+    #[allow(dead_code)]
+    pub fn count_gates(&self) -> GateCounts {
+        let mut gate_counts = GateCounts::default();
+        let mut visited_gates = HashSet::new();
+        let mut gates_to_visit = self.input_gates.clone();
+
+        while let Some(gate) = gates_to_visit.pop() {
+            let mut gate = gate.lock().unwrap();
+
+            if !visited_gates.insert(gate.get_unique_id()) {
+                continue;
+            }
+
+            gate_counts.merge(&gate.count_gates());
+
+            let gate_output = gate.fetch_output_signals_no_calculate().unwrap_or_default();
+            drop(gate);
+
+            for output in gate_output.into_iter() {
+                if let GateOutputState::Connected(next_gate_info) = output {
+                    gates_to_visit.push(next_gate_info.gate);
+                }
+            }
+        }
+
+        gate_counts
+    }
+
+    //The number of inputs and outputs of the complex gate itself, not of the gates inside it.
+    #[allow(dead_code)]
+    pub fn num_inputs(&self) -> usize {
+        self.simple_gate.num_inputs()
+    }
+
+    #[allow(dead_code)]
+    pub fn num_outputs(&self) -> usize {
+        self.simple_gate.num_outputs()
     }
 
     pub fn convert_output_gates_to_output_states(&mut self) {
@@ -677,6 +1258,64 @@ impl ComplexGateMembers {
         }
     }
 
+    pub fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        let mut tags_and_index: Vec<(&String, usize)> = self.gate_tags_to_index
+            .iter()
+            .filter(|(_, gate_tag_info)| gate_tag_info.tag_type == tag_type)
+            .map(|(tag, gate_tag_info)| (tag, gate_tag_info.index))
+            .collect();
+
+        tags_and_index.sort_by_key(|(_, index)| *index);
+
+        tags_and_index.into_iter().map(|(tag, _)| tag.clone()).collect()
+    }
+
+    //Groups input tags so they can all be driven at once by set_bus_group(). The first tag is the
+    // least significant bit.
+    #[allow(dead_code)]
+    pub fn define_bus_group(&mut self, group_name: &str, tags: &[&str]) {
+        assert_ne!(tags.len(), 0);
+        assert!(tags.len() <= u64::BITS as usize);
+
+        for tag in tags.iter() {
+            match self.gate_tags_to_index.get(*tag) {
+                Some(gate_tag_info) if gate_tag_info.tag_type == GateTagType::Input => {}
+                _ => panic!("Gate {} id {} did not contain input tag {} for bus group {}.", self.simple_gate.gate_type, self.simple_gate.unique_id.id, tag, group_name)
+            }
+        }
+
+        self.bus_groups.insert(
+            group_name.to_string(),
+            tags.iter().map(|tag| tag.to_string()).collect(),
+        );
+    }
+
+    //Sets each input of the bus group to the matching bit of value.
+    #[allow(dead_code)]
+    pub fn set_bus_group(&mut self, group_name: &str, value: u64) {
+        let tags = match self.bus_groups.get(group_name) {
+            None => panic!("Gate {} id {} did not contain bus group {}.", self.simple_gate.gate_type, self.simple_gate.unique_id.id, group_name),
+            Some(tags) => tags.clone(),
+        };
+
+        assert!(
+            tags.len() == u64::BITS as usize || value >> tags.len() == 0,
+            "Value {} does not fit in bus group {} of width {}.", value, group_name, tags.len()
+        );
+
+        for (i, tag) in tags.iter().enumerate() {
+            let signal = if (value >> i) & 1 == 1 { HIGH } else { LOW_ };
+            let input_index = self.get_index_from_tag(tag);
+            self.update_input_signal(
+                GateInput::new(
+                    input_index,
+                    signal,
+                    UniqueID::zero_id(),
+                )
+            );
+        }
+    }
+
     pub fn connect_output(
         &mut self,
         current_gate_id: UniqueID,
@@ -752,6 +1391,11 @@ impl ComplexGateMembers {
             false,
         );
 
+        //If a logic error occurred inside the circuit, the outputs are not valid.
+        if let Some(logic_error) = get_pending_logic_error() {
+            return Err(logic_error);
+        }
+
         self.fetch_output_signals_no_calculate(
             tag
         )
@@ -789,6 +1433,18 @@ impl ComplexGateMembers {
         self.simple_gate.internal_update_index_to_id(sending_id, gate_input_index, signal);
     }
 
+    //The id of the gate output driving the input at input_index, see
+    // BasicGateMembers::trace_input_source().
+    #[allow(dead_code)]
+    pub fn trace_input_source(&self, input_index: usize) -> Option<UniqueID> {
+        self.simple_gate.trace_input_source(input_index)
+    }
+
+    pub fn is_input_connected(&self, input_index: usize) -> bool {
+        //SimpleInput input index is always 0.
+        self.input_gates[input_index].lock().unwrap().is_input_connected(0)
+    }
+
     pub fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         //SimpleInput input index is always 0.
         self.input_gates[input_index].lock().unwrap().remove_connected_input(
@@ -823,6 +1479,114 @@ impl ComplexGateMembers {
     // }
 }
 
+//Generates the standard LogicGate impl for a struct with a `complex_gate: ComplexGateMembers` field.
+// Every method delegates to complex_gate, gates that need anything different should implement
+// LogicGate by hand.
+macro_rules! impl_complex_logic_gate {
+    ($gate:ty) => {
+        impl $crate::logic::foundations::LogicGate for $gate {
+            fn internal_connect_output(&mut self, current_gate_output_key: usize, next_gate_input_key: usize, next_gate: $crate::shared_mutex::SharedMutex<dyn $crate::logic::foundations::LogicGate>) -> $crate::logic::foundations::Signal {
+                self.complex_gate.connect_output(
+                    self.get_unique_id(),
+                    current_gate_output_key,
+                    next_gate_input_key,
+                    next_gate,
+                )
+            }
+
+            fn internal_update_index_to_id(&mut self, sending_id: $crate::logic::foundations::UniqueID, gate_input_index: usize, signal: $crate::logic::foundations::Signal) {
+                self.complex_gate.internal_update_index_to_id(sending_id, gate_input_index, signal);
+            }
+
+            fn update_input_signal(&mut self, input: $crate::logic::foundations::GateInput) -> $crate::logic::foundations::InputSignalReturn {
+                self.complex_gate.update_input_signal(input)
+            }
+
+            fn fetch_output_signals_calculate(&mut self) -> Result<Vec<$crate::logic::foundations::GateOutputState>, $crate::logic::foundations::GateLogicError> {
+                self.complex_gate.fetch_output_signals_calculate(
+                    &self.get_tag(),
+                )
+            }
+
+            fn fetch_output_signals_no_calculate(&mut self) -> Result<Vec<$crate::logic::foundations::GateOutputState>, $crate::logic::foundations::GateLogicError> {
+                self.complex_gate.fetch_output_signals_no_calculate(
+                    &self.get_tag(),
+                )
+            }
+
+            fn get_gate_type(&self) -> $crate::logic::foundations::GateType {
+                self.complex_gate.simple_gate.gate_type
+            }
+
+            fn get_unique_id(&self) -> $crate::logic::foundations::UniqueID {
+                self.complex_gate.simple_gate.unique_id
+            }
+
+            fn toggle_output_printing(&mut self, print_output: bool) {
+                self.complex_gate.simple_gate.should_print_output = print_output;
+            }
+
+            fn get_tag(&self) -> String {
+                self.complex_gate.simple_gate.tag.clone()
+            }
+
+            fn set_tag(&mut self, tag: &str) {
+                self.complex_gate.simple_gate.tag = tag.to_string();
+            }
+
+            fn get_index_from_tag(&self, tag: &str) -> usize {
+                self.complex_gate.get_index_from_tag(tag)
+            }
+
+            fn get_tags(&self, tag_type: $crate::logic::foundations::GateTagType) -> Vec<String> {
+                self.complex_gate.get_tags(tag_type)
+            }
+
+            fn is_input_connected(&self, input_index: usize) -> bool {
+                self.complex_gate.is_input_connected(input_index)
+            }
+
+            fn remove_connected_input(&mut self, input_index: usize, connected_id: $crate::logic::foundations::UniqueID) {
+                self.complex_gate.remove_connected_input(input_index, connected_id);
+            }
+
+            fn toggle_print_each_input_output_gate(&mut self, print_each_input_output_gate: bool) {
+                self.complex_gate.toggle_print_each_input_output_gate(print_each_input_output_gate);
+            }
+
+            fn num_children_gates(&self) -> usize {
+                self.complex_gate.simple_gate.number_child_gates
+            }
+
+            fn num_unique_gates(&self) -> usize {
+                self.complex_gate.count_unique_gates()
+            }
+
+            fn count_gates(&self) -> $crate::logic::foundations::GateCounts {
+                self.complex_gate.count_gates()
+            }
+
+            fn num_inputs(&self) -> usize {
+                self.complex_gate.num_inputs()
+            }
+
+            fn num_outputs(&self) -> usize {
+                self.complex_gate.num_outputs()
+            }
+
+            fn get_input_gates(&self) -> Vec<$crate::shared_mutex::SharedMutex<dyn $crate::logic::foundations::LogicGate>> {
+                self.complex_gate.input_gates.clone()
+            }
+
+            fn netlist(&self) -> Option<$crate::logic::netlist::Netlist> {
+                Some(self.complex_gate.netlist())
+            }
+        }
+    };
+}
+
+pub(crate) use impl_complex_logic_gate;
+
 pub struct GateLogic;
 
 impl GateLogic {
@@ -895,6 +1659,15 @@ impl GateLogic {
         }
     }
 
+    //XOr is HIGH when the inputs do not all match, so this is HIGH when they do. With two inputs that
+    // is the same as an even number of HIGH inputs. NONE is passed through the same as XOr.
+    pub fn calculate_output_for_xnor(input_signals: &Vec<Signal>) -> Signal {
+        match GateLogic::calculate_output_for_xor(input_signals) {
+            NONE => NONE,
+            xor_signal => GateLogic::calculate_output_for_not(&vec![xor_signal]),
+        }
+    }
+
     pub fn calculate_output_for_clock(input_signals: &Vec<Signal>) -> Signal {
         if *input_signals.first().unwrap() == LOW_ {
             HIGH
@@ -1079,6 +1852,25 @@ impl GateLogic {
         gate_type: &GateType,
         input_signals: &Vec<HashMap<UniqueID, Signal>>,
     ) -> Result<Signal, GateLogicError> {
+        let dominant_signal = match gate_type {
+            GateType::AndType | GateType::NandType => Some(LOW_),
+            GateType::OrType | GateType::NorType => Some(HIGH),
+            _ => None,
+        };
+
+        //Wide And and Or gates (such as the ones inside the control section) do not need to resolve
+        // every input.
+        if let Some(dominant_signal) = dominant_signal {
+            let (output_signal, _) = calculate_output_with_early_exit(input_signals, dominant_signal)?;
+
+            let output_signal = match gate_type {
+                GateType::NandType | GateType::NorType => GateLogic::calculate_output_for_not(&vec![output_signal]),
+                _ => output_signal,
+            };
+
+            return Ok(output_signal);
+        }
+
         let input_signals = calculate_input_signals_from_all_inputs(input_signals)?;
 
         let output_signal = match gate_type {
@@ -1088,6 +1880,7 @@ impl GateLogic {
             GateType::NorType => GateLogic::calculate_output_for_nor(&input_signals),
             GateType::NandType => GateLogic::calculate_output_for_nand(&input_signals),
             GateType::XOrType => GateLogic::calculate_output_for_xor(&input_signals),
+            GateType::XnorType => GateLogic::calculate_output_for_xnor(&input_signals),
             GateType::ClockType => GateLogic::calculate_output_for_clock(&input_signals),
             GateType::AutomaticInputType => GateLogic::calculate_output_for_automatic_input(&input_signals),
             GateType::SimpleInputType => GateLogic::calculate_output_for_simple_input(&input_signals),
@@ -1289,4 +2082,409 @@ pub fn connect_gates(
         input_index,
         output_signal,
     );
-}
\ No newline at end of file
+}
+
+//Connects every output of source to the input of destination with the same tag. Returns the tags
+// that were connected in source output order.
+#[allow(dead_code)]
+pub fn auto_connect_matching_tags(
+    source: SharedMutex<dyn LogicGate>,
+    destination: SharedMutex<dyn LogicGate>,
+) -> Vec<String> {
+    let source_output_tags = source.lock().unwrap().get_tags(GateTagType::Output);
+    let destination_input_tags = destination.lock().unwrap().get_tags(GateTagType::Input);
+
+    let mut connected_tags = Vec::new();
+    for tag in source_output_tags.into_iter() {
+        if !destination_input_tags.contains(&tag) {
+            continue;
+        }
+
+        let output_index = source.lock().unwrap().get_index_from_tag(tag.as_str());
+        let input_index = destination.lock().unwrap().get_index_from_tag(tag.as_str());
+
+        connect_gates(
+            source.clone(),
+            output_index,
+            destination.clone(),
+            input_index,
+        );
+
+        connected_tags.push(tag);
+    }
+
+    connected_tags
+}
+
+//connect_gates() only records the current output signal on the input, it does not recalculate
+// anything. This is fine while a circuit is being built because it will be primed afterwards. When
+// the circuit has already been primed, the new signal must be pushed through the input gate and
+// anything downstream of it.
+#[allow(dead_code)]
+pub fn connect_gates_live(
+    output_gate: SharedMutex<dyn LogicGate>,
+    output_index: usize,
+    input_gate: SharedMutex<dyn LogicGate>,
+    input_index: usize,
+) {
+    connect_gates(
+        output_gate,
+        output_index,
+        input_gate.clone(),
+        input_index,
+    );
+
+    run_circuit(
+        &vec![input_gate],
+        &Vec::new(),
+        false,
+        &mut |_clock_tick_inputs, _output_gates| {},
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::logic::arithmetic_gates::ArithmeticLogicUnit;
+    use crate::logic::basic_gates::{Not, Or};
+    use crate::logic::input_gates::AutomaticInput;
+    use crate::logic::control_section::ControlSection;
+    use crate::logic::memory_gates::OneBitMemoryCell;
+    use crate::logic::processor_components::VariableDecoder;
+    use crate::shared_mutex::new_shared_mutex;
+    use super::*;
+
+    //NAND_OUT is Not(And(A, B)), NOR_OUT is Not(Or(B, C)) and AND_OUT is And(A, C) which does not
+    // go through a Not.
+    struct NotAndNotOr {
+        complex_gate: ComplexGateMembers,
+    }
+
+    impl NotAndNotOr {
+        fn new() -> SharedMutex<Self> {
+            let input_gates: Vec<SharedMutex<dyn LogicGate>> = vec![
+                SimpleInput::new(2, "A"),
+                SimpleInput::new(2, "B"),
+                SimpleInput::new(2, "C"),
+            ];
+
+            let output_gates_logic: Vec<SharedMutex<SimpleOutput>> = vec![
+                SimpleOutput::new("NAND_OUT"),
+                SimpleOutput::new("NOR_OUT"),
+                SimpleOutput::new("AND_OUT"),
+            ];
+            let output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = output_gates_logic
+                .iter()
+                .map(|output_gate| output_gate.clone() as SharedMutex<dyn LogicGateAndOutputGate>)
+                .collect();
+
+            let and_gate = And::new(2, 1);
+            let and_not_gate = Not::new(1);
+            let or_gate = Or::new(2, 1);
+            let or_not_gate = Not::new(1);
+            let plain_and_gate = And::new(2, 1);
+
+            connect_gates(input_gates[0].clone(), 0, and_gate.clone(), 0);
+            connect_gates(input_gates[1].clone(), 0, and_gate.clone(), 1);
+            connect_gates(and_gate.clone(), 0, and_not_gate.clone(), 0);
+            connect_gates(and_not_gate.clone(), 0, output_gates_logic[0].clone(), 0);
+
+            connect_gates(input_gates[1].clone(), 1, or_gate.clone(), 0);
+            connect_gates(input_gates[2].clone(), 0, or_gate.clone(), 1);
+            connect_gates(or_gate.clone(), 0, or_not_gate.clone(), 0);
+            connect_gates(or_not_gate.clone(), 0, output_gates_logic[1].clone(), 0);
+
+            connect_gates(input_gates[0].clone(), 1, plain_and_gate.clone(), 0);
+            connect_gates(input_gates[2].clone(), 1, plain_and_gate.clone(), 1);
+            connect_gates(plain_and_gate.clone(), 0, output_gates_logic[2].clone(), 0);
+
+            let mut not_and_not_or = NotAndNotOr {
+                complex_gate: ComplexGateMembers::new(
+                    3,
+                    3,
+                    GateType::UnknownType,
+                    input_gates,
+                    output_gates,
+                ),
+            };
+
+            not_and_not_or.complex_gate.calculate_output_from_inputs_and_set_child_count(true);
+
+            new_shared_mutex(not_and_not_or.get_unique_id().id(), not_and_not_or)
+        }
+    }
+
+    impl_complex_logic_gate!(NotAndNotOr);
+
+    fn number_to_signals(number: u64, num_bits: usize) -> Vec<Signal> {
+        (0..num_bits)
+            .map(|i| if (number >> i) & 1 == 1 { HIGH } else { LOW_ })
+            .collect()
+    }
+
+    #[test]
+    fn auto_connect_matching_tags_connects_only_matching() {
+        //The ALU outputs o_n, A_L, EQ, Z, C_OUT, OP_VALID and SHIFT_LOST. Only the flag outputs are also inputs
+        // of the control section.
+        let alu = ArithmeticLogicUnit::new(8).unwrap();
+        let control_section = ControlSection::new(8);
+
+        let connected_tags = auto_connect_matching_tags(alu.clone(), control_section.clone());
+        assert_eq!(connected_tags, vec!["A_L", "EQ", "Z"]);
+
+        let input_tags = control_section.lock().unwrap().get_tags(GateTagType::Input);
+        let input_gates = control_section.lock().unwrap().get_input_gates();
+        for tag in input_tags.iter() {
+            let input_index = control_section.lock().unwrap().get_index_from_tag(tag);
+            let is_connected = input_gates[input_index].lock().unwrap().is_input_connected(0);
+            assert_eq!(is_connected, connected_tags.contains(tag), "tag {}", tag);
+        }
+    }
+
+    #[test]
+    fn is_input_connected_on_basic_and_complex_gates() {
+        let source_gate = Not::new(2);
+        let basic_gate = Not::new(1);
+        let complex_gate = VariableDecoder::new(2);
+
+        let connected_index = complex_gate.lock().unwrap().get_index_from_tag("i_1");
+        let unconnected_index = complex_gate.lock().unwrap().get_index_from_tag("i_0");
+
+        assert!(!basic_gate.lock().unwrap().is_input_connected(0));
+        assert!(!complex_gate.lock().unwrap().is_input_connected(connected_index));
+
+        connect_gates(
+            source_gate.clone(),
+            0,
+            basic_gate.clone(),
+            0,
+        );
+
+        connect_gates(
+            source_gate.clone(),
+            1,
+            complex_gate.clone(),
+            connected_index,
+        );
+
+        assert!(basic_gate.lock().unwrap().is_input_connected(0));
+        assert!(complex_gate.lock().unwrap().is_input_connected(connected_index));
+        assert!(!complex_gate.lock().unwrap().is_input_connected(unconnected_index));
+    }
+
+    #[test]
+    fn is_input_connected_after_disconnect() {
+        let source_gate = AutomaticInput::new(vec![HIGH], 2, "SOURCE");
+        let basic_gate = Not::new(1);
+        let complex_gate = VariableDecoder::new(2);
+
+        let connected_index = complex_gate.lock().unwrap().get_index_from_tag("i_1");
+
+        connect_gates(
+            source_gate.clone(),
+            0,
+            basic_gate.clone(),
+            0,
+        );
+
+        connect_gates(
+            source_gate.clone(),
+            1,
+            complex_gate.clone(),
+            connected_index,
+        );
+
+        source_gate.lock().unwrap().disconnect_gate(0);
+        source_gate.lock().unwrap().disconnect_gate(1);
+
+        //The last signal is kept on the inputs, but nothing is connected to them anymore.
+        assert!(!basic_gate.lock().unwrap().is_input_connected(0));
+        assert!(!complex_gate.lock().unwrap().is_input_connected(connected_index));
+    }
+
+    #[test]
+    fn trace_input_source_reports_connected_gate() {
+        let source_gate = Not::new(1);
+        let destination_gate = VariableDecoder::new(2);
+
+        let connected_index = destination_gate.lock().unwrap().get_index_from_tag("i_1");
+        let unconnected_index = destination_gate.lock().unwrap().get_index_from_tag("i_0");
+
+        connect_gates(
+            source_gate.clone(),
+            0,
+            destination_gate.clone(),
+            connected_index,
+        );
+
+        let source_id = source_gate.lock().unwrap().get_unique_id();
+        let destination_gate = destination_gate.lock().unwrap();
+
+        assert_eq!(destination_gate.complex_gate.trace_input_source(connected_index), Some(source_id));
+        assert_eq!(destination_gate.complex_gate.trace_input_source(unconnected_index), None);
+    }
+
+    #[test]
+    fn describe_gate_output_states() {
+        let not_gate = Not::new(1);
+        let output_gate = SimpleOutput::new("OUT");
+
+        connect_gates(
+            not_gate.clone(),
+            0,
+            output_gate.clone(),
+            0,
+        );
+
+        let output_gate_id = output_gate.lock().unwrap().get_unique_id().id();
+        let connected_output = not_gate.lock().unwrap().fetch_output_signals_calculate().unwrap();
+
+        assert_eq!(
+            connected_output[0].describe(0),
+            format!("out[0]->gate#{}.in[0]=HIGH", output_gate_id)
+        );
+
+        let not_connected_output = GateOutputState::NotConnected(LOW_);
+        assert_eq!(not_connected_output.describe(2), "out[2]=LOW");
+
+        let gate_input = GateInput::new(3, NONE, UniqueID::zero_id());
+        assert_eq!(gate_input.to_string(), "in[3]=NONE");
+    }
+
+    #[test]
+    fn signals_to_i64_negative_values() {
+        //Bit patterns are written most significant bit first.
+        let known_values: Vec<(u64, usize, i64)> = vec![
+            (0b1111_1111, 8, -1),
+            (0b1111_1110, 8, -2),
+            (0b1000_0001, 8, -127),
+            (0b1011, 4, -5),
+            (0b1, 1, -1),
+            (u64::MAX, 64, -1),
+        ];
+
+        for (bits, num_bits, expected) in known_values {
+            let signals = number_to_signals(bits, num_bits);
+            assert_eq!(signals_to_i64(&signals, num_bits), Some(expected));
+        }
+    }
+
+    #[test]
+    fn signals_to_i64_sign_bit_boundary() {
+        let max_positive = number_to_signals(0b0111_1111, 8);
+        assert_eq!(signals_to_u64(&max_positive, 8, Endianness::Little), Some(127));
+        assert_eq!(signals_to_i64(&max_positive, 8), Some(127));
+
+        let min_negative = number_to_signals(0b1000_0000, 8);
+        assert_eq!(signals_to_u64(&min_negative, 8, Endianness::Little), Some(128));
+        assert_eq!(signals_to_i64(&min_negative, 8), Some(-128));
+
+        //Only the first num_bits are used, so the same signals are positive at a narrower width.
+        assert_eq!(signals_to_i64(&min_negative, 7), Some(0));
+    }
+
+    #[test]
+    fn signals_to_i64_none_signal() {
+        let signals = vec![HIGH, NONE, LOW_, HIGH];
+        assert_eq!(signals_to_u64(&signals, 4, Endianness::Little), None);
+        assert_eq!(signals_to_i64(&signals, 4), None);
+    }
+
+    #[test]
+    fn u64_to_signals_endianness() {
+        let little_endian = u64_to_signals(0b1011_0001, 8, Endianness::Little);
+        let big_endian = u64_to_signals(0b1011_0001, 8, Endianness::Big);
+
+        assert_eq!(little_endian, vec![HIGH, LOW_, LOW_, LOW_, HIGH, HIGH, LOW_, HIGH]);
+        assert_eq!(big_endian, vec![HIGH, LOW_, HIGH, HIGH, LOW_, LOW_, LOW_, HIGH]);
+
+        let mut reversed = little_endian.clone();
+        reversed.reverse();
+        assert_eq!(reversed, big_endian);
+    }
+
+    #[test]
+    fn signals_to_u64_endianness_round_trip() {
+        for (value, num_bits) in [(0b1011_0001, 8), (0b0110, 4), (0b1, 1), (u64::MAX, 64)] {
+            for endianness in [Endianness::Little, Endianness::Big] {
+                let signals = u64_to_signals(value, num_bits, endianness);
+                assert_eq!(signals_to_u64(&signals, num_bits, endianness), Some(value));
+            }
+        }
+
+        //Reading signals with the opposite endianness reverses the bits.
+        let signals = u64_to_signals(0b0001, 4, Endianness::Little);
+        assert_eq!(signals_to_u64(&signals, 4, Endianness::Big), Some(0b1000));
+    }
+
+    #[test]
+    fn optimize_to_nand_nor_keeps_outputs() {
+        let original_gate = NotAndNotOr::new();
+        let optimized_gate = NotAndNotOr::new();
+        optimized_gate.lock().unwrap().complex_gate.optimize_to_nand_nor();
+
+        //Both the And and the Or were merged with their Not, the plain And was left alone.
+        assert_eq!(
+            optimized_gate.lock().unwrap().num_children_gates(),
+            original_gate.lock().unwrap().num_children_gates() - 2
+        );
+
+        for inputs in 0..8 {
+            let mut outputs = Vec::new();
+            for gate in [&original_gate, &optimized_gate] {
+                let mut gate = gate.lock().unwrap();
+                for (i, input_signal) in number_to_signals(inputs, 3).into_iter().enumerate() {
+                    gate.update_input_signal(GateInput::new(i, input_signal, UniqueID::zero_id()));
+                }
+
+                let output = gate.fetch_output_signals_calculate().unwrap();
+                outputs.push(extract_signals_from_gate_output_states(&output));
+            }
+
+            let (a, b, c) = (inputs & 1 == 1, inputs & 2 == 2, inputs & 4 == 4);
+            let signal = |value: bool| if value { HIGH } else { LOW_ };
+            assert_eq!(outputs[0], vec![signal(!(a && b)), signal(!(b || c)), signal(a && c)], "inputs {:03b}", inputs);
+            assert_eq!(outputs[0], outputs[1], "inputs {:03b}", inputs);
+        }
+    }
+
+    #[test]
+    fn detect_combinational_loops_skips_latches() {
+        let input_gates: Vec<SharedMutex<dyn LogicGate>> = vec![SimpleInput::new(2, "A")];
+        let output_gate = SimpleOutput::new("O");
+        let output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = vec![output_gate.clone()];
+
+        //The Or feeds back into the And, this is the loop that should be found.
+        let and_gate = And::new(2, 1);
+        let or_gate = Or::new(2, 2);
+        connect_gates(input_gates[0].clone(), 0, and_gate.clone(), 0);
+        connect_gates(and_gate.clone(), 0, or_gate.clone(), 0);
+        connect_gates(or_gate.clone(), 0, and_gate.clone(), 1);
+        connect_gates(or_gate.clone(), 1, output_gate.clone(), 0);
+
+        //The memory cell is built from cross coupled Nand gates and its output is fed back into its
+        // own enable. Neither is a combinational loop.
+        let memory_cell = OneBitMemoryCell::new(1);
+        let not_gate = Not::new(1);
+        let set_index = memory_cell.lock().unwrap().get_index_from_tag("S");
+        let enable_index = memory_cell.lock().unwrap().get_index_from_tag("E");
+        connect_gates(input_gates[0].clone(), 1, memory_cell.clone(), set_index);
+        connect_gates(memory_cell.clone(), 0, not_gate.clone(), 0);
+        connect_gates(not_gate.clone(), 0, memory_cell.clone(), enable_index);
+
+        let complex_gate = ComplexGateMembers::new(
+            1,
+            1,
+            GateType::UnknownType,
+            input_gates,
+            output_gates,
+        );
+
+        let mut expected_loop = vec![
+            and_gate.lock().unwrap().get_unique_id(),
+            or_gate.lock().unwrap().get_unique_id(),
+        ];
+        expected_loop.sort_by_key(|gate_id| gate_id.id());
+
+        assert_eq!(complex_gate.detect_combinational_loops(), vec![expected_loop]);
+    }
+}
@@ -154,6 +154,10 @@ impl LogicGate for Clock {
         true
     }
 
+    fn is_input_connected(&self, _input_index: usize) -> bool {
+        false
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         panic!("Clock never has any input. Passed id {}, passed index {}", connected_id.id(), input_index);
     }
@@ -166,6 +170,19 @@ impl LogicGate for Clock {
         0
     }
 
+    fn num_unique_gates(&self) -> usize {
+        1
+    }
+
+    //Input gates are the source of a signal so nothing is connected to them.
+    fn num_inputs(&self) -> usize {
+        0
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.output_states.len()
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         panic!("Input gates do not have input gates");
     }
@@ -314,6 +331,10 @@ impl LogicGate for AutomaticInput {
         true
     }
 
+    fn is_input_connected(&self, _input_index: usize) -> bool {
+        false
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         panic!("AutomaticInput never has any input. Passed id {}, passed index {}", connected_id.id(), input_index);
     }
@@ -326,6 +347,211 @@ impl LogicGate for AutomaticInput {
         0
     }
 
+    fn num_unique_gates(&self) -> usize {
+        1
+    }
+
+    fn num_inputs(&self) -> usize {
+        0
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.output_states.len()
+    }
+
+    fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
+        panic!("Input gates do not have input gates");
+    }
+}
+
+//Computes its outputs from the tick number each time its output is calculated. The tick number is
+// the number of times the output has been calculated, starting at 0. Each signal returned by
+// calculate_signals drives the output at the same index, so it must always return the same number
+// of signals. Unlike the AutomaticInput it never runs out of values.
+pub struct DynamicInput {
+    calculate_signals: Box<dyn FnMut(usize) -> Vec<Signal> + Send>,
+    next_tick: usize,
+    next_signals: Option<Vec<Signal>>,
+    output_states: Vec<GateOutputState>,
+    unique_id: UniqueID,
+    should_print_output: bool,
+    print_each_input_output_gate: bool,
+    gate_type: GateType,
+    tag: String,
+}
+
+#[allow(dead_code)]
+impl DynamicInput {
+    pub fn new(mut calculate_signals: Box<dyn FnMut(usize) -> Vec<Signal> + Send>, tag: &str) -> SharedMutex<Self> {
+        //The first tick is calculated up front to find the number of outputs. It is used the first
+        // time the output is calculated.
+        let first_signals = calculate_signals(0);
+        assert_ne!(first_signals.len(), 0);
+
+        let dynamic_input = DynamicInput {
+            calculate_signals,
+            next_tick: 0,
+            output_states: first_signals.iter().map(|signal| GateOutputState::NotConnected(signal.clone())).collect(),
+            next_signals: Some(first_signals),
+            unique_id: UniqueID::generate(),
+            should_print_output: false,
+            print_each_input_output_gate: true,
+            gate_type: GateType::DynamicInputType,
+            tag: String::from(tag),
+        };
+
+        new_shared_mutex(dynamic_input.get_unique_id().id(), dynamic_input)
+    }
+
+    //Note that this function makes calls to borrow_mut(). Therefore it cannot be used while running
+    // the circuit, only before or after.
+    pub fn disconnect_gate(
+        &mut self,
+        current_output_index: usize,
+    ) {
+        disconnect_gate(
+            current_output_index,
+            &mut self.output_states,
+            &self.gate_type,
+            self.unique_id,
+            self.tag.as_str(),
+        );
+    }
+
+    fn peek_next_signals(&mut self) -> &Vec<Signal> {
+        if self.next_signals.is_none() {
+            let next_signals = (self.calculate_signals)(self.next_tick);
+            assert_eq!(
+                next_signals.len(),
+                self.output_states.len(),
+                "DynamicInput tag {} returned {} signals on tick {}, expected {}.",
+                self.tag,
+                next_signals.len(),
+                self.next_tick,
+                self.output_states.len(),
+            );
+            self.next_signals = Some(next_signals);
+        }
+
+        self.next_signals.as_ref().unwrap()
+    }
+}
+
+impl LogicGate for DynamicInput {
+    fn internal_connect_output(&mut self, current_gate_output_key: usize, next_gate_input_key: usize, next_gate: SharedMutex<dyn LogicGate>) -> Signal {
+        let signal = self.peek_next_signals()[current_gate_output_key].clone();
+
+        self.output_states[current_gate_output_key] = GateOutputState::Connected(
+            ConnectedOutput {
+                throughput: GateInput::new(
+                    next_gate_input_key,
+                    signal.clone(),
+                    self.unique_id,
+                ),
+                gate: next_gate,
+            }
+        );
+
+        signal
+    }
+
+    fn internal_update_index_to_id(&mut self, _sending_id: UniqueID, _gate_input_index: usize, _signal: Signal) {}
+
+    fn update_input_signal(&mut self, _input: GateInput) -> InputSignalReturn {
+        //Want to return 1 here because run_circuit expects it.
+        InputSignalReturn {
+            changed_count_this_tick: 1,
+            input_signal_updated: false,
+        }
+    }
+
+    fn fetch_output_signals_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
+        self.peek_next_signals();
+        let signals = self.next_signals.take().unwrap();
+        self.next_tick += 1;
+
+        for (output_state, new_signal) in self.output_states.iter_mut().zip(signals) {
+            match output_state {
+                GateOutputState::NotConnected(signal) => {
+                    *signal = new_signal;
+                }
+                GateOutputState::Connected(connected_output) => {
+                    connected_output.throughput.signal = new_signal;
+                }
+            }
+        }
+
+        self.fetch_output_signals_no_calculate()
+    }
+
+    fn fetch_output_signals_no_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
+        let output_clone = self.output_states.clone();
+
+        if self.should_print_output && self.print_each_input_output_gate {
+            GateLogic::print_gate_output(
+                &self.gate_type,
+                &self.unique_id,
+                self.tag.as_str(),
+                &self.next_tick,
+                &output_clone,
+            );
+        }
+
+        Ok(output_clone)
+    }
+
+    fn get_gate_type(&self) -> GateType {
+        self.gate_type
+    }
+
+    fn get_unique_id(&self) -> UniqueID {
+        self.unique_id
+    }
+
+    fn toggle_output_printing(&mut self, print_output: bool) {
+        self.should_print_output = print_output;
+    }
+
+    fn get_tag(&self) -> String {
+        self.tag.clone()
+    }
+
+    fn set_tag(&mut self, tag: &str) {
+        self.tag = tag.to_string()
+    }
+
+    fn is_input_gate(&self) -> bool {
+        true
+    }
+
+    fn is_input_connected(&self, _input_index: usize) -> bool {
+        false
+    }
+
+    fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
+        panic!("DynamicInput never has any input. Passed id {}, passed index {}", connected_id.id(), input_index);
+    }
+
+    fn toggle_print_each_input_output_gate(&mut self, print_each_input_output_gate: bool) {
+        self.print_each_input_output_gate = print_each_input_output_gate;
+    }
+
+    fn num_children_gates(&self) -> usize {
+        0
+    }
+
+    fn num_unique_gates(&self) -> usize {
+        1
+    }
+
+    fn num_inputs(&self) -> usize {
+        0
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.output_states.len()
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         panic!("Input gates do not have input gates");
     }
@@ -429,6 +655,10 @@ impl LogicGate for SimpleInput {
         true
     }
 
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.members.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         self.members.remove_connected_input(
             input_index, connected_id,
@@ -443,6 +673,18 @@ impl LogicGate for SimpleInput {
         0
     }
 
+    fn num_unique_gates(&self) -> usize {
+        1
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.members.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.members.num_outputs()
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         panic!("Input gates do not have input gates");
     }
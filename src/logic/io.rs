@@ -0,0 +1,181 @@
+use crate::logic::foundations::{extract_signals_from_gate_output_states, signals_to_u64, Endianness, LogicGate, Signal};
+use crate::logic::foundations::Signal::{HIGH, LOW_};
+use crate::logic::variable_bit_cpu::VariableBitCPU;
+use crate::shared_mutex::SharedMutex;
+
+//The cpu IO lines during a single clock tick. An OUT instruction (0111 1 d rr) puts register rr on
+// the bus and pulses IO_CLK_S. Bit 2 of the instruction (d) is HIGH when the bus holds a device
+// address and LOW when it holds data for the selected device.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IoBusState {
+    pub cycle: usize,
+    pub io_clk_s: bool,
+    pub address: bool,
+    //None if any bit of the bus is NONE.
+    pub bus: Option<u64>,
+}
+
+#[allow(dead_code)]
+impl IoBusState {
+    pub fn read_from_cpu(cpu: &SharedMutex<VariableBitCPU>) -> Self {
+        let mut cpu = cpu.lock().unwrap();
+        let cpu_output = cpu.fetch_output_signals_no_calculate().unwrap();
+        let cpu_signals = extract_signals_from_gate_output_states(&cpu_output);
+        let gate_tags_to_index = &cpu.get_complex_gate().gate_tags_to_index;
+
+        let signal_for_tag = |tag: &str| cpu_signals[gate_tags_to_index[tag].index].clone();
+
+        let mut bus_signals = Vec::new();
+        while let Some(gate_tag_info) = gate_tags_to_index.get(&format!("{}_{}", VariableBitCPU::BUS, bus_signals.len())) {
+            bus_signals.push(cpu_signals[gate_tag_info.index].clone());
+        }
+
+        IoBusState {
+            cycle: cpu.cycles_since_reset(),
+            io_clk_s: signal_for_tag(VariableBitCPU::IO_CLK_S) == HIGH,
+            address: signal_for_tag(format!("{}_2", VariableBitCPU::IR).as_str()) == HIGH,
+            bus: signals_to_u64(&bus_signals, bus_signals.len(), Endianness::Little),
+        }
+    }
+}
+
+//A device connected to the cpu IO bus, see run_instructions_on_cpu_with_io_devices() inside
+// run_circuit.rs.
+pub trait IoDevice: Send {
+    //Called on every clock tick.
+    fn io_tick(&mut self, io_bus: &IoBusState);
+
+    //Called on every rising edge of the cpu clock.
+    fn clock_cycle(&mut self, cycle: usize);
+
+    //The interrupt request line.
+    #[allow(dead_code)]
+    fn irq(&self) -> Signal;
+}
+
+//Counts down once per clock cycle from the value written to it and raises IRQ when it reaches
+// zero. The device is selected by writing its address with OUT Addr, then the count is written
+// with OUT Data.
+pub struct TimerDevice {
+    address: u64,
+    selected: bool,
+    remaining_cycles: Option<u64>,
+    programmed_cycle: Option<usize>,
+    irq_cycle: Option<usize>,
+}
+
+#[allow(dead_code)]
+impl TimerDevice {
+    pub fn new(address: u64) -> Self {
+        TimerDevice {
+            address,
+            selected: false,
+            remaining_cycles: None,
+            programmed_cycle: None,
+            irq_cycle: None,
+        }
+    }
+
+    //The cycle the count was last written in.
+    pub fn programmed_cycle(&self) -> Option<usize> {
+        self.programmed_cycle
+    }
+
+    //The cycle IRQ was raised in.
+    pub fn irq_cycle(&self) -> Option<usize> {
+        self.irq_cycle
+    }
+
+    pub fn acknowledge_irq(&mut self) {
+        self.irq_cycle = None;
+    }
+}
+
+impl IoDevice for TimerDevice {
+    fn io_tick(&mut self, io_bus: &IoBusState) {
+        if !io_bus.io_clk_s {
+            return;
+        }
+
+        let bus = match io_bus.bus {
+            Some(bus) => bus,
+            None => return,
+        };
+
+        if io_bus.address {
+            self.selected = bus == self.address;
+        } else if self.selected {
+            //IO_CLK_S can be HIGH for more than one tick, writing the same count again is harmless.
+            self.remaining_cycles = Some(bus);
+            self.programmed_cycle = Some(io_bus.cycle);
+            self.irq_cycle = if bus == 0 { Some(io_bus.cycle) } else { None };
+        }
+    }
+
+    fn clock_cycle(&mut self, cycle: usize) {
+        if let Some(remaining_cycles) = self.remaining_cycles.as_mut() {
+            if *remaining_cycles > 0 {
+                *remaining_cycles -= 1;
+                if *remaining_cycles == 0 {
+                    self.irq_cycle = Some(cycle);
+                }
+            }
+        }
+    }
+
+    fn irq(&self) -> Signal {
+        if self.irq_cycle.is_some() {
+            HIGH
+        } else {
+            LOW_
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::run_circuit::run_instructions_on_cpu_with_io_devices;
+    use super::*;
+
+    #[test]
+    fn timer_device_raises_irq() {
+        let number_bits = 8;
+        let decoder_input_size = 2;
+        let timer_address = 3;
+        let countdown_cycles = 10;
+
+        let binary_strings = vec![
+            "00100000", //Data R0
+            "00000011", //3
+            "01111100", //Out Addr R0
+            "00100001", //Data R1
+            "00001010", //10
+            "01111001", //Out Data R1
+            "01100000", //Clf
+            "01100000", //Clf
+            "01100000", //Clf
+            "01100000", //Clf
+            "01100000", //Clf
+            "11001111", //End
+        ];
+
+        let mut timer = TimerDevice::new(timer_address);
+        let mut unselected_timer = TimerDevice::new(timer_address + 1);
+
+        let cpu = VariableBitCPU::new(number_bits, decoder_input_size);
+        run_instructions_on_cpu_with_io_devices(
+            &cpu,
+            decoder_input_size,
+            &binary_strings,
+            &mut [&mut timer, &mut unselected_timer],
+        );
+
+        let programmed_cycle = timer.programmed_cycle().unwrap();
+        assert_eq!(timer.irq(), HIGH);
+        assert_eq!(timer.irq_cycle(), Some(programmed_cycle + countdown_cycles));
+        assert!(timer.irq_cycle().unwrap() < cpu.lock().unwrap().cycles_since_reset());
+
+        assert_eq!(unselected_timer.programmed_cycle(), None);
+        assert_eq!(unselected_timer.irq(), LOW_);
+    }
+}
@@ -1,10 +1,12 @@
 use crate::logic::basic_gates::{Nand, Nor};
-use crate::logic::foundations::{ComplexGateMembers, connect_gates, GateInput, GateLogicError, GateOutputState, GateType, InputSignalReturn, LogicGate, push_reg_outputs_to_output_gates, Signal, UniqueID};
+use crate::logic::foundations::{ComplexGateMembers, connect_gates, extract_signals_from_gate_output_states, GateInput, GateLogicError, GateOutputState, GateCounts, GateType, InputSignalReturn, LogicGate, push_reg_outputs_to_output_gates, Signal, UniqueID, GateTagType};
 use crate::logic::input_gates::SimpleInput;
 use crate::logic::output_gates::{LogicGateAndOutputGate, SimpleOutput};
 
 #[allow(unused_imports)]
 use crate::logic::foundations::Signal::{LOW_, HIGH};
+use crate::logic::netlist::Netlist;
+use crate::run_circuit::run_circuit;
 use crate::shared_mutex::{new_shared_mutex, SharedMutex};
 
 pub struct SRLatch {
@@ -187,6 +189,14 @@ impl LogicGate for SRLatch {
         self.complex_gate.get_index_from_tag(tag)
     }
 
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         self.complex_gate.remove_connected_input(input_index, connected_id);
     }
@@ -199,9 +209,29 @@ impl LogicGate for SRLatch {
         self.complex_gate.simple_gate.number_child_gates
     }
 
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         self.complex_gate.input_gates.clone()
     }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
 }
 
 pub struct ActiveLowSRLatch {
@@ -382,6 +412,14 @@ impl LogicGate for ActiveLowSRLatch {
         self.complex_gate.get_index_from_tag(tag)
     }
 
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         self.complex_gate.remove_connected_input(input_index, connected_id);
     }
@@ -394,9 +432,29 @@ impl LogicGate for ActiveLowSRLatch {
         self.complex_gate.simple_gate.number_child_gates
     }
 
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         self.complex_gate.input_gates.clone()
     }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
 }
 
 pub struct OneBitMemoryCell {
@@ -639,6 +697,14 @@ impl LogicGate for OneBitMemoryCell {
         self.complex_gate.get_index_from_tag(tag)
     }
 
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         self.complex_gate.remove_connected_input(input_index, connected_id);
     }
@@ -651,9 +717,29 @@ impl LogicGate for OneBitMemoryCell {
         self.complex_gate.simple_gate.number_child_gates
     }
 
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         self.complex_gate.input_gates.clone()
     }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
 }
 
 pub struct VariableBitMemoryCell {
@@ -761,6 +847,56 @@ impl VariableBitMemoryCell {
             true,
         );
     }
+
+    //Stores value inside the cell without using the S input. The signals the cell inputs send to
+    // each one bit cell are overridden long enough to latch the value, then restored so the cell
+    // continues to follow its inputs afterwards. Bit 0 is the least significant bit.
+    pub fn overwrite_contents(&mut self, value: &[Signal]) {
+        assert_eq!(value.len(), self.one_bit_memory_cells.len());
+
+        let current_input_signal = |input_gate: &SharedMutex<dyn LogicGate>| {
+            let output = input_gate.lock().unwrap().fetch_output_signals_no_calculate().unwrap();
+            extract_signals_from_gate_output_states(&output)[0].clone()
+        };
+
+        let s_input_gate = self.complex_gate.input_gates[self.get_index_from_tag("S")].clone();
+        let s_input_id = s_input_gate.lock().unwrap().get_unique_id();
+        let s_signal = current_input_signal(&s_input_gate);
+
+        for (i, signal) in value.iter().enumerate() {
+            let input_gate = self.complex_gate.input_gates[i].clone();
+            let input_id = input_gate.lock().unwrap().get_unique_id();
+            let input_signal = current_input_signal(&input_gate);
+
+            let mut cell = self.one_bit_memory_cells[i].lock().unwrap();
+            let set_index = cell.get_index_from_tag("S");
+            let enable_index = cell.get_index_from_tag("E");
+
+            cell.update_input_signal(GateInput::new(set_index, signal.clone(), input_id));
+            cell.update_input_signal(GateInput::new(enable_index, HIGH, s_input_id));
+            cell.fetch_output_signals_calculate().unwrap();
+
+            cell.update_input_signal(GateInput::new(enable_index, s_signal.clone(), s_input_id));
+            cell.fetch_output_signals_calculate().unwrap();
+
+            cell.update_input_signal(GateInput::new(set_index, input_signal, input_id));
+            cell.fetch_output_signals_calculate().unwrap();
+        }
+
+        //Push the new value out to the output gates.
+        let cells: Vec<SharedMutex<dyn LogicGate>> = self.one_bit_memory_cells
+            .iter()
+            .map(|cell| cell.clone() as SharedMutex<dyn LogicGate>)
+            .collect();
+        run_circuit(
+            &cells,
+            &self.complex_gate.output_gates,
+            false,
+            &mut |_clock_tick_inputs, _output_gates| {},
+        );
+
+        self.complex_gate.convert_output_gates_to_output_states();
+    }
 }
 
 impl LogicGate for VariableBitMemoryCell {
@@ -819,6 +955,14 @@ impl LogicGate for VariableBitMemoryCell {
         self.complex_gate.get_index_from_tag(tag)
     }
 
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         self.complex_gate.remove_connected_input(input_index, connected_id);
     }
@@ -831,9 +975,29 @@ impl LogicGate for VariableBitMemoryCell {
         self.complex_gate.simple_gate.number_child_gates
     }
 
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         self.complex_gate.input_gates.clone()
     }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
 }
 
 #[cfg(test)]
@@ -844,7 +1008,7 @@ mod tests {
     use crate::logic::foundations::Signal::{HIGH, LOW_};
     use crate::logic::input_gates::AutomaticInput;
     use crate::logic::output_gates::{LogicGateAndOutputGate, SimpleOutput};
-    use crate::run_circuit::run_circuit;
+    use crate::run_circuit::{feedback_cycles, run_circuit, topological_order};
     use rand::Rng;
     use crate::test_stuff::run_multi_input_output_logic_gate;
     use super::*;
@@ -1052,6 +1216,37 @@ mod tests {
         assert_eq!(collected_output, output_signals);
     }
 
+    #[test]
+    fn sr_latch_feedback_cycle() {
+        let sr_latch = SRLatch::new();
+        let sr_latch = sr_latch.lock().unwrap();
+
+        let top_nor_id = sr_latch.top_nor_gate.lock().unwrap().get_unique_id();
+        let bottom_nor_id = sr_latch.bottom_nor_gate.lock().unwrap().get_unique_id();
+
+        let cycles = feedback_cycles(&*sr_latch);
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].len(), 2);
+        assert!(cycles[0].contains(&top_nor_id));
+        assert!(cycles[0].contains(&bottom_nor_id));
+
+        //The inputs and outputs are still ordered around the loop.
+        let order = topological_order(&*sr_latch);
+        assert!(!order.contains(&top_nor_id));
+        assert!(!order.contains(&bottom_nor_id));
+        assert_eq!(order.len(), sr_latch.complex_gate.input_gates.len() + sr_latch.complex_gate.output_gates.len());
+
+        let last_input_position = sr_latch.complex_gate.input_gates
+            .iter()
+            .map(|gate| order.iter().position(|&id| id == gate.lock().unwrap().get_unique_id()).unwrap())
+            .max()
+            .unwrap();
+        for output_gate in sr_latch.complex_gate.output_gates.iter() {
+            let output_id = output_gate.lock().unwrap().get_unique_id();
+            assert!(last_input_position < order.iter().position(|&id| id == output_id).unwrap());
+        }
+    }
+
     #[test]
     fn sr_gate_initialization() {
         let sr_latch = SRLatch::new();
@@ -0,0 +1,155 @@
+use std::fmt::Write;
+use crate::logic::foundations::GateType;
+
+//A single wire leaving output_index of a gate and entering input_index of the gate with gate_id.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetlistConnection {
+    pub output_index: usize,
+    pub gate_id: usize,
+    pub input_index: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetlistGate {
+    pub id: usize,
+    pub gate_type: GateType,
+    pub tag: String,
+    pub connections: Vec<NetlistConnection>,
+}
+
+//The gates directly inside a complex gate and the connections between them. Complex child gates
+// are listed as a single gate, call netlist() on them to see inside.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Netlist {
+    pub id: usize,
+    pub gate_type: GateType,
+    pub tag: String,
+    pub gates: Vec<NetlistGate>,
+}
+
+impl Netlist {
+    #[allow(dead_code)]
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+
+        write!(
+            json,
+            "{{\"id\":{},\"type\":{},\"tag\":{},\"gates\":[",
+            self.id,
+            json_string(&self.gate_type.to_string()),
+            json_string(&self.tag),
+        ).unwrap();
+
+        for (i, gate) in self.gates.iter().enumerate() {
+            if i != 0 {
+                json.push(',');
+            }
+
+            write!(
+                json,
+                "{{\"id\":{},\"type\":{},\"tag\":{},\"connections\":[",
+                gate.id,
+                json_string(&gate.gate_type.to_string()),
+                json_string(&gate.tag),
+            ).unwrap();
+
+            for (j, connection) in gate.connections.iter().enumerate() {
+                if j != 0 {
+                    json.push(',');
+                }
+
+                write!(
+                    json,
+                    "{{\"output_index\":{},\"gate_id\":{},\"input_index\":{}}}",
+                    connection.output_index,
+                    connection.gate_id,
+                    connection.input_index,
+                ).unwrap();
+            }
+
+            json.push_str("]}");
+        }
+
+        json.push_str("]}");
+        json
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(escaped, "\\u{:04x}", c as u32).unwrap(),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::logic::arithmetic_gates::FullAdder;
+    use crate::logic::basic_gates::Or;
+    use crate::logic::foundations::LogicGate;
+    use super::*;
+
+    #[test]
+    fn full_adder_netlist_json() {
+        let full_adder = FullAdder::new();
+        let netlist = full_adder.lock().unwrap().netlist().unwrap();
+
+        assert_eq!(netlist.gate_type, GateType::FullAdderType);
+
+        let gates_of_type = |gate_type: GateType| {
+            netlist.gates.iter()
+                .filter(|gate| gate.gate_type == gate_type)
+                .collect::<Vec<_>>()
+        };
+
+        let half_adders = gates_of_type(GateType::HalfAdderType);
+        let or_gates = gates_of_type(GateType::OrType);
+        assert_eq!(half_adders.len(), 2);
+        assert_eq!(or_gates.len(), 1);
+
+        let or_id = or_gates[0].id;
+        let half_adder_ids = half_adders.iter().map(|half_adder| half_adder.id).collect::<Vec<_>>();
+
+        //Both half adders carry into the Or gate and one feeds its sum into the other.
+        for half_adder in half_adders.iter() {
+            assert!(half_adder.connections.iter().any(|c| c.gate_id == or_id), "{:?}", half_adder);
+        }
+        assert!(
+            half_adders.iter().any(|half_adder|
+                half_adder.connections.iter().any(|c| half_adder_ids.contains(&c.gate_id))
+            )
+        );
+        assert_eq!(or_gates[0].connections.len(), 1);
+
+        let json = netlist.to_json();
+        assert!(json.starts_with(&format!("{{\"id\":{},\"type\":\"FULL_ADDER\"", netlist.id)), "{}", json);
+        assert_eq!(json.matches("\"type\":\"HALF_ADDER\"").count(), 2, "{}", json);
+        assert_eq!(json.matches("\"type\":\"OR\"").count(), 1, "{}", json);
+        assert!(
+            json.contains(&format!("\"gate_id\":{},\"input_index\":", or_id)),
+            "{}",
+            json
+        );
+    }
+
+    #[test]
+    fn basic_gate_has_no_netlist() {
+        let or_gate = Or::new(2, 1);
+        assert!(or_gate.lock().unwrap().netlist().is_none());
+    }
+
+    #[test]
+    fn json_string_escapes_tags() {
+        assert_eq!(json_string("a\"b\\c\n"), "\"a\\\"b\\\\c\\n\"");
+    }
+}
@@ -1,5 +1,7 @@
 use std::collections::HashMap;
 
+use crate::globals::record_none_output_warning;
+use crate::logic::foundations::Signal::{HIGH, LOW_, NONE};
 use crate::logic::foundations::{GateInput, GateOutputState, LogicGate, UniqueID, GateLogicError, GateType, GateLogic, Signal, OscillationDetection, InputSignalReturn, calculate_input_signal_from_single_inputs};
 use crate::shared_mutex::{new_shared_mutex, SharedMutex};
 
@@ -104,7 +106,15 @@ impl LogicGate for SimpleOutput {
     }
 
     fn fetch_output_signals_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
-        self.fetch_output_signals()
+        let output_states = self.fetch_output_signals()?;
+
+        if let Some(GateOutputState::NotConnected(NONE)) = output_states.first() {
+            record_none_output_warning(
+                format!("Gate {} id {} with tag {} output NONE.", self.gate_type, self.unique_id.id(), self.tag)
+            );
+        }
+
+        Ok(output_states)
     }
 
     fn fetch_output_signals_no_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
@@ -131,6 +141,12 @@ impl LogicGate for SimpleOutput {
         self.tag = tag.to_string()
     }
 
+    fn is_input_connected(&self, _input_index: usize) -> bool {
+        self.output_state
+            .keys()
+            .any(|id| *id != UniqueID::zero_id())
+    }
+
     fn remove_connected_input(&mut self, _input_index: usize, connected_id: UniqueID) {
         self.output_state
             .remove(&connected_id)
@@ -152,7 +168,306 @@ impl LogicGate for SimpleOutput {
         0
     }
 
+    fn num_unique_gates(&self) -> usize {
+        1
+    }
+
+    fn num_inputs(&self) -> usize {
+        1
+    }
+
+    fn num_outputs(&self) -> usize {
+        1
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         panic!("Output gates do not have input gates");
     }
 }
+
+//An output that only updates its visible value on the rising edge of its clock input. Between
+// edges it holds the previous value so sampling it mid-propagation always gives a stable result.
+// Input index 0 is the data and index 1 is the clock.
+pub struct LatchedOutput {
+    data_state: HashMap<UniqueID, Signal>,
+    clock_state: HashMap<UniqueID, Signal>,
+    previous_clock_signal: Signal,
+    latched_signal: Signal,
+    unique_id: UniqueID,
+    oscillation_detection: OscillationDetection,
+    should_print_output: bool,
+    print_each_input_output_gate: bool,
+    gate_type: GateType,
+    tag: String,
+}
+
+#[allow(dead_code)]
+impl LatchedOutput {
+    pub const DATA: usize = 0;
+    pub const CLK: usize = 1;
+
+    pub fn new(tag: &str) -> SharedMutex<Self> {
+        let latched_output = LatchedOutput {
+            data_state: HashMap::from([(UniqueID::zero_id(), LOW_)]),
+            clock_state: HashMap::from([(UniqueID::zero_id(), LOW_)]),
+            previous_clock_signal: LOW_,
+            latched_signal: LOW_,
+            unique_id: UniqueID::generate(),
+            oscillation_detection: OscillationDetection::new(),
+            should_print_output: false,
+            print_each_input_output_gate: true,
+            gate_type: GateType::LatchedOutputType,
+            tag: String::from(tag),
+        };
+        new_shared_mutex(
+            latched_output.get_unique_id().id(),
+            latched_output,
+        )
+    }
+
+    fn input_state(&mut self, gate_input_index: usize) -> &mut HashMap<UniqueID, Signal> {
+        match gate_input_index {
+            LatchedOutput::DATA => &mut self.data_state,
+            LatchedOutput::CLK => &mut self.clock_state,
+            _ => panic!("LatchedOutput only has a data input and a clock input, index {} was used.", gate_input_index),
+        }
+    }
+
+    fn fetch_output_signals(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
+        if self.should_print_output && self.print_each_input_output_gate {
+            GateLogic::print_gate_output(
+                &self.gate_type,
+                &self.unique_id,
+                &self.get_tag(),
+                &None::<Signal>,
+                &self.latched_signal,
+            );
+        }
+
+        Ok(vec![GateOutputState::NotConnected(self.latched_signal.clone())])
+    }
+}
+
+impl OutputGate for LatchedOutput {
+    fn get_output_tag(&self) -> String {
+        self.tag.clone()
+    }
+}
+
+impl LogicGate for LatchedOutput {
+    fn internal_connect_output(
+        &mut self,
+        _current_gate_output_key: usize,
+        _next_gate_input_key: usize,
+        _next_gate: SharedMutex<dyn LogicGate>
+    ) -> Signal {
+        panic!("An output gate should be the end of the circuit, it should never connect to another input.");
+    }
+
+    fn internal_update_index_to_id(&mut self, sending_id: UniqueID, gate_input_index: usize, signal: Signal) {
+        let input_state = self.input_state(gate_input_index);
+
+        //Whenever an input is updated, remove the zero index. Even adding the zero index it will
+        // simply be inserted immediately afterwards.
+        input_state.remove(&UniqueID::zero_id());
+
+        //This is a temporary signal. When the input is updated afterwards, it will add it.
+        input_state.insert(sending_id, signal);
+    }
+
+    fn update_input_signal(&mut self, input: GateInput) -> InputSignalReturn {
+        let changed_count_this_tick = self.oscillation_detection.detect_oscillation(
+            &self.gate_type,
+            &self.unique_id,
+            &self.tag,
+            &input.sending_id,
+        );
+
+        let input_state = self.input_state(input.input_index);
+        let input_signal_updated = if input_state[&input.sending_id] == input.signal {
+            false
+        } else {
+            input_state.insert(input.sending_id, input.signal.clone());
+            true
+        };
+
+        if input_signal_updated && input.input_index == LatchedOutput::CLK {
+            //If the clock is in an unknown state the edge is ignored.
+            let clock_signal = calculate_input_signal_from_single_inputs(&self.clock_state)
+                .unwrap_or(self.previous_clock_signal.clone());
+
+            if self.previous_clock_signal == LOW_ && clock_signal == HIGH {
+                if let Ok(data_signal) = calculate_input_signal_from_single_inputs(&self.data_state) {
+                    self.latched_signal = data_signal;
+                }
+            }
+
+            self.previous_clock_signal = clock_signal;
+        }
+
+        InputSignalReturn {
+            changed_count_this_tick,
+            input_signal_updated,
+        }
+    }
+
+    fn fetch_output_signals_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
+        self.fetch_output_signals()
+    }
+
+    fn fetch_output_signals_no_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
+        self.fetch_output_signals()
+    }
+
+    fn get_gate_type(&self) -> GateType {
+        self.gate_type
+    }
+
+    fn get_unique_id(&self) -> UniqueID {
+        self.unique_id
+    }
+
+    fn toggle_output_printing(&mut self, print_output: bool) {
+        self.should_print_output = print_output;
+    }
+
+    fn get_tag(&self) -> String {
+        self.tag.to_string()
+    }
+
+    fn set_tag(&mut self, tag: &str) {
+        self.tag = tag.to_string()
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        let input_state = match input_index {
+            LatchedOutput::DATA => &self.data_state,
+            LatchedOutput::CLK => &self.clock_state,
+            _ => panic!("LatchedOutput only has a data input and a clock input, index {} was used.", input_index),
+        };
+
+        input_state
+            .keys()
+            .any(|id| *id != UniqueID::zero_id())
+    }
+
+    fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
+        let gate_type = self.gate_type;
+        let unique_id = self.unique_id.id();
+        let tag = self.tag.clone();
+        self.input_state(input_index)
+            .remove(&connected_id)
+            .unwrap_or_else(||
+                panic!(
+                    "When attempting to disconnect a gate, the gate with type {} id {} tag {} was not connected.",
+                    gate_type,
+                    unique_id,
+                    tag
+                )
+            );
+    }
+
+    fn toggle_print_each_input_output_gate(&mut self, print_each_input_output_gate: bool) {
+        self.print_each_input_output_gate = print_each_input_output_gate;
+    }
+
+    fn num_children_gates(&self) -> usize {
+        0
+    }
+
+    fn num_unique_gates(&self) -> usize {
+        1
+    }
+
+    //The data and clock inputs.
+    fn num_inputs(&self) -> usize {
+        2
+    }
+
+    fn num_outputs(&self) -> usize {
+        1
+    }
+
+    fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
+        panic!("Output gates do not have input gates");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::globals::{disable_none_output_warnings, enable_none_output_warnings};
+    use crate::logic::basic_gates::Not;
+    use crate::logic::foundations::{connect_gates, extract_signals_from_gate_output_states};
+    use crate::logic::input_gates::SimpleInput;
+    use super::*;
+
+    fn latched_signal(latched_output: &SharedMutex<LatchedOutput>) -> Signal {
+        let output = latched_output.lock().unwrap().fetch_output_signals_no_calculate().unwrap();
+        extract_signals_from_gate_output_states(&output)[0].clone()
+    }
+
+    #[test]
+    fn latched_output_only_changes_on_clock_edge() {
+        let data_gate = Not::new(1);
+        let clock_gate = SimpleInput::new(1, "CLK");
+        let latched_output = LatchedOutput::new("o");
+
+        connect_gates(data_gate.clone(), 0, latched_output.clone(), LatchedOutput::DATA);
+        connect_gates(clock_gate.clone(), 0, latched_output.clone(), LatchedOutput::CLK);
+
+        let data_id = data_gate.lock().unwrap().get_unique_id();
+        let clock_id = clock_gate.lock().unwrap().get_unique_id();
+
+        let update_input = |input_index: usize, sending_id: UniqueID, signal: Signal| {
+            latched_output.lock().unwrap().update_input_signal(
+                GateInput::new(input_index, signal, sending_id)
+            );
+        };
+
+        //Each data change simulates another propagation pass inside the same clock phase.
+        update_input(LatchedOutput::DATA, data_id, HIGH);
+        assert_eq!(latched_signal(&latched_output), LOW_);
+        update_input(LatchedOutput::DATA, data_id, LOW_);
+        update_input(LatchedOutput::DATA, data_id, HIGH);
+        assert_eq!(latched_signal(&latched_output), LOW_);
+
+        update_input(LatchedOutput::CLK, clock_id, HIGH);
+        assert_eq!(latched_signal(&latched_output), HIGH);
+
+        //Changes while the clock is HIGH are not visible.
+        update_input(LatchedOutput::DATA, data_id, LOW_);
+        assert_eq!(latched_signal(&latched_output), HIGH);
+
+        //The falling edge does not latch either.
+        update_input(LatchedOutput::CLK, clock_id, LOW_);
+        assert_eq!(latched_signal(&latched_output), HIGH);
+
+        update_input(LatchedOutput::CLK, clock_id, HIGH);
+        assert_eq!(latched_signal(&latched_output), LOW_);
+    }
+
+    #[test]
+    fn simple_output_none_warning() {
+        let output_gate = SimpleOutput::new("undriven");
+
+        let update_output = |signal: Signal| {
+            let mut output_gate = output_gate.lock().unwrap();
+            output_gate.update_input_signal(GateInput::new(0, signal, UniqueID::zero_id()));
+            output_gate.fetch_output_signals_calculate().unwrap();
+        };
+
+        //Nothing is recorded until the warnings are enabled.
+        update_output(NONE);
+
+        enable_none_output_warnings();
+        update_output(HIGH);
+        update_output(NONE);
+        let warnings = disable_none_output_warnings();
+
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("undriven"), "{}", warnings[0]);
+
+        update_output(NONE);
+        assert!(disable_none_output_warnings().is_empty());
+    }
+}
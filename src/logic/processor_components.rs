@@ -1,14 +1,16 @@
 use std::time::Instant;
 use crate::logic::basic_gates::{And, ControlledBuffer, Not, Or, Splitter};
-use crate::logic::complex_logic::VariableBitCPUEnable;
-use crate::logic::foundations::{build_simple_inputs_and_outputs, build_simple_inputs_and_outputs_with_and, ComplexGateMembers, connect_gates, GateInput, GateLogicError, GateOutputState, GateType, InputSignalReturn, LogicGate, push_reg_outputs_to_output_gates, Signal, UniqueID};
+use crate::logic::complex_logic::{VariableBitCPUEnable, VariableBitMultiplexer};
+use crate::logic::foundations::{build_simple_inputs_and_outputs, build_simple_inputs_and_outputs_with_and, calculate_input_signals_from_all_inputs, ComplexGateMembers, connect_gates, extract_signals_from_gate_output_states, GateInput, GateLogicError, GateOutputState, GateCounts, GateType, InputSignalReturn, LogicGate, push_reg_outputs_to_output_gates, Signal, UniqueID, GateTagType, impl_complex_logic_gate};
 use crate::logic::input_gates::SimpleInput;
 use crate::logic::output_gates::{LogicGateAndOutputGate, SimpleOutput};
 
 #[allow(unused_imports)]
 use crate::logic::foundations::Signal::{LOW_, HIGH};
-use crate::logic::memory_gates::VariableBitMemoryCell;
+use crate::logic::memory_gates::{OneBitMemoryCell, VariableBitMemoryCell};
+use crate::logic::netlist::Netlist;
 use crate::RAM_TIME;
+use crate::run_circuit::run_circuit;
 use crate::shared_mutex::{new_shared_mutex, SharedMutex};
 
 pub struct VariableBitRegister {
@@ -140,6 +142,55 @@ impl VariableBitRegister {
             true,
         );
     }
+
+    //Stores value inside the register without using the S input. The signals the register inputs
+    // send to the memory cell are overridden long enough to latch the value, then restored so the
+    // register continues to follow its inputs afterwards. Bit 0 is the least significant bit.
+    pub fn overwrite_contents(&mut self, value: &[Signal]) {
+        let number_bits = self.complex_gate.input_gates.len() - 2;
+        assert_eq!(value.len(), number_bits);
+
+        let current_input_signal = |input_gate: &SharedMutex<dyn LogicGate>| {
+            let output = input_gate.lock().unwrap().fetch_output_signals_no_calculate().unwrap();
+            extract_signals_from_gate_output_states(&output)[0].clone()
+        };
+
+        let s_input_gate = self.complex_gate.input_gates[self.get_index_from_tag("S")].clone();
+        let s_input_id = s_input_gate.lock().unwrap().get_unique_id();
+        let s_signal = current_input_signal(&s_input_gate);
+
+        let mut memory = self.memory.lock().unwrap();
+        let memory_set_index = memory.get_index_from_tag("S");
+
+        for (i, signal) in value.iter().enumerate() {
+            let input_id = self.complex_gate.input_gates[i].lock().unwrap().get_unique_id();
+            memory.update_input_signal(GateInput::new(i, signal.clone(), input_id));
+        }
+        memory.update_input_signal(GateInput::new(memory_set_index, HIGH, s_input_id));
+        memory.fetch_output_signals_calculate().unwrap();
+
+        memory.update_input_signal(GateInput::new(memory_set_index, s_signal, s_input_id));
+        memory.fetch_output_signals_calculate().unwrap();
+
+        for i in 0..number_bits {
+            let input_gate = self.complex_gate.input_gates[i].clone();
+            let input_id = input_gate.lock().unwrap().get_unique_id();
+            memory.update_input_signal(GateInput::new(i, current_input_signal(&input_gate), input_id));
+        }
+        memory.fetch_output_signals_calculate().unwrap();
+
+        drop(memory);
+
+        //Push the new value through the enable and out to the output gates.
+        run_circuit(
+            &vec![self.memory.clone()],
+            &self.complex_gate.output_gates,
+            false,
+            &mut |_clock_tick_inputs, _output_gates| {},
+        );
+
+        self.complex_gate.convert_output_gates_to_output_states();
+    }
 }
 
 impl LogicGate for VariableBitRegister {
@@ -197,6 +248,14 @@ impl LogicGate for VariableBitRegister {
         self.complex_gate.get_index_from_tag(tag)
     }
 
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         self.complex_gate.remove_connected_input(input_index, connected_id);
     }
@@ -209,15 +268,38 @@ impl LogicGate for VariableBitRegister {
         self.complex_gate.simple_gate.number_child_gates
     }
 
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         self.complex_gate.input_gates.clone()
     }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
 }
 
 pub struct VariableDecoder {
     pub complex_gate: ComplexGateMembers,
     and_gates: Vec<SharedMutex<And>>,
     not_gates: Vec<SharedMutex<Not>>,
+    //The input signals the outputs were last calculated from. None means the outputs must be
+    // recalculated on the next fetch.
+    cached_input_signals: Option<Vec<Signal>>,
 }
 
 #[allow(dead_code)]
@@ -263,6 +345,7 @@ impl VariableDecoder {
             ),
             and_gates,
             not_gates,
+            cached_input_signals: None,
         };
 
         decoder.build_and_prime_circuit(number_inputs, number_outputs, output_gates_logic);
@@ -328,6 +411,15 @@ impl VariableDecoder {
             true,
         );
     }
+
+    pub fn get_cached_input_signals(&self) -> Option<Vec<Signal>> {
+        self.cached_input_signals.clone()
+    }
+
+    //Forces the outputs to be recalculated on the next fetch.
+    pub fn invalidate_cache(&mut self) {
+        self.cached_input_signals = None;
+    }
 }
 
 impl LogicGate for VariableDecoder {
@@ -341,19 +433,38 @@ impl LogicGate for VariableDecoder {
     }
 
     fn internal_update_index_to_id(&mut self, sending_id: UniqueID, gate_input_index: usize, signal: Signal) {
+        self.invalidate_cache();
         self.complex_gate.internal_update_index_to_id(sending_id, gate_input_index, signal);
     }
 
     fn update_input_signal(&mut self, input: GateInput) -> InputSignalReturn {
         //ActiveLowSRLatch has an `invalid` state of LOW LOW. However, this is not being enforced by
         // assertions because it may be an intermediate state.
+
+        //The same signals are sent every clock tick, only an actual change needs a recalculation.
+        let input_signals = &self.complex_gate.simple_gate.input_signals[input.input_index];
+        if input_signals.get(&input.sending_id) != Some(&input.signal) {
+            self.invalidate_cache();
+        }
+
         self.complex_gate.update_input_signal(input)
     }
 
     fn fetch_output_signals_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
-        self.complex_gate.fetch_output_signals_calculate(
+        if self.cached_input_signals.is_some() {
+            return self.fetch_output_signals_no_calculate();
+        }
+
+        let output = self.complex_gate.fetch_output_signals_calculate(
             &self.get_tag(),
-        )
+        );
+
+        //If the inputs cannot be resolved to a single signal each, nothing is cached.
+        self.cached_input_signals = calculate_input_signals_from_all_inputs(
+            &self.complex_gate.simple_gate.input_signals
+        ).ok();
+
+        output
     }
 
     fn fetch_output_signals_no_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
@@ -386,7 +497,16 @@ impl LogicGate for VariableDecoder {
         self.complex_gate.get_index_from_tag(tag)
     }
 
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
+        self.invalidate_cache();
         self.complex_gate.remove_connected_input(input_index, connected_id);
     }
 
@@ -398,9 +518,29 @@ impl LogicGate for VariableDecoder {
         self.complex_gate.simple_gate.number_child_gates
     }
 
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         self.complex_gate.input_gates.clone()
     }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
 }
 
 pub struct SingleRAMCell {
@@ -607,6 +747,22 @@ impl SingleRAMCell {
             true,
         );
     }
+
+    //Stores value inside the cell without using the H, V and S inputs. Bit 0 is the least
+    // significant bit.
+    pub fn overwrite_contents(&mut self, value: &[Signal]) {
+        self.register.lock().unwrap().overwrite_contents(value);
+
+        //Push the new value out to the reg outputs and through the controlled buffer.
+        run_circuit(
+            &vec![self.register.clone()],
+            &self.complex_gate.output_gates,
+            false,
+            &mut |_clock_tick_inputs, _output_gates| {},
+        );
+
+        self.complex_gate.convert_output_gates_to_output_states();
+    }
 }
 
 impl LogicGate for SingleRAMCell {
@@ -665,6 +821,14 @@ impl LogicGate for SingleRAMCell {
         self.complex_gate.get_index_from_tag(tag)
     }
 
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         self.complex_gate.remove_connected_input(input_index, connected_id);
     }
@@ -677,9 +841,38 @@ impl LogicGate for SingleRAMCell {
         self.complex_gate.simple_gate.number_child_gates
     }
 
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         self.complex_gate.input_gates.clone()
     }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum MemoryLoadError {
+    /// More values were given than the RAM has addresses. Holds the number of values given and the
+    /// number of addresses.
+    TooManyAddresses(usize, usize),
+    /// A value is not the same width as the bus. Holds the address and the width of the value.
+    WrongWidth(usize, usize),
 }
 
 pub struct RAMUnit {
@@ -691,6 +884,7 @@ pub struct RAMUnit {
     vertical_decoder_splitter: SharedMutex<Splitter>,
     controlled_buffer: SharedMutex<ControlledBuffer>,
     ram_cells: Vec<SharedMutex<SingleRAMCell>>,
+    num_bits: usize,
 }
 
 #[allow(dead_code)]
@@ -700,6 +894,61 @@ impl RAMUnit {
         format!("cell_{}_bit_{}", ram_cell_index, bit_index)
     }
 
+    pub fn new_zero_filled(bus_size_in_bits: usize, decoder_input_size: usize) -> SharedMutex<Self> {
+        let ram_unit = Self::new(bus_size_in_bits, decoder_input_size);
+        ram_unit.lock().unwrap().zero_fill_ram();
+        ram_unit
+    }
+
+    //Returns the address currently latched inside the memory address register. Bit 0 is the least
+    // significant bit.
+    pub fn read_memory_address(&self) -> Vec<Signal> {
+        let mar_output = self.memory_address_register.lock().unwrap().fetch_output_signals_no_calculate().unwrap();
+        let mut mar_signals = extract_signals_from_gate_output_states(&mar_output);
+
+        //The o_n outputs are followed by the reg_n outputs which hold the same bits.
+        mar_signals.truncate(mar_signals.len() / 2);
+        mar_signals
+    }
+
+    //Stores contents[i] inside RAM address i without clocking it in through the memory address
+    // register and the bus. Nothing is stored unless every value is valid. Bit 0 of each value is
+    // the least significant bit.
+    pub fn load_memory(&mut self, contents: &[Vec<Signal>]) -> Result<(), MemoryLoadError> {
+        if contents.len() > self.ram_cells.len() {
+            return Err(MemoryLoadError::TooManyAddresses(contents.len(), self.ram_cells.len()));
+        }
+
+        if let Some((address, value)) = contents.iter().enumerate().find(|(_, value)| value.len() != self.num_bits) {
+            return Err(MemoryLoadError::WrongWidth(address, value.len()));
+        }
+
+        let mut loaded_cells: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+        for (address, value) in contents.iter().enumerate() {
+            self.ram_cells[address].lock().unwrap().overwrite_contents(value);
+            loaded_cells.push(self.ram_cells[address].clone());
+        }
+
+        //Push the new values out to the cell outputs of the RAMUnit.
+        run_circuit(
+            &loaded_cells,
+            &self.complex_gate.output_gates,
+            false,
+            &mut |_clock_tick_inputs, _output_gates| {},
+        );
+
+        self.complex_gate.convert_output_gates_to_output_states();
+
+        Ok(())
+    }
+
+    //Sets the latch inside every RAM cell to LOW. The cells are overwritten directly the same way
+    // load_memory() does it, so this works after the RAMUnit has been connected to a bus.
+    pub fn zero_fill_ram(&mut self) {
+        let contents = vec![vec![LOW_; self.num_bits]; self.ram_cells.len()];
+        self.load_memory(&contents).unwrap();
+    }
+
     pub fn new(bus_size_in_bits: usize, decoder_input_size: usize) -> SharedMutex<Self> {
         assert_ne!(bus_size_in_bits, 0);
         assert_ne!(decoder_input_size, 0);
@@ -770,6 +1019,7 @@ impl RAMUnit {
             vertical_decoder_splitter: Splitter::new(num_ram_cells_in_row, num_ram_cells_in_row),
             controlled_buffer: ControlledBuffer::new(bus_size_in_bits),
             ram_cells,
+            num_bits: bus_size_in_bits,
         };
 
         ram_cell.memory_address_register.lock().unwrap().set_tag("memory_address_register");
@@ -1036,6 +1286,14 @@ impl LogicGate for RAMUnit {
         self.complex_gate.get_index_from_tag(tag)
     }
 
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         self.complex_gate.remove_connected_input(input_index, connected_id);
     }
@@ -1048,123 +1306,226 @@ impl LogicGate for RAMUnit {
         self.complex_gate.simple_gate.number_child_gates
     }
 
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         self.complex_gate.input_gates.clone()
     }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
 }
 
 //This is a higher level thing for the CPU to connect to and add one.
-pub struct VariableBitBusOne {
+//RAM with one write port and one read port that are addressed independently. Unlike RAMUnit
+// there is no memory address register, the addresses are decoded directly from the inputs.
+pub struct DualPortRam {
     complex_gate: ComplexGateMembers,
-    and_gates: Vec<SharedMutex<And>>,
-    or_gate: SharedMutex<Or>,
-    not_gate: SharedMutex<Not>,
+    write_decoder: SharedMutex<VariableDecoder>,
+    read_decoder: SharedMutex<VariableDecoder>,
+    write_and_gates: Vec<SharedMutex<And>>,
+    memory_cells: Vec<SharedMutex<VariableBitMemoryCell>>,
+    read_controlled_buffers: Vec<SharedMutex<ControlledBuffer>>,
+    controlled_buffer: SharedMutex<ControlledBuffer>,
 }
 
 #[allow(dead_code)]
-impl VariableBitBusOne {
-    pub fn new(number_bits: usize) -> SharedMutex<Self> {
-        assert_ne!(number_bits, 0);
+impl DualPortRam {
+    pub const WRITE_ADDRESS: &'static str = "w_addr";
+    pub const READ_ADDRESS: &'static str = "r_addr";
+
+    //Inputs are the write data bus i_{n}, the write address w_addr_{n}, the read address r_addr_{n},
+    // S which saves the write data bus to the write address and E which enables the read data bus
+    // o_{n}.
+    pub fn new(address_bits: usize, data_bits: usize) -> SharedMutex<Self> {
+        assert_ne!(address_bits, 0);
+        assert_ne!(data_bits, 0);
+
+        let num_ram_cells = usize::pow(2, address_bits as u32);
 
         let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
         let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
         let mut output_gates_logic: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
 
-        let mut and_gates = Vec::new();
+        for i in 0..data_bits {
+            let input_tag = format!("i_{}", i);
+            input_gates.push(SimpleInput::new(num_ram_cells, input_tag.as_str()));
 
-        build_simple_inputs_and_outputs_with_and(
-            number_bits,
-            &mut input_gates,
-            &mut output_gates,
-            &mut output_gates_logic,
-            &mut and_gates,
-        );
+            let output_tag = format!("o_{}", i);
+            let output_gate = SimpleOutput::new(output_tag.as_str());
+            output_gates.push(output_gate.clone());
+            output_gates_logic.push(output_gate);
+        }
 
-        and_gates.pop();
+        for address_tag in [Self::WRITE_ADDRESS, Self::READ_ADDRESS] {
+            for i in 0..address_bits {
+                let input_tag = format!("{}_{}", address_tag, i);
+                input_gates.push(SimpleInput::new(1, input_tag.as_str()));
+            }
+        }
 
-        let set_input_gate = SimpleInput::new(2, "BUS_1");
+        input_gates.push(SimpleInput::new(num_ram_cells, "S"));
+        input_gates.push(SimpleInput::new(1, "E"));
 
-        input_gates.push(set_input_gate.clone());
+        let mut write_and_gates = Vec::with_capacity(num_ram_cells);
+        let mut memory_cells = Vec::with_capacity(num_ram_cells);
+        let mut read_controlled_buffers = Vec::with_capacity(num_ram_cells);
+        for i in 0..num_ram_cells {
+            write_and_gates.push(And::new(2, 1));
 
-        let mut bit_register = VariableBitBusOne {
+            let memory_cell = VariableBitMemoryCell::new(data_bits);
+            memory_cell.lock().unwrap().set_tag(format!("memory_cell_{}", i).as_str());
+            memory_cells.push(memory_cell);
+
+            read_controlled_buffers.push(ControlledBuffer::new(data_bits));
+        }
+
+        let mut dual_port_ram = DualPortRam {
             complex_gate: ComplexGateMembers::new(
-                number_bits + 1,
-                number_bits,
-                GateType::VariableBitBusOneType,
+                data_bits + address_bits * 2 + 2,
+                data_bits,
+                GateType::DualPortRamType,
                 input_gates,
                 output_gates,
             ),
-            and_gates,
-            or_gate: Or::new(2, 1),
-            not_gate: Not::new(number_bits - 1),
+            write_decoder: VariableDecoder::new(address_bits),
+            read_decoder: VariableDecoder::new(address_bits),
+            write_and_gates,
+            memory_cells,
+            read_controlled_buffers,
+            controlled_buffer: ControlledBuffer::new(data_bits),
         };
 
-        bit_register.build_and_prime_circuit(number_bits, output_gates_logic);
+        dual_port_ram.write_decoder.lock().unwrap().set_tag("write_decoder");
+        dual_port_ram.read_decoder.lock().unwrap().set_tag("read_decoder");
+        dual_port_ram.controlled_buffer.lock().unwrap().set_tag("controlled_buffer");
 
-        new_shared_mutex(bit_register.get_unique_id().id(), bit_register)
+        dual_port_ram.build_and_prime_circuit(
+            address_bits,
+            data_bits,
+            num_ram_cells,
+            output_gates_logic,
+        );
+
+        new_shared_mutex(dual_port_ram.get_unique_id().id(), dual_port_ram)
     }
 
     fn build_and_prime_circuit(
         &mut self,
-        number_bits: usize,
+        address_bits: usize,
+        data_bits: usize,
+        num_ram_cells: usize,
         output_gates: Vec<SharedMutex<dyn LogicGate>>,
     ) {
-        let bus_one_input = self.complex_gate.input_gates[self.get_index_from_tag("BUS_1")].clone();
+        let set_input_gate = self.complex_gate.input_gates[self.get_index_from_tag("S")].clone();
+        let enable_input_gate = self.complex_gate.input_gates[self.get_index_from_tag("E")].clone();
 
-        connect_gates(
-            bus_one_input.clone(),
-            0,
-            self.not_gate.clone(),
-            0,
-        );
+        for i in 0..address_bits {
+            let write_address_index = self.get_index_from_tag(format!("{}_{}", Self::WRITE_ADDRESS, i).as_str());
+            connect_gates(
+                self.complex_gate.input_gates[write_address_index].clone(),
+                0,
+                self.write_decoder.clone(),
+                i,
+            );
 
-        for i in 0..number_bits {
+            let read_address_index = self.get_index_from_tag(format!("{}_{}", Self::READ_ADDRESS, i).as_str());
+            connect_gates(
+                self.complex_gate.input_gates[read_address_index].clone(),
+                0,
+                self.read_decoder.clone(),
+                i,
+            );
+        }
 
-            if i == 0 {
-                connect_gates(
-                    self.complex_gate.input_gates[i].clone(),
-                    0,
-                    self.or_gate.clone(),
-                    0,
-                );
+        for i in 0..num_ram_cells {
+            connect_gates(
+                self.write_decoder.clone(),
+                i,
+                self.write_and_gates[i].clone(),
+                0,
+            );
 
-                connect_gates(
-                    bus_one_input.clone(),
-                    1,
-                    self.or_gate.clone(),
-                    1,
-                );
+            connect_gates(
+                set_input_gate.clone(),
+                i,
+                self.write_and_gates[i].clone(),
+                1,
+            );
 
+            let memory_cell_set_index = self.memory_cells[i].lock().unwrap().get_index_from_tag("S");
+            connect_gates(
+                self.write_and_gates[i].clone(),
+                0,
+                self.memory_cells[i].clone(),
+                memory_cell_set_index,
+            );
+
+            let read_buffer_enable_index = self.read_controlled_buffers[i].lock().unwrap().get_index_from_tag("E");
+            connect_gates(
+                self.read_decoder.clone(),
+                i,
+                self.read_controlled_buffers[i].clone(),
+                read_buffer_enable_index,
+            );
+
+            for j in 0..data_bits {
                 connect_gates(
-                    self.or_gate.clone(),
-                    0,
-                    output_gates[i].clone(),
-                    0,
-                );
-            } else {
-                connect_gates(
-                    self.complex_gate.input_gates[i].clone(),
-                    0,
-                    self.and_gates[i - 1].clone(),
-                    0,
+                    self.complex_gate.input_gates[j].clone(),
+                    i,
+                    self.memory_cells[i].clone(),
+                    j,
                 );
 
+                let memory_cell_output_index = self.memory_cells[i].lock().unwrap().get_index_from_tag(format!("o_{}", j).as_str());
                 connect_gates(
-                    self.not_gate.clone(),
-                    i - 1,
-                    self.and_gates[i - 1].clone(),
-                    1,
+                    self.memory_cells[i].clone(),
+                    memory_cell_output_index,
+                    self.read_controlled_buffers[i].clone(),
+                    j,
                 );
 
                 connect_gates(
-                    self.and_gates[i - 1].clone(),
-                    0,
-                    output_gates[i].clone(),
-                    0,
+                    self.read_controlled_buffers[i].clone(),
+                    j,
+                    self.controlled_buffer.clone(),
+                    j,
                 );
             }
         }
 
+        let controlled_buffer_enable_index = self.controlled_buffer.lock().unwrap().get_index_from_tag("E");
+        connect_gates(
+            enable_input_gate,
+            0,
+            self.controlled_buffer.clone(),
+            controlled_buffer_enable_index,
+        );
+
+        for (j, output_gate) in output_gates.into_iter().enumerate() {
+            connect_gates(
+                self.controlled_buffer.clone(),
+                j,
+                output_gate,
+                0,
+            );
+        }
+
         //Prime gates
         self.complex_gate.calculate_output_from_inputs_and_set_child_count(
             true,
@@ -1172,7 +1533,7 @@ impl VariableBitBusOne {
     }
 }
 
-impl LogicGate for VariableBitBusOne {
+impl LogicGate for DualPortRam {
     fn internal_connect_output(&mut self, current_gate_output_key: usize, next_gate_input_key: usize, next_gate: SharedMutex<dyn LogicGate>) -> Signal {
         self.complex_gate.connect_output(
             self.get_unique_id(),
@@ -1197,7 +1558,6 @@ impl LogicGate for VariableBitBusOne {
             &self.get_tag(),
         )
     }
-
     fn fetch_output_signals_no_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
         self.complex_gate.fetch_output_signals_no_calculate(
             &self.get_tag(),
@@ -1228,6 +1588,14 @@ impl LogicGate for VariableBitBusOne {
         self.complex_gate.get_index_from_tag(tag)
     }
 
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         self.complex_gate.remove_connected_input(input_index, connected_id);
     }
@@ -1240,8 +1608,438 @@ impl LogicGate for VariableBitBusOne {
         self.complex_gate.simple_gate.number_child_gates
     }
 
-    fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
-        self.complex_gate.input_gates.clone()
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
+    fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
+        self.complex_gate.input_gates.clone()
+    }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
+}
+
+//A register that either loads the data bus i_{n} in parallel or shifts one bit in from SER_IN,
+// depending on LOAD. Each bit is a master slave stage so the register changes once each time CLK
+// falls. While shifting every bit moves down by one, SER_IN enters at the top bit and the bottom
+// bit o_0 is also output on SER_OUT. This means a loaded value leaves SER_OUT least significant bit
+// first. Every bit starts LOW.
+pub struct ShiftRegister {
+    complex_gate: ComplexGateMembers,
+    master_cells: Vec<SharedMutex<OneBitMemoryCell>>,
+    slave_cells: Vec<SharedMutex<OneBitMemoryCell>>,
+    next_value_multiplexer: SharedMutex<VariableBitMultiplexer>,
+    clk_not: SharedMutex<Not>,
+}
+
+#[allow(dead_code)]
+impl ShiftRegister {
+    pub const LOAD: &'static str = "LOAD";
+    pub const SER_IN: &'static str = "SER_IN";
+    pub const SER_OUT: &'static str = "SER_OUT";
+    pub const CLK: &'static str = "CLK";
+
+    pub fn new(num_bits: usize) -> SharedMutex<Self> {
+        assert_ne!(num_bits, 0);
+
+        let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+        let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
+        let mut output_gates_logic: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+        let mut master_cells = Vec::new();
+        let mut slave_cells = Vec::new();
+
+        for i in 0..num_bits {
+            let input_tag = format!("i_{}", i);
+            input_gates.push(SimpleInput::new(1, input_tag.as_str()));
+
+            let output_tag = format!("o_{}", i);
+            let output_gate = SimpleOutput::new(output_tag.as_str());
+            output_gates.push(output_gate.clone());
+            output_gates_logic.push(output_gate);
+
+            //Each slave drives its output and the bit below it, the bottom slave drives SER_OUT.
+            master_cells.push(OneBitMemoryCell::new(1));
+            slave_cells.push(OneBitMemoryCell::new(2));
+        }
+
+        input_gates.push(SimpleInput::new(1, ShiftRegister::SER_IN));
+        input_gates.push(SimpleInput::new(1, ShiftRegister::LOAD));
+
+        //Each master cell and the Not gate that drives the slave cells.
+        input_gates.push(SimpleInput::new(num_bits + 1, ShiftRegister::CLK));
+
+        let ser_out_gate = SimpleOutput::new(ShiftRegister::SER_OUT);
+        output_gates.push(ser_out_gate.clone());
+        output_gates_logic.push(ser_out_gate);
+
+        let mut shift_register = ShiftRegister {
+            complex_gate: ComplexGateMembers::new(
+                num_bits + 3,
+                num_bits + 1,
+                GateType::ShiftRegisterType,
+                input_gates,
+                output_gates,
+            ),
+            master_cells,
+            slave_cells,
+            next_value_multiplexer: VariableBitMultiplexer::new(num_bits, 2),
+            clk_not: Not::new(num_bits),
+        };
+
+        shift_register.build_and_prime_circuit(num_bits, output_gates_logic);
+
+        new_shared_mutex(shift_register.get_unique_id().id(), shift_register)
+    }
+
+    fn build_and_prime_circuit(
+        &mut self,
+        num_bits: usize,
+        output_gates: Vec<SharedMutex<dyn LogicGate>>,
+    ) {
+        let clk_input = self.complex_gate.input_gates[self.get_index_from_tag(ShiftRegister::CLK)].clone();
+        let load_input = self.complex_gate.input_gates[self.get_index_from_tag(ShiftRegister::LOAD)].clone();
+        let ser_in_input = self.complex_gate.input_gates[self.get_index_from_tag(ShiftRegister::SER_IN)].clone();
+
+        connect_gates(
+            clk_input.clone(),
+            num_bits,
+            self.clk_not.clone(),
+            0,
+        );
+
+        //LOAD HIGH selects the data bus, LOW selects the shifted bits.
+        let multiplexer_control_index = self.next_value_multiplexer.lock().unwrap().get_index_from_tag("C_0");
+        connect_gates(
+            load_input,
+            0,
+            self.next_value_multiplexer.clone(),
+            multiplexer_control_index,
+        );
+
+        let top_shift_index = self.next_value_multiplexer.lock().unwrap().get_index_from_tag(format!("I_0_bit_{}", num_bits - 1).as_str());
+        connect_gates(
+            ser_in_input,
+            0,
+            self.next_value_multiplexer.clone(),
+            top_shift_index,
+        );
+
+        for i in 0..num_bits {
+            let master_enable_index = self.master_cells[i].lock().unwrap().get_index_from_tag("E");
+            let master_set_index = self.master_cells[i].lock().unwrap().get_index_from_tag("S");
+            let master_output_index = self.master_cells[i].lock().unwrap().get_index_from_tag("Q");
+            let slave_enable_index = self.slave_cells[i].lock().unwrap().get_index_from_tag("E");
+            let slave_set_index = self.slave_cells[i].lock().unwrap().get_index_from_tag("S");
+            let slave_output_index = self.slave_cells[i].lock().unwrap().get_index_from_tag("Q");
+            let slave_shift_output_index = self.slave_cells[i].lock().unwrap().get_index_from_tag("Q_1");
+
+            //Data bus -> Multiplexer -> Master
+            let input_index = self.get_index_from_tag(format!("i_{}", i).as_str());
+            let multiplexer_input_index = self.next_value_multiplexer.lock().unwrap().get_index_from_tag(format!("I_1_bit_{}", i).as_str());
+            connect_gates(
+                self.complex_gate.input_gates[input_index].clone(),
+                0,
+                self.next_value_multiplexer.clone(),
+                multiplexer_input_index,
+            );
+
+            let multiplexer_output_index = self.next_value_multiplexer.lock().unwrap().get_index_from_tag(format!("o_{}", i).as_str());
+            connect_gates(
+                self.next_value_multiplexer.clone(),
+                multiplexer_output_index,
+                self.master_cells[i].clone(),
+                master_set_index,
+            );
+
+            //Masters load while CLK is HIGH and the slaves copy them once CLK is LOW.
+            connect_gates(
+                clk_input.clone(),
+                i,
+                self.master_cells[i].clone(),
+                master_enable_index,
+            );
+
+            connect_gates(
+                self.clk_not.clone(),
+                i,
+                self.slave_cells[i].clone(),
+                slave_enable_index,
+            );
+
+            connect_gates(
+                self.master_cells[i].clone(),
+                master_output_index,
+                self.slave_cells[i].clone(),
+                slave_set_index,
+            );
+
+            let output_index = self.get_index_from_tag(format!("o_{}", i).as_str());
+            connect_gates(
+                self.slave_cells[i].clone(),
+                slave_output_index,
+                output_gates[output_index].clone(),
+                0,
+            );
+
+            //Slave -> Multiplexer shift input of the bit below or SER_OUT
+            if i == 0 {
+                let ser_out_index = self.get_index_from_tag(ShiftRegister::SER_OUT);
+                connect_gates(
+                    self.slave_cells[i].clone(),
+                    slave_shift_output_index,
+                    output_gates[ser_out_index].clone(),
+                    0,
+                );
+            } else {
+                let shift_input_index = self.next_value_multiplexer.lock().unwrap().get_index_from_tag(format!("I_0_bit_{}", i - 1).as_str());
+                connect_gates(
+                    self.slave_cells[i].clone(),
+                    slave_shift_output_index,
+                    self.next_value_multiplexer.clone(),
+                    shift_input_index,
+                );
+            }
+        }
+
+        //Prime gates
+        self.complex_gate.calculate_output_from_inputs_and_set_child_count(
+            true,
+        );
+    }
+}
+
+impl_complex_logic_gate!(ShiftRegister);
+
+pub struct VariableBitBusOne {
+    complex_gate: ComplexGateMembers,
+    and_gates: Vec<SharedMutex<And>>,
+    or_gate: SharedMutex<Or>,
+    not_gate: SharedMutex<Not>,
+}
+
+#[allow(dead_code)]
+impl VariableBitBusOne {
+    pub fn new(number_bits: usize) -> SharedMutex<Self> {
+        assert_ne!(number_bits, 0);
+
+        let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+        let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
+        let mut output_gates_logic: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+
+        let mut and_gates = Vec::new();
+
+        build_simple_inputs_and_outputs_with_and(
+            number_bits,
+            &mut input_gates,
+            &mut output_gates,
+            &mut output_gates_logic,
+            &mut and_gates,
+        );
+
+        and_gates.pop();
+
+        let set_input_gate = SimpleInput::new(2, "BUS_1");
+
+        input_gates.push(set_input_gate.clone());
+
+        let mut bit_register = VariableBitBusOne {
+            complex_gate: ComplexGateMembers::new(
+                number_bits + 1,
+                number_bits,
+                GateType::VariableBitBusOneType,
+                input_gates,
+                output_gates,
+            ),
+            and_gates,
+            or_gate: Or::new(2, 1),
+            not_gate: Not::new(number_bits - 1),
+        };
+
+        bit_register.build_and_prime_circuit(number_bits, output_gates_logic);
+
+        new_shared_mutex(bit_register.get_unique_id().id(), bit_register)
+    }
+
+    fn build_and_prime_circuit(
+        &mut self,
+        number_bits: usize,
+        output_gates: Vec<SharedMutex<dyn LogicGate>>,
+    ) {
+        let bus_one_input = self.complex_gate.input_gates[self.get_index_from_tag("BUS_1")].clone();
+
+        connect_gates(
+            bus_one_input.clone(),
+            0,
+            self.not_gate.clone(),
+            0,
+        );
+
+        for i in 0..number_bits {
+
+            if i == 0 {
+                connect_gates(
+                    self.complex_gate.input_gates[i].clone(),
+                    0,
+                    self.or_gate.clone(),
+                    0,
+                );
+
+                connect_gates(
+                    bus_one_input.clone(),
+                    1,
+                    self.or_gate.clone(),
+                    1,
+                );
+
+                connect_gates(
+                    self.or_gate.clone(),
+                    0,
+                    output_gates[i].clone(),
+                    0,
+                );
+            } else {
+                connect_gates(
+                    self.complex_gate.input_gates[i].clone(),
+                    0,
+                    self.and_gates[i - 1].clone(),
+                    0,
+                );
+
+                connect_gates(
+                    self.not_gate.clone(),
+                    i - 1,
+                    self.and_gates[i - 1].clone(),
+                    1,
+                );
+
+                connect_gates(
+                    self.and_gates[i - 1].clone(),
+                    0,
+                    output_gates[i].clone(),
+                    0,
+                );
+            }
+        }
+
+        //Prime gates
+        self.complex_gate.calculate_output_from_inputs_and_set_child_count(
+            true,
+        );
+    }
+}
+
+impl LogicGate for VariableBitBusOne {
+    fn internal_connect_output(&mut self, current_gate_output_key: usize, next_gate_input_key: usize, next_gate: SharedMutex<dyn LogicGate>) -> Signal {
+        self.complex_gate.connect_output(
+            self.get_unique_id(),
+            current_gate_output_key,
+            next_gate_input_key,
+            next_gate,
+        )
+    }
+
+    fn internal_update_index_to_id(&mut self, sending_id: UniqueID, gate_input_index: usize, signal: Signal) {
+        self.complex_gate.internal_update_index_to_id(sending_id, gate_input_index, signal);
+    }
+
+    fn update_input_signal(&mut self, input: GateInput) -> InputSignalReturn {
+        //ActiveLowSRLatch has an `invalid` state of LOW LOW. However, this is not being enforced by
+        // assertions because it may be an intermediate state.
+        self.complex_gate.update_input_signal(input)
+    }
+
+    fn fetch_output_signals_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
+        self.complex_gate.fetch_output_signals_calculate(
+            &self.get_tag(),
+        )
+    }
+
+    fn fetch_output_signals_no_calculate(&mut self) -> Result<Vec<GateOutputState>, GateLogicError> {
+        self.complex_gate.fetch_output_signals_no_calculate(
+            &self.get_tag(),
+        )
+    }
+
+    fn get_gate_type(&self) -> GateType {
+        self.complex_gate.simple_gate.gate_type
+    }
+
+    fn get_unique_id(&self) -> UniqueID {
+        self.complex_gate.simple_gate.unique_id
+    }
+
+    fn toggle_output_printing(&mut self, print_output: bool) {
+        self.complex_gate.simple_gate.should_print_output = print_output;
+    }
+
+    fn get_tag(&self) -> String {
+        self.complex_gate.simple_gate.tag.clone()
+    }
+
+    fn set_tag(&mut self, tag: &str) {
+        self.complex_gate.simple_gate.tag = tag.to_string();
+    }
+
+    fn get_index_from_tag(&self, tag: &str) -> usize {
+        self.complex_gate.get_index_from_tag(tag)
+    }
+
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
+    fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
+        self.complex_gate.remove_connected_input(input_index, connected_id);
+    }
+
+    fn toggle_print_each_input_output_gate(&mut self, print_each_input_output_gate: bool) {
+        self.complex_gate.toggle_print_each_input_output_gate(print_each_input_output_gate);
+    }
+
+    fn num_children_gates(&self) -> usize {
+        self.complex_gate.simple_gate.number_child_gates
+    }
+
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
+    fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
+        self.complex_gate.input_gates.clone()
+    }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
     }
 }
 
@@ -1250,7 +2048,8 @@ mod tests {
     use std::collections::HashMap;
     use crate::logic::foundations::Signal::{HIGH, LOW_, NONE};
     use rand::Rng;
-    use crate::test_stuff::run_multi_input_output_logic_gate;
+    use crate::logic::foundations::extract_signals_from_gate_output_states;
+    use crate::test_stuff::{run_multi_input_output_logic_gate, run_multi_input_output_logic_gate_return};
     use super::*;
 
     #[test]
@@ -1406,6 +2205,58 @@ mod tests {
         );
     }
 
+    #[test]
+    fn decoder_cache() {
+        let number_inputs = 3;
+        let decoder = VariableDecoder::new(number_inputs);
+
+        let set_select_bit = |input_index: usize, signal: Signal| {
+            decoder.lock().unwrap().update_input_signal(
+                GateInput::new(
+                    input_index,
+                    signal,
+                    UniqueID::zero_id(),
+                )
+            );
+        };
+
+        let fetch_signals = || {
+            let output = decoder.lock().unwrap().fetch_output_signals_calculate().unwrap();
+            extract_signals_from_gate_output_states(&output)
+        };
+
+        //Select output 5 (101).
+        set_select_bit(0, HIGH);
+        set_select_bit(1, LOW_);
+        set_select_bit(2, HIGH);
+
+        let calculated_output = fetch_signals();
+        assert_eq!(decoder.lock().unwrap().get_cached_input_signals(), Some(vec![HIGH, LOW_, HIGH]));
+
+        //Sending the same signals again must not clear the cache.
+        set_select_bit(0, HIGH);
+        assert!(decoder.lock().unwrap().get_cached_input_signals().is_some());
+        let cached_output = fetch_signals();
+
+        decoder.lock().unwrap().invalidate_cache();
+        let recalculated_output = fetch_signals();
+
+        let mut expected_output = vec![LOW_; 8];
+        expected_output[5] = HIGH;
+        assert_eq!(calculated_output, expected_output);
+        assert_eq!(cached_output, recalculated_output);
+        assert_eq!(cached_output, expected_output);
+
+        //Changing a select bit invalidates the cache, select output 4 (100).
+        set_select_bit(0, LOW_);
+        assert_eq!(decoder.lock().unwrap().get_cached_input_signals(), None);
+
+        let mut expected_output = vec![LOW_; 8];
+        expected_output[4] = HIGH;
+        assert_eq!(fetch_signals(), expected_output);
+        assert_eq!(decoder.lock().unwrap().get_cached_input_signals(), Some(vec![LOW_, LOW_, HIGH]));
+    }
+
     fn single_ram_cell_low_v_h(
         v_signal: Signal,
         h_signal: Signal,
@@ -1554,6 +2405,177 @@ mod tests {
         );
     }
 
+    #[test]
+    fn ram_unit_zero_fill() {
+        let bus_size_in_bits = 4;
+        let decoder_input_size = 1;
+        let num_ram_cells = usize::pow(2, (decoder_input_size * 2) as u32);
+        let ram_unit = RAMUnit::new_zero_filled(bus_size_in_bits, decoder_input_size);
+
+        let output_signals: Vec<Signal> = ram_unit.lock().unwrap().fetch_output_signals_calculate().unwrap()
+            .into_iter()
+            .map(|output| {
+                match output {
+                    GateOutputState::NotConnected(signal) => signal,
+                    GateOutputState::Connected(connected_output) => connected_output.throughput.signal,
+                }
+            })
+            .collect();
+
+        for i in 0..num_ram_cells {
+            for j in 0..bus_size_in_bits {
+                let output_tag = RAMUnit::get_ram_output_string(i, j);
+                let output_index = ram_unit.lock().unwrap().get_index_from_tag(output_tag.as_str());
+                assert_eq!(output_signals[output_index], LOW_);
+            }
+        }
+
+        //Read back an address that was never written.
+        run_multi_input_output_logic_gate(
+            vec![],
+            vec![
+                vec![NONE; bus_size_in_bits],
+                vec![LOW_; bus_size_in_bits],
+            ],
+            HashMap::from(
+                [
+                    ("addr", vec![
+                        vec![HIGH, HIGH], //0b11
+                        vec![HIGH, HIGH], //0b11
+                    ]),
+                    ("SA", vec![vec![HIGH], vec![LOW_]]),
+                    ("S", vec![vec![LOW_]; 2]),
+                    ("E", vec![vec![LOW_], vec![HIGH]]),
+                ]
+            ),
+            ram_unit,
+        );
+    }
+
+    #[test]
+    fn ram_unit_load_memory() {
+        let bus_size_in_bits = 4;
+        let decoder_input_size = 1;
+        let ram_unit = RAMUnit::new_zero_filled(bus_size_in_bits, decoder_input_size);
+
+        let program = vec![
+            vec![HIGH, LOW_, LOW_, HIGH],
+            vec![LOW_, HIGH, HIGH, LOW_],
+            vec![HIGH, HIGH, LOW_, HIGH],
+        ];
+
+        {
+            let mut ram_unit = ram_unit.lock().unwrap();
+
+            assert_eq!(
+                ram_unit.load_memory(&vec![vec![LOW_; bus_size_in_bits]; 5]),
+                Err(MemoryLoadError::TooManyAddresses(5, 4)),
+            );
+            assert_eq!(
+                ram_unit.load_memory(&[vec![LOW_; bus_size_in_bits], vec![LOW_; 3]]),
+                Err(MemoryLoadError::WrongWidth(1, 3)),
+            );
+
+            ram_unit.load_memory(&program).unwrap();
+        }
+
+        //Read every address back through the memory address register and E.
+        run_multi_input_output_logic_gate(
+            vec![],
+            vec![
+                vec![NONE; bus_size_in_bits],
+                program[2].clone(),
+                vec![NONE; bus_size_in_bits],
+                program[0].clone(),
+                vec![NONE; bus_size_in_bits],
+                program[1].clone(),
+                vec![NONE; bus_size_in_bits],
+                vec![LOW_; bus_size_in_bits],
+            ],
+            HashMap::from(
+                [
+                    ("addr", vec![
+                        vec![LOW_, HIGH], //0b10
+                        vec![LOW_, HIGH], //0b10
+                        vec![LOW_, LOW_], //0b00
+                        vec![LOW_, LOW_], //0b00
+                        vec![HIGH, LOW_], //0b01
+                        vec![HIGH, LOW_], //0b01
+                        vec![HIGH, HIGH], //0b11
+                        vec![HIGH, HIGH], //0b11
+                    ]),
+                    ("SA", vec![
+                        vec![HIGH], vec![LOW_],
+                        vec![HIGH], vec![LOW_],
+                        vec![HIGH], vec![LOW_],
+                        vec![HIGH], vec![LOW_],
+                    ]),
+                    ("S", vec![vec![LOW_]; 8]),
+                    ("E", vec![
+                        vec![LOW_], vec![HIGH],
+                        vec![LOW_], vec![HIGH],
+                        vec![LOW_], vec![HIGH],
+                        vec![LOW_], vec![HIGH],
+                    ]),
+                ]
+            ),
+            ram_unit,
+        );
+    }
+
+    #[test]
+    fn dual_port_ram_read_while_writing() {
+        run_multi_input_output_logic_gate(
+            vec![
+                vec![HIGH, LOW_, HIGH], // Save to address 1.
+                vec![HIGH, LOW_, HIGH], // Stop saving.
+                vec![LOW_, HIGH, HIGH], // Save to address 2 while reading address 1.
+                vec![LOW_, HIGH, HIGH], // Stop saving, read address 2 while address 1 is addressed for writing.
+                vec![HIGH, HIGH, HIGH], // Read address 1 with S and E LOW.
+            ],
+            vec![
+                vec![NONE, NONE, NONE],
+                vec![NONE, NONE, NONE],
+                vec![HIGH, LOW_, HIGH],
+                vec![LOW_, HIGH, HIGH],
+                vec![NONE, NONE, NONE],
+            ],
+            HashMap::from(
+                [
+                    (DualPortRam::WRITE_ADDRESS, vec![
+                        vec![HIGH, LOW_], //1
+                        vec![HIGH, LOW_], //1
+                        vec![LOW_, HIGH], //2
+                        vec![HIGH, LOW_], //1
+                        vec![HIGH, LOW_], //1
+                    ]),
+                    (DualPortRam::READ_ADDRESS, vec![
+                        vec![LOW_, LOW_], //0
+                        vec![LOW_, LOW_], //0
+                        vec![HIGH, LOW_], //1
+                        vec![LOW_, HIGH], //2
+                        vec![HIGH, LOW_], //1
+                    ]),
+                    ("S", vec![
+                        vec![HIGH],
+                        vec![LOW_],
+                        vec![HIGH],
+                        vec![LOW_],
+                        vec![LOW_],
+                    ]),
+                    ("E", vec![
+                        vec![LOW_],
+                        vec![LOW_],
+                        vec![HIGH],
+                        vec![HIGH],
+                        vec![LOW_],
+                    ]),
+                ]
+            ),
+            DualPortRam::new(2, 3),
+        );
+    }
+
     #[test]
     fn variable_bit_bus_one_test() {
         //If the BUS_1 input is HIGH, the output returns one. Otherwise, it passes the input
@@ -1578,4 +2600,99 @@ mod tests {
             VariableBitBusOne::new(num_bits),
         );
     }
-}
\ No newline at end of file
+
+    //Each step is a full clock cycle of LOAD, SER_IN and the data bus. Returns SER_OUT after each
+    // falling edge.
+    fn run_shift_register(num_bits: usize, steps: &[(bool, bool, usize)]) -> Vec<Signal> {
+        let to_signals = |value: usize| -> Vec<Signal> {
+            (0..num_bits)
+                .map(|i| if (value >> i) & 1 == 1 { HIGH } else { LOW_ })
+                .collect()
+        };
+        let to_signal = |value: bool| if value { HIGH } else { LOW_ };
+
+        let mut data_signals = Vec::new();
+        let mut load_signals = Vec::new();
+        let mut ser_in_signals = Vec::new();
+        let mut clk_signals = Vec::new();
+        let mut output_signals = Vec::new();
+
+        let mut expected_value = 0;
+        for &(load, ser_in, data) in steps {
+            //The register only changes once CLK falls.
+            for clk in [HIGH, LOW_] {
+                if clk == LOW_ {
+                    expected_value =
+                        if load {
+                            data
+                        } else {
+                            (expected_value >> 1) | (usize::from(ser_in) << (num_bits - 1))
+                        };
+                }
+
+                data_signals.push(to_signals(data));
+                load_signals.push(vec![to_signal(load)]);
+                ser_in_signals.push(vec![to_signal(ser_in)]);
+                clk_signals.push(vec![clk]);
+
+                let mut output = to_signals(expected_value);
+                output.push(to_signal(expected_value & 1 == 1)); //SER_OUT
+                output_signals.push(output);
+            }
+        }
+
+        let collected_output = run_multi_input_output_logic_gate_return(
+            vec![],
+            &output_signals,
+            HashMap::from(
+                [
+                    ("i", data_signals),
+                    (ShiftRegister::LOAD, load_signals),
+                    (ShiftRegister::SER_IN, ser_in_signals),
+                    (ShiftRegister::CLK, clk_signals),
+                ]
+            ),
+            ShiftRegister::new(num_bits),
+        );
+
+        assert_eq!(collected_output, output_signals);
+
+        collected_output
+            .iter()
+            .skip(1)
+            .step_by(2)
+            .map(|output| output[num_bits].clone())
+            .collect()
+    }
+
+    #[test]
+    fn shift_register_parallel_load_then_shift_out() {
+        let num_bits = 4;
+        let value = 0b1011;
+
+        let mut steps = vec![(true, false, value)];
+        steps.extend(vec![(false, false, 0); num_bits]);
+
+        let serial_output = run_shift_register(num_bits, &steps);
+
+        //The loaded value leaves least significant bit first, then the LOW shifted in follows.
+        assert_eq!(serial_output, vec![HIGH, HIGH, LOW_, HIGH, LOW_]);
+    }
+
+    #[test]
+    fn shift_register_serial_shift_in() {
+        let num_bits = 8;
+        let value: usize = 0b1001_0110;
+
+        //Shifting in least significant bit first leaves the value on the parallel outputs, which
+        // run_shift_register() checks after every step.
+        let steps: Vec<(bool, bool, usize)> = (0..num_bits)
+            .map(|i| (false, (value >> i) & 1 == 1, 0))
+            .collect();
+
+        let serial_output = run_shift_register(num_bits, &steps);
+
+        //o_0 holds the first bit shifted in.
+        assert_eq!(serial_output.last(), Some(&LOW_));
+    }
+}
@@ -1,18 +1,53 @@
-use crate::logic::arithmetic_gates::ArithmeticLogicUnit;
+use crate::logic::arithmetic_gates::{ArithmeticLogicUnit, VariableBitDivider};
 use crate::logic::basic_gates::{And, ControlledBuffer, Not, Splitter};
 use crate::logic::complex_logic::{FourCycleClockHookup, VariableBitCounter, VariableBitMultiplexer};
 use crate::logic::control_section::ControlSection;
 
-use crate::logic::foundations::{ComplexGateMembers, connect_gates, GateInput, GateLogicError, GateOutputState, GateType, InputSignalReturn, LogicGate, Signal, UniqueID};
+use crate::logic::foundations::{ComplexGateMembers, connect_gates, extract_signals_from_gate_output_states, signals_to_u64, Endianness, GateInput, GateLogicError, GateOutputState, GateCounts, GateTagType, GateType, InputSignalReturn, LogicGate, Signal, UniqueID};
 use crate::logic::output_gates::{LogicGateAndOutputGate, SimpleOutput};
 
 #[allow(unused_imports)]
 use crate::logic::foundations::Signal::{LOW_, HIGH};
 use crate::logic::input_gates::{Clock, SimpleInput};
+use crate::logic::io::IoDevice;
 use crate::logic::memory_gates::{OneBitMemoryCell, VariableBitMemoryCell};
 use crate::logic::processor_components::{RAMUnit, VariableBitBusOne, VariableBitRegister};
+use crate::logic::netlist::Netlist;
+use crate::run_circuit::run_circuit;
 use crate::shared_mutex::{new_shared_mutex, SharedMutex};
 
+//What run_instructions_on_cpu() does when DIV_BY_ZERO of the divider passed to
+// VariableBitCPU::set_div_by_zero_trap() goes HIGH.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DivByZeroTrap {
+    //Stops the program immediately.
+    Halt,
+    //Continues the program from the address once the current instruction has completed.
+    Jump(u64),
+}
+
+//Reported when a store writes to an address passed to VariableBitCPU::add_ram_watchpoint(). The
+// values are None if any bit of the RAM cell is NONE.
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RamWatchpointHit {
+    pub address: usize,
+    pub old_value: Option<u64>,
+    pub new_value: Option<u64>,
+    pub cycle: usize,
+}
+
+//The settings of a cpu that are not part of the circuit, see VariableBitCPU::take_run_settings().
+#[allow(dead_code)]
+pub struct CpuRunSettings {
+    initial_state_seed: Option<u64>,
+    reset_vector: u64,
+    io_devices: Vec<SharedMutex<dyn IoDevice>>,
+    div_by_zero_trap: Option<(SharedMutex<VariableBitDivider>, DivByZeroTrap)>,
+    ram_watchpoints: Vec<usize>,
+}
+
 #[allow(dead_code)]
 #[derive(Clone)]
 pub enum Register {
@@ -24,7 +59,7 @@ pub enum Register {
 
 #[allow(dead_code)]
 impl Register {
-    fn binary(reg: Register) -> &'static str {
+    pub fn binary(reg: Register) -> &'static str {
         match reg {
             Register::R0 => "00",
             Register::R1 => "01",
@@ -66,7 +101,7 @@ impl ALUInstruction {
             ALUInstruction::AND => "100",
             ALUInstruction::OR => "101",
             ALUInstruction::XOR => "110",
-            ALUInstruction::CMP => "111", //Not hooked up
+            ALUInstruction::CMP => "111", //Only sets the flags, reg_b is not written.
         }
     }
 }
@@ -94,7 +129,11 @@ pub enum Instructions {
 
 #[allow(dead_code)]
 impl Instructions {
-    fn binary(instruction: Self) -> String {
+    //No instruction uses this encoding, JMPR is always 001100rr. The instruction after it is decoded
+    // from the extended instruction table, see ControlSection.
+    pub const EXTENDED_PREFIX: &'static str = "00111111";
+
+    pub fn binary(instruction: Self) -> String {
         let binary_string =
             match instruction {
                 Instructions::End => "11001111".to_string(),
@@ -138,6 +177,68 @@ impl Instructions {
 
         binary_string
     }
+
+    //Returns the mnemonic for an instruction. Only the lowest 8 bits (the last 8 characters) are
+    // used so that instructions read from a wider bus can be passed in directly.
+    pub fn opcode_name(binary: &str) -> &'static str {
+        assert!(binary.len() >= 8);
+        let binary = &binary[binary.len() - 8..];
+
+        if binary == "11001111" {
+            return "END";
+        }
+
+        if binary == Self::EXTENDED_PREFIX {
+            return "EXT";
+        }
+
+        if binary.starts_with('1') {
+            return match &binary[1..4] {
+                "000" => "ADD",
+                "001" => "SHR",
+                "010" => "SHL",
+                "011" => "NOT",
+                "100" => "AND",
+                "101" => "OR",
+                "110" => "XOR",
+                _ => "CMP",
+            };
+        }
+
+        match &binary[0..4] {
+            "0000" => "LD",
+            "0001" => "ST",
+            "0010" => "DATA",
+            "0011" => "JMPR",
+            "0100" => "JMP",
+            "0101" => "JMPIF",
+            "0110" => "CLF",
+            _ => "IO",
+        }
+    }
+
+    //Returns the mnemonic for the instruction after Instructions::EXTENDED_PREFIX, see
+    // ControlSection. Only the lowest 8 bits are used, the same as opcode_name().
+    pub fn extended_opcode_name(binary: &str) -> &'static str {
+        assert!(binary.len() >= 8);
+        let binary = &binary[binary.len() - 8..];
+
+        //An extended ALU instruction is the base one with the carry flag as its carry in.
+        if binary.starts_with('1') {
+            return match &binary[1..4] {
+                "000" => "ADC",
+                _ => Self::opcode_name(binary),
+            };
+        }
+
+        match &binary[0..4] {
+            "0000" => "MOV",
+            "0001" => "SWAP",
+            "0010" => "LDI",
+            //The rest of the extended table does nothing during steps 4-6.
+            _ => "NOP",
+        }
+    }
 }
 
 pub struct VariableBitCPU {
@@ -172,6 +273,19 @@ pub struct VariableBitCPU {
     counter_and: SharedMutex<And>,
     load_input_splitter: SharedMutex<Splitter>,
     reset_controlled_buffer: SharedMutex<ControlledBuffer>,
+    //This is not part of the circuit, it is incremented by whatever drives the clock.
+    cycles_since_reset: usize,
+    //This is not part of the circuit, see randomize_initial_state().
+    initial_state_seed: Option<u64>,
+    //This is not part of the circuit, see set_reset_vector().
+    reset_vector: u64,
+    //These are not part of the circuit, see attach_io_device().
+    io_devices: Vec<SharedMutex<dyn IoDevice>>,
+    //This is not part of the circuit, see set_div_by_zero_trap().
+    div_by_zero_trap: Option<(SharedMutex<VariableBitDivider>, DivByZeroTrap)>,
+    //These are not part of the circuit, see add_ram_watchpoint().
+    ram_watchpoints: Vec<usize>,
+    ram_watchpoint_hit: Option<RamWatchpointHit>,
 }
 
 #[allow(dead_code)]
@@ -182,6 +296,7 @@ impl VariableBitCPU {
     pub const MARS: &'static str = "MARS";
     pub const RAM: &'static str = "RAM";
     pub const CLK_IN: &'static str = "CLK_IN";
+    pub const HALT: &'static str = "HALT";
 
     //Outputs
     pub const R0: &'static str = "R0";
@@ -219,6 +334,10 @@ impl VariableBitCPU {
         input_gates.push(SimpleInput::new(3, VariableBitCPU::RESET));
         input_gates.push(SimpleInput::new(1, VariableBitCPU::MARS));
 
+        //HALT is not connected to anything inside the cpu. run_instructions_on_cpu() reads it before
+        // each clock tick, see set_halt() inside build_circuit.rs.
+        input_gates.push(SimpleInput::new(1, VariableBitCPU::HALT));
+
         //This must be the last input.
         input_gates.push(SimpleInput::new(1, VariableBitCPU::CLK_IN));
 
@@ -269,7 +388,7 @@ impl VariableBitCPU {
 
         let mut cpu = VariableBitCPU {
             complex_gate: ComplexGateMembers::new(
-                number_bits + 4,
+                number_bits + 5,
                 num_ram_cells * number_bits + 9 * number_bits + 8,
                 GateType::VariableBitCPUType,
                 input_gates,
@@ -289,7 +408,7 @@ impl VariableBitCPU {
             instruction_address_register: VariableBitRegister::new(number_bits),
             instruction_register: VariableBitMemoryCell::new(number_bits),
             ram: RAMUnit::new(number_bits, ram_cells_decoder_input),
-            alu: ArithmeticLogicUnit::new(number_bits),
+            alu: ArithmeticLogicUnit::new(number_bits).unwrap(),
             bus_1: VariableBitBusOne::new(number_bits),
             tmp: VariableBitMemoryCell::new(number_bits),
             c_tmp: OneBitMemoryCell::new(1),
@@ -305,6 +424,13 @@ impl VariableBitCPU {
             counter_and: And::new(2, 1),
             load_input_splitter: Splitter::new(1, 4),
             reset_controlled_buffer: ControlledBuffer::new(number_bits),
+            cycles_since_reset: 0,
+            initial_state_seed: None,
+            reset_vector: 0,
+            io_devices: Vec::new(),
+            div_by_zero_trap: None,
+            ram_watchpoints: Vec::new(),
+            ram_watchpoint_hit: None,
         };
 
         cpu.four_cycle_clock_hookup.lock().unwrap().set_tag("four_cycle_clock_hookup");
@@ -737,7 +863,8 @@ impl VariableBitCPU {
             input_index,
         );
 
-        let input_index = self.c_tmp.lock().unwrap().get_index_from_tag("S");
+        //OneBitMemoryCell saves S when E is HIGH, so TMP_S latches the carry flag into c_tmp.
+        let input_index = self.c_tmp.lock().unwrap().get_index_from_tag("E");
         let output_index = self.temp_s_splitter.lock().unwrap().get_index_for_output(0, 1);
         connect_gates(
             self.temp_s_splitter.clone(),
@@ -1328,7 +1455,7 @@ impl VariableBitCPU {
             input_index,
         );
 
-        let input_index = self.c_tmp.lock().unwrap().get_index_from_tag("E");
+        let input_index = self.c_tmp.lock().unwrap().get_index_from_tag("S");
         let output_index = self.flags_c_out_splitter.lock().unwrap().get_index_for_output(0, 1);
         connect_gates(
             self.flags_c_out_splitter.clone(),
@@ -1516,6 +1643,275 @@ impl VariableBitCPU {
     pub fn get_complex_gate(&self) -> &ComplexGateMembers {
         &self.complex_gate
     }
+
+    //Returns the instruction currently latched inside the instruction register. Bit 0 is the least
+    // significant bit.
+    pub fn read_ir(&self) -> Vec<Signal> {
+        let ir_output = self.instruction_register.lock().unwrap().fetch_output_signals_no_calculate().unwrap();
+        let mut ir_signals = extract_signals_from_gate_output_states(&ir_output);
+
+        //The o_n outputs are followed by the reg_n outputs which hold the same bits.
+        ir_signals.truncate(ir_signals.len() / 2);
+        ir_signals
+    }
+
+    //Returns the address currently latched inside the memory address register. Bit 0 is the least
+    // significant bit.
+    pub fn read_memory_address(&self) -> Vec<Signal> {
+        self.ram.lock().unwrap().read_memory_address()
+    }
+
+    //True once the End instruction has been latched into the instruction register. END is decoded
+    // from the instruction register alone so it stays HIGH until the register is overwritten.
+    pub fn is_halted(&self) -> bool {
+        let mut control_section = self.control_section.lock().unwrap();
+        let end_index = control_section.get_index_from_tag(ControlSection::END);
+        let control_output = control_section.fetch_output_signals_no_calculate().unwrap();
+        let control_signals = extract_signals_from_gate_output_states(&control_output);
+
+        control_signals[end_index] == HIGH
+    }
+
+    //Returns each external input tag of the cpu sorted by index along with whether a gate is
+    // currently connected to it.
+    pub fn list_external_inputs(&self) -> Vec<(String, bool)> {
+        self.get_tags(GateTagType::Input)
+            .into_iter()
+            .map(|tag| {
+                let connected = self.is_input_connected(self.get_index_from_tag(tag.as_str()));
+                (tag, connected)
+            })
+            .collect()
+    }
+
+    //The RAM address being written to when RAM_S is HIGH, otherwise None. The address is also None
+    // if any bit of the memory address register is NONE.
+    pub fn pending_ram_store(&self) -> Option<u64> {
+        let ram_s_signal = {
+            let mut control_section = self.control_section.lock().unwrap();
+            let ram_s_index = control_section.get_index_from_tag(ControlSection::RAM_S);
+            let control_output = control_section.fetch_output_signals_no_calculate().unwrap();
+            extract_signals_from_gate_output_states(&control_output)[ram_s_index].clone()
+        };
+
+        if ram_s_signal != HIGH {
+            return None;
+        }
+
+        let memory_address = self.ram.lock().unwrap().read_memory_address();
+        signals_to_u64(&memory_address, memory_address.len(), Endianness::Little)
+    }
+
+    //Returns the carry, a larger, equal and zero flags in that order.
+    pub fn get_flags(&self) -> Vec<Signal> {
+        let flags_output = self.flags.lock().unwrap().fetch_output_signals_no_calculate().unwrap();
+        extract_signals_from_gate_output_states(&flags_output)
+    }
+
+    //Returns the index of the active control section step (0 is step 1).
+    pub fn get_current_step(&self) -> Option<usize> {
+        self.control_section.lock().unwrap().get_current_step()
+    }
+
+    //The number of rising edges driven into CLK_IN since the cpu was built or last reset. See
+    // run_instructions() and reset_circuit() in run_circuit.rs.
+    pub fn cycles_since_reset(&self) -> usize {
+        self.cycles_since_reset
+    }
+
+    pub fn increment_cycles_since_reset(&mut self) {
+        self.cycles_since_reset += 1;
+    }
+
+    pub fn clear_cycles_since_reset(&mut self) {
+        self.cycles_since_reset = 0;
+    }
+
+    //When a seed is set, run_instructions_on_cpu() and the functions built on it fill every RAM cell
+    // not used by the program and the general purpose registers with pseudo-random bits before the
+    // program starts. The same seed always produces the same values.
+    pub fn randomize_initial_state(&mut self, seed: u64) {
+        self.initial_state_seed = Some(seed);
+    }
+
+    pub fn initial_state_seed(&self) -> Option<u64> {
+        self.initial_state_seed
+    }
+
+    //run_instructions_on_cpu() and the functions built on it start the program from address
+    // instead of address 0 once the program has been loaded into RAM.
+    pub fn set_reset_vector(&mut self, address: u64) {
+        self.reset_vector = address;
+    }
+
+    pub fn reset_vector(&self) -> u64 {
+        self.reset_vector
+    }
+
+    //run_instructions_on_cpu() and the functions built on it tick every attached device along with
+    // any devices passed to them directly.
+    pub fn attach_io_device(&mut self, io_device: SharedMutex<dyn IoDevice>) {
+        self.io_devices.push(io_device);
+    }
+
+    pub fn io_devices(&self) -> Vec<SharedMutex<dyn IoDevice>> {
+        self.io_devices.clone()
+    }
+
+    //run_instructions_on_cpu() and the functions built on it drive divider with R0 divided by R1
+    // after every clock tick and take trap each time DIV_BY_ZERO goes from LOW to HIGH. The cpu has
+    // no divide instruction so the quotient and remainder are not read back. divider must be as wide
+    // as the bus.
+    pub fn set_div_by_zero_trap(&mut self, divider: SharedMutex<VariableBitDivider>, trap: DivByZeroTrap) {
+        self.div_by_zero_trap = Some((divider, trap));
+    }
+
+    pub fn div_by_zero_trap(&self) -> Option<(SharedMutex<VariableBitDivider>, DivByZeroTrap)> {
+        self.div_by_zero_trap.clone()
+    }
+
+    //run_instructions_on_cpu() and the functions built on it stop the program the first time a
+    // store writes to address. The hit can be read afterward with take_ram_watchpoint_hit().
+    pub fn add_ram_watchpoint(&mut self, address: usize) {
+        if !self.ram_watchpoints.contains(&address) {
+            self.ram_watchpoints.push(address);
+        }
+    }
+
+    pub fn clear_ram_watchpoints(&mut self) {
+        self.ram_watchpoints.clear();
+    }
+
+    pub fn ram_watchpoints(&self) -> Vec<usize> {
+        self.ram_watchpoints.clone()
+    }
+
+    pub fn record_ram_watchpoint_hit(&mut self, hit: Option<RamWatchpointHit>) {
+        self.ram_watchpoint_hit = hit;
+    }
+
+    //Returns the watchpoint that stopped the last run, None if the program was not stopped by a
+    // watchpoint.
+    pub fn take_ram_watchpoint_hit(&mut self) -> Option<RamWatchpointHit> {
+        self.ram_watchpoint_hit.take()
+    }
+
+    //Removes the initial state seed, reset vector, IO devices, div by zero trap and RAM watchpoints
+    // so that run_instructions_on_cpu() runs a program the same way it would on a new cpu. They are
+    // put back with restore_run_settings().
+    pub fn take_run_settings(&mut self) -> CpuRunSettings {
+        CpuRunSettings {
+            initial_state_seed: self.initial_state_seed.take(),
+            reset_vector: std::mem::take(&mut self.reset_vector),
+            io_devices: std::mem::take(&mut self.io_devices),
+            div_by_zero_trap: self.div_by_zero_trap.take(),
+            ram_watchpoints: std::mem::take(&mut self.ram_watchpoints),
+        }
+    }
+
+    pub fn restore_run_settings(&mut self, run_settings: CpuRunSettings) {
+        self.initial_state_seed = run_settings.initial_state_seed;
+        self.reset_vector = run_settings.reset_vector;
+        self.io_devices = run_settings.io_devices;
+        self.div_by_zero_trap = run_settings.div_by_zero_trap;
+        self.ram_watchpoints = run_settings.ram_watchpoints;
+    }
+
+    //Replaces the contents of R0-R3 without running any instructions. Bit 0 of each value is the
+    // least significant bit.
+    pub fn overwrite_general_purpose_registers(&mut self, values: &[Vec<Signal>; 4]) {
+        let registers = [
+            self.register_0.clone(),
+            self.register_1.clone(),
+            self.register_2.clone(),
+            self.register_3.clone(),
+        ];
+
+        for (register, value) in registers.iter().zip(values.iter()) {
+            self.overwrite_register(register, value);
+        }
+
+        self.complex_gate.convert_output_gates_to_output_states();
+    }
+
+    //Replaces the contents of IAR without running any instructions. Bit 0 of value is the least
+    // significant bit.
+    pub fn overwrite_instruction_address_register(&mut self, value: &[Signal]) {
+        let register = self.instruction_address_register.clone();
+        self.overwrite_register(&register, value);
+
+        self.complex_gate.convert_output_gates_to_output_states();
+    }
+
+    //Sets R0-R3, IAR, ACC, TMP, FLAGS and IR back to LOW without running any instructions. The
+    // new values are propagated through the rest of the cpu, so this should only be called while
+    // the clock is stopped.
+    pub fn clear_registers(&mut self) {
+        let number_bits = self.read_ir().len();
+        let zero = vec![LOW_; number_bits];
+
+        let registers = [
+            self.register_0.clone(),
+            self.register_1.clone(),
+            self.register_2.clone(),
+            self.register_3.clone(),
+            self.instruction_address_register.clone(),
+            self.acc.clone(),
+        ];
+
+        let mut changed_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+        for register in registers {
+            register.lock().unwrap().overwrite_contents(&zero);
+            changed_gates.push(register);
+        }
+
+        let memory_cells = [
+            (self.tmp.clone(), number_bits),
+            (self.flags.clone(), 4),
+            (self.instruction_register.clone(), number_bits),
+        ];
+
+        for (memory_cell, number_bits) in memory_cells {
+            memory_cell.lock().unwrap().overwrite_contents(&vec![LOW_; number_bits]);
+            changed_gates.push(memory_cell);
+        }
+
+        run_circuit(
+            &changed_gates,
+            &self.complex_gate.output_gates,
+            false,
+            &mut |_clock_tick_inputs, _output_gates| {},
+        );
+
+        self.complex_gate.convert_output_gates_to_output_states();
+    }
+
+    fn overwrite_register(&self, register: &SharedMutex<VariableBitRegister>, value: &[Signal]) {
+        register.lock().unwrap().overwrite_contents(value);
+
+        //The register outputs only reach the cpu output gates (the bus is not enabled).
+        run_circuit(
+            &vec![register.clone() as SharedMutex<dyn LogicGate>],
+            &self.complex_gate.output_gates,
+            false,
+            &mut |_clock_tick_inputs, _output_gates| {},
+        );
+    }
+
+    //Sets every RAM cell to LOW without running any instructions, see RAMUnit::zero_fill_ram().
+    pub fn zero_fill_ram(&mut self) {
+        self.ram.lock().unwrap().zero_fill_ram();
+
+        //The RAM cell outputs only reach the cpu output gates (the bus is not enabled).
+        run_circuit(
+            &vec![self.ram.clone() as SharedMutex<dyn LogicGate>],
+            &self.complex_gate.output_gates,
+            false,
+            &mut |_clock_tick_inputs, _output_gates| {},
+        );
+
+        self.complex_gate.convert_output_gates_to_output_states();
+    }
 }
 
 impl LogicGate for VariableBitCPU {
@@ -1572,6 +1968,14 @@ impl LogicGate for VariableBitCPU {
         self.complex_gate.get_index_from_tag(tag)
     }
 
+    fn get_tags(&self, tag_type: GateTagType) -> Vec<String> {
+        self.complex_gate.get_tags(tag_type)
+    }
+
+    fn is_input_connected(&self, input_index: usize) -> bool {
+        self.complex_gate.is_input_connected(input_index)
+    }
+
     fn remove_connected_input(&mut self, input_index: usize, connected_id: UniqueID) {
         self.complex_gate.remove_connected_input(input_index, connected_id);
     }
@@ -1584,21 +1988,42 @@ impl LogicGate for VariableBitCPU {
         self.complex_gate.simple_gate.number_child_gates
     }
 
+    fn num_unique_gates(&self) -> usize {
+        self.complex_gate.count_unique_gates()
+    }
+
+    fn count_gates(&self) -> GateCounts {
+        self.complex_gate.count_gates()
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.complex_gate.num_inputs()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.complex_gate.num_outputs()
+    }
+
     fn get_input_gates(&self) -> Vec<SharedMutex<dyn LogicGate>> {
         self.complex_gate.input_gates.clone()
     }
+
+    fn netlist(&self) -> Option<Netlist> {
+        Some(self.complex_gate.netlist())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::time::Duration;
     use rand::Rng;
-    use crate::logic::foundations::{connect_gates, LogicGate, Signal};
+    use crate::globals::{get_pending_logic_error, return_logic_errors};
+    use crate::logic::foundations::{connect_gates, GateLogicError, LogicGate, Signal, signals_to_u64, Endianness, UniqueID};
     use crate::logic::foundations::Signal::{HIGH, LOW_};
     use crate::logic::input_gates::{AutomaticInput};
     use crate::logic::processor_components::RAMUnit;
     use crate::logic::variable_bit_cpu::{ALUInstruction, Instructions, Register, VariableBitCPU};
-    use crate::run_circuit::{collect_signals_from_logic_gate, compare_generate_and_collected_output, generate_default_output, load_values_into_ram, run_circuit, run_instructions};
+    use crate::run_circuit::{collect_signals_from_logic_gate, compare_generate_and_collected_output, generate_default_output, load_values_into_ram, run_circuit, run_instructions, run_instructions_on_cpu_with_observer, try_run_instructions_on_cpu};
     use crate::shared_mutex::SharedMutex;
     use crate::test_stuff::{run_test_with_timeout};
 
@@ -2048,6 +2473,43 @@ mod tests {
         assert!(!failed);
     }
 
+    #[test]
+    fn run_instructions_returns_logic_error() {
+        let number_bits = 8;
+        let decoder_input_size = 1;
+        let cpu = VariableBitCPU::new(number_bits, decoder_input_size);
+
+        //Drive a second valid signal alongside CLK_IN so that the contention can never be
+        // resolved once the clock starts running.
+        cpu.lock().unwrap().end_input_and_gate.lock().unwrap().internal_update_index_to_id(
+            UniqueID::zero_id(),
+            0,
+            LOW_,
+        );
+
+        let end_instruction = Instructions::binary(Instructions::End);
+        let binary_strings = vec![
+            end_instruction.as_str(),
+        ];
+
+        let result = try_run_instructions_on_cpu(
+            &cpu,
+            decoder_input_size,
+            &binary_strings,
+        );
+
+        match result {
+            Err(GateLogicError::UnresolvableContention(gates)) => {
+                assert!(gates.contains("end_input_and_gate"));
+            }
+            _ => panic!("Expected UnresolvableContention, got {:?}", result),
+        }
+
+        //The flag is only set for the duration of the run.
+        assert!(!return_logic_errors());
+        assert_eq!(get_pending_logic_error(), None);
+    }
+
     #[test]
     fn load_to_ram() {
         let number_bits = 8;
@@ -2392,6 +2854,268 @@ mod tests {
         );
     }
 
+    #[test]
+    fn add_ignores_carry_flag_and_adc_uses_it() {
+        let number_bits = 8;
+        let decoder_input_size = 2;
+
+        let binary_strings = vec![
+            "00100000", //Data R0
+            "11111111", //255
+            "00100001", //Data R1
+            "00000001", //1
+            "10000001", //Add R0 R1, sets the carry flag
+            "00100010", //Data R2
+            "00000010", //2
+            "00100011", //Data R3
+            "00000011", //3
+            "10001011", //Add R2 R3, the carry flag is not added in
+            "00100001", //Data R1
+            "00000001", //1
+            "10000001", //Add R0 R1, sets the carry flag
+            Instructions::EXTENDED_PREFIX,
+            "10001011", //Adc R2 R3, the carry flag is added in
+            "11001111", //End
+        ];
+
+        let cpu = run_instructions(
+            number_bits,
+            decoder_input_size,
+            &binary_strings,
+        );
+
+        let collected_signals = collect_signals_from_logic_gate(cpu.clone());
+        let register_value = |register_tag: &str| {
+            let register_signals: Vec<Signal> = (0..number_bits)
+                .map(|i| {
+                    let index = cpu.lock().unwrap().get_index_from_tag(format!("{}_{}", register_tag, i).as_str());
+                    collected_signals[index].clone()
+                })
+                .collect();
+            signals_to_u64(&register_signals, number_bits, Endianness::Little)
+        };
+
+        assert_eq!(register_value(VariableBitCPU::R1), Some(0));
+        assert_eq!(register_value(VariableBitCPU::R3), Some(2 + (2 + 3) + 1));
+    }
+
+    #[test]
+    fn extended_move_instruction() {
+        let number_bits = 8;
+        let decoder_input_size = 2;
+
+        let binary_strings = vec![
+            "00100001", //Data R1
+            "00000101", //5
+            Instructions::EXTENDED_PREFIX,
+            "00000110", //Mov R1 R2
+            "00100011", //Data R3, back to the base table
+            "00000111", //7
+            "11001111", //End
+        ];
+
+        let cpu = run_instructions(
+            number_bits,
+            decoder_input_size,
+            &binary_strings,
+        );
+
+        let collected_signals = collect_signals_from_logic_gate(cpu.clone());
+        let register_value = |register_tag: &str| {
+            let register_signals: Vec<Signal> = (0..number_bits)
+                .map(|i| {
+                    let index = cpu.lock().unwrap().get_index_from_tag(format!("{}_{}", register_tag, i).as_str());
+                    collected_signals[index].clone()
+                })
+                .collect();
+            signals_to_u64(&register_signals, number_bits, Endianness::Little)
+        };
+
+        assert_eq!(register_value(VariableBitCPU::R1), Some(5));
+        assert_eq!(register_value(VariableBitCPU::R2), Some(5));
+        assert_eq!(register_value(VariableBitCPU::R3), Some(7));
+    }
+
+    #[test]
+    fn extended_swap_instruction() {
+        let number_bits = 8;
+        let decoder_input_size = 2;
+
+        let binary_strings = vec![
+            "00100001", //Data R1
+            "00000101", //5
+            "00100010", //Data R2
+            "00001001", //9
+            Instructions::EXTENDED_PREFIX,
+            "00010110", //Swap R1 R2
+            "00100011", //Data R3, back to the base table
+            "00000111", //7
+            "11001111", //End
+        ];
+
+        let cpu = run_instructions(
+            number_bits,
+            decoder_input_size,
+            &binary_strings,
+        );
+
+        let collected_signals = collect_signals_from_logic_gate(cpu.clone());
+        let register_value = |register_tag: &str| {
+            let register_signals: Vec<Signal> = (0..number_bits)
+                .map(|i| {
+                    let index = cpu.lock().unwrap().get_index_from_tag(format!("{}_{}", register_tag, i).as_str());
+                    collected_signals[index].clone()
+                })
+                .collect();
+            signals_to_u64(&register_signals, number_bits, Endianness::Little)
+        };
+
+        assert_eq!(register_value(VariableBitCPU::R1), Some(9));
+        assert_eq!(register_value(VariableBitCPU::R2), Some(5));
+        assert_eq!(register_value(VariableBitCPU::R3), Some(7));
+    }
+
+    #[test]
+    fn extended_load_immediate_instruction() {
+        let number_bits = 8;
+        let decoder_input_size = 2;
+
+        let binary_strings = vec![
+            Instructions::EXTENDED_PREFIX,
+            "00100010", //Ldi R2
+            "00101010", //42
+            "00100011", //Data R3, back to the base table
+            "00000111", //7
+            "11001111", //End
+        ];
+
+        let cpu = run_instructions(
+            number_bits,
+            decoder_input_size,
+            &binary_strings,
+        );
+
+        let collected_signals = collect_signals_from_logic_gate(cpu.clone());
+        let register_value = |register_tag: &str| {
+            let register_signals: Vec<Signal> = (0..number_bits)
+                .map(|i| {
+                    let index = cpu.lock().unwrap().get_index_from_tag(format!("{}_{}", register_tag, i).as_str());
+                    collected_signals[index].clone()
+                })
+                .collect();
+            signals_to_u64(&register_signals, number_bits, Endianness::Little)
+        };
+
+        assert_eq!(register_value(VariableBitCPU::R2), Some(42));
+        assert_eq!(register_value(VariableBitCPU::R3), Some(7));
+    }
+
+    #[test]
+    fn read_ir_after_fetch() {
+        let number_bits = 8;
+        let decoder_input_size = 2;
+
+        let binary_strings = vec![
+            "00100001", //Data R1
+            "00000101", //5
+            "10000101", //Add R1 R1
+            "11001111", //End
+        ];
+
+        //Step 4 is the first step after the fetch and the first step that executes the instruction.
+        let mut fetched_instructions = Vec::new();
+        let mut previous_step = None;
+        let cpu = VariableBitCPU::new(number_bits, decoder_input_size);
+        run_instructions_on_cpu_with_observer(
+            &cpu,
+            decoder_input_size,
+            &binary_strings,
+            |cpu| {
+                let cpu = cpu.lock().unwrap();
+                let current_step = cpu.get_current_step();
+                if current_step == Some(3) && previous_step != Some(3) {
+                    fetched_instructions.push(cpu.read_ir());
+                }
+                previous_step = current_step;
+            },
+        );
+
+        let to_signals = |binary: &str| -> Vec<Signal> {
+            binary.chars().rev().map(|c| if c == '1' { HIGH } else { LOW_ }).collect()
+        };
+
+        assert_eq!(fetched_instructions, vec![to_signals("00100001"), to_signals("10000101")]);
+
+        //The cpu stops once End is fetched.
+        assert_eq!(cpu.lock().unwrap().read_ir(), to_signals("11001111"));
+    }
+
+    #[test]
+    fn is_halted_after_end() {
+        let number_bits = 8;
+        let decoder_input_size = 2;
+
+        let binary_strings = vec![
+            "00100001", //Data R1
+            "00000101", //5
+            "10000101", //Add R1 R1
+            "11001111", //End
+        ];
+
+        let mut halted_per_tick = Vec::new();
+        let cpu = VariableBitCPU::new(number_bits, decoder_input_size);
+        assert!(!cpu.lock().unwrap().is_halted());
+
+        run_instructions_on_cpu_with_observer(
+            &cpu,
+            decoder_input_size,
+            &binary_strings,
+            |cpu| {
+                let cpu = cpu.lock().unwrap();
+                halted_per_tick.push((cpu.read_ir(), cpu.is_halted()));
+            },
+        );
+
+        let end_instruction: Vec<Signal> = "11001111".chars().rev().map(|c| if c == '1' { HIGH } else { LOW_ }).collect();
+
+        //Only ticks where End is inside the instruction register report halted.
+        assert!(halted_per_tick.iter().any(|(_, halted)| !halted));
+        for (ir, halted) in halted_per_tick.iter() {
+            assert_eq!(*halted, *ir == end_instruction);
+        }
+
+        assert!(cpu.lock().unwrap().is_halted());
+    }
+
+    #[test]
+    fn list_external_inputs_follows_connections() {
+        let number_bits = 8;
+        let cpu = VariableBitCPU::new(number_bits, 2);
+
+        let external_inputs = cpu.lock().unwrap().list_external_inputs();
+
+        assert_eq!(external_inputs.len(), number_bits + 5);
+        assert!(external_inputs.contains(&(VariableBitCPU::CLK_IN.to_string(), false)));
+        assert!(external_inputs.iter().all(|(_, connected)| !connected));
+
+        let clock = AutomaticInput::new(vec![HIGH], 1, "CLK");
+        let clk_in_index = cpu.lock().unwrap().get_index_from_tag(VariableBitCPU::CLK_IN);
+        connect_gates(
+            clock.clone(),
+            0,
+            cpu.clone(),
+            clk_in_index,
+        );
+
+        let external_inputs = cpu.lock().unwrap().list_external_inputs();
+        assert!(external_inputs.contains(&(VariableBitCPU::CLK_IN.to_string(), true)));
+
+        clock.lock().unwrap().disconnect_gate(0);
+
+        let external_inputs = cpu.lock().unwrap().list_external_inputs();
+        assert!(external_inputs.contains(&(VariableBitCPU::CLK_IN.to_string(), false)));
+    }
+
     #[test]
     fn shift_right_instruction() {
         let number_bits = 8;
@@ -2842,4 +3566,34 @@ mod tests {
             vec![LOW_; 8],
         )
     }
+
+    #[test]
+    fn clear_flags_instruction_ignores_low_bits() {
+        let number_bits = 8;
+        let decoder_input_size = 2;
+
+        //Every 0110 instruction is a CLF, the low bits are not decoded.
+        let binary_strings = vec![
+            "00100000", //Data R0
+            "11111111", //255
+            "10100001", //Shl R0 R1, sets the carry flag
+            "01101111", //Clf
+            "11001111", //End
+        ];
+
+        let cpu = run_instructions(
+            number_bits,
+            decoder_input_size,
+            &binary_strings,
+        );
+
+        let collected_signals = collect_signals_from_logic_gate(
+            cpu.lock().unwrap().flags.clone()
+        );
+
+        assert_eq!(
+            collected_signals,
+            vec![LOW_; 8],
+        )
+    }
 }
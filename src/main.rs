@@ -8,6 +8,10 @@ mod globals;
 mod build_circuit;
 mod test_stuff;
 mod shared_mutex;
+mod assembler;
+mod dot;
+mod vcd;
+mod isa;
 
 use std::fs::File;
 use std::io::Read;
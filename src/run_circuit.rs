@@ -1,19 +1,27 @@
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::cell::{Cell, RefCell};
+use std::collections::{hash_map, HashMap, HashSet, VecDeque};
 use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
 use std::sync::{Arc, Condvar};
 use std::{fmt, thread};
 use std::fmt::Formatter;
 use std::thread::JoinHandle;
 use std::time::{Duration, Instant};
-use crate::globals::{CLOCK_TICK_NUMBER, END_OUTPUT_GATE_TAG, get_clock_tick_number, RUN_CIRCUIT_IS_HIGH_LEVEL};
-use crate::logic::foundations::{connect_gates, extract_string_from_connected_output, extract_string_from_gate_output_states, GateInput, GateLogicError, GateOutputState, InputSignalReturn, LogicGate, Signal, UniqueID};
-use crate::logic::foundations::Signal::{HIGH, LOW_};
+use crate::globals::{CLOCK_TICK_NUMBER, END_OUTPUT_GATE_TAG, get_clock_tick_number, get_pending_logic_error, return_logic_errors, RUN_CIRCUIT_IS_HIGH_LEVEL, set_return_logic_errors, store_logic_error, take_pending_logic_error};
+use crate::build_circuit::{halt_asserted, CpuConfig, CpuSnapshot};
+use crate::logic::foundations::{connect_gates, extract_signals_from_gate_output_states, extract_string_from_connected_output, extract_string_from_gate_output_states, GateInput, GateLogicError, GateOutputState, GateTagType, GateType, InputSignalReturn, LogicGate, Signal, u64_to_signals, Endianness, UniqueID};
+use crate::logic::foundations::Signal::{HIGH, LOW_, NONE};
 use crate::logic::input_gates::{AutomaticInput, Clock};
+use crate::logic::io::{IoBusState, IoDevice};
 use crate::logic::output_gates::{LogicGateAndOutputGate, SimpleOutput};
 use crate::logic::processor_components::RAMUnit;
-use crate::logic::variable_bit_cpu::VariableBitCPU;
+use crate::logic::variable_bit_cpu::{DivByZeroTrap, Instructions, RamWatchpointHit, VariableBitCPU};
 use crate::{ALU_TIME, CONTROL_SECTION_TIME, RAM_TIME};
+use crate::logic::arithmetic_gates::VariableBitDivider;
 use crate::logic::basic_gates::Or;
+use crate::logic::complex_logic::FourCycleClockHookup;
+use crate::logic::control_section::ControlSection;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use crate::shared_mutex::{new_used_mutex, SharedMutex, UsedMutex};
 use crate::test_stuff::extract_output_tags_sorted_by_index;
 
@@ -474,7 +482,7 @@ impl RunCircuitThreadPool {
                                             }
                                         }
                                         Err(err) => {
-                                            match err {
+                                            match &err {
                                                 GateLogicError::NoMoreAutomaticInputsRemaining => {
                                                     println!("No More AutomaticInputs remaining. Shutting down.");
                                                     Self::internal_shutdown(
@@ -486,6 +494,15 @@ impl RunCircuitThreadPool {
                                                 GateLogicError::MultipleValidSignalsWhenCalculating => {
                                                     multiple_valid_signals.push(running_gate.gate.clone());
                                                 }
+                                                GateLogicError::OscillationDetected(_)
+                                                | GateLogicError::UnresolvableContention(_) => {
+                                                    println!("Logic error {:?} occurred. Shutting down.", err);
+                                                    Self::internal_shutdown(
+                                                        &mut shutdown_clone,
+                                                        &mut signal_clone,
+                                                        &mut thread_pool_lists_clone,
+                                                    );
+                                                }
                                             }
                                         }
                                     }
@@ -1299,8 +1316,165 @@ pub fn run_circuit<F>(
     handle_output: &mut F,
 ) -> bool where
     F: FnMut(&Vec<(String, Vec<GateOutputState>)>, &Vec<SharedMutex<dyn LogicGateAndOutputGate>>)
+{
+    let (continue_clock, _unsettled_gates) = run_circuit_with_pass_budget(
+        input_gates,
+        output_gates,
+        propagate_signal_through_circuit,
+        None,
+        handle_output,
+    );
+
+    continue_clock
+}
+
+//The order run_circuit() calculates the gates waiting for an updated input in. Both strategies
+// settle a combinational circuit to the same outputs, but they can take a different number of
+// passes to get there. Gates with an undetermined starting state such as SR latches may settle
+// differently because the order they first receive their inputs in changes.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchedulingStrategy {
+    //Every gate waiting in the current pass is calculated before any of the gates they drive.
+    BreadthFirst,
+    //The most recently scheduled basic gate is calculated first, so a signal is followed as deep
+    // into the circuit as it goes before the next gate in the pass is calculated. Complex gates
+    // still wait for the next pass. A complex gate settles its whole circuit as soon as it is
+    // calculated, so calculating it while another gate driving the same bus has not switched off
+    // yet would see both signals as valid.
+    DepthFirst,
+}
+
+thread_local! {
+    //The strategy used by every run_circuit() on this thread, including the ones that settle the
+    // gates nested inside complex gates.
+    static SCHEDULING_STRATEGY: Cell<SchedulingStrategy> = const { Cell::new(SchedulingStrategy::BreadthFirst) };
+
+    //Every pass made by run_circuit() on this thread, nested complex gates included.
+    static NUM_PASSES: Cell<usize> = const { Cell::new(0) };
+}
+
+#[allow(dead_code)]
+//Same as run_circuit() except that the gates are calculated in the order of scheduling_strategy.
+// The strategy is also used while settling every complex gate inside the circuit. Returns if the
+// circuit has input remaining along with the total number of passes it took, nested complex gates
+// included.
+pub fn run_circuit_with_strategy<F>(
+    input_gates: &Vec<SharedMutex<dyn LogicGate>>,
+    output_gates: &Vec<SharedMutex<dyn LogicGateAndOutputGate>>,
+    propagate_signal_through_circuit: bool,
+    scheduling_strategy: SchedulingStrategy,
+    handle_output: &mut F,
+) -> (bool, usize) where
+    F: FnMut(&Vec<(String, Vec<GateOutputState>)>, &Vec<SharedMutex<dyn LogicGateAndOutputGate>>)
+{
+    let previous_strategy = SCHEDULING_STRATEGY.with(|strategy| strategy.replace(scheduling_strategy));
+    let starting_passes = NUM_PASSES.with(|num_passes| num_passes.get());
+
+    let continue_clock = run_circuit(
+        input_gates,
+        output_gates,
+        propagate_signal_through_circuit,
+        handle_output,
+    );
+
+    let num_passes = NUM_PASSES.with(|num_passes| num_passes.get()) - starting_passes;
+    SCHEDULING_STRATEGY.with(|strategy| strategy.set(previous_strategy));
+
+    (continue_clock, num_passes)
+}
+
+//Runs a circuit the same way run_circuit() does except the clock ticks are paced to happen at
+// clock_hz. The rate can be changed with set_clock_hz() from inside of the closure passed to
+// run_realtime(), the new rate is used starting with the next clock tick.
+#[allow(dead_code)]
+pub struct RealtimeRunner {
+    clock_hz: f64,
+}
+
+#[allow(dead_code)]
+impl RealtimeRunner {
+    pub fn new(clock_hz: f64) -> Self {
+        let mut runner = RealtimeRunner {
+            clock_hz: 0.0,
+        };
+
+        runner.set_clock_hz(clock_hz);
+
+        runner
+    }
+
+    pub fn set_clock_hz(&mut self, clock_hz: f64) {
+        assert!(clock_hz > 0.0, "The clock must run at a positive rate.");
+        self.clock_hz = clock_hz;
+    }
+
+    pub fn clock_hz(&self) -> f64 {
+        self.clock_hz
+    }
+
+    //Runs clock ticks until the circuit has no input remaining and returns the number of clock ticks
+    // that were run. After each tick the remainder of the period is slept away, a tick that takes
+    // longer than the period is followed immediately by the next one.
+    pub fn run_realtime<F>(
+        &mut self,
+        input_gates: &Vec<SharedMutex<dyn LogicGate>>,
+        output_gates: &Vec<SharedMutex<dyn LogicGateAndOutputGate>>,
+        handle_output: &mut F,
+    ) -> usize where
+        F: FnMut(&mut Self, &Vec<(String, Vec<GateOutputState>)>, &Vec<SharedMutex<dyn LogicGateAndOutputGate>>)
+    {
+        let mut num_clock_ticks = 0;
+        let mut propagate_signal_through_circuit = true;
+
+        loop {
+            let tick_start = Instant::now();
+
+            let continue_clock = run_circuit(
+                input_gates,
+                output_gates,
+                propagate_signal_through_circuit,
+                &mut |clock_tick_inputs, output_gates| {
+                    handle_output(self, clock_tick_inputs, output_gates);
+                },
+            );
+
+            propagate_signal_through_circuit = false;
+
+            //The final call only reports that the input has run out.
+            if !continue_clock {
+                break;
+            }
+            num_clock_ticks += 1;
+
+            //The rate is read every tick so that a change made by handle_output applies right away.
+            let period = Duration::from_secs_f64(1.0 / self.clock_hz);
+            let elapsed = tick_start.elapsed();
+            if elapsed < period {
+                thread::sleep(period - elapsed);
+            }
+        }
+
+        num_clock_ticks
+    }
+}
+
+//Same as run_circuit() except that at most pass_budget passes are made through the circuit. Each
+// pass calculates every gate that was waiting for an updated input. If the budget runs out before
+// the circuit settles, the gates that were still waiting are returned. Passing them back in as
+// input_gates will continue propagating from where this call stopped.
+pub fn run_circuit_with_pass_budget<F>(
+    input_gates: &Vec<SharedMutex<dyn LogicGate>>,
+    output_gates: &Vec<SharedMutex<dyn LogicGateAndOutputGate>>,
+    propagate_signal_through_circuit: bool,
+    pass_budget: Option<usize>,
+    handle_output: &mut F,
+) -> (bool, Vec<SharedMutex<dyn LogicGate>>) where
+    F: FnMut(&Vec<(String, Vec<GateOutputState>)>, &Vec<SharedMutex<dyn LogicGateAndOutputGate>>)
 {
     let mut continue_clock = true;
+    let mut num_passes: usize = 0;
+    let scheduling_strategy = SCHEDULING_STRATEGY.with(|strategy| strategy.get());
 
     let print_output =
         if RUN_CIRCUIT_IS_HIGH_LEVEL.load(Ordering::SeqCst) {
@@ -1318,24 +1492,59 @@ pub fn run_circuit<F>(
         println!("run_circuit");
     }
     while !next_gates.is_empty() {
+        //A logic error was stored instead of panicking (see set_return_logic_errors()), the state
+        // of the circuit can no longer be trusted so stop running it.
+        if get_pending_logic_error().is_some() {
+            return (false, Vec::new());
+        }
+
+        if pass_budget.is_some_and(|pass_budget| num_passes >= pass_budget) {
+            break;
+        }
+        num_passes += 1;
+        NUM_PASSES.with(|total_passes| total_passes.set(total_passes.get() + 1));
+
         if print_output {
             println!("next_gates.len() = {}", next_gates.len());
         }
-        let gates = next_gates;
+        let mut gates: VecDeque<SharedMutex<dyn LogicGate>> = match scheduling_strategy {
+            SchedulingStrategy::BreadthFirst => next_gates.into(),
+            //Gates are popped from the back, reversing them keeps the first gate calculated first.
+            SchedulingStrategy::DepthFirst => next_gates.into_iter().rev().collect(),
+        };
         next_gates = Vec::new();
         let mut next_gates_set = HashSet::new();
         let mut num_invalid_gates: usize = 0;
+        let mut num_calculated_gates: usize = 0;
+
+        loop {
+            let gate_cell = match scheduling_strategy {
+                SchedulingStrategy::BreadthFirst => gates.pop_front(),
+                SchedulingStrategy::DepthFirst => gates.pop_back(),
+            };
+
+            let Some(gate_cell) = gate_cell else {
+                break;
+            };
 
-        for gate_cell in gates.into_iter() {
             let mut gate = gate_cell.lock().unwrap();
+            num_calculated_gates += 1;
+
+            //With depth first, next_gates_set holds the gates still waiting in this pass. Once a
+            // gate is calculated it can be scheduled again.
+            if scheduling_strategy == SchedulingStrategy::DepthFirst {
+                next_gates_set.remove(&gate.get_unique_id());
+            }
             // unique_gates.insert(gate.get_unique_id());
 
             let gate_output = gate.fetch_output_signals_calculate();
 
             let gate_output = if let Err(err) = gate_output {
                 match err {
-                    GateLogicError::NoMoreAutomaticInputsRemaining => {
-                        return false;
+                    GateLogicError::NoMoreAutomaticInputsRemaining
+                    | GateLogicError::OscillationDetected(_)
+                    | GateLogicError::UnresolvableContention(_) => {
+                        return (false, Vec::new());
                     }
                     GateLogicError::MultipleValidSignalsWhenCalculating => {
                         num_invalid_gates += 1;
@@ -1406,13 +1615,22 @@ pub fn run_circuit<F>(
                         }
 
                         if should_update_gate {
+                            record_settle_pass(&next_gate);
+
                             if print_output {
                                 println!("Pushing gate {} tag {}", next_gate.lock().unwrap().get_gate_type(), next_gate.lock().unwrap().get_tag());
                             }
                             // drop(mutable_next_gate);
                             // println!("next_gates.insert()");
                             next_gates_set.insert(gate_id);
-                            next_gates.push(next_gate);
+                            let follow_gate_now = scheduling_strategy == SchedulingStrategy::DepthFirst
+                                && next_gate.lock().unwrap().num_children_gates() == 0;
+
+                            if follow_gate_now {
+                                gates.push_back(next_gate);
+                            } else {
+                                next_gates.push(next_gate);
+                            }
                         }
                     }
                 }
@@ -1422,7 +1640,14 @@ pub fn run_circuit<F>(
         //This is set up to handle invalid states. If all gates are in an invalid state the app will
         // panic. See calculate_input_signal_from_single_inputs() in foundations.rs for more
         // details.
-        if num_invalid_gates > 0 && num_invalid_gates == next_gates.len() {
+        let all_gates_invalid = match scheduling_strategy {
+            SchedulingStrategy::BreadthFirst => num_invalid_gates == next_gates.len(),
+            //Gates scheduled during the pass are followed until nothing is left, so the circuit is
+            // only stuck when the invalid gates were the only gates calculated.
+            SchedulingStrategy::DepthFirst => num_invalid_gates == num_calculated_gates,
+        };
+
+        if num_invalid_gates > 0 && all_gates_invalid {
             let mut gates = Vec::new();
             for gate in next_gates {
                 let mut_gate = gate.lock().unwrap();
@@ -1430,6 +1655,14 @@ pub fn run_circuit<F>(
                     format!("Gate {} id {} with tag {}.", mut_gate.get_gate_type(), mut_gate.get_unique_id().id(), mut_gate.get_tag())
                 );
             }
+
+            if return_logic_errors() {
+                store_logic_error(
+                    GateLogicError::UnresolvableContention(gates.join("\n"))
+                );
+                return (false, Vec::new());
+            }
+
             panic!("All gates inside the circuit have returned invalid input, aborting.\nInvalid Gate List\n{:#?}", gates);
         }
     }
@@ -1439,7 +1672,7 @@ pub fn run_circuit<F>(
         &output_gates,
     );
 
-    continue_clock
+    (continue_clock, next_gates)
 }
 
 fn check_if_next_gate_should_be_stored(
@@ -1463,10 +1696,283 @@ fn check_if_next_gate_should_be_stored(
     input_signal_updated || (propagate_signal && changed_count_this_tick == 1) && !contains_id
 }
 
+thread_local! {
+    //Counts every gate that run_circuit() scheduled to be calculated again by its GateType while
+    // settle_pass_breakdown() is running. None means nothing is being counted.
+    static SETTLE_PASS_BREAKDOWN: RefCell<Option<HashMap<GateType, usize>>> = const { RefCell::new(None) };
+}
+
+fn record_settle_pass(gate: &SharedMutex<dyn LogicGate>) {
+    SETTLE_PASS_BREAKDOWN.with(|breakdown| {
+        if let Some(breakdown) = breakdown.borrow_mut().as_mut() {
+            let gate_type = gate.lock().unwrap().get_gate_type();
+            *breakdown.entry(gate_type).or_insert(0) += 1;
+        }
+    });
+}
+
+#[allow(dead_code)]
+//Calculates the gate and returns how many times a gate of each GateType had to be calculated again
+// because one of its inputs changed while the gate settled. Gates nested inside complex gates are
+// counted as well, so the types with the largest counts are the ones that dominate the time it
+// takes the circuit to converge. The inputs of the gate should be updated before this is called.
+pub fn settle_pass_breakdown(gate: &SharedMutex<dyn LogicGate>) -> HashMap<GateType, usize> {
+    SETTLE_PASS_BREAKDOWN.with(|breakdown| *breakdown.borrow_mut() = Some(HashMap::new()));
+
+    //If a logic error is returned, the passes made up to that point are still reported.
+    let _ = gate.lock().unwrap().fetch_output_signals_calculate();
+
+    SETTLE_PASS_BREAKDOWN.with(|breakdown| breakdown.borrow_mut().take().unwrap_or_default())
+}
+
+//Returns the gates directly inside a complex gate along with the indices of the gates each one
+// drives. Gates are indexed in the order they were found starting from the input gates.
+fn collect_child_gate_graph(gate: &dyn LogicGate) -> (Vec<UniqueID>, Vec<Vec<usize>>) {
+    let mut gate_ids = Vec::new();
+    let mut gate_indices = HashMap::new();
+    let mut edges: Vec<Vec<usize>> = Vec::new();
+    let mut next_gates = VecDeque::new();
+
+    for input_gate in gate.get_input_gates() {
+        let gate_id = input_gate.lock().unwrap().get_unique_id();
+        if let hash_map::Entry::Vacant(entry) = gate_indices.entry(gate_id) {
+            entry.insert(gate_ids.len());
+            gate_ids.push(gate_id);
+            edges.push(Vec::new());
+            next_gates.push_back(input_gate);
+        }
+    }
+
+    while let Some(gate_cell) = next_gates.pop_front() {
+        let mut child_gate = gate_cell.lock().unwrap();
+        let gate_index = gate_indices[&child_gate.get_unique_id()];
+        let gate_output = child_gate.fetch_output_signals_no_calculate().unwrap();
+        drop(child_gate);
+
+        for output in gate_output.into_iter() {
+            if let GateOutputState::Connected(next_gate_info) = output {
+                let next_gate_id = next_gate_info.gate.lock().unwrap().get_unique_id();
+                let next_gate_index = match gate_indices.get(&next_gate_id) {
+                    Some(next_gate_index) => *next_gate_index,
+                    None => {
+                        gate_indices.insert(next_gate_id, gate_ids.len());
+                        gate_ids.push(next_gate_id);
+                        edges.push(Vec::new());
+                        next_gates.push_back(next_gate_info.gate);
+                        gate_ids.len() - 1
+                    }
+                };
+
+                if !edges[gate_index].contains(&next_gate_index) {
+                    edges[gate_index].push(next_gate_index);
+                }
+            }
+        }
+    }
+
+    (gate_ids, edges)
+}
+
+//Tarjan's algorithm, each returned component is a set of gates that can all reach each other.
+pub(crate) fn strongly_connected_components(edges: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    struct TarjanState {
+        next_index: usize,
+        indices: Vec<Option<usize>>,
+        low_links: Vec<usize>,
+        on_stack: Vec<bool>,
+        stack: Vec<usize>,
+        components: Vec<Vec<usize>>,
+    }
+
+    fn visit(node: usize, edges: &[Vec<usize>], state: &mut TarjanState) {
+        state.indices[node] = Some(state.next_index);
+        state.low_links[node] = state.next_index;
+        state.next_index += 1;
+        state.stack.push(node);
+        state.on_stack[node] = true;
+
+        for &next_node in edges[node].iter() {
+            match state.indices[next_node] {
+                None => {
+                    visit(next_node, edges, state);
+                    state.low_links[node] = state.low_links[node].min(state.low_links[next_node]);
+                }
+                Some(next_index) if state.on_stack[next_node] => {
+                    state.low_links[node] = state.low_links[node].min(next_index);
+                }
+                Some(_) => {}
+            }
+        }
+
+        if Some(state.low_links[node]) == state.indices[node] {
+            let mut component = Vec::new();
+            loop {
+                let member = state.stack.pop().unwrap();
+                state.on_stack[member] = false;
+                component.push(member);
+                if member == node {
+                    break;
+                }
+            }
+            component.sort();
+            state.components.push(component);
+        }
+    }
+
+    let mut state = TarjanState {
+        next_index: 0,
+        indices: vec![None; edges.len()],
+        low_links: vec![0; edges.len()],
+        on_stack: vec![false; edges.len()],
+        stack: Vec::new(),
+        components: Vec::new(),
+    };
+
+    for node in 0..edges.len() {
+        if state.indices[node].is_none() {
+            visit(node, edges, &mut state);
+        }
+    }
+
+    state.components
+}
+
+//Returns the gates that are not part of a feedback loop in dependency order along with every
+// feedback loop.
+fn topological_sort(gate: &dyn LogicGate) -> (Vec<UniqueID>, Vec<Vec<UniqueID>>) {
+    let (gate_ids, edges) = collect_child_gate_graph(gate);
+    let mut components = strongly_connected_components(&edges);
+    components.sort_by_key(|component| component[0]);
+
+    let mut component_of_gate = vec![0; gate_ids.len()];
+    for (component_index, component) in components.iter().enumerate() {
+        for &member in component.iter() {
+            component_of_gate[member] = component_index;
+        }
+    }
+
+    //Each loop is collapsed into a single node so that the gates around it can still be ordered.
+    let mut component_edges = vec![Vec::new(); components.len()];
+    let mut in_degree = vec![0; components.len()];
+    for (node, next_nodes) in edges.iter().enumerate() {
+        for &next_node in next_nodes.iter() {
+            let from = component_of_gate[node];
+            let to = component_of_gate[next_node];
+            if from != to && !component_edges[from].contains(&to) {
+                component_edges[from].push(to);
+                in_degree[to] += 1;
+            }
+        }
+    }
+
+    let mut order = Vec::new();
+    let mut cycles = Vec::new();
+    let mut ready: VecDeque<usize> = (0..components.len())
+        .filter(|&component_index| in_degree[component_index] == 0)
+        .collect();
+
+    while let Some(component_index) = ready.pop_front() {
+        let component = &components[component_index];
+        let is_cycle = component.len() > 1 || edges[component[0]].contains(&component[0]);
+
+        let component_gate_ids = component.iter().map(|&member| gate_ids[member]);
+        if is_cycle {
+            cycles.push(component_gate_ids.collect());
+        } else {
+            order.extend(component_gate_ids);
+        }
+
+        for &next_component in component_edges[component_index].iter() {
+            in_degree[next_component] -= 1;
+            if in_degree[next_component] == 0 {
+                ready.push_back(next_component);
+            }
+        }
+    }
+
+    (order, cycles)
+}
+
+#[allow(dead_code)]
+//Returns the gates directly inside a complex gate with every gate coming after the gates that
+// drive it. Gates that are part of a feedback loop are left out, see feedback_cycles().
+pub fn topological_order(gate: &dyn LogicGate) -> Vec<UniqueID> {
+    topological_sort(gate).0
+}
+
+#[allow(dead_code)]
+//Returns each feedback loop directly inside a complex gate (such as the cross coupled gates of a
+// latch).
+pub fn feedback_cycles(gate: &dyn LogicGate) -> Vec<Vec<UniqueID>> {
+    topological_sort(gate).1
+}
+
+//The output signals of every gate directly inside a complex gate at a single point in time. Nested
+// complex gates are captured as a single gate.
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    pub gate_outputs: HashMap<UniqueID, Vec<Signal>>,
+}
+
+#[allow(dead_code)]
+impl Snapshot {
+    pub fn capture(gate: &dyn LogicGate) -> Self {
+        let mut gate_outputs = HashMap::new();
+        let mut next_gates: VecDeque<SharedMutex<dyn LogicGate>> = gate.get_input_gates().into_iter().collect();
+
+        while let Some(gate_cell) = next_gates.pop_front() {
+            let mut child_gate = gate_cell.lock().unwrap();
+            let gate_id = child_gate.get_unique_id();
+            if gate_outputs.contains_key(&gate_id) {
+                continue;
+            }
+
+            let gate_output = child_gate.fetch_output_signals_no_calculate().unwrap();
+            drop(child_gate);
+
+            gate_outputs.insert(gate_id, extract_signals_from_gate_output_states(&gate_output));
+
+            for output in gate_output.into_iter() {
+                if let GateOutputState::Connected(next_gate_info) = output {
+                    next_gates.push_back(next_gate_info.gate);
+                }
+            }
+        }
+
+        Snapshot {
+            gate_outputs,
+        }
+    }
+}
+
+#[allow(dead_code)]
+//Returns the ids of the gates whose outputs are different in curr than they were in prev, sorted by
+// id. Gates that only appear in curr are also returned.
+pub fn gates_changed_between(prev: &Snapshot, curr: &Snapshot) -> Vec<UniqueID> {
+    let mut changed_gates: Vec<UniqueID> = curr.gate_outputs
+        .iter()
+        .filter(|(gate_id, signals)| prev.gate_outputs.get(gate_id) != Some(signals))
+        .map(|(gate_id, _)| *gate_id)
+        .collect();
+
+    changed_gates.sort_by_key(|gate_id| gate_id.id());
+    changed_gates
+}
+
+#[allow(dead_code)]
 pub fn count_gates_in_circuit(
     input_gates: &Vec<SharedMutex<dyn LogicGate>>,
 ) -> usize {
+    collect_gates_in_circuit(input_gates).len()
+}
+
+//Returns every gate reachable from the input gates, each gate is only returned once.
+pub fn collect_gates_in_circuit(
+    input_gates: &Vec<SharedMutex<dyn LogicGate>>,
+) -> Vec<SharedMutex<dyn LogicGate>> {
     let mut unique_gates = HashSet::new();
+    let mut collected_gates = Vec::new();
     let mut next_gates: Vec<SharedMutex<dyn LogicGate>> = input_gates.clone();
 
     while !next_gates.is_empty() {
@@ -1486,6 +1992,10 @@ pub fn count_gates_in_circuit(
                     GateLogicError::NoMoreAutomaticInputsRemaining => {
                         panic!("AutomaticInput should not be used with count_gates_in_circuit().")
                     }
+                    GateLogicError::OscillationDetected(_)
+                    | GateLogicError::UnresolvableContention(_) => {
+                        panic!("Logic error {:?} occurred while counting gates.", err)
+                    }
                     GateLogicError::MultipleValidSignalsWhenCalculating => {
                         num_invalid_gates += 1;
                         drop(gate);
@@ -1498,6 +2008,7 @@ pub fn count_gates_in_circuit(
             };
 
             drop(gate);
+            collected_gates.push(gate_cell);
             for output in gate_output.into_iter() {
                 match output {
                     GateOutputState::NotConnected(_signal) => {}
@@ -1532,7 +2043,7 @@ pub fn count_gates_in_circuit(
         }
     }
 
-    unique_gates.len()
+    collected_gates
 }
 
 pub fn generate_default_output(cpu: &SharedMutex<VariableBitCPU>) -> Vec<Signal> {
@@ -1559,7 +2070,7 @@ pub fn generate_default_output(cpu: &SharedMutex<VariableBitCPU>) -> Vec<Signal>
     // VariableBitCPU::IO_CLK_E
     // VariableBitCPU::IO_CLK_S
 
-    let mut generated_signals = vec![LOW_; cpu.lock().unwrap().get_complex_gate().output_gates.len()];
+    let mut generated_signals = vec![LOW_; cpu.lock().unwrap().num_outputs()];
     let clke_index = cpu.lock().unwrap().get_complex_gate().gate_tags_to_index[VariableBitCPU::CLKE].index;
     generated_signals[clke_index] = HIGH;
     generated_signals
@@ -1638,81 +2149,608 @@ pub fn run_instructions(
     decoder_input_size: usize,
     binary_strings: &Vec<&str>,
 ) -> SharedMutex<VariableBitCPU> {
+    run_instructions_internal(
+        number_bits,
+        decoder_input_size,
+        binary_strings,
+        None,
+    )
+}
+
+#[allow(dead_code)]
+//Same as run_instructions() except every signal driven into the cpu inputs is recorded, starting
+// with the RAM load. The returned Stimulus can be passed to replay() on a newly built cpu.
+pub fn run_instructions_and_record_stimulus(
+    number_bits: usize,
+    decoder_input_size: usize,
+    binary_strings: &Vec<&str>,
+) -> (SharedMutex<VariableBitCPU>, Stimulus) {
+    let mut stimulus = Stimulus::new();
+
+    let cpu = run_instructions_internal(
+        number_bits,
+        decoder_input_size,
+        binary_strings,
+        Some(&mut stimulus),
+    );
+
+    (cpu, stimulus)
+}
+
+#[allow(dead_code)]
+//Same as run_instructions() except the clock is stopped after max_clock_ticks clock ticks. The
+// returned bool is true if the program reached END before the limit.
+pub fn run_instructions_with_limit(
+    number_bits: usize,
+    decoder_input_size: usize,
+    binary_strings: &Vec<&str>,
+    max_clock_ticks: usize,
+) -> (SharedMutex<VariableBitCPU>, bool) {
     let cpu = VariableBitCPU::new(number_bits, decoder_input_size);
 
-    let num_ram_cells = usize::pow(2, (decoder_input_size * 2) as u32);
-    assert!(binary_strings.len() <= num_ram_cells);
     if !binary_strings.is_empty() {
         assert_eq!(binary_strings[0].len(), number_bits);
     }
 
-    println!("Beginning to load values into RAM");
-
-    let start_load = Instant::now();
+    let previous_limit = CLOCK_TICK_LIMIT.with(|limit| limit.replace(Some(max_clock_ticks)));
 
-    load_values_into_ram(
+    let program_stop = run_program_on_cpu(
         &cpu,
+        decoder_input_size,
         binary_strings,
-        num_ram_cells,
-    );
-
-    let complete_load = Instant::now();
+        None,
+        &mut [],
+        None,
+    ).unwrap();
 
-    let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
-    let clock = Clock::new(1, "PRIMARY_CLOCK");
-    let clk_in_index = cpu.lock().unwrap().get_index_from_tag(VariableBitCPU::CLK_IN);
-    cpu.lock().unwrap().get_clock_synced_with_cpu(&clock);
-
-    connect_gates(
-        clock.clone(),
-        0,
-        cpu.clone(),
-        clk_in_index,
-    );
-
-    input_gates.push(clock.clone());
-
-    let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
-    let end_output_gate = SimpleOutput::new(END_OUTPUT_GATE_TAG);
+    CLOCK_TICK_LIMIT.with(|limit| limit.set(previous_limit));
 
-    let cpu_end_index = cpu.lock().unwrap().get_index_from_tag(VariableBitCPU::END);
-    connect_gates(
-        cpu.clone(),
-        cpu_end_index,
-        end_output_gate.clone(),
-        0,
-    );
+    (cpu, program_stop == ProgramStop::End)
+}
 
-    output_gates.push(end_output_gate.clone());
+fn run_instructions_internal(
+    number_bits: usize,
+    decoder_input_size: usize,
+    binary_strings: &Vec<&str>,
+    stimulus: Option<&mut Stimulus>,
+) -> SharedMutex<VariableBitCPU> {
+    let cpu = VariableBitCPU::new(number_bits, decoder_input_size);
 
-    println!("\nCompleted load in {} clock-ticks. Beginning program.\n", get_clock_tick_number());
-    unsafe {
-        CLOCK_TICK_NUMBER = 0;
-        RAM_TIME = Duration::new(0, 0);
-        CONTROL_SECTION_TIME = Duration::new(0, 0);
-        ALU_TIME = Duration::new(0, 0);
+    if !binary_strings.is_empty() {
+        assert_eq!(binary_strings[0].len(), number_bits);
     }
-    let mut continue_load_operation = true;
-    let mut propagate_signal = true;
-    while continue_load_operation {
-        unsafe {
-            CLOCK_TICK_NUMBER += 1;
-        }
 
-        continue_load_operation = run_circuit(
-            &input_gates,
-            &output_gates,
-            propagate_signal,
-            &mut |_clock_tick_inputs, _output_gates| {},
-        );
+    //Logic errors panic unless set_return_logic_errors() was enabled, so this will only fail if
+    // the caller opted in.
+    run_program_on_cpu(
+        &cpu,
+        decoder_input_size,
+        binary_strings,
+        stimulus,
+        &mut [],
+        None,
+    ).unwrap();
 
-        propagate_signal = false;
-    }
+    cpu
+}
 
-    let complete_run = Instant::now();
+#[allow(dead_code)]
+//Loads and runs the program on a cpu that has already been built, see CpuConfig inside
+// build_circuit.rs. Returns why the program stopped.
+pub fn run_instructions_on_cpu(
+    cpu: &SharedMutex<VariableBitCPU>,
+    decoder_input_size: usize,
+    binary_strings: &Vec<&str>,
+) -> ProgramStop {
+    run_program_on_cpu(
+        cpu,
+        decoder_input_size,
+        binary_strings,
+        None,
+        &mut [],
+        None,
+    ).unwrap()
+}
 
-    let run_time = complete_run.duration_since(complete_load);
-    println!("Loading took {:?}", complete_load.duration_since(start_load));
+#[allow(dead_code)]
+//Same as run_instructions_on_cpu() except the devices are attached to the cpu IO bus while the
+// program runs, see IoDevice inside io.rs.
+pub fn run_instructions_on_cpu_with_io_devices(
+    cpu: &SharedMutex<VariableBitCPU>,
+    decoder_input_size: usize,
+    binary_strings: &Vec<&str>,
+    io_devices: &mut [&mut dyn IoDevice],
+) -> ProgramStop {
+    run_program_on_cpu(
+        cpu,
+        decoder_input_size,
+        binary_strings,
+        None,
+        io_devices,
+        None,
+    ).unwrap()
+}
+
+#[allow(dead_code)]
+//Same as run_instructions_on_cpu() except observer is called with the cpu after every clock tick.
+// The cpu is not locked when observer is called. While the HALT input of the cpu is HIGH the clock
+// is not advanced and observer is still called once per held tick so that it can release HALT,
+// see set_halt() inside build_circuit.rs.
+pub fn run_instructions_on_cpu_with_observer<F>(
+    cpu: &SharedMutex<VariableBitCPU>,
+    decoder_input_size: usize,
+    binary_strings: &Vec<&str>,
+    mut observer: F,
+) -> ProgramStop
+    where F: FnMut(&SharedMutex<VariableBitCPU>)
+{
+    run_program_on_cpu(
+        cpu,
+        decoder_input_size,
+        binary_strings,
+        None,
+        &mut [],
+        Some(&mut observer),
+    ).unwrap()
+}
+
+#[allow(dead_code)]
+//Same as run_instructions() except logic errors (oscillation or contention that can never be
+// resolved) are returned instead of panicking.
+pub fn try_run_instructions(
+    number_bits: usize,
+    decoder_input_size: usize,
+    binary_strings: &Vec<&str>,
+) -> Result<SharedMutex<VariableBitCPU>, GateLogicError> {
+    let cpu = VariableBitCPU::new(number_bits, decoder_input_size);
+
+    if !binary_strings.is_empty() {
+        assert_eq!(binary_strings[0].len(), number_bits);
+    }
+
+    try_run_instructions_on_cpu(
+        &cpu,
+        decoder_input_size,
+        binary_strings,
+    )?;
+
+    Ok(cpu)
+}
+
+#[allow(dead_code)]
+//Loads and runs the program on a cpu that has already been built. This allows the cpu to be
+// modified before it runs. Logic errors are returned instead of panicking.
+pub fn try_run_instructions_on_cpu(
+    cpu: &SharedMutex<VariableBitCPU>,
+    decoder_input_size: usize,
+    binary_strings: &Vec<&str>,
+) -> Result<(), GateLogicError> {
+    let previous_return_logic_errors = return_logic_errors();
+    set_return_logic_errors(true);
+    take_pending_logic_error();
+
+    let result = run_program_on_cpu(
+        cpu,
+        decoder_input_size,
+        binary_strings,
+        None,
+        &mut [],
+        None,
+    );
+
+    take_pending_logic_error();
+    set_return_logic_errors(previous_return_logic_errors);
+
+    result.map(|_| ())
+}
+
+//An output of the cpu that did not end up with the same signal on every config passed to
+// run_and_compare(). There is one signal per config in the same order as the configs.
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputDivergence {
+    pub tag: String,
+    pub signals: Vec<Signal>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct ComparisonReport {
+    //The final signals of each cpu keyed by output tag, in the same order as the configs.
+    pub outputs: Vec<HashMap<String, Signal>>,
+    //Sorted by tag.
+    pub divergences: Vec<OutputDivergence>,
+}
+
+#[allow(dead_code)]
+impl ComparisonReport {
+    pub fn is_identical(&self) -> bool {
+        self.divergences.is_empty()
+    }
+}
+
+#[allow(dead_code)]
+//Builds a cpu from each config, runs the same program on all of them and compares every output
+// once they have finished. This is meant for checking that different cpu builds behave the same
+// way. Every config must have a bus width that matches the machine code. Only the outputs that
+// every cpu has are compared, so builds with different amounts of RAM can be compared.
+pub fn run_and_compare(
+    configs: &[CpuConfig],
+    machine_code: &Vec<&str>,
+) -> ComparisonReport {
+    let mut outputs = Vec::new();
+    for config in configs.iter() {
+        let cpu = config.build();
+
+        run_instructions_on_cpu(
+            &cpu,
+            config.decoder_input_size(),
+            machine_code,
+        );
+
+        let tags_sorted_by_index = extract_output_tags_sorted_by_index(cpu.lock().unwrap().get_complex_gate());
+        let collected_signals = collect_signals_from_logic_gate(cpu.clone());
+
+        outputs.push(
+            tags_sorted_by_index.into_iter().zip(collected_signals).collect::<HashMap<String, Signal>>()
+        );
+    }
+
+    let mut shared_tags: Vec<&String> = match outputs.first() {
+        Some(first_output) => first_output
+            .keys()
+            .filter(|tag| outputs.iter().all(|output| output.contains_key(*tag)))
+            .collect(),
+        None => Vec::new(),
+    };
+    shared_tags.sort();
+
+    let mut divergences = Vec::new();
+    for tag in shared_tags {
+        let signals: Vec<Signal> = outputs.iter().map(|output| output[tag].clone()).collect();
+
+        if signals.iter().any(|signal| *signal != signals[0]) {
+            divergences.push(
+                OutputDivergence {
+                    tag: tag.clone(),
+                    signals,
+                }
+            );
+        }
+    }
+
+    ComparisonReport {
+        outputs,
+        divergences,
+    }
+}
+
+//Why a program stopped running.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProgramStop {
+    //The program reached END.
+    End,
+    //The clock tick limit of run_instructions_with_limit() was reached.
+    ClockTickLimit,
+    //HALT was asserted with no observer to release it, see set_halt() inside build_circuit.rs.
+    HaltHeld,
+    //DIV_BY_ZERO went HIGH with DivByZeroTrap::Halt, see VariableBitCPU::set_div_by_zero_trap().
+    DivByZero,
+    //A RAM address passed to VariableBitCPU::add_ram_watchpoint() was written.
+    Watchpoint,
+}
+
+//The divider is not part of the cpu, so it is driven with R0 as the dividend and R1 as the divisor.
+fn drive_divider_from_registers(cpu: &SharedMutex<VariableBitCPU>, divider: &SharedMutex<VariableBitDivider>) {
+    let mut cpu = cpu.lock().unwrap();
+    let cpu_output = cpu.fetch_output_signals_no_calculate().unwrap();
+    let cpu_signals = extract_signals_from_gate_output_states(&cpu_output);
+    let gate_tags_to_index = &cpu.get_complex_gate().gate_tags_to_index;
+    let mut divider = divider.lock().unwrap();
+
+    for (register_tag, operand) in [(VariableBitCPU::R0, "a"), (VariableBitCPU::R1, "b")] {
+        let mut bit = 0;
+        while let Some(gate_tag_info) = gate_tags_to_index.get(&format!("{}_{}", register_tag, bit)) {
+            let input_index = divider.get_index_from_tag(format!("{}_{}", operand, bit).as_str());
+            divider.update_input_signal(
+                GateInput::new(
+                    input_index,
+                    cpu_signals[gate_tag_info.index].clone(),
+                    UniqueID::zero_id(),
+                )
+            );
+            bit += 1;
+        }
+    }
+}
+
+fn div_by_zero_asserted(divider: &SharedMutex<VariableBitDivider>) -> bool {
+    let mut divider = divider.lock().unwrap();
+    let div_by_zero_index = divider.get_index_from_tag("DIV_BY_ZERO");
+    let divider_output = divider.fetch_output_signals_calculate().unwrap();
+    extract_signals_from_gate_output_states(&divider_output)[div_by_zero_index] == HIGH
+}
+
+//Called after every clock tick, see run_instructions_on_cpu_with_observer().
+type CpuObserver<'a> = &'a mut dyn FnMut(&SharedMutex<VariableBitCPU>);
+
+fn run_program_on_cpu(
+    cpu: &SharedMutex<VariableBitCPU>,
+    decoder_input_size: usize,
+    binary_strings: &Vec<&str>,
+    mut stimulus: Option<&mut Stimulus>,
+    io_devices: &mut [&mut dyn IoDevice],
+    mut observer: Option<CpuObserver>,
+) -> Result<ProgramStop, GateLogicError> {
+    let num_ram_cells = usize::pow(2, (decoder_input_size * 2) as u32);
+    assert!(binary_strings.len() <= num_ram_cells);
+
+    println!("Beginning to load values into RAM");
+
+    let start_load = Instant::now();
+
+    //See VariableBitCPU::randomize_initial_state().
+    let mut initial_state_rng = cpu.lock().unwrap().initial_state_seed().map(StdRng::seed_from_u64);
+    let number_bits = binary_strings.first().map_or(0, |binary_string| binary_string.len());
+
+    let random_ram_values: Vec<String> = match initial_state_rng.as_mut() {
+        Some(rng) => {
+            (binary_strings.len()..num_ram_cells)
+                .map(|_| (0..number_bits).map(|_| if rng.gen() { '1' } else { '0' }).collect())
+                .collect()
+        }
+        None => Vec::new(),
+    };
+
+    let mut ram_values = binary_strings.clone();
+    ram_values.extend(random_ram_values.iter().map(|value| value.as_str()));
+
+    load_values_into_ram_internal(
+        cpu,
+        &ram_values,
+        num_ram_cells,
+        stimulus.as_deref_mut(),
+    );
+
+    if let Some(logic_error) = get_pending_logic_error() {
+        return Err(logic_error);
+    }
+
+    //Loading RAM expects the registers to be LOW, so they are randomized afterwards.
+    if let Some(rng) = initial_state_rng.as_mut() {
+        let register_values: [Vec<Signal>; 4] = std::array::from_fn(|_| {
+            (0..number_bits).map(|_| if rng.gen() { HIGH } else { LOW_ }).collect()
+        });
+
+        cpu.lock().unwrap().overwrite_general_purpose_registers(&register_values);
+    }
+
+    //Loading RAM leaves IAR at address 0, see VariableBitCPU::set_reset_vector().
+    let reset_vector = cpu.lock().unwrap().reset_vector();
+    if reset_vector != 0 {
+        assert!(reset_vector < num_ram_cells as u64);
+        let iar_value = u64_to_signals(reset_vector, number_bits, Endianness::Little);
+        cpu.lock().unwrap().overwrite_instruction_address_register(&iar_value);
+    }
+
+    let attached_io_devices = cpu.lock().unwrap().io_devices();
+
+    //See VariableBitCPU::set_div_by_zero_trap().
+    let div_by_zero_trap = cpu.lock().unwrap().div_by_zero_trap();
+    if let Some((_, DivByZeroTrap::Jump(address))) = div_by_zero_trap.as_ref() {
+        assert!(*address < num_ram_cells as u64);
+    }
+    let mut div_by_zero = div_by_zero_trap.as_ref().is_some_and(|(divider, _)| {
+        drive_divider_from_registers(cpu, divider);
+        div_by_zero_asserted(divider)
+    });
+    let mut div_by_zero_jump = None;
+    let mut div_by_zero_previous_step = cpu.lock().unwrap().get_current_step();
+
+    let complete_load = Instant::now();
+
+    let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+    let clock = Clock::new(1, "PRIMARY_CLOCK");
+    let clock_tag = clock.lock().unwrap().get_tag();
+    let clk_in_index = cpu.lock().unwrap().get_index_from_tag(VariableBitCPU::CLK_IN);
+    cpu.lock().unwrap().get_clock_synced_with_cpu(&clock);
+
+    connect_gates(
+        clock.clone(),
+        0,
+        cpu.clone(),
+        clk_in_index,
+    );
+
+    if let Some(stimulus) = stimulus.as_mut() {
+        stimulus.record_connected_inputs(cpu, &[VariableBitCPU::CLK_IN.to_string()]);
+    }
+
+    input_gates.push(clock.clone());
+
+    let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
+    let end_output_gate = SimpleOutput::new(END_OUTPUT_GATE_TAG);
+
+    let cpu_end_index = cpu.lock().unwrap().get_index_from_tag(VariableBitCPU::END);
+    connect_gates(
+        cpu.clone(),
+        cpu_end_index,
+        end_output_gate.clone(),
+        0,
+    );
+
+    output_gates.push(end_output_gate.clone());
+
+    println!("\nCompleted load in {} clock-ticks. Beginning program.\n", get_clock_tick_number());
+    unsafe {
+        CLOCK_TICK_NUMBER = 0;
+        RAM_TIME = Duration::new(0, 0);
+        CONTROL_SECTION_TIME = Duration::new(0, 0);
+        ALU_TIME = Duration::new(0, 0);
+    }
+    let mut continue_load_operation = true;
+    let mut propagate_signal = true;
+    let mut previous_step = cpu.lock().unwrap().get_current_step();
+    let watched_addresses = cpu.lock().unwrap().ram_watchpoints();
+    cpu.lock().unwrap().record_ram_watchpoint_hit(None);
+    let mut watched_values: Vec<Option<u64>> = if watched_addresses.is_empty() {
+        Vec::new()
+    } else {
+        let ram = CpuSnapshot::capture(cpu).ram;
+        watched_addresses.iter().map(|&address| ram.get(address).copied().flatten()).collect()
+    };
+    let mut watchpoint_hit = false;
+    let clock_tick_limit = CLOCK_TICK_LIMIT.with(|limit| limit.get());
+    let mut num_clock_ticks = 0;
+    let mut program_stop = ProgramStop::End;
+    while continue_load_operation {
+        if clock_tick_limit.is_some_and(|clock_tick_limit| num_clock_ticks >= clock_tick_limit) {
+            println!("Clock tick limit reached, stopping the program.");
+            program_stop = ProgramStop::ClockTickLimit;
+            break;
+        }
+
+        //The clock is held while HALT is asserted. Held ticks still count towards the clock tick
+        // limit so that a HALT that is never released cannot stall the program forever.
+        if halt_asserted(cpu) {
+            match observer.as_mut() {
+                Some(observer) => observer(cpu),
+                None => {
+                    program_stop = ProgramStop::HaltHeld;
+                    break;
+                }
+            }
+            num_clock_ticks += 1;
+            continue;
+        }
+
+        unsafe {
+            CLOCK_TICK_NUMBER += 1;
+        }
+        num_clock_ticks += 1;
+
+        continue_load_operation = run_circuit(
+            &input_gates,
+            &output_gates,
+            propagate_signal,
+            &mut |clock_tick_inputs, _output_gates| {
+                if register_dump_enabled() || instruction_trace_enabled() || snapshot_trace_enabled() {
+                    //The stepper moving back to step 1 means an instruction has completed.
+                    let current_step = cpu.lock().unwrap().get_current_step();
+                    if current_step == Some(0) && previous_step != Some(0) {
+                        if register_dump_enabled() {
+                            dump_registers(cpu);
+                        }
+                        if instruction_trace_enabled() {
+                            trace_instruction(cpu);
+                        }
+                        if snapshot_trace_enabled() {
+                            record_snapshot(cpu);
+                        }
+                    }
+                    previous_step = current_step;
+                }
+
+                if clock_went_high(&clock_tag, clock_tick_inputs) {
+                    cpu.lock().unwrap().increment_cycles_since_reset();
+
+                    let cycle = cpu.lock().unwrap().cycles_since_reset();
+                    for io_device in io_devices.iter_mut() {
+                        io_device.clock_cycle(cycle);
+                    }
+                    for io_device in attached_io_devices.iter() {
+                        io_device.lock().unwrap().clock_cycle(cycle);
+                    }
+                }
+
+                if !io_devices.is_empty() || !attached_io_devices.is_empty() {
+                    let io_bus = IoBusState::read_from_cpu(cpu);
+                    for io_device in io_devices.iter_mut() {
+                        io_device.io_tick(&io_bus);
+                    }
+                    for io_device in attached_io_devices.iter() {
+                        io_device.lock().unwrap().io_tick(&io_bus);
+                    }
+                }
+
+                if let Some(stimulus) = stimulus.as_mut() {
+                    stimulus.record(cpu, clock_tick_inputs);
+                }
+
+                if let Some(observer) = observer.as_mut() {
+                    observer(cpu);
+                }
+
+                if !watched_addresses.is_empty() && !watchpoint_hit {
+                    let ram = CpuSnapshot::capture(cpu).ram;
+                    let pending_store = cpu.lock().unwrap().pending_ram_store();
+
+                    for (i, &address) in watched_addresses.iter().enumerate() {
+                        let new_value = ram.get(address).copied().flatten();
+                        if pending_store == Some(address as u64) {
+                            let hit = RamWatchpointHit {
+                                address,
+                                old_value: watched_values[i],
+                                new_value,
+                                cycle: cpu.lock().unwrap().cycles_since_reset(),
+                            };
+                            println!("Watchpoint on RAM address {} hit, {:?} -> {:?}", hit.address, hit.old_value, hit.new_value);
+                            cpu.lock().unwrap().record_ram_watchpoint_hit(Some(hit));
+                            watchpoint_hit = true;
+                            break;
+                        }
+                        watched_values[i] = new_value;
+                    }
+                }
+            },
+        );
+
+        propagate_signal = false;
+
+        if let Some((divider, trap)) = div_by_zero_trap.as_ref() {
+            let previous_div_by_zero = div_by_zero;
+            drive_divider_from_registers(cpu, divider);
+            div_by_zero = div_by_zero_asserted(divider);
+
+            if div_by_zero && !previous_div_by_zero {
+                match trap {
+                    DivByZeroTrap::Halt => {
+                        program_stop = ProgramStop::DivByZero;
+                        break;
+                    }
+                    DivByZeroTrap::Jump(address) => div_by_zero_jump = Some(*address),
+                }
+            }
+
+            //The stepper moving back to step 1 means an instruction has completed.
+            let current_step = cpu.lock().unwrap().get_current_step();
+            if current_step == Some(0) && div_by_zero_previous_step != Some(0) {
+                if let Some(address) = div_by_zero_jump.take() {
+                    let iar_value = u64_to_signals(address, number_bits, Endianness::Little);
+                    cpu.lock().unwrap().overwrite_instruction_address_register(&iar_value);
+                }
+            }
+            div_by_zero_previous_step = current_step;
+        }
+
+        if watchpoint_hit {
+            println!("Watchpoint hit, stopping the program.");
+            program_stop = ProgramStop::Watchpoint;
+            break;
+        }
+    }
+
+    clock.lock().unwrap().disconnect_gate(0);
+
+    if let Some(logic_error) = get_pending_logic_error() {
+        return Err(logic_error);
+    }
+
+    let complete_run = Instant::now();
+
+    let run_time = complete_run.duration_since(complete_load);
+    println!("Loading took {:?}", complete_load.duration_since(start_load));
     println!("Run took {:?}", run_time);
     println!("Total took {:?}", complete_run.duration_since(start_load));
     println!(
@@ -1722,9 +2760,382 @@ pub fn run_instructions(
         } else {
             get_clock_tick_number() as u64 / complete_run.duration_since(complete_load).as_secs()
         }
-    );
+    );
+
+    Ok(program_stop)
+}
+
+thread_local! {
+    //Holds every register dump printed since enable_register_dump() was called. None means the
+    // dump is disabled.
+    static REGISTER_DUMPS: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+
+    //Holds the instruction register of every instruction completed since enable_instruction_trace()
+    // was called. None means the trace is disabled.
+    static INSTRUCTION_TRACE: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+
+    //Holds a snapshot of the cpu after every instruction completed since enable_snapshot_trace()
+    // was called. None means the trace is disabled.
+    static SNAPSHOT_TRACE: RefCell<Option<Vec<CpuSnapshot>>> = const { RefCell::new(None) };
+
+    //The clock ticks a program may run for before it is stopped, see run_instructions_with_limit().
+    static CLOCK_TICK_LIMIT: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+#[allow(dead_code)]
+//When enabled, run_instructions() prints the registers after every instruction on this thread.
+pub fn enable_register_dump() {
+    REGISTER_DUMPS.with(|dumps| *dumps.borrow_mut() = Some(Vec::new()));
+}
+
+#[allow(dead_code)]
+//Returns every dump that was printed while the dump was enabled.
+pub fn disable_register_dump() -> Vec<String> {
+    REGISTER_DUMPS.with(|dumps| dumps.borrow_mut().take().unwrap_or_default())
+}
+
+fn register_dump_enabled() -> bool {
+    REGISTER_DUMPS.with(|dumps| dumps.borrow().is_some())
+}
+
+fn dump_registers(cpu: &SharedMutex<VariableBitCPU>) {
+    let dump = format_register_dump(cpu);
+    println!("{}", dump);
+
+    REGISTER_DUMPS.with(|dumps| {
+        if let Some(dumps) = dumps.borrow_mut().as_mut() {
+            dumps.push(dump);
+        }
+    });
+}
+
+#[allow(dead_code)]
+//When enabled, run_instructions() records the instruction register after every instruction on
+// this thread.
+pub fn enable_instruction_trace() {
+    INSTRUCTION_TRACE.with(|trace| *trace.borrow_mut() = Some(Vec::new()));
+}
+
+#[allow(dead_code)]
+//Returns every executed instruction as a binary string with the most significant bit first.
+pub fn disable_instruction_trace() -> Vec<String> {
+    INSTRUCTION_TRACE.with(|trace| trace.borrow_mut().take().unwrap_or_default())
+}
+
+fn instruction_trace_enabled() -> bool {
+    INSTRUCTION_TRACE.with(|trace| trace.borrow().is_some())
+}
+
+fn trace_instruction(cpu: &SharedMutex<VariableBitCPU>) {
+    let mut cpu = cpu.lock().unwrap();
+    let cpu_output = cpu.fetch_output_signals_no_calculate().unwrap();
+    let cpu_signals = extract_signals_from_gate_output_states(&cpu_output);
+
+    let mut instruction = String::new();
+    let mut bit_number: usize = 0;
+    while let Some(gate_tag_info) = cpu.get_complex_gate().gate_tags_to_index.get(&format!("{}_{}", VariableBitCPU::IR, bit_number)) {
+        instruction.insert(0, if cpu_signals[gate_tag_info.index] == HIGH { '1' } else { '0' });
+        bit_number += 1;
+    }
+
+    INSTRUCTION_TRACE.with(|trace| {
+        if let Some(trace) = trace.borrow_mut().as_mut() {
+            trace.push(instruction);
+        }
+    });
+}
+
+#[allow(dead_code)]
+//When enabled, run_instructions() captures a CpuSnapshot after every instruction on this thread.
+// The snapshots can be compared with diff_cpu_state() inside build_circuit.rs.
+pub fn enable_snapshot_trace() {
+    SNAPSHOT_TRACE.with(|trace| *trace.borrow_mut() = Some(Vec::new()));
+}
+
+#[allow(dead_code)]
+//Returns every snapshot captured while the trace was enabled.
+pub fn disable_snapshot_trace() -> Vec<CpuSnapshot> {
+    SNAPSHOT_TRACE.with(|trace| trace.borrow_mut().take().unwrap_or_default())
+}
+
+fn snapshot_trace_enabled() -> bool {
+    SNAPSHOT_TRACE.with(|trace| trace.borrow().is_some())
+}
+
+fn record_snapshot(cpu: &SharedMutex<VariableBitCPU>) {
+    let snapshot = CpuSnapshot::capture(cpu);
+
+    SNAPSHOT_TRACE.with(|trace| {
+        if let Some(trace) = trace.borrow_mut().as_mut() {
+            trace.push(snapshot);
+        }
+    });
+}
+
+#[allow(dead_code)]
+//Counts how many times each opcode appears in a trace returned by disable_instruction_trace().
+pub fn opcode_histogram(instruction_trace: &[String]) -> HashMap<String, usize> {
+    let mut histogram = HashMap::new();
+    for instruction in instruction_trace {
+        *histogram.entry(Instructions::opcode_name(instruction).to_string()).or_insert(0) += 1;
+    }
+    histogram
+}
+
+//Formats the registers in hex on a single line. The flags are printed as C (carry), A (a larger),
+// E (equal) and Z (zero) or - if the flag is not set.
+pub fn format_register_dump(cpu: &SharedMutex<VariableBitCPU>) -> String {
+    let mut cpu = cpu.lock().unwrap();
+    let cpu_output = cpu.fetch_output_signals_no_calculate().unwrap();
+    let cpu_signals = extract_signals_from_gate_output_states(&cpu_output);
+
+    let register_tags = [
+        VariableBitCPU::R0,
+        VariableBitCPU::R1,
+        VariableBitCPU::R2,
+        VariableBitCPU::R3,
+        VariableBitCPU::IAR,
+        VariableBitCPU::ACC,
+        VariableBitCPU::TMP,
+    ];
+
+    let mut columns = Vec::new();
+    for register_tag in register_tags {
+        let mut value: u64 = 0;
+        let mut number_bits: usize = 0;
+        //Bit 0 is the least significant bit.
+        while let Some(gate_tag_info) = cpu.get_complex_gate().gate_tags_to_index.get(&format!("{}_{}", register_tag, number_bits)) {
+            if cpu_signals[gate_tag_info.index] == HIGH {
+                value |= 1 << number_bits;
+            }
+            number_bits += 1;
+        }
+
+        columns.push(
+            format!("{} {:0width$X}", register_tag, value, width = number_bits.div_ceil(4))
+        );
+    }
+
+    let flags: String = cpu.get_flags()
+        .iter()
+        .zip(['C', 'A', 'E', 'Z'])
+        .map(|(signal, flag)| if *signal == HIGH { flag } else { '-' })
+        .collect();
+
+    columns.push(format!("FLAGS {}", flags));
+
+    columns.join(" | ")
+}
+
+fn clock_went_high(
+    clock_tag: &str,
+    clock_tick_inputs: &[(String, Vec<GateOutputState>)],
+) -> bool {
+    clock_tick_inputs
+        .iter()
+        .filter(|(tag, _)| tag == clock_tag)
+        .flat_map(|(_, output_states)| output_states.iter())
+        .any(|output_state| {
+            match output_state {
+                GateOutputState::NotConnected(signal) => *signal == HIGH,
+                GateOutputState::Connected(connected_output) => connected_output.throughput.signal == HIGH,
+            }
+        })
+}
+
+//Runs the clock of the gate until the output with the given tag first goes HIGH. Returns the number
+// of clock cycles that elapsed before that happened or None if it did not happen within max_cycles.
+// A clock cycle is four clock-ticks, the length of a single step of the stepper. Gates with a CLK_IN
+// input such as the VariableBitCPU are driven directly by the clock, gates with CLK, CLKE and CLKS
+// inputs such as the ControlSection are driven through a FourCycleClockHookup. The clock stays
+// connected afterwards so it should only be run once on a gate.
+#[allow(dead_code)]
+pub fn cycles_until_assert(
+    gate: &SharedMutex<dyn LogicGate>,
+    tag: &str,
+    max_cycles: usize,
+) -> Option<usize> {
+    let clock_ticks_per_cycle = 4;
+
+    let output_index = gate.lock().unwrap().get_index_from_tag(tag);
+    let output_asserted = || {
+        let output_states = gate.lock().unwrap().fetch_output_signals_no_calculate().unwrap();
+        extract_signals_from_gate_output_states(&output_states)[output_index] == HIGH
+    };
+
+    let clock = Clock::new(1, "PRIMARY_CLOCK");
+    let input_tags = gate.lock().unwrap().get_tags(GateTagType::Input);
+    if input_tags.iter().any(|input_tag| input_tag == VariableBitCPU::CLK_IN) {
+        let clk_in_index = gate.lock().unwrap().get_index_from_tag(VariableBitCPU::CLK_IN);
+
+        //Start the clock at the same signal the gate is already at so the first tick is an edge.
+        let clk_in_gate = gate.lock().unwrap().get_input_gates()[clk_in_index].clone();
+        let clk_in_output = clk_in_gate.lock().unwrap().fetch_output_signals_no_calculate().unwrap();
+        clock.lock().unwrap().set_clock_state(
+            extract_signals_from_gate_output_states(&clk_in_output).first().unwrap().clone()
+        );
+
+        connect_gates(
+            clock.clone(),
+            0,
+            gate.clone(),
+            clk_in_index,
+        );
+    } else {
+        let clock_hookup = FourCycleClockHookup::new();
+        let clk_in_index = clock_hookup.lock().unwrap().get_index_from_tag(FourCycleClockHookup::CLK_IN);
+        connect_gates(
+            clock.clone(),
+            0,
+            clock_hookup.clone(),
+            clk_in_index,
+        );
+
+        for (hookup_tag, gate_tag) in [
+            (FourCycleClockHookup::CLK_OUT, ControlSection::CLOCK),
+            (FourCycleClockHookup::CLKE, ControlSection::CLOCK_ENABLE),
+            (FourCycleClockHookup::CLKS, ControlSection::CLOCK_SET),
+        ] {
+            let hookup_index = clock_hookup.lock().unwrap().get_index_from_tag(hookup_tag);
+            let gate_index = gate.lock().unwrap().get_index_from_tag(gate_tag);
+            connect_gates(
+                clock_hookup.clone(),
+                hookup_index,
+                gate.clone(),
+                gate_index,
+            );
+        }
+
+        //Push the starting signals of the hookup through the gate without advancing the clock.
+        run_circuit(
+            &vec![clock_hookup],
+            &Vec::new(),
+            false,
+            &mut |_clock_tick_inputs, _output_gates| {},
+        );
+    }
+
+    let input_gates: Vec<SharedMutex<dyn LogicGate>> = vec![clock];
+    let output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
+    for clock_tick in 0..(max_cycles * clock_ticks_per_cycle) {
+        if output_asserted() {
+            return Some(clock_tick / clock_ticks_per_cycle);
+        }
+
+        unsafe {
+            CLOCK_TICK_NUMBER += 1;
+        }
+
+        run_circuit(
+            &input_gates,
+            &output_gates,
+            false,
+            &mut |_clock_tick_inputs, _output_gates| {},
+        );
+    }
+
+    None
+}
+
+#[allow(dead_code)]
+//Pulses the RESET input of the cpu HIGH then LOW and clears the cycle count. Like
+// load_values_into_ram() it should be run without any inputs connected to the cpu itself.
+pub fn reset_circuit(
+    cpu: &SharedMutex<VariableBitCPU>,
+) {
+    let reset_input = AutomaticInput::new(
+        vec![HIGH, LOW_],
+        1,
+        "RESET",
+    );
+
+    let reset_index = cpu.lock().unwrap().get_index_from_tag(VariableBitCPU::RESET);
+    connect_gates(
+        reset_input.clone(),
+        0,
+        cpu.clone(),
+        reset_index,
+    );
+
+    let input_gates: Vec<SharedMutex<dyn LogicGate>> = vec![reset_input.clone()];
+
+    let mut continue_reset = true;
+    while continue_reset {
+        continue_reset = run_circuit(
+            &input_gates,
+            &Vec::new(),
+            false,
+            &mut |_clock_tick_inputs, _output_gates| {},
+        );
+    }
+
+    //The last signal (LOW) is kept on the input after it is disconnected.
+    reset_input.lock().unwrap().disconnect_gate(0);
+
+    cpu.lock().unwrap().clear_cycles_since_reset();
+}
+
+//Returns a cpu that has already run a program to the state VariableBitCPU::new() leaves it in. The
+// clock is ticked until the stepper is back at step 1 in the same clock phase a new cpu starts in,
+// then the registers and RAM are cleared and RESET is pulsed. HALT must not be held.
+pub fn reset_cpu_to_initial_state(
+    cpu: &SharedMutex<VariableBitCPU>,
+) {
+    let clock_at_initial_phase = || {
+        let mut cpu = cpu.lock().unwrap();
+        let output = cpu.fetch_output_signals_no_calculate().unwrap();
+        let signals = extract_signals_from_gate_output_states(&output);
+        let signal = |tag: &str| signals[cpu.get_index_from_tag(tag)].clone();
+
+        cpu.get_current_step() == Some(0)
+            && signal(VariableBitCPU::CLKE) == HIGH
+            && signal(VariableBitCPU::CLKS) == LOW_
+            && signal(VariableBitCPU::CLK_OUT) == LOW_
+    };
+
+    if !clock_at_initial_phase() {
+        let clock = Clock::new(1, "PRIMARY_CLOCK");
+        cpu.lock().unwrap().get_clock_synced_with_cpu(&clock);
+
+        let clk_in_index = cpu.lock().unwrap().get_index_from_tag(VariableBitCPU::CLK_IN);
+        connect_gates(
+            clock.clone(),
+            0,
+            cpu.clone(),
+            clk_in_index,
+        );
+
+        let input_gates: Vec<SharedMutex<dyn LogicGate>> = vec![clock.clone()];
+
+        //Enough for two full instructions of six steps with four clock ticks each.
+        let max_clock_ticks = 2 * 6 * 4;
+        let mut num_clock_ticks = 0;
+        while !clock_at_initial_phase() {
+            assert!(
+                num_clock_ticks < max_clock_ticks,
+                "The stepper did not return to step 1, HALT may be held."
+            );
+
+            run_circuit(
+                &input_gates,
+                &Vec::new(),
+                false,
+                &mut |_clock_tick_inputs, _output_gates| {},
+            );
+            num_clock_ticks += 1;
+        }
 
-    cpu
+        //The last signal is kept on CLK_IN after it is disconnected.
+        clock.lock().unwrap().disconnect_gate(0);
+    }
+
+    {
+        let mut cpu = cpu.lock().unwrap();
+        cpu.zero_fill_ram();
+        cpu.clear_registers();
+    }
+
+    reset_circuit(cpu);
 }
 
 //This should leave the cpu in the same state as it started in. The only difference is that
@@ -1734,6 +3145,20 @@ pub fn load_values_into_ram(
     cpu: &SharedMutex<VariableBitCPU>,
     binary_strings: &Vec<&str>,
     num_ram_cells: usize,
+) {
+    load_values_into_ram_internal(
+        cpu,
+        binary_strings,
+        num_ram_cells,
+        None,
+    );
+}
+
+fn load_values_into_ram_internal(
+    cpu: &SharedMutex<VariableBitCPU>,
+    binary_strings: &Vec<&str>,
+    num_ram_cells: usize,
+    mut stimulus: Option<&mut Stimulus>,
 ) {
     let automatic_inputs = convert_binary_to_inputs_for_load(
         binary_strings.clone(),
@@ -1806,6 +3231,19 @@ pub fn load_values_into_ram(
         automatic_input_gates.push(input.clone());
     }
 
+    if let Some(stimulus) = stimulus.as_mut() {
+        let mut connected_tags = vec![
+            VariableBitCPU::CLK_IN.to_string(),
+            VariableBitCPU::LOAD.to_string(),
+            VariableBitCPU::MARS.to_string(),
+        ];
+        connected_tags.extend(
+            (0..automatic_inputs.len()).map(|i| format!("{}_{}", VariableBitCPU::RAM, i))
+        );
+
+        stimulus.record_connected_inputs(cpu, &connected_tags);
+    }
+
     let mut continue_load_operation = true;
     let mut propagate_signal = true;
     while continue_load_operation {
@@ -1818,7 +3256,11 @@ pub fn load_values_into_ram(
             &input_gates,
             &Vec::new(),
             propagate_signal,
-            &mut |_clock_tick_inputs, _output_gates| {},
+            &mut |clock_tick_inputs, _output_gates| {
+                if let Some(stimulus) = stimulus.as_mut() {
+                    stimulus.record(cpu, clock_tick_inputs);
+                }
+            },
         );
 
         propagate_signal = false;
@@ -1829,8 +3271,24 @@ pub fn load_values_into_ram(
         automatic_input_gate.lock().unwrap().disconnect_gate(0);
     }
 
+    //The last clock-tick ends as soon as LOAD runs out of signals. The clock has already driven
+    // CLK_IN by then so it is recorded as its own tick.
+    if let Some(stimulus) = stimulus.as_mut() {
+        stimulus.record_connected_inputs(cpu, &[VariableBitCPU::CLK_IN.to_string()]);
+    }
+
     clock.lock().unwrap().disconnect_gate(0);
 
+    //The RAM inputs and CLK_IN are left undriven after being disconnected.
+    if let Some(stimulus) = stimulus.as_mut() {
+        for i in 0..automatic_inputs.len() {
+            stimulus.record_signal(format!("{}_{}", VariableBitCPU::RAM, i).as_str(), NONE);
+        }
+        stimulus.record_signal(VariableBitCPU::CLK_IN, NONE);
+        stimulus.record_signal(VariableBitCPU::LOAD, LOW_);
+        stimulus.record_signal(VariableBitCPU::MARS, LOW_);
+    }
+
     //LOAD and MAR_S must be tied back to LOW before completing. They have already been
     // disconnected so the zero id is used.
     cpu.lock().unwrap().update_input_signal(
@@ -1849,6 +3307,12 @@ pub fn load_values_into_ram(
         )
     );
 
+    //The RAM contents cannot be verified after a logic error, the caller is expected to check
+    // get_pending_logic_error().
+    if get_pending_logic_error().is_some() {
+        return;
+    }
+
     let mut generated_output = generate_default_output(&cpu);
 
     for (i, binary_string) in binary_strings.iter().enumerate() {
@@ -1900,9 +3364,210 @@ pub fn compare_generate_and_collected_output(
     failed
 }
 
+//The tick is counted from the start of the recording, not from the clock-tick number which is reset
+// when a program starts running.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StimulusEvent {
+    pub tick: usize,
+    pub tag: String,
+    pub signal: Signal,
+}
+
+//A log of every signal driven into the external inputs of a cpu. The tag is the cpu input tag
+// (for example VariableBitCPU::CLK_IN) not the tag of the gate driving it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Stimulus {
+    pub events: Vec<StimulusEvent>,
+    next_tick: usize,
+}
+
+#[allow(dead_code)]
+impl Stimulus {
+    pub fn new() -> Self {
+        Stimulus {
+            events: Vec::new(),
+            next_tick: 0,
+        }
+    }
+
+    //This is meant to be called from inside the handle_output closure passed to run_circuit().
+    pub fn record(
+        &mut self,
+        cpu: &SharedMutex<VariableBitCPU>,
+        clock_tick_inputs: &[(String, Vec<GateOutputState>)],
+    ) {
+        let cpu_id = cpu.lock().unwrap().get_unique_id();
+        let input_tags: HashMap<usize, String> = cpu.lock().unwrap().get_complex_gate().gate_tags_to_index
+            .iter()
+            .filter(|(_, gate_tag_info)| gate_tag_info.tag_type == GateTagType::Input)
+            .map(|(tag, gate_tag_info)| (gate_tag_info.index, tag.clone()))
+            .collect();
+        let tick = self.next_tick;
+        self.next_tick += 1;
+
+        for (_input_gate_tag, output_states) in clock_tick_inputs.iter() {
+            for output_state in output_states.iter() {
+                let connected_output =
+                    match output_state {
+                        GateOutputState::NotConnected(_) => continue,
+                        GateOutputState::Connected(connected_output) => connected_output,
+                    };
+
+                if connected_output.gate.lock().unwrap().get_unique_id() != cpu_id {
+                    continue;
+                }
+
+                self.events.push(
+                    StimulusEvent {
+                        tick,
+                        tag: input_tags[&connected_output.throughput.input_index].clone(),
+                        signal: connected_output.throughput.signal.clone(),
+                    }
+                );
+            }
+        }
+    }
+
+    //Records a signal that was set directly on a cpu input between clock-ticks (for example the
+    // LOAD input being tied back to LOW). It takes effect on the next recorded tick.
+    pub fn record_signal(&mut self, tag: &str, signal: Signal) {
+        self.events.push(
+            StimulusEvent {
+                tick: self.next_tick,
+                tag: tag.to_string(),
+                signal,
+            }
+        );
+    }
+
+    //Records the signal currently on each of the cpu inputs as its own tick. This is for signals that
+    // reach the cpu outside of a recorded clock-tick, such as when an input is first connected.
+    pub fn record_connected_inputs(
+        &mut self,
+        cpu: &SharedMutex<VariableBitCPU>,
+        tags: &[String],
+    ) {
+        for tag in tags.iter() {
+            let cpu = cpu.lock().unwrap();
+            let input_index = cpu.get_index_from_tag(tag.as_str());
+            let output_states = cpu.get_complex_gate().input_gates[input_index].lock().unwrap().fetch_output_signals_calculate().unwrap();
+            let signal = extract_signals_from_gate_output_states(&output_states).remove(0);
+            drop(cpu);
+
+            self.record_signal(tag.as_str(), signal);
+        }
+
+        self.next_tick += 1;
+    }
+
+    pub fn num_ticks(&self) -> usize {
+        let ticks: HashSet<usize> = self.events.iter().map(|event| event.tick).collect();
+        ticks.len()
+    }
+
+    //The signal on each tag for every tick from the first recorded tick to the last. A tag holds its
+    // last recorded signal until its next recorded tick and is NONE before its first. When a tag was
+    // recorded more than once in a tick the last one is used. Tags are in the order they were first
+    // recorded.
+    pub fn signals_by_tick(&self) -> Vec<(String, Vec<Signal>)> {
+        let mut events: Vec<&StimulusEvent> = self.events.iter().collect();
+        events.sort_by_key(|event| event.tick);
+
+        let (first_tick, last_tick) =
+            match (events.first(), events.last()) {
+                (Some(first_event), Some(last_event)) => (first_event.tick, last_event.tick),
+                _ => return Vec::new(),
+            };
+
+        let mut tags: Vec<String> = Vec::new();
+        let mut events_by_tag: HashMap<String, Vec<&StimulusEvent>> = HashMap::new();
+        for event in events.into_iter() {
+            if !events_by_tag.contains_key(&event.tag) {
+                tags.push(event.tag.clone());
+            }
+
+            events_by_tag
+                .entry(event.tag.clone())
+                .or_default()
+                .push(event);
+        }
+
+        tags.into_iter()
+            .map(|tag| {
+                let mut tag_events = events_by_tag[&tag].iter().peekable();
+                let mut signal = NONE;
+                let mut signals = Vec::with_capacity(last_tick - first_tick + 1);
+                for tick in first_tick..=last_tick {
+                    while let Some(event) = tag_events.next_if(|event| event.tick <= tick) {
+                        signal = event.signal.clone();
+                    }
+                    signals.push(signal.clone());
+                }
+
+                (tag, signals)
+            })
+            .collect()
+    }
+}
+
+#[allow(dead_code)]
+//Drives the cpu inputs with a previously recorded Stimulus, one recorded tick per clock-tick. See
+// Stimulus::signals_by_tick() for the signal driven on each tick. A Stimulus recorded by
+// run_instructions_and_record_stimulus() includes the RAM load so it should be replayed on a newly
+// built cpu. Nothing else should be connected to the recorded inputs.
+pub fn replay(
+    cpu: &SharedMutex<VariableBitCPU>,
+    stimulus: &Stimulus,
+) {
+    let mut automatic_input_gates: Vec<SharedMutex<AutomaticInput>> = Vec::new();
+    let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+    for (tag, signals) in stimulus.signals_by_tick().into_iter() {
+        let automatic_input = AutomaticInput::new(
+            signals,
+            1,
+            tag.as_str(),
+        );
+
+        let input_index = cpu.lock().unwrap().get_index_from_tag(tag.as_str());
+        connect_gates(
+            automatic_input.clone(),
+            0,
+            cpu.clone(),
+            input_index,
+        );
+
+        input_gates.push(automatic_input.clone());
+        automatic_input_gates.push(automatic_input);
+    }
+
+    let mut continue_replay = !input_gates.is_empty();
+    let mut propagate_signal = true;
+    while continue_replay {
+        unsafe {
+            CLOCK_TICK_NUMBER += 1;
+        }
+
+        continue_replay = run_circuit(
+            &input_gates,
+            &Vec::new(),
+            propagate_signal,
+            &mut |_clock_tick_inputs, _output_gates| {},
+        );
+
+        propagate_signal = false;
+    }
+
+    //Disconnect all inputs so that future connections can be made.
+    for automatic_input_gate in automatic_input_gates.into_iter() {
+        automatic_input_gate.lock().unwrap().disconnect_gate(0);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::time::Duration;
+    use crate::build_circuit::set_halt;
+    use crate::logic::arithmetic_gates::ArithmeticLogicUnit;
     use crate::logic::basic_gates::{And, Not, Or};
     use crate::logic::foundations::Signal::{HIGH, LOW_};
     use crate::logic::input_gates::AutomaticInput;
@@ -2448,6 +4113,579 @@ mod tests {
         }
     }
 
+    #[test]
+    fn record_and_replay_stimulus() {
+        let number_bits = 8;
+        let decoder_input_size = 1;
+
+        let binary_strings = vec![
+            "00100001", //Data R1
+            "00000101", //5
+            "11001111", //End
+        ];
+
+        let (recorded_cpu, stimulus) = run_instructions_and_record_stimulus(
+            number_bits,
+            decoder_input_size,
+            &binary_strings,
+        );
+
+        assert_ne!(stimulus.num_ticks(), 0);
+
+        //The RAM load is recorded along with the program so the replay starts from a new cpu.
+        let recorded_tags: HashSet<&str> = stimulus.events.iter().map(|event| event.tag.as_str()).collect();
+        assert!(recorded_tags.contains(VariableBitCPU::CLK_IN));
+        assert!(recorded_tags.contains(VariableBitCPU::LOAD));
+        assert!(recorded_tags.contains(VariableBitCPU::MARS));
+
+        let replayed_cpu = VariableBitCPU::new(number_bits, decoder_input_size);
+
+        replay(&replayed_cpu, &stimulus);
+
+        let recorded_signals = collect_signals_from_logic_gate(recorded_cpu.clone());
+        let replayed_signals = collect_signals_from_logic_gate(replayed_cpu.clone());
+
+        let end_index = replayed_cpu.lock().unwrap().get_index_from_tag(VariableBitCPU::END);
+        assert_eq!(replayed_signals[end_index], HIGH);
+
+        let failed = compare_generate_and_collected_output(
+            &replayed_cpu,
+            recorded_signals,
+            replayed_signals,
+        );
+
+        assert!(!failed);
+    }
+
+    #[test]
+    fn stimulus_signals_by_tick_holds_each_tag() {
+        let mut stimulus = Stimulus::new();
+        let mut push_event = |tick: usize, tag: &str, signal: Signal| {
+            stimulus.events.push(StimulusEvent { tick, tag: tag.to_string(), signal });
+        };
+
+        push_event(0, "A", HIGH);
+        push_event(3, "A", LOW_);
+        push_event(1, "B", LOW_);
+        push_event(1, "B", HIGH);
+        push_event(4, "B", LOW_);
+
+        assert_eq!(
+            stimulus.signals_by_tick(),
+            vec![
+                ("A".to_string(), vec![HIGH, HIGH, HIGH, LOW_, LOW_]),
+                ("B".to_string(), vec![NONE, HIGH, HIGH, HIGH, LOW_]),
+            ]
+        );
+    }
+
+    #[test]
+    fn cycles_since_reset_counts_clock_cycles() {
+        let number_bits = 8;
+        let decoder_input_size = 2;
+
+        let binary_strings = vec![
+            "00100001", //Data R1
+            "00000101", //5
+            "00100010", //Data R2
+            "00000011", //3
+            "11001111", //End
+        ];
+
+        let cpu = run_instructions(
+            number_bits,
+            decoder_input_size,
+            &binary_strings,
+        );
+
+        //A step is four clock-ticks which is two rising edges on CLK_IN. Both DATA instructions run
+        // all six steps and END stops the clock on the third rising edge after it starts.
+        let cycles_per_step = 2;
+        let num_data_instructions = 2;
+        let expected_cycles = num_data_instructions * 6 * cycles_per_step + 3;
+
+        assert_eq!(cpu.lock().unwrap().cycles_since_reset(), expected_cycles);
+
+        reset_circuit(&cpu);
+
+        assert_eq!(cpu.lock().unwrap().cycles_since_reset(), 0);
+    }
+
+    #[test]
+    fn reset_cpu_to_initial_state_allows_a_second_program() {
+        let number_bits = 8;
+        let decoder_input_size = 2;
+
+        let initial_signals = collect_signals_from_logic_gate(
+            VariableBitCPU::new(number_bits, decoder_input_size)
+        );
+
+        let cpu = run_instructions(
+            number_bits,
+            decoder_input_size,
+            &vec![
+                "00100000", //Data R0
+                "00000101", //5
+                "00100001", //Data R1
+                "00000011", //3
+                "10000001", //Add R0 R1
+                "11001111", //End
+            ],
+        );
+
+        reset_cpu_to_initial_state(&cpu);
+
+        assert_eq!(collect_signals_from_logic_gate(cpu.clone()), initial_signals);
+        assert_eq!(cpu.lock().unwrap().cycles_since_reset(), 0);
+
+        let program_stop = run_instructions_on_cpu(
+            &cpu,
+            decoder_input_size,
+            &vec![
+                "00100000", //Data R0
+                "00000111", //7
+                "10000000", //Add R0 R0
+                "11001111", //End
+            ],
+        );
+
+        assert_eq!(program_stop, ProgramStop::End);
+
+        let snapshot = CpuSnapshot::capture(&cpu);
+        assert!(snapshot.registers.contains(&(VariableBitCPU::R0.to_string(), Some(14))));
+    }
+
+    #[test]
+    fn register_dump_after_each_instruction() {
+        let binary_strings = vec![
+            "00100001", //Data R1
+            "00000101", //5
+            "00100010", //Data R2
+            "00000011", //3
+            "11001111", //End
+        ];
+
+        enable_register_dump();
+
+        run_instructions(
+            8,
+            2,
+            &binary_strings,
+        );
+
+        let dumps = disable_register_dump();
+
+        assert!(dumps.len() >= 2, "{:#?}", dumps);
+
+        assert!(dumps[0].contains("R1 05"), "{}", dumps[0]);
+        assert!(dumps[0].contains("R2 00"), "{}", dumps[0]);
+
+        assert!(dumps[1].contains("R1 05"), "{}", dumps[1]);
+        assert!(dumps[1].contains("R2 03"), "{}", dumps[1]);
+
+        assert!(!register_dump_enabled());
+    }
+
+    #[test]
+    fn opcode_histogram_multiplication_program() {
+        let program = include_str!("../programs/multiplication.ms");
+        let binary_strings: Vec<&str> = program.lines().collect();
+
+        enable_instruction_trace();
+
+        run_instructions(
+            8,
+            3,
+            &binary_strings,
+        );
+
+        let instruction_trace = disable_instruction_trace();
+        let histogram = opcode_histogram(&instruction_trace);
+
+        //The loop runs once for each of the 8 bits of R0. Each pass shifts R0 right once and shifts
+        // both R1 and R3 left once. R0 is 5 (101) so the add only runs for the two set bits.
+        assert_eq!(histogram.get("SHR"), Some(&8), "{:#?}", histogram);
+        assert_eq!(histogram.get("SHL"), Some(&16), "{:#?}", histogram);
+        assert_eq!(histogram.get("ADD"), Some(&2), "{:#?}", histogram);
+        assert_eq!(histogram.get("DATA"), Some(&3), "{:#?}", histogram);
+        assert_eq!(histogram.get("XOR"), Some(&1), "{:#?}", histogram);
+
+        assert!(!instruction_trace_enabled());
+    }
+
+    const RAM_WATCHPOINT_PROGRAM: [&str; 8] = [
+        "00100000", //Data R0
+        "00001110", //14
+        "00100011", //Data R3
+        "11111010", //250
+        "00010011", //Store R0 R3
+        "00100001", //Data R1
+        "00000111", //7
+        "11001111", //End
+    ];
+
+    #[test]
+    fn ram_watchpoint_stops_on_store() {
+        let cpu = VariableBitCPU::new(8, 2);
+        cpu.lock().unwrap().add_ram_watchpoint(14);
+
+        let program_stop = run_instructions_on_cpu(
+            &cpu,
+            2,
+            &RAM_WATCHPOINT_PROGRAM.to_vec(),
+        );
+
+        assert_eq!(program_stop, ProgramStop::Watchpoint);
+
+        let hit = cpu.lock().unwrap().take_ram_watchpoint_hit().unwrap();
+        assert_eq!(hit.address, 14);
+        assert_eq!(hit.old_value, Some(0));
+        assert_eq!(hit.new_value, Some(250));
+
+        //The program stopped at the store, so neither R1 nor End were reached.
+        let snapshot = CpuSnapshot::capture(&cpu);
+        let r1 = snapshot.registers.iter().find(|(tag, _)| tag == VariableBitCPU::R1).unwrap();
+        assert_eq!(r1.1, Some(0));
+        assert!(!cpu.lock().unwrap().is_halted());
+
+        assert_eq!(cpu.lock().unwrap().take_ram_watchpoint_hit(), None);
+    }
+
+    #[test]
+    fn ram_watchpoints_belong_to_one_cpu() {
+        let watched_cpu = VariableBitCPU::new(8, 2);
+        let unwatched_cpu = VariableBitCPU::new(8, 2);
+        watched_cpu.lock().unwrap().add_ram_watchpoint(14);
+
+        let program_stop = run_instructions_on_cpu(
+            &unwatched_cpu,
+            2,
+            &RAM_WATCHPOINT_PROGRAM.to_vec(),
+        );
+
+        assert_eq!(program_stop, ProgramStop::End);
+        assert_eq!(unwatched_cpu.lock().unwrap().take_ram_watchpoint_hit(), None);
+
+        let program_stop = run_instructions_on_cpu(
+            &watched_cpu,
+            2,
+            &RAM_WATCHPOINT_PROGRAM.to_vec(),
+        );
+
+        assert_eq!(program_stop, ProgramStop::Watchpoint);
+        assert!(unwatched_cpu.lock().unwrap().ram_watchpoints().is_empty());
+        assert_eq!(unwatched_cpu.lock().unwrap().take_ram_watchpoint_hit(), None);
+        assert_eq!(watched_cpu.lock().unwrap().take_ram_watchpoint_hit().unwrap().new_value, Some(250));
+    }
+
+    #[test]
+    fn held_halt_still_ends_the_program() {
+        let binary_strings = vec![
+            "00100001", //Data R1
+            "00000101", //5
+            "11001111", //End
+        ];
+
+        //The observer never releases HALT so only the clock tick limit can end the run.
+        let cpu = CpuConfig::new().build();
+        set_halt(&cpu, HIGH);
+        let mut held_ticks = 0;
+        let previous_limit = CLOCK_TICK_LIMIT.with(|limit| limit.replace(Some(25)));
+        let program_stop = run_program_on_cpu(
+            &cpu,
+            2,
+            &binary_strings,
+            None,
+            &mut [],
+            Some(&mut |_cpu: &SharedMutex<VariableBitCPU>| held_ticks += 1),
+        ).unwrap();
+        CLOCK_TICK_LIMIT.with(|limit| limit.set(previous_limit));
+
+        assert_eq!(program_stop, ProgramStop::ClockTickLimit);
+        assert_eq!(held_ticks, 25);
+        let snapshot = CpuSnapshot::capture(&cpu);
+        let r1 = snapshot.registers.iter().find(|(tag, _)| tag == VariableBitCPU::R1).unwrap();
+        assert_eq!(r1.1, Some(0));
+
+        //Without an observer nothing can release HALT so the run stops straight away.
+        let cpu = CpuConfig::new().build();
+        set_halt(&cpu, HIGH);
+        let program_stop = run_program_on_cpu(
+            &cpu,
+            2,
+            &binary_strings,
+            None,
+            &mut [],
+            None,
+        ).unwrap();
+
+        assert_eq!(program_stop, ProgramStop::HaltHeld);
+        let snapshot = CpuSnapshot::capture(&cpu);
+        let r1 = snapshot.registers.iter().find(|(tag, _)| tag == VariableBitCPU::R1).unwrap();
+        assert_eq!(r1.1, Some(0));
+    }
+
+    //The run loop drives the divider with R0 / R1. R1 is set to 3 then 0 so DIV_BY_ZERO goes from
+    // LOW to HIGH once it is loaded.
+    fn run_div_by_zero_program(trap: DivByZeroTrap) -> (ProgramStop, SharedMutex<VariableBitDivider>, CpuSnapshot) {
+        let binary_strings = vec![
+            "00100001", //Data R1
+            "00000011", //3
+            "00100000", //Data R0
+            "00000110", //6
+            "00100001", //Data R1
+            "00000000", //0
+            "00100010", //Data R2
+            "00000001", //1
+            "11001111", //End
+            "00000000",
+            "00100011", //Data R3
+            "00000101", //5
+            "11001111", //End
+        ];
+
+        let divider = VariableBitDivider::new(8).unwrap();
+        let cpu = CpuConfig::new()
+            .div_by_zero_trap(divider.clone(), trap)
+            .build();
+
+        let program_stop = run_instructions_on_cpu(&cpu, 2, &binary_strings);
+
+        (program_stop, divider, CpuSnapshot::capture(&cpu))
+    }
+
+    #[test]
+    fn div_by_zero_trap_halts() {
+        let (program_stop, divider, snapshot) = run_div_by_zero_program(DivByZeroTrap::Halt);
+
+        assert!(div_by_zero_asserted(&divider));
+        assert_eq!(program_stop, ProgramStop::DivByZero);
+
+        //The program stopped before Data R2 could run.
+        let registers: Vec<Option<u64>> = [VariableBitCPU::R0, VariableBitCPU::R1, VariableBitCPU::R2, VariableBitCPU::R3]
+            .iter()
+            .map(|register_tag| snapshot.registers.iter().find(|(tag, _)| tag == register_tag).unwrap().1)
+            .collect();
+        assert_eq!(registers, vec![Some(6), Some(0), Some(0), Some(0)]);
+    }
+
+    #[test]
+    fn div_by_zero_trap_jumps_to_vector() {
+        let (program_stop, divider, snapshot) = run_div_by_zero_program(DivByZeroTrap::Jump(10));
+
+        assert!(div_by_zero_asserted(&divider));
+        assert_eq!(program_stop, ProgramStop::End);
+
+        //Data R2 was skipped and the program continued from the vector.
+        let registers: Vec<Option<u64>> = [VariableBitCPU::R0, VariableBitCPU::R1, VariableBitCPU::R2, VariableBitCPU::R3]
+            .iter()
+            .map(|register_tag| snapshot.registers.iter().find(|(tag, _)| tag == register_tag).unwrap().1)
+            .collect();
+        assert_eq!(registers, vec![Some(6), Some(0), Some(0), Some(5)]);
+    }
+
+    #[test]
+    fn cycles_until_ram_enable_asserts() {
+        //The fetch puts IAR on the bus during step 1 then reads RAM during step 2.
+        let control_section = ControlSection::new(8);
+        let control_section: SharedMutex<dyn LogicGate> = control_section;
+        assert_eq!(cycles_until_assert(&control_section, ControlSection::RAM_E, 6), Some(1));
+
+        //IAR is set during step 3.
+        let control_section = ControlSection::new(8);
+        let control_section: SharedMutex<dyn LogicGate> = control_section;
+        assert_eq!(cycles_until_assert(&control_section, ControlSection::IAR_S, 6), Some(2));
+
+        //The instruction register holds LOAD which never reaches END.
+        let control_section = ControlSection::new(8);
+        let control_section: SharedMutex<dyn LogicGate> = control_section;
+        assert_eq!(cycles_until_assert(&control_section, ControlSection::END, 6), None);
+    }
+
+    #[test]
+    fn run_and_compare_identical_builds() {
+        let binary_strings = vec![
+            "00100001", //Data R1
+            "00000101", //5
+            "00100010", //Data R2
+            "00000011", //3
+            "10000110", //Add R1 R2
+            "11001111", //End
+        ];
+
+        //There is only a single ALU implementation, so the builds differ by the size of the RAM.
+        let report = run_and_compare(
+            &[
+                CpuConfig::new().address_bits(4),
+                CpuConfig::new().address_bits(6),
+            ],
+            &binary_strings,
+        );
+
+        assert!(report.is_identical(), "{:?}", report.divergences);
+        assert_eq!(report.outputs.len(), 2);
+        assert_eq!(report.outputs[0][&format!("{}_0", VariableBitCPU::R2)], LOW_);
+        assert_eq!(report.outputs[0][&format!("{}_3", VariableBitCPU::R2)], HIGH);
+    }
+
+    #[test]
+    fn run_and_compare_reports_divergence() {
+        //R0 is never set, so the result depends on the initial state of the registers.
+        let binary_strings = vec![
+            "00100001", //Data R1
+            "00000101", //5
+            "10000001", //Add R0 R1
+            "11001111", //End
+        ];
+
+        let report = run_and_compare(
+            &[
+                CpuConfig::new(),
+                CpuConfig::new().randomize_initial_state(42),
+            ],
+            &binary_strings,
+        );
+
+        assert!(!report.is_identical());
+
+        let r1_divergence = report.divergences
+            .iter()
+            .find(|divergence| divergence.tag == format!("{}_1", VariableBitCPU::R1))
+            .unwrap();
+        assert_eq!(r1_divergence.signals, vec![LOW_, HIGH]);
+    }
+
+    #[test]
+    fn settle_pass_breakdown_alu_adder() {
+        let num_bits = 8;
+        let alu = ArithmeticLogicUnit::new(num_bits).unwrap();
+
+        //A, B and C all LOW selects the adder. 0xFF + 1 carries through every bit.
+        {
+            let mut alu = alu.lock().unwrap();
+            for tag in ["A", "B", "C", "C_IN"] {
+                let input_index = alu.get_index_from_tag(tag);
+                alu.update_input_signal(GateInput::new(input_index, LOW_, UniqueID::zero_id()));
+            }
+            for i in 0..num_bits {
+                let a_index = alu.get_index_from_tag(format!("a_{}", i).as_str());
+                alu.update_input_signal(GateInput::new(a_index, HIGH, UniqueID::zero_id()));
+            }
+            let b_0_index = alu.get_index_from_tag("b_0");
+            alu.update_input_signal(GateInput::new(b_0_index, HIGH, UniqueID::zero_id()));
+        }
+
+        let alu: SharedMutex<dyn LogicGate> = alu;
+        let breakdown = settle_pass_breakdown(&alu);
+        let passes = |gate_type: GateType| breakdown.get(&gate_type).copied().unwrap_or(0);
+
+        //The carry ripples through every full adder.
+        assert!(passes(GateType::FullAdderType) >= num_bits, "{:?}", breakdown);
+
+        let adder_passes = passes(GateType::VariableBitAdderType)
+            + passes(GateType::FullAdderType)
+            + passes(GateType::HalfAdderType);
+        assert!(adder_passes > passes(GateType::NotType), "{:?}", breakdown);
+        assert!(passes(GateType::FullAdderType) > passes(GateType::NotType), "{:?}", breakdown);
+    }
+
+    #[test]
+    fn scheduling_strategy_alu_same_outputs() {
+        let num_bits = 8;
+
+        //Each row is A, B and C followed by the a and b inputs as numbers.
+        let operations = [
+            (LOW_, LOW_, LOW_, 0b1011_0110, 0b0110_1101),
+            (HIGH, LOW_, LOW_, 0b1011_0110, 0b0110_1101),
+            (HIGH, LOW_, HIGH, 0b1000_0001, 0b0001_1000),
+            (LOW_, HIGH, HIGH, 0b1000_0001, 0b0001_1000),
+            (LOW_, HIGH, LOW_, 0b1100_0011, 0b0000_0000),
+            (LOW_, LOW_, HIGH, 0b1100_0011, 0b0000_0000),
+            (LOW_, LOW_, LOW_, 0b1111_1111, 0b0000_0001),
+        ];
+
+        let run_alu = |scheduling_strategy: SchedulingStrategy| {
+            let alu = ArithmeticLogicUnit::new(num_bits).unwrap();
+            let mut outputs = Vec::new();
+            let mut total_passes = 0;
+
+            for (a, b, c, a_num, b_num) in operations.iter() {
+                {
+                    let mut alu = alu.lock().unwrap();
+                    let mut set_input = |tag: &str, signal: Signal| {
+                        let input_index = alu.get_index_from_tag(tag);
+                        alu.update_input_signal(GateInput::new(input_index, signal, UniqueID::zero_id()));
+                    };
+
+                    set_input("A", a.clone());
+                    set_input("B", b.clone());
+                    set_input("C", c.clone());
+                    set_input("C_IN", LOW_);
+                    for i in 0..num_bits {
+                        let bit = |num: usize| if (num >> i) & 1 == 1 { HIGH } else { LOW_ };
+                        set_input(format!("a_{}", i).as_str(), bit(*a_num));
+                        set_input(format!("b_{}", i).as_str(), bit(*b_num));
+                    }
+                }
+
+                let (_continue_clock, num_passes) = run_circuit_with_strategy(
+                    &vec![alu.clone()],
+                    &Vec::new(),
+                    false,
+                    scheduling_strategy,
+                    &mut |_clock_tick_inputs, _output_gates| {},
+                );
+                total_passes += num_passes;
+
+                let output_states = alu.lock().unwrap().fetch_output_signals_no_calculate().unwrap();
+                outputs.push(extract_signals_from_gate_output_states(&output_states));
+            }
+
+            (outputs, total_passes)
+        };
+
+        let (breadth_first_outputs, breadth_first_passes) = run_alu(SchedulingStrategy::BreadthFirst);
+        let (depth_first_outputs, depth_first_passes) = run_alu(SchedulingStrategy::DepthFirst);
+
+        assert_eq!(breadth_first_outputs, depth_first_outputs);
+        assert!(breadth_first_passes > 0);
+        assert!(depth_first_passes > 0);
+    }
+
+    #[test]
+    fn realtime_runner_changes_clock_hz() {
+        let input_gate = AutomaticInput::new(vec![HIGH; 9], 1, "IN");
+        let output_gate = SimpleOutput::new("OUT");
+        connect_gates(input_gate.clone(), 0, output_gate.clone(), 0);
+
+        let input_gates: Vec<SharedMutex<dyn LogicGate>> = vec![input_gate];
+        let output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = vec![output_gate];
+
+        let mut tick_times = Vec::new();
+        let mut runner = RealtimeRunner::new(100.0);
+        let num_clock_ticks = runner.run_realtime(
+            &input_gates,
+            &output_gates,
+            &mut |runner, _clock_tick_inputs, _output_gates| {
+                tick_times.push(Instant::now());
+                if tick_times.len() == 5 {
+                    runner.set_clock_hz(20.0);
+                }
+            },
+        );
+
+        assert_eq!(num_clock_ticks, tick_times.len());
+        assert_eq!(runner.clock_hz(), 20.0);
+
+        let intervals: Vec<Duration> = tick_times.windows(2).map(|times| times[1] - times[0]).collect();
+        let (fast_intervals, slow_intervals) = intervals.split_at(4);
+
+        //Sleeping guarantees the lower bound, the upper bound is kept loose for slow machines.
+        assert!(fast_intervals.iter().all(|interval| *interval >= Duration::from_millis(9)), "{:?}", intervals);
+        assert!(slow_intervals.iter().all(|interval| *interval >= Duration::from_millis(45)), "{:?}", intervals);
+        assert!(fast_intervals.iter().sum::<Duration>() / 4 < Duration::from_millis(40), "{:?}", intervals);
+    }
+
     //TODO: delete this test
     #[test]
     fn force_deadlock() {
@@ -1,13 +1,19 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufWriter, Write};
 use std::sync::Arc;
 use std::sync::mpsc::channel;
 use std::thread;
 use std::time::Duration;
+use crate::build_circuit::CpuSnapshot;
 use crate::globals::{CLOCK_TICK_NUMBER, get_clock_tick_number};
-use crate::logic::foundations::{ComplexGateMembers, connect_gates, GateInput, GateOutputState, GateTagInfo, GateTagType, LogicGate, Signal, UniqueID};
+use crate::logic::arithmetic_gates::VariableBitMultiplier;
+use crate::logic::foundations::Signal::{HIGH, LOW_, NONE};
+use crate::logic::foundations::{ComplexGateMembers, connect_gates, Endianness, extract_signals_from_gate_output_states, GateInput, GateOutputState, GateTagInfo, GateTagType, GateType, LogicGate, Signal, signals_to_u64, u64_to_signals, UniqueID};
 use crate::logic::input_gates::AutomaticInput;
 use crate::logic::output_gates::{LogicGateAndOutputGate, SimpleOutput};
-use crate::run_circuit::{run_circuit, start_clock};
+use crate::logic::variable_bit_cpu::VariableBitCPU;
+use crate::run_circuit::{reset_cpu_to_initial_state, run_circuit, run_instructions_on_cpu, start_clock};
 use crate::shared_mutex::SharedMutex;
 
 #[allow(dead_code)]
@@ -292,6 +298,45 @@ pub fn test_simple_gate(
     );
 }
 
+//Each row of the table is (inputs, outputs) where index 0 is input or output 0. A fresh gate is
+// built for every row so that no state carries over between rows.
+#[allow(dead_code)]
+pub fn assert_truth_table<G, F>(
+    gate_factory: F,
+    table: &[(&[Signal], &[Signal])],
+) where
+    G: LogicGate,
+    F: Fn() -> SharedMutex<G>,
+{
+    for (row, (inputs, outputs)) in table.iter().enumerate() {
+        let gate = gate_factory();
+        let mut gate = gate.lock().unwrap();
+
+        for (i, signal) in inputs.iter().enumerate() {
+            gate.update_input_signal(
+                GateInput::new(
+                    i,
+                    signal.clone(),
+                    UniqueID::zero_id(),
+                )
+            );
+        }
+
+        let output_states = gate.fetch_output_signals_calculate().unwrap();
+        let collected_outputs = extract_signals_from_gate_output_states(&output_states);
+
+        assert!(collected_outputs.len() >= outputs.len());
+        assert_eq!(
+            &collected_outputs[..outputs.len()],
+            *outputs,
+            "{} truth table row {} failed for inputs {:?}",
+            gate.get_gate_type(),
+            row,
+            inputs,
+        );
+    }
+}
+
 #[allow(dead_code)]
 pub fn extract_output_tags_sorted_by_index(complex_gate: &ComplexGateMembers) -> Vec<String> {
     let tags_and_index: Vec<(&String, &GateTagInfo)> = complex_gate.gate_tags_to_index.iter().collect();
@@ -311,3 +356,263 @@ pub fn extract_output_tags_sorted_by_index(complex_gate: &ComplexGateMembers) ->
     let tags_sorted_by_index: Vec<String> = tags_and_index.iter().map(|(tag, _)| (*tag).clone()).collect();
     tags_sorted_by_index
 }
+
+//Returns the standard static CMOS transistor cost of a primitive gate. Each primitive is costed as
+// its two input version. Gates that are only wiring or stimulus cost nothing and complex gates
+// return None so that their children can be summed instead.
+fn primitive_transistor_count(gate_type: GateType) -> Option<usize> {
+    match gate_type {
+        GateType::NotType => Some(2),
+        GateType::NandType | GateType::NorType => Some(4),
+        GateType::AndType | GateType::OrType => Some(6),
+        GateType::XOrType | GateType::XnorType => Some(12),
+        GateType::ControlledBufferType => Some(6),
+        GateType::SplitterType
+        | GateType::FanoutSplitterType
+        | GateType::SimpleInputType
+        | GateType::SimpleOutputType
+        | GateType::LatchedOutputType
+        | GateType::AutomaticInputType
+        | GateType::DynamicInputType
+        | GateType::ClockType
+        | GateType::UnknownType => Some(0),
+        _ => None,
+    }
+}
+
+#[allow(dead_code)]
+pub fn transistor_count(gate: &dyn LogicGate) -> usize {
+    if let Some(count) = primitive_transistor_count(gate.get_gate_type()) {
+        return count;
+    }
+
+    let mut unique_gates = HashSet::new();
+    let mut next_gates = gate.get_input_gates();
+    for input_gate in next_gates.iter() {
+        unique_gates.insert(input_gate.lock().unwrap().get_unique_id());
+    }
+
+    let mut total_count = 0;
+    while let Some(gate_cell) = next_gates.pop() {
+        let mut child_gate = gate_cell.lock().unwrap();
+        total_count += transistor_count(&*child_gate);
+
+        let gate_output = child_gate.fetch_output_signals_no_calculate().unwrap();
+        drop(child_gate);
+
+        for output in gate_output.into_iter() {
+            if let GateOutputState::Connected(next_gate_info) = output {
+                let gate_id = next_gate_info.gate.lock().unwrap().get_unique_id();
+                if unique_gates.insert(gate_id) {
+                    next_gates.push(next_gate_info.gate);
+                }
+            }
+        }
+    }
+
+    total_count
+}
+
+//Writes a header row of tags followed by one row per tick. HIGH is written as 1, LOW as 0 and NONE
+// as x. Each sample must hold one signal per tag in the same order as the tags.
+#[allow(dead_code)]
+pub fn write_signals_csv(path: &str, tags: &[&str], samples: &[Vec<Signal>]) -> std::io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+
+    writeln!(writer, "{}", tags.join(","))?;
+
+    for (tick, sample) in samples.iter().enumerate() {
+        assert_eq!(sample.len(), tags.len(), "Sample for tick {} does not match the number of tags.", tick);
+
+        let row: Vec<&str> = sample
+            .iter()
+            .map(|signal| match signal {
+                HIGH => "1",
+                LOW_ => "0",
+                NONE => "x",
+            })
+            .collect();
+
+        writeln!(writer, "{}", row.join(","))?;
+    }
+
+    writer.flush()
+}
+
+//One vector per clock tick for each of the clock inputs of the control section.
+#[allow(dead_code)]
+pub struct ClockSchedule {
+    pub clock: Vec<Vec<Signal>>,
+    pub clock_enable: Vec<Vec<Signal>>,
+    pub clock_set: Vec<Vec<Signal>>,
+}
+
+//Each phase of an instruction is a full clock cycle of four ticks. The clock rises on the second
+// tick, clock set is only HIGH on that same tick and clock enable drops on the last tick.
+#[allow(dead_code)]
+pub fn build_clock_schedule(phases_per_instruction: usize, num_instructions: usize) -> ClockSchedule {
+    let mut clock = Vec::new();
+    let mut clock_enable = Vec::new();
+    let mut clock_set = Vec::new();
+
+    for _ in 0..phases_per_instruction * num_instructions {
+        clock.push(vec![LOW_]);
+        clock.push(vec![HIGH]);
+        clock.push(vec![HIGH]);
+        clock.push(vec![LOW_]);
+
+        clock_enable.push(vec![HIGH]);
+        clock_enable.push(vec![HIGH]);
+        clock_enable.push(vec![HIGH]);
+        clock_enable.push(vec![LOW_]);
+
+        clock_set.push(vec![LOW_]);
+        clock_set.push(vec![HIGH]);
+        clock_set.push(vec![LOW_]);
+        clock_set.push(vec![LOW_]);
+    }
+
+    ClockSchedule {
+        clock,
+        clock_enable,
+        clock_set,
+    }
+}
+
+//Runs programs/multiplication.ms with each pair of operands in place of the two it loads and
+// multiplies them again with a VariableBitMultiplier, asserting both products match. A single cpu is
+// built and returned to its initial state between pairs. The program leaves its product in R2
+// which only holds the low num_bits bits so only those bits are compared.
+#[allow(dead_code)]
+pub fn compare_mul_software_vs_hardware(num_bits: usize, operands: &[(u64, u64)]) {
+    let decoder_input_size = 3;
+    let cpu = VariableBitCPU::new(num_bits, decoder_input_size);
+    let program: Vec<&str> = include_str!("../programs/multiplication.ms").lines().collect();
+
+    for (i, &(a, b)) in operands.iter().enumerate() {
+        assert!(a < 1 << num_bits);
+        assert!(b < 1 << num_bits);
+
+        if i > 0 {
+            reset_cpu_to_initial_state(&cpu);
+        }
+
+        //Lines 1 and 3 are the data loaded into R0 and R1.
+        let a_string = format!("{:0width$b}", a, width = num_bits);
+        let b_string = format!("{:0width$b}", b, width = num_bits);
+        let mut binary_strings = program.clone();
+        binary_strings[1] = a_string.as_str();
+        binary_strings[3] = b_string.as_str();
+
+        run_instructions_on_cpu(
+            &cpu,
+            decoder_input_size,
+            &binary_strings,
+        );
+
+        let snapshot = CpuSnapshot::capture(&cpu);
+        let software_product = snapshot.registers.iter()
+            .find(|(tag, _)| tag == VariableBitCPU::R2)
+            .unwrap()
+            .1;
+
+        let product_bits = num_bits * 2;
+        let hardware_output = run_multi_input_output_logic_gate_return(
+            vec![],
+            &vec![vec![NONE; product_bits]],
+            HashMap::from(
+                [
+                    ("a", vec![u64_to_signals(a, num_bits, Endianness::Little)]),
+                    ("b", vec![u64_to_signals(b, num_bits, Endianness::Little)]),
+                ]
+            ),
+            VariableBitMultiplier::new(num_bits).unwrap(),
+        );
+        let hardware_product = signals_to_u64(hardware_output.last().unwrap(), product_bits, Endianness::Little);
+
+        assert_eq!(hardware_product, Some(a * b), "a: {} b: {}", a, b);
+        assert_eq!(software_product, Some((a * b) & ((1 << num_bits) - 1)), "a: {} b: {}", a, b);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use super::*;
+
+    #[test]
+    fn write_signals_csv_round_trip() {
+        let path = std::env::temp_dir().join(format!("logical_cpu_signals_{}.csv", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        write_signals_csv(
+            path,
+            &["CLK", "o_0", "o_1"],
+            &[vec![HIGH, LOW_, NONE]],
+        ).unwrap();
+
+        let contents = fs::read_to_string(path).unwrap();
+        fs::remove_file(path).unwrap();
+
+        let rows: Vec<Vec<&str>> = contents
+            .lines()
+            .map(|line| line.split(',').collect())
+            .collect();
+
+        assert_eq!(
+            rows,
+            vec![
+                vec!["CLK", "o_0", "o_1"],
+                vec!["1", "0", "x"],
+            ]
+        );
+    }
+
+    #[test]
+    fn clock_schedule_five_phase_instruction() {
+        let phases_per_instruction = 5;
+        let num_instructions = 3;
+        let schedule = build_clock_schedule(phases_per_instruction, num_instructions);
+
+        let num_ticks = 4 * phases_per_instruction * num_instructions;
+        assert_eq!(schedule.clock.len(), num_ticks);
+        assert_eq!(schedule.clock_enable.len(), num_ticks);
+        assert_eq!(schedule.clock_set.len(), num_ticks);
+
+        let rising_edges: Vec<usize> = (1..num_ticks)
+            .filter(|&tick| schedule.clock[tick - 1] == vec![LOW_] && schedule.clock[tick] == vec![HIGH])
+            .collect();
+        let clock_set_ticks: Vec<usize> = (0..num_ticks)
+            .filter(|&tick| schedule.clock_set[tick] == vec![HIGH])
+            .collect();
+        let clock_enable_low_ticks: Vec<usize> = (0..num_ticks)
+            .filter(|&tick| schedule.clock_enable[tick] == vec![LOW_])
+            .collect();
+
+        let phases = 0..phases_per_instruction * num_instructions;
+        assert_eq!(rising_edges, phases.clone().map(|phase| 4 * phase + 1).collect::<Vec<usize>>());
+        assert_eq!(clock_set_ticks, rising_edges);
+        assert_eq!(clock_enable_low_ticks, phases.map(|phase| 4 * phase + 3).collect::<Vec<usize>>());
+    }
+
+    #[test]
+    fn mul_software_matches_hardware() {
+        compare_mul_software_vs_hardware(8, &[(16, 17)]);
+    }
+
+    //Each program takes around 40 seconds and the captured gate output grows by over a gigabyte,
+    // so the remaining operand pairs only run on request.
+    #[test]
+    #[ignore]
+    fn mul_software_matches_hardware_all_operands() {
+        compare_mul_software_vs_hardware(
+            8,
+            &[
+                (0, 9), //Zero
+                (255, 1), //Identity
+                (12, 13), //No overflow
+                (16, 17), //Overflow
+            ],
+        );
+    }
+}
@@ -0,0 +1,405 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Error, ErrorKind, Write};
+use crate::logic::foundations::{extract_signals_from_gate_output_states, GateOutputState, Signal};
+use crate::logic::foundations::Signal::{HIGH, LOW_, NONE};
+use crate::logic::output_gates::LogicGateAndOutputGate;
+use crate::logic::variable_bit_cpu::VariableBitCPU;
+use crate::run_circuit::{replay, Stimulus, StimulusEvent};
+use crate::shared_mutex::SharedMutex;
+
+//VCD identifiers are made of the printable ASCII characters from ! to ~.
+fn vcd_identifier(mut index: usize) -> String {
+    let mut identifier = String::new();
+    loop {
+        identifier.push((b'!' + (index % 94) as u8) as char);
+        index /= 94;
+        if index == 0 {
+            break;
+        }
+        index -= 1;
+    }
+    identifier
+}
+
+fn signal_to_vcd_value(signal: &Signal) -> char {
+    match signal {
+        HIGH => '1',
+        LOW_ => '0',
+        NONE => 'x',
+    }
+}
+
+//Writes a recorded Stimulus as a VCD file with one 1 bit wire per cpu input tag. The time of each
+// value change is the clock-tick it was recorded in.
+#[allow(dead_code)]
+pub fn write_stimulus_vcd(path: &str, stimulus: &Stimulus) -> std::io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+
+    let mut events: Vec<&StimulusEvent> = stimulus.events.iter().collect();
+    events.sort_by_key(|event| event.tick);
+
+    let mut identifiers: HashMap<&str, String> = HashMap::new();
+    let mut tags = Vec::new();
+    for event in events.iter() {
+        if !identifiers.contains_key(event.tag.as_str()) {
+            identifiers.insert(event.tag.as_str(), vcd_identifier(tags.len()));
+            tags.push(event.tag.as_str());
+        }
+    }
+
+    writeln!(writer, "$timescale 1ns $end")?;
+    writeln!(writer, "$scope module cpu $end")?;
+    for tag in tags.iter() {
+        writeln!(writer, "$var wire 1 {} {} $end", identifiers[tag], tag)?;
+    }
+    writeln!(writer, "$upscope $end")?;
+    writeln!(writer, "$enddefinitions $end")?;
+
+    let mut current_values: HashMap<&str, Signal> = HashMap::new();
+    let mut current_tick = None;
+    for event in events.iter() {
+        if current_values.get(event.tag.as_str()) == Some(&event.signal) {
+            continue;
+        }
+
+        if current_tick != Some(event.tick) {
+            writeln!(writer, "#{}", event.tick)?;
+            current_tick = Some(event.tick);
+        }
+
+        writeln!(writer, "{}{}", signal_to_vcd_value(&event.signal), identifiers[event.tag.as_str()])?;
+        current_values.insert(event.tag.as_str(), event.signal.clone());
+    }
+
+    //The final time is always written so that trailing ticks without a change are replayed.
+    if let Some(last_event) = events.last() {
+        if current_tick != Some(last_event.tick) {
+            writeln!(writer, "#{}", last_event.tick)?;
+        }
+    }
+
+    writer.flush()
+}
+
+//Records the tagged input and output gates of a circuit as a VCD file while it runs. Pass
+// record_tick() the arguments of the closure given to run_circuit(), each call is one time step.
+// The $var declarations are written on the first tick from the gates seen then, gates are
+// identified by their tag so any tag that first shows up on a later tick is not recorded. The
+// writer is flushed when the recorder is dropped.
+pub struct VcdRecorder<W: Write> {
+    writer: W,
+    identifiers: HashMap<String, String>,
+    current_values: HashMap<String, Signal>,
+    tick: usize,
+}
+
+#[allow(dead_code)]
+impl<W: Write> VcdRecorder<W> {
+    pub fn new(writer: W) -> Self {
+        VcdRecorder {
+            writer,
+            identifiers: HashMap::new(),
+            current_values: HashMap::new(),
+            tick: 0,
+        }
+    }
+
+    pub fn record_tick(
+        &mut self,
+        clock_tick_inputs: &[(String, Vec<GateOutputState>)],
+        output_gates: &Vec<SharedMutex<dyn LogicGateAndOutputGate>>,
+    ) -> std::io::Result<()> {
+        let mut tagged_signals: Vec<(String, Signal)> = Vec::new();
+        for (tag, output_states) in clock_tick_inputs.iter() {
+            if let Some(signal) = extract_signals_from_gate_output_states(output_states).first() {
+                tagged_signals.push((tag.clone(), signal.clone()));
+            }
+        }
+
+        for output_gate in output_gates.iter() {
+            let mut output_gate = output_gate.lock().unwrap();
+            let output_states = output_gate.fetch_output_signals_no_calculate().unwrap();
+            if let Some(signal) = extract_signals_from_gate_output_states(&output_states).first() {
+                tagged_signals.push((output_gate.get_output_tag(), signal.clone()));
+            }
+        }
+
+        if self.tick == 0 {
+            self.write_definitions(&tagged_signals)?;
+        }
+
+        let mut tick_written = false;
+        for (tag, signal) in tagged_signals {
+            let Some(identifier) = self.identifiers.get(&tag) else {
+                continue;
+            };
+
+            if self.current_values.get(&tag) == Some(&signal) {
+                continue;
+            }
+
+            if !tick_written {
+                writeln!(self.writer, "#{}", self.tick)?;
+                tick_written = true;
+            }
+
+            writeln!(self.writer, "{}{}", signal_to_vcd_value(&signal), identifier)?;
+            self.current_values.insert(tag, signal);
+        }
+
+        self.tick += 1;
+
+        Ok(())
+    }
+
+    fn write_definitions(&mut self, tagged_signals: &[(String, Signal)]) -> std::io::Result<()> {
+        writeln!(self.writer, "$timescale 1ns $end")?;
+        writeln!(self.writer, "$scope module circuit $end")?;
+        for (tag, _signal) in tagged_signals.iter() {
+            if self.identifiers.contains_key(tag) {
+                continue;
+            }
+
+            let identifier = vcd_identifier(self.identifiers.len());
+            writeln!(self.writer, "$var wire 1 {} {} $end", identifier, tag)?;
+            self.identifiers.insert(tag.clone(), identifier);
+        }
+        writeln!(self.writer, "$upscope $end")?;
+        writeln!(self.writer, "$enddefinitions $end")
+    }
+}
+
+impl<W: Write> Drop for VcdRecorder<W> {
+    fn drop(&mut self) {
+        let _ = self.writer.flush();
+    }
+}
+
+//Reads the value changes of every 1 bit wire inside a VCD file and drives the cpu inputs with
+// them, one VCD time step per clock-tick. signal_to_tag_map maps the VCD signal name to the cpu
+// input tag it drives, signals that are not inside the map are ignored. A signal keeps its value
+// until it changes, so every mapped input is driven on each time step between the first and last
+// time inside the file. See replay() inside run_circuit.rs for the state the cpu should be in.
+#[allow(dead_code)]
+pub fn drive_from_vcd(
+    cpu: &SharedMutex<VariableBitCPU>,
+    path: &str,
+    signal_to_tag_map: &HashMap<String, String>,
+) -> std::io::Result<()> {
+    let contents = std::fs::read_to_string(path)?;
+    let invalid_data = |message: String| Error::new(ErrorKind::InvalidData, message);
+
+    let mut tokens = contents.split_whitespace();
+    let mut identifier_to_tag: HashMap<String, String> = HashMap::new();
+    let mut value_changes: Vec<(usize, String, Signal)> = Vec::new();
+    let mut current_time = None;
+
+    while let Some(token) = tokens.next() {
+        if token == "$var" {
+            let declaration: Vec<&str> = tokens.by_ref().take_while(|token| *token != "$end").collect();
+            if declaration.len() < 4 {
+                return Err(invalid_data(format!("Incomplete $var declaration {:?}.", declaration)));
+            }
+
+            let (size, identifier, name) = (declaration[1], declaration[2], declaration[3]);
+            if let Some(tag) = signal_to_tag_map.get(name) {
+                if size != "1" {
+                    return Err(invalid_data(format!("Signal {} is {} bits wide, only 1 bit signals can be driven.", name, size)));
+                }
+                identifier_to_tag.insert(identifier.to_string(), tag.clone());
+            }
+        } else if token.starts_with('$') {
+            //$dumpvars and friends only wrap value changes, everything else is skipped.
+            if !matches!(token, "$dumpvars" | "$dumpall" | "$dumpon" | "$dumpoff" | "$end") {
+                tokens.by_ref().find(|token| *token == "$end");
+            }
+        } else if let Some(time) = token.strip_prefix('#') {
+            current_time = Some(
+                time.parse::<usize>().map_err(|_| invalid_data(format!("Invalid time {}.", token)))?
+            );
+        } else {
+            let time = current_time.ok_or_else(|| invalid_data(format!("Value change {} before the first time.", token)))?;
+
+            let mut characters = token.chars();
+            let signal = match characters.next() {
+                Some('1') => HIGH,
+                Some('0') => LOW_,
+                Some('x' | 'X' | 'z' | 'Z') => NONE,
+                _ => return Err(invalid_data(format!("Unsupported value change {}.", token))),
+            };
+
+            let identifier = characters.as_str();
+            if let Some(tag) = identifier_to_tag.get(identifier) {
+                value_changes.push((time, tag.clone(), signal));
+            }
+        }
+    }
+
+    let mut stimulus = Stimulus::new();
+    if let (Some(first_time), Some(last_time)) = (value_changes.first().map(|change| change.0), current_time) {
+        let mut tags: Vec<&String> = Vec::new();
+        for (_, tag, _) in value_changes.iter() {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+
+        let mut current_values: HashMap<&String, Signal> = HashMap::new();
+        let mut changes = value_changes.iter().peekable();
+        for tick in first_time..=last_time {
+            while let Some((_, tag, signal)) = changes.next_if(|change| change.0 <= tick) {
+                current_values.insert(tag, signal.clone());
+            }
+
+            for tag in tags.iter() {
+                stimulus.events.push(
+                    StimulusEvent {
+                        tick,
+                        tag: tag.to_string(),
+                        signal: current_values.get(tag).cloned().unwrap_or(NONE),
+                    }
+                );
+            }
+        }
+    }
+
+    replay(cpu, &stimulus);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use crate::logic::foundations::{connect_gates, LogicGate};
+    use crate::logic::input_gates::AutomaticInput;
+    use crate::logic::output_gates::SimpleOutput;
+    use crate::logic::processor_components::VariableBitRegister;
+    use crate::run_circuit::run_circuit;
+    use crate::run_circuit::{collect_signals_from_logic_gate, compare_generate_and_collected_output, run_instructions_and_record_stimulus};
+    use super::*;
+
+    #[test]
+    fn vcd_identifiers_are_unique() {
+        let identifiers: Vec<String> = (0..200).map(vcd_identifier).collect();
+
+        assert_eq!(identifiers[0], "!");
+        assert_eq!(identifiers[93], "~");
+        assert_eq!(identifiers[94], "!!");
+        for (i, identifier) in identifiers.iter().enumerate() {
+            assert!(!identifiers[i + 1..].contains(identifier));
+        }
+    }
+
+    #[test]
+    fn vcd_recorder_register() {
+        let register = VariableBitRegister::new(2);
+
+        //The value is saved on the second tick then enabled onto the outputs on the third.
+        let tagged_inputs = [
+            ("i_0", vec![HIGH, HIGH, LOW_, LOW_]),
+            ("i_1", vec![LOW_, LOW_, LOW_, LOW_]),
+            ("S", vec![LOW_, HIGH, LOW_, LOW_]),
+            ("E", vec![LOW_, LOW_, HIGH, HIGH]),
+        ];
+
+        let mut input_gates: Vec<SharedMutex<dyn LogicGate>> = Vec::new();
+        for (tag, signals) in tagged_inputs {
+            let input_gate = AutomaticInput::new(signals, 1, tag);
+            let input_index = register.lock().unwrap().get_index_from_tag(tag);
+            connect_gates(input_gate.clone(), 0, register.clone(), input_index);
+            input_gates.push(input_gate);
+        }
+
+        let mut output_gates: Vec<SharedMutex<dyn LogicGateAndOutputGate>> = Vec::new();
+        for tag in ["o_0", "o_1"] {
+            let output_gate = SimpleOutput::new(tag);
+            let output_index = register.lock().unwrap().get_index_from_tag(tag);
+            connect_gates(register.clone(), output_index, output_gate.clone(), 0);
+            output_gates.push(output_gate);
+        }
+
+        let mut vcd: Vec<u8> = Vec::new();
+        {
+            let mut recorder = VcdRecorder::new(&mut vcd);
+
+            let mut propagate_signal_through_circuit = true;
+            while run_circuit(
+                &input_gates,
+                &output_gates,
+                propagate_signal_through_circuit,
+                &mut |clock_tick_inputs, output_gates| {
+                    recorder.record_tick(clock_tick_inputs, output_gates).unwrap();
+                },
+            ) {
+                propagate_signal_through_circuit = false;
+            }
+        }
+
+        let vcd = String::from_utf8(vcd).unwrap();
+
+        for tag in ["i_0", "i_1", "S", "E", "o_0", "o_1"] {
+            assert!(vcd.contains(&format!(" {} $end", tag)), "{}", vcd);
+        }
+        assert!(vcd.contains("$enddefinitions $end\n#0\n"), "{}", vcd);
+
+        //o_0 goes HIGH once the saved value is enabled.
+        let o_0_identifier = vcd.lines()
+            .find(|line| line.ends_with(" o_0 $end"))
+            .and_then(|line| line.split_whitespace().nth(3))
+            .unwrap();
+        assert!(vcd.contains(&format!("#2\n1{}", o_0_identifier)) || vcd.contains(&format!("\n1{}\n", o_0_identifier)), "{}", vcd);
+    }
+
+    #[test]
+    fn drive_from_exported_vcd() {
+        let number_bits = 8;
+        let decoder_input_size = 1;
+
+        let binary_strings = vec![
+            "00100001", //Data R1
+            "00000101", //5
+            "11001111", //End
+        ];
+
+        let (recorded_cpu, stimulus) = run_instructions_and_record_stimulus(
+            number_bits,
+            decoder_input_size,
+            &binary_strings,
+        );
+
+        let path = std::env::temp_dir().join(format!("logical_cpu_stimulus_{}.vcd", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        write_stimulus_vcd(path, &stimulus).unwrap();
+
+        let vcd = fs::read_to_string(path).unwrap();
+        assert!(vcd.contains(&format!("$var wire 1 ! {} $end", VariableBitCPU::CLK_IN)), "{}", vcd);
+
+        //The RAM load is part of the recording so every recorded input is driven on a new cpu.
+        let driven_cpu = VariableBitCPU::new(number_bits, decoder_input_size);
+
+        let signal_to_tag_map: HashMap<String, String> = stimulus.events
+            .iter()
+            .map(|event| (event.tag.clone(), event.tag.clone()))
+            .collect();
+
+        drive_from_vcd(&driven_cpu, path, &signal_to_tag_map).unwrap();
+        fs::remove_file(path).unwrap();
+
+        let recorded_signals = collect_signals_from_logic_gate(recorded_cpu.clone());
+        let driven_signals = collect_signals_from_logic_gate(driven_cpu.clone());
+
+        let end_index = driven_cpu.lock().unwrap().get_index_from_tag(VariableBitCPU::END);
+        assert_eq!(driven_signals[end_index], HIGH);
+
+        let failed = compare_generate_and_collected_output(
+            &driven_cpu,
+            recorded_signals,
+            driven_signals,
+        );
+
+        assert!(!failed);
+    }
+}